@@ -0,0 +1,143 @@
+//! 语法树节点编号模块
+//!
+//! 每个 `Expr` 和 `Stmt` 节点都带有一个 `NodeId`。语法分析器创建节点时使用
+//! `NodeId::DUMMY`，解析完成后由 `assign_node_ids` 按先序遍历统一编号，
+//! 同一次编号中的节点编号从 0 开始连续且互不相同。
+//! 语义分析阶段可以用 `NodeMap` 按编号为节点附加信息，而不必修改语法树本身。
+//!
+//! 节点的位置是 `data::Span`：驻留的文件名加上起止的行和列，可以按值复制。
+//! 节点之间仍然由 `Box` 连接，没有放进按 `NodeId` 索引的数组。
+
+use crate::parser::{Expr, Stmt};
+use crate::visit::{VisitorMut, walk_expr_mut, walk_stmt_mut};
+use std::fmt;
+
+/// 语法树节点的编号
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct NodeId(u32);
+
+impl NodeId {
+    /// 尚未编号的节点使用的占位编号
+    pub const DUMMY: NodeId = NodeId(u32::MAX);
+
+    pub fn new(index: usize) -> NodeId {
+        assert!(index < u32::MAX as usize, "too many AST nodes");
+        NodeId(index as u32)
+    }
+
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+
+    pub fn is_dummy(self) -> bool {
+        self == NodeId::DUMMY
+    }
+}
+
+impl fmt::Debug for NodeId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_dummy() {
+            write!(f, "NodeId(DUMMY)")
+        } else {
+            write!(f, "NodeId({})", self.0)
+        }
+    }
+}
+
+/// 以节点编号为键的附加信息表
+///
+/// 编号是连续的小整数，因此直接用编号作为下标存放在 Vec 中
+#[derive(Debug, Clone, PartialEq)]
+pub struct NodeMap<T> {
+    entries: Vec<Option<T>>,
+}
+
+impl<T> Default for NodeMap<T> {
+    fn default() -> Self {
+        NodeMap {
+            entries: Vec::new(),
+        }
+    }
+}
+
+impl<T> NodeMap<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 记录节点的信息，返回该节点原有的信息
+    pub fn insert(&mut self, id: NodeId, value: T) -> Option<T> {
+        assert!(!id.is_dummy(), "cannot key a NodeMap by NodeId::DUMMY");
+        if self.entries.len() <= id.index() {
+            self.entries.resize_with(id.index() + 1, || None);
+        }
+        self.entries[id.index()].replace(value)
+    }
+
+    pub fn get(&self, id: NodeId) -> Option<&T> {
+        self.entries.get(id.index())?.as_ref()
+    }
+
+    pub fn get_mut(&mut self, id: NodeId) -> Option<&mut T> {
+        self.entries.get_mut(id.index())?.as_mut()
+    }
+
+    pub fn remove(&mut self, id: NodeId) -> Option<T> {
+        self.entries.get_mut(id.index())?.take()
+    }
+
+    pub fn contains(&self, id: NodeId) -> bool {
+        self.get(id).is_some()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.iter().filter(|entry| entry.is_some()).count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// 按编号顺序遍历所有记录
+    pub fn iter(&self) -> impl Iterator<Item = (NodeId, &T)> {
+        self.entries
+            .iter()
+            .enumerate()
+            .filter_map(|(index, entry)| Some((NodeId::new(index), entry.as_ref()?)))
+    }
+}
+
+/// 按先序遍历为语句及其包含的所有表达式和语句编号，返回编号的节点个数
+///
+/// 已有的编号会被覆盖，因此改写语法树之后可以重新编号
+pub fn assign_node_ids(statements: &mut [Stmt]) -> usize {
+    let mut numberer = Numberer { next: 0 };
+    numberer.visit_block_mut(statements);
+    numberer.next
+}
+
+/// 按先序遍历分配编号
+struct Numberer {
+    next: usize,
+}
+
+impl Numberer {
+    fn next_id(&mut self) -> NodeId {
+        let id = NodeId::new(self.next);
+        self.next += 1;
+        id
+    }
+}
+
+impl VisitorMut for Numberer {
+    fn visit_stmt_mut(&mut self, stmt: &mut Stmt) {
+        stmt.set_id(self.next_id());
+        walk_stmt_mut(self, stmt);
+    }
+
+    fn visit_expr_mut(&mut self, expr: &mut Expr) {
+        expr.set_id(self.next_id());
+        walk_expr_mut(self, expr);
+    }
+}
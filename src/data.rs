@@ -1,19 +1,21 @@
+use crate::intern::Symbol;
 use std::fmt;
 
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum Token {
     // 核心关键字，直接体现语言哲学
-    Fn,       // fn
-    Let,      // let
-    Var,      // var
-    With,     // with
-    Contract, // contract
-    Impl,     // impl
-    Mut,      // mut (作为一个独立的关键字)
-    Effect,   // effect (代数效应关键字)
-    Handle,   // handle (处理器关键字)
-    EffectGroup,   // effect_group (效果组关键字)
-    HandlerGroup,  // handler_group (处理器组关键字)
+    Fn,           // fn
+    Let,          // let
+    Var,          // var
+    With,         // with
+    Contract,     // contract
+    Impl,         // impl
+    Mut,          // mut (作为一个独立的关键字)
+    Effect,       // effect (代数效应关键字)
+    Handle,       // handle (处理器关键字)
+    EffectGroup,  // effect_group (效果组关键字)
+    HandlerGroup, // handler_group (处理器组关键字)
 
     // 控制流与其他通用关键字
     If,
@@ -28,10 +30,13 @@ pub enum Token {
     Return,
     As,
     Use,
+    Mod,
     Pub,
     Enum,
     Struct,
     Trait,
+    Type,
+    Where,
 
     // 布尔字面量
     True,
@@ -115,6 +120,7 @@ pub enum Token {
 
 /// 词法分析错误类型，提供更精确的错误信息
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum LexicalError {
     /// 未知字符
     UnknownCharacter(char),
@@ -132,6 +138,8 @@ pub enum LexicalError {
     UnexpectedEofInLiteral,
     /// 数字格式错误
     InvalidNumberFormat,
+    /// 块注释没有以 */ 结束
+    UnterminatedComment,
 }
 
 impl fmt::Display for LexicalError {
@@ -147,24 +155,199 @@ impl fmt::Display for LexicalError {
             LexicalError::UnknownEscapeSequence(c) => write!(f, "Unknown escape sequence: \\{}", c),
             LexicalError::UnexpectedEofInLiteral => write!(f, "Unexpected end of file in literal"),
             LexicalError::InvalidNumberFormat => write!(f, "Invalid number format"),
+            LexicalError::UnterminatedComment => write!(f, "Unterminated block comment"),
         }
     }
 }
 
+impl Token {
+    /// 记号在源代码中的写法，字面量按转义后的形式给出
+    pub fn lexeme(&self) -> String {
+        let text = match self {
+            Token::IntegerLiteral(text) | Token::FloatLiteral(text) | Token::Identifier(text) => {
+                return text.clone();
+            }
+            Token::StringLiteral(text) => return format!("{:?}", text),
+            Token::CharLiteral(c) => return format!("{:?}", c),
+            Token::Error(error) => return error.to_string(),
+            Token::Fn => "fn",
+            Token::Let => "let",
+            Token::Var => "var",
+            Token::With => "with",
+            Token::Contract => "contract",
+            Token::Impl => "impl",
+            Token::Mut => "mut",
+            Token::Effect => "effect",
+            Token::Handle => "handle",
+            Token::EffectGroup => "effect_group",
+            Token::HandlerGroup => "handler_group",
+            Token::If => "if",
+            Token::Else => "else",
+            Token::For => "for",
+            Token::In => "in",
+            Token::Loop => "loop",
+            Token::While => "while",
+            Token::Match => "match",
+            Token::Break => "break",
+            Token::Continue => "continue",
+            Token::Return => "return",
+            Token::As => "as",
+            Token::Use => "use",
+            Token::Mod => "mod",
+            Token::Pub => "pub",
+            Token::Enum => "enum",
+            Token::Struct => "struct",
+            Token::Trait => "trait",
+            Token::Type => "type",
+            Token::Where => "where",
+            Token::True => "true",
+            Token::False => "false",
+            Token::Async => "async",
+            Token::Await => "await",
+            Token::Try => "try",
+            Token::Plus => "+",
+            Token::Minus => "-",
+            Token::Star => "*",
+            Token::Slash => "/",
+            Token::Percent => "%",
+            Token::Ampersand => "&",
+            Token::Pipe => "|",
+            Token::Caret => "^",
+            Token::Bang => "!",
+            Token::Equal => "=",
+            Token::Less => "<",
+            Token::Greater => ">",
+            Token::Dot => ".",
+            Token::Comma => ",",
+            Token::Semicolon => ";",
+            Token::Colon => ":",
+            Token::Question => "?",
+            Token::At => "@",
+            Token::Hash => "#",
+            Token::Dollar => "$",
+            Token::Underscore => "_",
+            Token::PlusEqual => "+=",
+            Token::MinusEqual => "-=",
+            Token::StarEqual => "*=",
+            Token::SlashEqual => "/=",
+            Token::PercentEqual => "%=",
+            Token::AmpersandEqual => "&=",
+            Token::PipeEqual => "|=",
+            Token::CaretEqual => "^=",
+            Token::ShlEqual => "<<=",
+            Token::ShrEqual => ">>=",
+            Token::MutRef => "&mut",
+            Token::PathSep => "::",
+            Token::Arrow => "->",
+            Token::FatArrow => "=>",
+            Token::EqualEqual => "==",
+            Token::BangEqual => "!=",
+            Token::LessEqual => "<=",
+            Token::GreaterEqual => ">=",
+            Token::And => "&&",
+            Token::Or => "||",
+            Token::Shl => "<<",
+            Token::Shr => ">>",
+            Token::Range => "..",
+            Token::RangeInclusive => "..=",
+            Token::LParen => "(",
+            Token::RParen => ")",
+            Token::LBrace => "{",
+            Token::RBrace => "}",
+            Token::LBracket => "[",
+            Token::RBracket => "]",
+            Token::Eof => "",
+        };
+        text.to_string()
+    }
+}
+
 impl fmt::Display for Token {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{:?}", self)
+        write!(f, "{}", self.lexeme())
+    }
+}
+
+/// 源代码中的一段区间：从 (line, column) 开始，到 (end_line, end_column) 之前结束
+///
+/// 文件名是驻留的 `Symbol`，行列用 `u32` 存放，区间可以按值复制
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Span {
+    pub file: Symbol,
+    pub line: u32,
+    pub column: u32,
+    pub end_line: u32,
+    pub end_column: u32,
+}
+
+impl Span {
+    /// 覆盖位于 location 处的记号的区间
+    pub fn of_token(location: Location<'_>, token: &Token) -> Span {
+        Span::between(location, location.after(token))
+    }
+
+    /// 位于 (line, column) 处的空区间，用于输入末尾之类没有记号的位置
+    pub fn empty(file: &str, line: usize, column: usize) -> Span {
+        let location = Location { line, column, file };
+        Span::between(location, location)
+    }
+
+    /// 从 start 开始、到 end 之前结束的区间，文件取自 start
+    pub fn between(start: Location<'_>, end: Location<'_>) -> Span {
+        Span {
+            file: Symbol::intern(start.file),
+            line: position(start.line),
+            column: position(start.column),
+            end_line: position(end.line),
+            end_column: position(end.column),
+        }
+    }
+
+    /// 区间的起点
+    pub fn start(&self) -> Location<'static> {
+        Location {
+            line: self.line as usize,
+            column: self.column as usize,
+            file: self.file.as_str(),
+        }
+    }
+
+    /// 区间的终点，即最后一个字符之后的位置
+    pub fn end(&self) -> Location<'static> {
+        Location {
+            line: self.end_line as usize,
+            column: self.end_column as usize,
+            file: self.file.as_str(),
+        }
     }
 }
 
+/// 行号和列号压缩为 u32
+fn position(value: usize) -> u32 {
+    u32::try_from(value).expect("source position exceeds u32")
+}
+
 #[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Location<'a> {
     pub line: usize,
     pub column: usize,
     pub file: &'a str,
 }
 
+impl<'a> Location<'a> {
+    /// 位于此处的记号之后的位置
+    pub fn after(self, token: &Token) -> Location<'a> {
+        Location {
+            column: self.column + token.lexeme().chars().count(),
+            ..self
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Locatable<'a, T> {
     pub location: Location<'a>,
     pub data: T,
@@ -184,6 +367,16 @@ impl<'a> From<(Location<'a>, LexicalError)> for Locatable<'a, LexicalError> {
     }
 }
 
+// 没有词法错误的记号也可以作为词法分析器输出的记号流交给语法分析器
+impl<'a> From<Locatable<'a, Token>> for Locatable<'a, Result<Token, LexicalError>> {
+    fn from(token: Locatable<'a, Token>) -> Self {
+        Locatable {
+            location: token.location,
+            data: Ok(token.data),
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum Keyword {
     Fn,
@@ -195,8 +388,8 @@ pub enum Keyword {
     Mut,
     Effect,
     Handle,
-    EffectGroup,   // effect_group
-    HandlerGroup,  // handler_group
+    EffectGroup,  // effect_group
+    HandlerGroup, // handler_group
     If,
     Else,
     For,
@@ -209,10 +402,13 @@ pub enum Keyword {
     Return,
     As,
     Use,
+    Mod,
     Pub,
     Enum,
     Struct,
     Trait,
+    Type,
+    Where,
     True,
     False,
     Async,
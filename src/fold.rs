@@ -0,0 +1,875 @@
+//! 语法树折叠模块
+//!
+//! `Fold` 按值消耗语法树并构建新的语法树，适合把一种节点整体替换为另一种节点的改写，
+//! 例如脱糖。每种节点都有一个 `fold_*` 方法，默认实现调用本模块中同名的 `walk_*`
+//! 函数折叠子节点后原样重建，叶子节点的默认实现原样返回。
+//! 只需要读取或原地修改语法树时使用 `visit` 模块。
+
+use crate::parser::{
+    Capture, ContractClause, ContractDeclaration, EffectDeclaration, EffectGroupDeclaration,
+    EffectOperation, EffectRow, EnumDeclaration, EnumVariant, Expr, FunctionSignature,
+    GenericParameter, HandlerClause, HandlerDeclaration, HandlerGroupDeclaration, HandlerReference,
+    ImplDeclaration, Literal, ModuleBody, ModuleDeclaration, Parameter, ReturnClause, Stmt,
+    StructDeclaration, StructFields, TraitDeclaration, TraitItem, TypeAliasDeclaration,
+    UseDeclaration, VariantArguments, WherePredicate,
+};
+
+/// 按值折叠语法树
+pub trait Fold {
+    fn fold_stmt(&mut self, stmt: Stmt) -> Stmt {
+        walk_stmt(self, stmt)
+    }
+
+    fn fold_expr(&mut self, expr: Expr) -> Expr {
+        walk_expr(self, expr)
+    }
+
+    /// 语句序列：函数体、块、处理器子句体等
+    fn fold_block(&mut self, statements: Vec<Stmt>) -> Vec<Stmt> {
+        walk_block(self, statements)
+    }
+
+    fn fold_literal(&mut self, literal: Literal) -> Literal {
+        literal
+    }
+
+    fn fold_parameter(&mut self, parameter: Parameter) -> Parameter {
+        parameter
+    }
+
+    fn fold_generic_parameter(&mut self, generic: GenericParameter) -> GenericParameter {
+        generic
+    }
+
+    fn fold_where_predicate(&mut self, predicate: WherePredicate) -> WherePredicate {
+        predicate
+    }
+
+    fn fold_effect_row(&mut self, effects: EffectRow) -> EffectRow {
+        effects
+    }
+
+    fn fold_capture(&mut self, capture: Capture) -> Capture {
+        capture
+    }
+
+    fn fold_contract_clause(&mut self, clause: ContractClause) -> ContractClause {
+        walk_contract_clause(self, clause)
+    }
+
+    fn fold_function_signature(&mut self, signature: FunctionSignature) -> FunctionSignature {
+        walk_function_signature(self, signature)
+    }
+
+    fn fold_effect_declaration(&mut self, declaration: EffectDeclaration) -> EffectDeclaration {
+        walk_effect_declaration(self, declaration)
+    }
+
+    fn fold_effect_operation(&mut self, operation: EffectOperation) -> EffectOperation {
+        operation
+    }
+
+    fn fold_handler_declaration(&mut self, declaration: HandlerDeclaration) -> HandlerDeclaration {
+        walk_handler_declaration(self, declaration)
+    }
+
+    fn fold_handler_clause(&mut self, clause: HandlerClause) -> HandlerClause {
+        walk_handler_clause(self, clause)
+    }
+
+    fn fold_return_clause(&mut self, clause: ReturnClause) -> ReturnClause {
+        walk_return_clause(self, clause)
+    }
+
+    fn fold_handler_reference(&mut self, handler: HandlerReference) -> HandlerReference {
+        walk_handler_reference(self, handler)
+    }
+
+    fn fold_effect_group_declaration(
+        &mut self,
+        declaration: EffectGroupDeclaration,
+    ) -> EffectGroupDeclaration {
+        declaration
+    }
+
+    fn fold_handler_group_declaration(
+        &mut self,
+        declaration: HandlerGroupDeclaration,
+    ) -> HandlerGroupDeclaration {
+        declaration
+    }
+
+    fn fold_use_declaration(&mut self, declaration: UseDeclaration) -> UseDeclaration {
+        declaration
+    }
+
+    fn fold_struct_declaration(&mut self, declaration: StructDeclaration) -> StructDeclaration {
+        walk_struct_declaration(self, declaration)
+    }
+
+    fn fold_struct_fields(&mut self, fields: StructFields) -> StructFields {
+        fields
+    }
+
+    fn fold_enum_declaration(&mut self, declaration: EnumDeclaration) -> EnumDeclaration {
+        walk_enum_declaration(self, declaration)
+    }
+
+    fn fold_enum_variant(&mut self, variant: EnumVariant) -> EnumVariant {
+        walk_enum_variant(self, variant)
+    }
+
+    fn fold_type_alias_declaration(
+        &mut self,
+        declaration: TypeAliasDeclaration,
+    ) -> TypeAliasDeclaration {
+        walk_type_alias_declaration(self, declaration)
+    }
+
+    fn fold_trait_declaration(&mut self, declaration: TraitDeclaration) -> TraitDeclaration {
+        walk_trait_declaration(self, declaration)
+    }
+
+    fn fold_trait_item(&mut self, item: TraitItem) -> TraitItem {
+        walk_trait_item(self, item)
+    }
+
+    fn fold_impl_declaration(&mut self, declaration: ImplDeclaration) -> ImplDeclaration {
+        walk_impl_declaration(self, declaration)
+    }
+
+    fn fold_contract_declaration(
+        &mut self,
+        declaration: ContractDeclaration,
+    ) -> ContractDeclaration {
+        walk_contract_declaration(self, declaration)
+    }
+
+    fn fold_module_declaration(&mut self, declaration: ModuleDeclaration) -> ModuleDeclaration {
+        walk_module_declaration(self, declaration)
+    }
+
+    fn fold_variant_arguments(&mut self, arguments: VariantArguments) -> VariantArguments {
+        walk_variant_arguments(self, arguments)
+    }
+}
+
+fn fold_boxed<F: Fold + ?Sized>(folder: &mut F, expr: Expr) -> Box<Expr> {
+    Box::new(folder.fold_expr(expr))
+}
+
+fn fold_exprs<F: Fold + ?Sized>(folder: &mut F, exprs: Vec<Expr>) -> Vec<Expr> {
+    exprs
+        .into_iter()
+        .map(|expr| folder.fold_expr(expr))
+        .collect()
+}
+
+fn fold_all<F: Fold + ?Sized, T>(
+    folder: &mut F,
+    items: Vec<T>,
+    mut fold: impl FnMut(&mut F, T) -> T,
+) -> Vec<T> {
+    items.into_iter().map(|item| fold(folder, item)).collect()
+}
+
+pub fn walk_stmt<F: Fold + ?Sized>(folder: &mut F, stmt: Stmt) -> Stmt {
+    match stmt {
+        Stmt::Expression {
+            id,
+            span,
+            expression,
+            has_semicolon,
+        } => Stmt::Expression {
+            id,
+            span,
+            expression: folder.fold_expr(expression),
+            has_semicolon,
+        },
+        Stmt::Let {
+            id,
+            span,
+            identifier,
+            initializer,
+        } => Stmt::Let {
+            id,
+            span,
+            identifier,
+            initializer: initializer.map(|expr| folder.fold_expr(expr)),
+        },
+        Stmt::Var {
+            id,
+            span,
+            identifier,
+            initializer,
+        } => Stmt::Var {
+            id,
+            span,
+            identifier,
+            initializer: initializer.map(|expr| folder.fold_expr(expr)),
+        },
+        Stmt::Function {
+            id,
+            span,
+            visibility,
+            is_async,
+            name,
+            generics,
+            parameters,
+            return_type,
+            effects,
+            where_clause,
+            contracts,
+            body,
+        } => Stmt::Function {
+            id,
+            span,
+            visibility,
+            is_async,
+            name,
+            generics: fold_all(folder, generics, F::fold_generic_parameter),
+            parameters: fold_all(folder, parameters, F::fold_parameter),
+            return_type,
+            effects: folder.fold_effect_row(effects),
+            where_clause: fold_all(folder, where_clause, F::fold_where_predicate),
+            contracts: fold_all(folder, contracts, F::fold_contract_clause),
+            body: folder.fold_block(body),
+        },
+        Stmt::Block {
+            id,
+            span,
+            statements,
+        } => Stmt::Block {
+            id,
+            span,
+            statements: folder.fold_block(statements),
+        },
+        Stmt::Effect {
+            id,
+            span,
+            visibility,
+            declaration,
+        } => Stmt::Effect {
+            id,
+            span,
+            visibility,
+            declaration: folder.fold_effect_declaration(declaration),
+        },
+        Stmt::Handler {
+            id,
+            span,
+            visibility,
+            declaration,
+        } => Stmt::Handler {
+            id,
+            span,
+            visibility,
+            declaration: folder.fold_handler_declaration(declaration),
+        },
+        Stmt::EffectGroup {
+            id,
+            span,
+            visibility,
+            declaration,
+        } => Stmt::EffectGroup {
+            id,
+            span,
+            visibility,
+            declaration: folder.fold_effect_group_declaration(declaration),
+        },
+        Stmt::HandlerGroup {
+            id,
+            span,
+            visibility,
+            declaration,
+        } => Stmt::HandlerGroup {
+            id,
+            span,
+            visibility,
+            declaration: folder.fold_handler_group_declaration(declaration),
+        },
+        Stmt::Use {
+            id,
+            span,
+            visibility,
+            declaration,
+        } => Stmt::Use {
+            id,
+            span,
+            visibility,
+            declaration: folder.fold_use_declaration(declaration),
+        },
+        Stmt::Struct {
+            id,
+            span,
+            visibility,
+            declaration,
+        } => Stmt::Struct {
+            id,
+            span,
+            visibility,
+            declaration: folder.fold_struct_declaration(declaration),
+        },
+        Stmt::Enum {
+            id,
+            span,
+            visibility,
+            declaration,
+        } => Stmt::Enum {
+            id,
+            span,
+            visibility,
+            declaration: folder.fold_enum_declaration(declaration),
+        },
+        Stmt::TypeAlias {
+            id,
+            span,
+            visibility,
+            declaration,
+        } => Stmt::TypeAlias {
+            id,
+            span,
+            visibility,
+            declaration: folder.fold_type_alias_declaration(declaration),
+        },
+        Stmt::Trait {
+            id,
+            span,
+            visibility,
+            declaration,
+        } => Stmt::Trait {
+            id,
+            span,
+            visibility,
+            declaration: folder.fold_trait_declaration(declaration),
+        },
+        Stmt::Impl {
+            id,
+            span,
+            declaration,
+        } => Stmt::Impl {
+            id,
+            span,
+            declaration: folder.fold_impl_declaration(declaration),
+        },
+        Stmt::Contract {
+            id,
+            span,
+            visibility,
+            declaration,
+        } => Stmt::Contract {
+            id,
+            span,
+            visibility,
+            declaration: folder.fold_contract_declaration(declaration),
+        },
+        Stmt::Module {
+            id,
+            span,
+            visibility,
+            declaration,
+        } => Stmt::Module {
+            id,
+            span,
+            visibility,
+            declaration: folder.fold_module_declaration(declaration),
+        },
+        Stmt::Error { .. } => stmt,
+    }
+}
+
+pub fn walk_expr<F: Fold + ?Sized>(folder: &mut F, expr: Expr) -> Expr {
+    match expr {
+        Expr::Literal {
+            id,
+            span,
+            value,
+        } => Expr::Literal {
+            id,
+            span,
+            value: folder.fold_literal(value),
+        },
+        Expr::Identifier { .. } | Expr::Path { .. } | Expr::Result { .. } => expr,
+        Expr::Binary {
+            id,
+            span,
+            left,
+            operator,
+            right,
+        } => Expr::Binary {
+            id,
+            span,
+            left: fold_boxed(folder, *left),
+            operator,
+            right: fold_boxed(folder, *right),
+        },
+        Expr::Assign {
+            id,
+            span,
+            target,
+            operator,
+            value,
+        } => Expr::Assign {
+            id,
+            span,
+            target: fold_boxed(folder, *target),
+            operator,
+            value: fold_boxed(folder, *value),
+        },
+        Expr::Unary {
+            id,
+            span,
+            operator,
+            operand,
+        } => Expr::Unary {
+            id,
+            span,
+            operator,
+            operand: fold_boxed(folder, *operand),
+        },
+        Expr::Cast {
+            id,
+            span,
+            expression,
+            target_type,
+        } => Expr::Cast {
+            id,
+            span,
+            expression: fold_boxed(folder, *expression),
+            target_type,
+        },
+        Expr::Range {
+            id,
+            span,
+            start,
+            end,
+            inclusive,
+        } => Expr::Range {
+            id,
+            span,
+            start: start.map(|start| fold_boxed(folder, *start)),
+            end: end.map(|end| fold_boxed(folder, *end)),
+            inclusive,
+        },
+        Expr::Call {
+            id,
+            span,
+            function,
+            arguments,
+        } => Expr::Call {
+            id,
+            span,
+            function: fold_boxed(folder, *function),
+            arguments: fold_exprs(folder, arguments),
+        },
+        Expr::Grouping {
+            id,
+            span,
+            expression,
+        } => Expr::Grouping {
+            id,
+            span,
+            expression: fold_boxed(folder, *expression),
+        },
+        Expr::Tuple {
+            id,
+            span,
+            elements,
+        } => Expr::Tuple {
+            id,
+            span,
+            elements: fold_exprs(folder, elements),
+        },
+        Expr::Array {
+            id,
+            span,
+            elements,
+        } => Expr::Array {
+            id,
+            span,
+            elements: fold_exprs(folder, elements),
+        },
+        Expr::ArrayRepeat {
+            id,
+            span,
+            value,
+            count,
+        } => Expr::ArrayRepeat {
+            id,
+            span,
+            value: fold_boxed(folder, *value),
+            count: fold_boxed(folder, *count),
+        },
+        Expr::Async {
+            id,
+            span,
+            statements,
+            effects,
+        } => Expr::Async {
+            id,
+            span,
+            statements: folder.fold_block(statements),
+            effects,
+        },
+        Expr::TryBlock {
+            id,
+            span,
+            statements,
+            discharged_effects,
+        } => Expr::TryBlock {
+            id,
+            span,
+            statements: folder.fold_block(statements),
+            discharged_effects,
+        },
+        Expr::Block {
+            id,
+            span,
+            statements,
+        } => Expr::Block {
+            id,
+            span,
+            statements: folder.fold_block(statements),
+        },
+        Expr::Field {
+            id,
+            span,
+            object,
+            field,
+        } => Expr::Field {
+            id,
+            span,
+            object: fold_boxed(folder, *object),
+            field,
+        },
+        Expr::MethodCall {
+            id,
+            span,
+            receiver,
+            method,
+            arguments,
+        } => Expr::MethodCall {
+            id,
+            span,
+            receiver: fold_boxed(folder, *receiver),
+            method,
+            arguments: fold_exprs(folder, arguments),
+        },
+        Expr::Index {
+            id,
+            span,
+            object,
+            index,
+        } => Expr::Index {
+            id,
+            span,
+            object: fold_boxed(folder, *object),
+            index: fold_boxed(folder, *index),
+        },
+        Expr::Try {
+            id,
+            span,
+            expression,
+        } => Expr::Try {
+            id,
+            span,
+            expression: fold_boxed(folder, *expression),
+        },
+        Expr::Await {
+            id,
+            span,
+            expression,
+        } => Expr::Await {
+            id,
+            span,
+            expression: fold_boxed(folder, *expression),
+        },
+        Expr::StructLiteral {
+            id,
+            span,
+            path,
+            fields,
+            base,
+        } => Expr::StructLiteral {
+            id,
+            span,
+            path,
+            fields: fields
+                .into_iter()
+                .map(|(name, value)| (name, folder.fold_expr(value)))
+                .collect(),
+            base: base.map(|base| fold_boxed(folder, *base)),
+        },
+        Expr::EnumConstructor {
+            id,
+            span,
+            enum_name,
+            variant,
+            arguments,
+        } => Expr::EnumConstructor {
+            id,
+            span,
+            enum_name,
+            variant,
+            arguments: folder.fold_variant_arguments(arguments),
+        },
+        Expr::With {
+            id,
+            span,
+            handlers,
+            body,
+            discharged_effects,
+        } => Expr::With {
+            id,
+            span,
+            handlers: fold_all(folder, handlers, F::fold_handler_reference),
+            body: folder.fold_block(body),
+            discharged_effects,
+        },
+        Expr::Resume {
+            id,
+            span,
+            value,
+        } => Expr::Resume {
+            id,
+            span,
+            value: value.map(|value| fold_boxed(folder, *value)),
+        },
+        Expr::Old {
+            id,
+            span,
+            expression,
+        } => Expr::Old {
+            id,
+            span,
+            expression: fold_boxed(folder, *expression),
+        },
+        Expr::Closure {
+            id,
+            span,
+            is_move,
+            parameters,
+            return_type,
+            effects,
+            body,
+            captures,
+        } => Expr::Closure {
+            id,
+            span,
+            is_move,
+            parameters: fold_all(folder, parameters, F::fold_parameter),
+            return_type,
+            effects: folder.fold_effect_row(effects),
+            body: fold_boxed(folder, *body),
+            captures: fold_all(folder, captures, F::fold_capture),
+        },
+        Expr::EffectOperation {
+            id,
+            span,
+            effect,
+            operation,
+            arguments,
+        } => Expr::EffectOperation {
+            id,
+            span,
+            effect,
+            operation,
+            arguments: fold_exprs(folder, arguments),
+        },
+    }
+}
+
+pub fn walk_block<F: Fold + ?Sized>(folder: &mut F, statements: Vec<Stmt>) -> Vec<Stmt> {
+    fold_all(folder, statements, F::fold_stmt)
+}
+
+pub fn walk_contract_clause<F: Fold + ?Sized>(
+    folder: &mut F,
+    clause: ContractClause,
+) -> ContractClause {
+    match clause {
+        ContractClause::Requires(condition) => {
+            ContractClause::Requires(folder.fold_expr(condition))
+        }
+        ContractClause::Ensures(condition) => ContractClause::Ensures(folder.fold_expr(condition)),
+        ContractClause::Invariant(condition) => {
+            ContractClause::Invariant(folder.fold_expr(condition))
+        }
+        ContractClause::Named(_) => clause,
+    }
+}
+
+pub fn walk_function_signature<F: Fold + ?Sized>(
+    folder: &mut F,
+    mut signature: FunctionSignature,
+) -> FunctionSignature {
+    signature.generics = fold_all(folder, signature.generics, F::fold_generic_parameter);
+    signature.parameters = fold_all(folder, signature.parameters, F::fold_parameter);
+    signature.effects = folder.fold_effect_row(signature.effects);
+    signature.where_clause = fold_all(folder, signature.where_clause, F::fold_where_predicate);
+    signature.contracts = fold_all(folder, signature.contracts, F::fold_contract_clause);
+    signature
+}
+
+pub fn walk_effect_declaration<F: Fold + ?Sized>(
+    folder: &mut F,
+    mut declaration: EffectDeclaration,
+) -> EffectDeclaration {
+    declaration.generics = fold_all(folder, declaration.generics, F::fold_generic_parameter);
+    declaration.operations = fold_all(folder, declaration.operations, F::fold_effect_operation);
+    declaration
+}
+
+pub fn walk_handler_declaration<F: Fold + ?Sized>(
+    folder: &mut F,
+    mut declaration: HandlerDeclaration,
+) -> HandlerDeclaration {
+    declaration.clauses = fold_all(folder, declaration.clauses, F::fold_handler_clause);
+    declaration.return_clause = declaration
+        .return_clause
+        .map(|clause| folder.fold_return_clause(clause));
+    declaration.finally_clause = declaration
+        .finally_clause
+        .map(|body| folder.fold_block(body));
+    declaration
+}
+
+pub fn walk_handler_clause<F: Fold + ?Sized>(
+    folder: &mut F,
+    mut clause: HandlerClause,
+) -> HandlerClause {
+    clause.parameters = fold_all(folder, clause.parameters, F::fold_parameter);
+    clause.body = folder.fold_block(clause.body);
+    clause
+}
+
+pub fn walk_return_clause<F: Fold + ?Sized>(
+    folder: &mut F,
+    mut clause: ReturnClause,
+) -> ReturnClause {
+    clause.parameter = folder.fold_parameter(clause.parameter);
+    clause.body = folder.fold_block(clause.body);
+    clause
+}
+
+pub fn walk_handler_reference<F: Fold + ?Sized>(
+    folder: &mut F,
+    handler: HandlerReference,
+) -> HandlerReference {
+    match handler {
+        HandlerReference::Named(_) => handler,
+        HandlerReference::Inline(declaration) => {
+            HandlerReference::Inline(folder.fold_handler_declaration(declaration))
+        }
+    }
+}
+
+pub fn walk_struct_declaration<F: Fold + ?Sized>(
+    folder: &mut F,
+    mut declaration: StructDeclaration,
+) -> StructDeclaration {
+    declaration.generics = fold_all(folder, declaration.generics, F::fold_generic_parameter);
+    declaration.fields = folder.fold_struct_fields(declaration.fields);
+    declaration.contracts = fold_all(folder, declaration.contracts, F::fold_contract_clause);
+    declaration
+}
+
+pub fn walk_enum_declaration<F: Fold + ?Sized>(
+    folder: &mut F,
+    mut declaration: EnumDeclaration,
+) -> EnumDeclaration {
+    declaration.generics = fold_all(folder, declaration.generics, F::fold_generic_parameter);
+    declaration.variants = fold_all(folder, declaration.variants, F::fold_enum_variant);
+    declaration
+}
+
+pub fn walk_enum_variant<F: Fold + ?Sized>(
+    folder: &mut F,
+    mut variant: EnumVariant,
+) -> EnumVariant {
+    variant.fields = folder.fold_struct_fields(variant.fields);
+    variant
+}
+
+pub fn walk_type_alias_declaration<F: Fold + ?Sized>(
+    folder: &mut F,
+    mut declaration: TypeAliasDeclaration,
+) -> TypeAliasDeclaration {
+    declaration.generics = fold_all(folder, declaration.generics, F::fold_generic_parameter);
+    declaration
+}
+
+pub fn walk_trait_declaration<F: Fold + ?Sized>(
+    folder: &mut F,
+    mut declaration: TraitDeclaration,
+) -> TraitDeclaration {
+    declaration.generics = fold_all(folder, declaration.generics, F::fold_generic_parameter);
+    declaration.where_clause = fold_all(folder, declaration.where_clause, F::fold_where_predicate);
+    declaration.contracts = fold_all(folder, declaration.contracts, F::fold_contract_clause);
+    declaration.items = fold_all(folder, declaration.items, F::fold_trait_item);
+    declaration
+}
+
+pub fn walk_trait_item<F: Fold + ?Sized>(folder: &mut F, item: TraitItem) -> TraitItem {
+    match item {
+        TraitItem::Method {
+            signature,
+            default_body,
+        } => TraitItem::Method {
+            signature: folder.fold_function_signature(signature),
+            default_body: default_body.map(|body| folder.fold_block(body)),
+        },
+        TraitItem::AssociatedType { .. } => item,
+    }
+}
+
+pub fn walk_impl_declaration<F: Fold + ?Sized>(
+    folder: &mut F,
+    mut declaration: ImplDeclaration,
+) -> ImplDeclaration {
+    declaration.generics = fold_all(folder, declaration.generics, F::fold_generic_parameter);
+    declaration.where_clause = fold_all(folder, declaration.where_clause, F::fold_where_predicate);
+    declaration.items = fold_all(folder, declaration.items, F::fold_stmt);
+    declaration
+}
+
+pub fn walk_contract_declaration<F: Fold + ?Sized>(
+    folder: &mut F,
+    mut declaration: ContractDeclaration,
+) -> ContractDeclaration {
+    declaration.parameters = fold_all(folder, declaration.parameters, F::fold_parameter);
+    declaration.clauses = fold_all(folder, declaration.clauses, F::fold_contract_clause);
+    declaration
+}
+
+pub fn walk_module_declaration<F: Fold + ?Sized>(
+    folder: &mut F,
+    mut declaration: ModuleDeclaration,
+) -> ModuleDeclaration {
+    declaration.body = match declaration.body {
+        ModuleBody::Inline(items) => ModuleBody::Inline(fold_all(folder, items, F::fold_stmt)),
+        ModuleBody::File { path, items } => ModuleBody::File {
+            path,
+            items: fold_all(folder, items, F::fold_stmt),
+        },
+    };
+    declaration
+}
+
+pub fn walk_variant_arguments<F: Fold + ?Sized>(
+    folder: &mut F,
+    arguments: VariantArguments,
+) -> VariantArguments {
+    match arguments {
+        VariantArguments::Unit => arguments,
+        VariantArguments::Tuple(arguments) => {
+            VariantArguments::Tuple(fold_exprs(folder, arguments))
+        }
+        VariantArguments::Named(fields) => VariantArguments::Named(
+            fields
+                .into_iter()
+                .map(|(name, value)| (name, folder.fold_expr(value)))
+                .collect(),
+        ),
+    }
+}
@@ -0,0 +1,381 @@
+//! 代码格式化模块
+//!
+//! 格式化以语法树打印（`printer` 模块）为基础：先构建无损语法树，确认源代码没有错误后，
+//! 把每条注释挂到语句、代码块或声明成员上，再按行宽打印语法树。
+//! - 语句之间的注释留在原处，与上一条语句在同一行的注释仍然接在它的末尾
+//! - 与左花括号在同一行的注释仍然接在左花括号之后
+//! - 效果、处理器、结构体、枚举、特征和契约中成员之间的注释留在成员之间
+//! - 表达式和声明头部中的注释（包括行尾注释）独占一行，移到所在语句之前：
+//!   打印器按语句打印，表达式和声明的组成部分内部没有挂载点
+//! - 语句之间连续的空行合并为一个
+//!
+//! 注释的挂载点只依赖语法树和注释的相对位置，因此格式化的结果再次格式化不会改变
+
+use crate::data::{LexicalError, Span, Token};
+use crate::parser::ParseError;
+use crate::printer::{Anchor, Comment, Position, Trivia, format_program};
+use crate::syntax::{SyntaxElement, SyntaxKind, SyntaxNode, SyntaxToken, SyntaxTree};
+use std::fmt;
+
+/// 格式化选项
+#[derive(Debug, Clone, PartialEq)]
+pub struct FormatOptions {
+    /// 行宽，逗号分隔的列表（参数、数组、结构体字面量等）超出时每项占一行
+    pub line_width: usize,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        FormatOptions { line_width: 100 }
+    }
+}
+
+/// 无法格式化的原因，源代码有错误时不做任何修改
+#[derive(Debug, Clone, PartialEq)]
+pub enum FormatError {
+    Lexical(Span, LexicalError),
+    Parse(ParseError),
+}
+
+impl fmt::Display for FormatError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FormatError::Lexical(span, error) => {
+                write!(f, "{}:{}:{}: {}", span.file, span.line, span.column, error)
+            }
+            FormatError::Parse(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+/// 格式化一个源文件，源代码有词法或语法错误时返回第一个错误
+pub fn format_source(
+    file: &str,
+    source: &str,
+    options: &FormatOptions,
+) -> Result<String, FormatError> {
+    let tree = SyntaxTree::parse(file, source);
+    if let Some((span, error)) = tree.lexical_errors().first() {
+        return Err(FormatError::Lexical(*span, error.clone()));
+    }
+    if let Some(error) = tree.errors().first() {
+        return Err(FormatError::Parse(error.clone()));
+    }
+
+    let mut attacher = Attacher {
+        tree: &tree,
+        source,
+        trivia: Trivia::default(),
+        blocks: 0,
+    };
+    attacher.statement_list(&tree.root().children_with_tokens(), None, Anchor::End);
+    Ok(format_program(
+        tree.statements(),
+        attacher.trivia,
+        options.line_width,
+    ))
+}
+
+/// 声明成员之间的分隔方式
+#[derive(Clone, Copy)]
+enum Separator {
+    /// 成员以 `;` 或代码块结尾：效果、特征、契约和处理器
+    Semicolon,
+    /// 成员以 `,` 分隔：结构体字段和枚举变体，类型参数中的逗号不算
+    Comma,
+}
+
+/// 遍历无损语法树，为每条注释选择挂载点
+struct Attacher<'a> {
+    tree: &'a SyntaxTree,
+    source: &'a str,
+    trivia: Trivia,
+    /// 已经遇到的块节点个数，按左花括号的顺序编号，与打印时代码块的编号相同
+    blocks: usize,
+}
+
+impl Attacher<'_> {
+    fn position(&self, offset: usize) -> Position {
+        let location = self.tree.location(offset);
+        (location.line, location.column)
+    }
+
+    /// 语句在语法树中的位置，与 `Stmt::location` 相同：块语句的位置是右花括号
+    fn item_position(&self, node: &SyntaxNode) -> Position {
+        match node.kind() {
+            SyntaxKind::BlockStatement => self.position(node.text_range().end - 1),
+            _ => self.position(node.text_range().start),
+        }
+    }
+
+    /// 节点最后一个字符所在的行
+    fn end_line(&self, node: &SyntaxNode) -> usize {
+        self.tree.location(node.text_range().end - 1).line
+    }
+
+    fn attach(&mut self, anchor: Anchor, comment: Comment) {
+        self.trivia
+            .comments
+            .entry(anchor)
+            .or_default()
+            .push(comment);
+    }
+
+    fn comment(&self, token: &SyntaxToken, blank_line_before: bool) -> Comment {
+        Comment {
+            text: token.text().trim_end().to_string(),
+            offset: token.text_range().start,
+            blank_line_before,
+        }
+    }
+
+    /// 注释所在的行在它之前只有空白
+    fn is_own_line(&self, token: &SyntaxToken) -> bool {
+        let before = &self.source[..token.text_range().start];
+        before
+            .rsplit('\n')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .is_empty()
+    }
+
+    /// 注释所在的行在它之后只有空白
+    fn ends_line(&self, token: &SyntaxToken) -> bool {
+        let after = &self.source[token.text_range().end..];
+        after
+            .split('\n')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .is_empty()
+    }
+
+    /// 语句列表：源文件、代码块、内联模块和实现的主体。
+    /// start 是与左花括号在同一行的注释，end 是最后一条语句之后的注释
+    fn statement_list(&mut self, elements: &[SyntaxElement], start: Option<Anchor>, end: Anchor) {
+        // 上一条语句的位置和结束的行
+        let mut previous: Option<(Position, usize)> = None;
+        let mut pending = Vec::new();
+        let mut blank_line = false;
+        for element in elements {
+            match element {
+                SyntaxElement::Token(token) => match token.kind() {
+                    SyntaxKind::Whitespace => {
+                        // 列表开头的空行不保留
+                        let started = previous.is_some() || !pending.is_empty();
+                        blank_line |= started && token.text().matches('\n').count() >= 2;
+                    }
+                    SyntaxKind::LineComment | SyntaxKind::BlockComment => {
+                        let line = self.tree.location(token.text_range().start).line;
+                        match previous {
+                            Some((position, end_line))
+                                if line == end_line && !self.is_own_line(token) =>
+                            {
+                                let comment = self.comment(token, false);
+                                self.attach(Anchor::After(position), comment);
+                            }
+                            None if pending.is_empty()
+                                && let Some(start) = start
+                                && !self.is_own_line(token)
+                                && self.ends_line(token) =>
+                            {
+                                let comment = self.comment(token, false);
+                                self.attach(start, comment);
+                            }
+                            _ => pending.push(self.comment(token, blank_line)),
+                        }
+                        blank_line = false;
+                    }
+                    _ => {}
+                },
+                SyntaxElement::Node(node) if node.kind().is_item() => {
+                    let position = self.item_position(node);
+                    for comment in pending.drain(..) {
+                        self.attach(Anchor::Before(position), comment);
+                    }
+                    if blank_line {
+                        self.trivia.blank_lines.insert(position);
+                        blank_line = false;
+                    }
+                    self.item(node, position);
+                    previous = Some((position, self.end_line(node)));
+                }
+                SyntaxElement::Node(node) => self.block(node),
+            }
+        }
+        for comment in pending {
+            self.attach(end, comment);
+        }
+    }
+
+    fn block(&mut self, node: &SyntaxNode) {
+        let index = self.blocks;
+        self.blocks += 1;
+        self.statement_list(
+            &node.children_with_tokens(),
+            Some(Anchor::BlockStart(index)),
+            Anchor::BlockEnd(index),
+        );
+    }
+
+    /// 一条语句。声明头部和表达式中的注释移到语句之前
+    fn item(&mut self, node: &SyntaxNode, position: Position) {
+        let elements = flatten(node);
+        let separator = match node.kind() {
+            SyntaxKind::Effect | SyntaxKind::Trait | SyntaxKind::Contract | SyntaxKind::Handler => {
+                Some(Separator::Semicolon)
+            }
+            SyntaxKind::Struct | SyntaxKind::Enum => Some(Separator::Comma),
+            _ => None,
+        };
+        let has_items = matches!(node.kind(), SyntaxKind::Module | SyntaxKind::Impl);
+        // 成员或语句写在声明的主体中，元组结构体之类没有主体的声明没有成员
+        let body = (separator.is_some() || has_items)
+            .then(|| body_start(&elements))
+            .flatten()
+            .unwrap_or(elements.len());
+
+        for element in &elements[..body] {
+            match element {
+                SyntaxElement::Token(token) if token.kind().is_trivia() => {
+                    if token.kind() != SyntaxKind::Whitespace {
+                        let comment = self.comment(token, false);
+                        self.attach(Anchor::Before(position), comment);
+                    }
+                }
+                SyntaxElement::Token(_) => {}
+                SyntaxElement::Node(node) if node.kind() == SyntaxKind::Block => self.block(node),
+                SyntaxElement::Node(node) => {
+                    let position = self.item_position(node);
+                    self.item(node, position);
+                }
+            }
+        }
+
+        let body = &elements[(body + 1).min(elements.len())..];
+        if has_items {
+            let count = body
+                .iter()
+                .filter(
+                    |element| matches!(element, SyntaxElement::Node(node) if node.kind().is_item()),
+                )
+                .count();
+            self.statement_list(
+                body,
+                Some(Anchor::MemberStart(position)),
+                Anchor::Member(position, count),
+            );
+        } else if let Some(separator) = separator {
+            self.members(body, position, separator);
+        }
+    }
+
+    /// 声明主体中的成员，body 从左花括号之后开始
+    fn members(&mut self, body: &[SyntaxElement], owner: Position, separator: Separator) {
+        // 已经开始的成员个数，以及最后一个成员是否还没有结束
+        let mut started = 0;
+        let mut open = false;
+        let mut depth = 0usize;
+        let mut angle_depth = 0usize;
+        let mut last_line = None;
+        for element in body {
+            let token = match element {
+                SyntaxElement::Node(node) => {
+                    if !open {
+                        started += 1;
+                    }
+                    self.block(node);
+                    open = depth > 0;
+                    last_line = Some(self.end_line(node));
+                    continue;
+                }
+                SyntaxElement::Token(token) => token,
+            };
+
+            match token.kind() {
+                SyntaxKind::Whitespace => continue,
+                SyntaxKind::LineComment | SyntaxKind::BlockComment => {
+                    let line = self.tree.location(token.text_range().start).line;
+                    let comment = self.comment(token, false);
+                    if self.is_own_line(token) {
+                        self.attach(Anchor::Member(owner, started), comment);
+                    } else if started == 0 && self.ends_line(token) {
+                        // 与主体的左花括号在同一行
+                        self.attach(Anchor::MemberStart(owner), comment);
+                    } else if last_line == Some(line) {
+                        self.attach(Anchor::MemberEnd(owner, started - 1), comment);
+                        // 多行块注释之后同一行上的注释也跟在这个成员后面
+                        last_line = Some(self.tree.location(token.text_range().end).line);
+                    } else {
+                        self.attach(Anchor::Member(owner, started), comment);
+                    }
+                    continue;
+                }
+                _ => {}
+            }
+
+            last_line = Some(self.tree.location(token.text_range().start).line);
+            let data = token.token();
+            let is_separator = depth == 0
+                && match separator {
+                    Separator::Semicolon => data == Some(&Token::Semicolon),
+                    Separator::Comma => data == Some(&Token::Comma) && angle_depth == 0,
+                };
+            match data {
+                Some(Token::LBrace | Token::LParen | Token::LBracket) => depth += 1,
+                Some(Token::RBrace | Token::RParen | Token::RBracket) => {
+                    // 主体的右花括号
+                    if depth == 0 {
+                        break;
+                    }
+                    depth -= 1;
+                }
+                Some(Token::Less) => angle_depth += 1,
+                Some(Token::Greater) => angle_depth = angle_depth.saturating_sub(1),
+                Some(Token::Shr) => angle_depth = angle_depth.saturating_sub(2),
+                _ => {}
+            }
+
+            if is_separator {
+                open = false;
+            } else if !open {
+                started += 1;
+                open = true;
+            }
+        }
+    }
+}
+
+/// 语句的子元素，表达式和声明组成部分的节点展开为其中的记号，只保留代码块和嵌套的语句
+fn flatten(node: &SyntaxNode) -> Vec<SyntaxElement> {
+    let mut elements = Vec::new();
+    for element in node.children_with_tokens() {
+        match element {
+            SyntaxElement::Node(child)
+                if child.kind() != SyntaxKind::Block && !child.kind().is_item() =>
+            {
+                elements.extend(flatten(&child));
+            }
+            element => elements.push(element),
+        }
+    }
+    elements
+}
+
+/// 声明主体的左花括号在子元素中的下标：括号之外的第一个 `{`
+fn body_start(elements: &[SyntaxElement]) -> Option<usize> {
+    let mut depth = 0usize;
+    for (index, element) in elements.iter().enumerate() {
+        let SyntaxElement::Token(token) = element else {
+            continue;
+        };
+        match token.token() {
+            Some(Token::LParen | Token::LBracket) => depth += 1,
+            Some(Token::RParen | Token::RBracket) => depth = depth.saturating_sub(1),
+            Some(Token::LBrace) if depth == 0 => return Some(index),
+            _ => {}
+        }
+    }
+    None
+}
@@ -0,0 +1,155 @@
+//! 字符串驻留模块
+//!
+//! 语法树中的标识符和文件名大量重复，驻留后每个不同的字符串只保存一份，
+//! 节点中只存放 4 字节的 `Symbol`，复制和比较都只是比较编号。
+//! 驻留的字符串归全局的驻留表所有，在程序运行期间一直存在。
+//!
+//! 字符串按编号存放在逐块分配、只追加的表中，已经写入的位置不再改变，
+//! 因此读取 `Symbol` 对应的字符串不需要加锁；只有驻留新字符串时才需要锁住查找表。
+
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::Deref;
+use std::sync::{LazyLock, Mutex, OnceLock};
+
+/// 驻留的字符串
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Symbol(u32);
+
+/// 第一块的容量，之后每块的容量是前一块的两倍
+const FIRST_CHUNK: usize = 64;
+
+/// 块的个数，足够容纳 u32 范围内的所有编号
+const CHUNKS: usize = 27;
+
+type Chunk = Box<[OnceLock<Box<str>>]>;
+
+/// 编号到字符串的表，编号为 i 的字符串写入后不再改变
+static STRINGS: [OnceLock<Chunk>; CHUNKS] = [const { OnceLock::new() }; CHUNKS];
+
+/// 字符串到编号的查找表，只在驻留时使用
+static SYMBOLS: LazyLock<Mutex<HashMap<&'static str, Symbol>>> = LazyLock::new(Default::default);
+
+/// 编号所在的块和块内的下标
+fn slot(index: usize) -> (usize, usize) {
+    let chunk = (index / FIRST_CHUNK + 1).ilog2() as usize;
+    (chunk, index - FIRST_CHUNK * ((1 << chunk) - 1))
+}
+
+impl Symbol {
+    /// 驻留字符串，相同的字符串总是得到相同的 Symbol
+    pub fn intern(string: &str) -> Symbol {
+        let mut symbols = SYMBOLS.lock().unwrap();
+        if let Some(symbol) = symbols.get(string) {
+            return *symbol;
+        }
+
+        let index = symbols.len();
+        assert!(index < u32::MAX as usize, "too many interned strings");
+        let (chunk, offset) = slot(index);
+        let chunk = STRINGS[chunk]
+            .get_or_init(|| (0..FIRST_CHUNK << chunk).map(|_| OnceLock::new()).collect());
+        let stored: &'static str = chunk[offset].get_or_init(|| string.into());
+        let symbol = Symbol(index as u32);
+        symbols.insert(stored, symbol);
+        symbol
+    }
+
+    /// 驻留的字符串，读取时不加锁
+    pub fn as_str(&self) -> &'static str {
+        let (chunk, offset) = slot(self.0 as usize);
+        STRINGS[chunk]
+            .get()
+            .and_then(|chunk| chunk[offset].get())
+            .expect("Symbol is always created by Symbol::intern")
+    }
+}
+
+impl Deref for Symbol {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl AsRef<str> for Symbol {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl From<&str> for Symbol {
+    fn from(string: &str) -> Self {
+        Symbol::intern(string)
+    }
+}
+
+impl From<String> for Symbol {
+    fn from(string: String) -> Self {
+        Symbol::intern(&string)
+    }
+}
+
+impl From<&String> for Symbol {
+    fn from(string: &String) -> Self {
+        Symbol::intern(string)
+    }
+}
+
+impl PartialEq<str> for Symbol {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for Symbol {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl PartialEq<String> for Symbol {
+    fn eq(&self, other: &String) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<Symbol> for str {
+    fn eq(&self, other: &Symbol) -> bool {
+        self == other.as_str()
+    }
+}
+
+impl PartialEq<Symbol> for &str {
+    fn eq(&self, other: &Symbol) -> bool {
+        *self == other.as_str()
+    }
+}
+
+impl PartialEq<Symbol> for String {
+    fn eq(&self, other: &Symbol) -> bool {
+        self == other.as_str()
+    }
+}
+
+impl fmt::Display for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+// 与 String 的调试输出相同，语法树的调试输出不因驻留而改变
+impl fmt::Debug for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+// 序列化为字符串本身，编号只在本次运行中有意义
+#[cfg(feature = "serde")]
+impl serde::Serialize for Symbol {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
@@ -29,10 +29,13 @@ static KEYWORDS: Map<&'static str, Keyword> = phf::phf_map! {
     "return" => Keyword::Return,
     "as" => Keyword::As,
     "use" => Keyword::Use,
+    "mod" => Keyword::Mod,
     "pub" => Keyword::Pub,
     "enum" => Keyword::Enum,
     "struct" => Keyword::Struct,
     "trait" => Keyword::Trait,
+    "type" => Keyword::Type,
+    "where" => Keyword::Where,
     "true" => Keyword::True,
     "false" => Keyword::False,
     "async" => Keyword::Async,
@@ -40,29 +43,71 @@ static KEYWORDS: Map<&'static str, Keyword> = phf::phf_map! {
     "try" => Keyword::Try,
 };
 
+/// 记号之间的空白和注释
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TriviaKind {
+    Whitespace,
+    /// `// ...`，不包括行尾的换行
+    LineComment,
+    /// `/* ... */`，可以嵌套
+    BlockComment,
+}
+
+/// 一段空白或注释及其原文
+#[derive(Debug, Clone, PartialEq)]
+pub struct Trivia {
+    pub kind: TriviaKind,
+    pub text: String,
+}
+
+/// 带原文和前导空白、注释的记号，用于构建无损语法树
+#[derive(Debug, Clone)]
+pub struct RawToken<'a> {
+    /// 记号之前的空白和注释
+    pub leading_trivia: Vec<Trivia>,
+    pub token: Locatable<'a, Result<Token, LexicalError>>,
+    /// 记号在源代码中的原文，词法错误时是出错前读入的字符
+    pub text: String,
+}
+
 pub struct Lexer<'a, R: Read> {
     location: Location<'a>,
     reader: BufReader<R>,
     iterator: IntoIter<char>,
-    current: Option<char>,
+    // 回退的字符栈，栈顶是下一个要读取的字符
+    pushback: Vec<char>,
+    // 当前记号或trivia已读入的原文
+    text: String,
+    // 下一个记号之前的trivia，读到文件末尾后是文件末尾的trivia
+    trivia: Vec<Trivia>,
 }
 
 impl<'a, R: Read> Lexer<'a, R> {
     pub fn new(filename: &'a str, stream: BufReader<R>) -> Lexer<'a, R> {
         Lexer {
+            // 读入第一行时行号加一，因此从0开始
             location: Location {
-                line: 1,
+                line: 0,
                 column: 0,
                 file: filename,
             },
             reader: stream,
             iterator: Vec::new().into_iter(),
-            current: None,
+            pushback: Vec::new(),
+            text: String::new(),
+            trivia: Vec::new(),
         }
     }
 
     fn next_char(&mut self) -> Option<char> {
-        if let Some(c) = self.current.take() {
+        let c = self.read_char()?;
+        self.text.push(c);
+        Some(c)
+    }
+
+    fn read_char(&mut self) -> Option<char> {
+        if let Some(c) = self.pushback.pop() {
+            self.location.column += 1;
             Some(c)
         } else {
             match self.iterator.next() {
@@ -89,27 +134,123 @@ impl<'a, R: Read> Lexer<'a, R> {
     }
 
     fn unput(&mut self, c: Option<char>) {
-        self.current = c;
-        if self.location.column > 0 && c.is_some() {
-            self.location.column -= 1;
+        if let Some(c) = c {
+            self.pushback.push(c);
+            self.text.pop();
+            if self.location.column > 0 {
+                self.location.column -= 1;
+            }
         }
     }
 
     fn peek(&mut self) -> Option<char> {
-        if self.current.is_none() {
-            self.current = self.next_char();
+        if self.pushback.is_empty() {
+            let c = self.next_char();
+            self.unput(c);
         }
-        self.current
+        self.pushback.last().copied()
     }
 
-    fn skip_whitespace(&mut self) {
-        while let Some(c) = self.peek() {
-            if c.is_ascii_whitespace() {
-                self.next_char();
-            } else {
-                break;
+    /// 读取下一个记号及其原文和前导trivia
+    ///
+    /// 到达文件末尾时返回 None，此后 trailing_trivia 返回文件末尾的trivia
+    pub fn next_raw(&mut self) -> Option<RawToken<'a>> {
+        let result = self.skip_trivia();
+        let leading_trivia = std::mem::take(&mut self.trivia);
+        if let Err(location) = result {
+            return Some(RawToken {
+                leading_trivia,
+                token: Locatable {
+                    location,
+                    data: Err(LexicalError::UnterminatedComment),
+                },
+                text: std::mem::take(&mut self.text),
+            });
+        }
+
+        self.text.clear();
+        let Some(c) = self.next_char() else {
+            self.trivia = leading_trivia;
+            return None;
+        };
+        let location = self.location;
+        let data = self.lex_token(c);
+
+        Some(RawToken {
+            leading_trivia,
+            token: Locatable { location, data },
+            text: std::mem::take(&mut self.text),
+        })
+    }
+
+    /// 文件末尾最后一个记号之后的trivia，在 next_raw 返回 None 之后调用
+    pub fn trailing_trivia(&mut self) -> Vec<Trivia> {
+        std::mem::take(&mut self.trivia)
+    }
+
+    /// 跳过空白和注释并记录到 self.trivia 中，块注释没有结束时返回它的位置
+    fn skip_trivia(&mut self) -> Result<(), Location<'a>> {
+        loop {
+            self.text.clear();
+            let kind = match self.peek() {
+                Some(c) if c.is_ascii_whitespace() => {
+                    while self.peek().is_some_and(|c| c.is_ascii_whitespace()) {
+                        self.next_char();
+                    }
+                    TriviaKind::Whitespace
+                }
+                Some('/') => {
+                    self.next_char();
+                    let location = self.location;
+                    match self.peek() {
+                        Some('/') => {
+                            while self.peek().is_some_and(|c| c != '\n') {
+                                self.next_char();
+                            }
+                            TriviaKind::LineComment
+                        }
+                        Some('*') => {
+                            self.next_char();
+                            if !self.skip_block_comment() {
+                                return Err(location);
+                            }
+                            TriviaKind::BlockComment
+                        }
+                        _ => {
+                            self.unput(Some('/'));
+                            return Ok(());
+                        }
+                    }
+                }
+                _ => return Ok(()),
+            };
+            self.trivia.push(Trivia {
+                kind,
+                text: std::mem::take(&mut self.text),
+            });
+        }
+    }
+
+    /// 跳过块注释的剩余部分（开头的 /* 已被消费），到达文件末尾时返回 false
+    fn skip_block_comment(&mut self) -> bool {
+        let mut depth = 1;
+        while let Some(c) = self.next_char() {
+            match c {
+                '*' if self.peek() == Some('/') => {
+                    self.next_char();
+                    depth -= 1;
+                    if depth == 0 {
+                        return true;
+                    }
+                }
+                '/' if self.peek() == Some('*') => {
+                    self.next_char();
+                    depth += 1;
+                }
+                _ => {}
             }
         }
+        false
     }
 
     fn parse_number(&mut self) -> Result<Token, LexicalError> {
@@ -344,18 +485,10 @@ impl<'a, R: Read> Lexer<'a, R> {
         }
         id
     }
-}
-
-impl<'a, R: Read> Iterator for Lexer<'a, R> {
-    type Item = Locatable<'a, Result<Token, LexicalError>>;
 
-    fn next(&mut self) -> Option<Self::Item> {
-        self.skip_whitespace();
-
-        let c = self.next_char()?;
-        let location = self.location.clone();
-
-        let data = match c {
+    /// 从第一个字符 c 开始识别一个记号
+    fn lex_token(&mut self, c: char) -> Result<Token, LexicalError> {
+        match c {
             '0'..='9' => {
                 self.unput(Some(c));
                 self.parse_number()
@@ -391,10 +524,13 @@ impl<'a, R: Read> Iterator for Lexer<'a, R> {
                         Keyword::Return => Ok(Token::Return),
                         Keyword::As => Ok(Token::As),
                         Keyword::Use => Ok(Token::Use),
+                        Keyword::Mod => Ok(Token::Mod),
                         Keyword::Pub => Ok(Token::Pub),
                         Keyword::Enum => Ok(Token::Enum),
                         Keyword::Struct => Ok(Token::Struct),
                         Keyword::Trait => Ok(Token::Trait),
+                        Keyword::Type => Ok(Token::Type),
+                        Keyword::Where => Ok(Token::Where),
                         Keyword::True => Ok(Token::True),
                         Keyword::False => Ok(Token::False),
                         Keyword::Async => Ok(Token::Async),
@@ -462,56 +598,59 @@ impl<'a, R: Read> Iterator for Lexer<'a, R> {
             }
             '&' => {
                 // 检查是否是 &mut
-                let peek_location = self.location.clone();
                 if self.peek() == Some('m') {
                     self.next_char(); // 消费 'm'
                     if self.peek() == Some('u') {
                         self.next_char(); // 消费 'u'
                         if self.peek() == Some('t') {
                             self.next_char(); // 消费 't'
-                            Ok(Token::MutRef)
+                            if self
+                                .peek()
+                                .is_some_and(|c| c.is_ascii_alphanumeric() || c == '_')
+                            {
+                                // 是 &mutable 这样的标识符，不是 &mut，回退
+                                self.unput(Some('t')); // 回退 't'
+                                self.unput(Some('u')); // 回退 'u'
+                                self.unput(Some('m')); // 回退 'm'
+                                Ok(Token::Ampersand)
+                            } else {
+                                Ok(Token::MutRef)
+                            }
                         } else {
-                            // 不是 &mut，回退并检查是否是 &&
-                            self.unput(Some('t')); // 回退 't'
+                            // 不是 &mut，回退
                             self.unput(Some('u')); // 回退 'u'
                             self.unput(Some('m')); // 回退 'm'
-                            self.location = peek_location;
-                            if self.peek() == Some('&') {
-                                self.next_char(); // 消费第二个 '&'
-                                Ok(Token::And)
-                            } else {
-                                Ok(Token::Ampersand)
-                            }
+                            Ok(Token::Ampersand)
                         }
                     } else {
-                        // 不是 &mut，回退并检查是否是 &&
-                        self.unput(Some('u')); // 回退 'u'
+                        // 不是 &mut，回退
                         self.unput(Some('m')); // 回退 'm'
-                        self.location = peek_location;
-                        if self.peek() == Some('&') {
-                            self.next_char(); // 消费第二个 '&'
-                            Ok(Token::And)
-                        } else {
-                            Ok(Token::Ampersand)
-                        }
+                        Ok(Token::Ampersand)
                     }
                 } else if self.peek() == Some('&') {
                     // 是 &&
                     self.next_char(); // 消费第二个 '&'
                     Ok(Token::And)
+                } else if self.peek() == Some('=') {
+                    // 是 &=
+                    self.next_char(); // 消费 '='
+                    Ok(Token::AmpersandEqual)
                 } else {
                     // 单独的 &
                     Ok(Token::Ampersand)
                 }
             }
-            '|' => {
-                if self.peek() == Some('|') {
+            '|' => match self.peek() {
+                Some('|') => {
                     self.next_char();
                     Ok(Token::Or)
-                } else {
-                    Ok(Token::Pipe)
                 }
-            }
+                Some('=') => {
+                    self.next_char();
+                    Ok(Token::PipeEqual)
+                }
+                _ => Ok(Token::Pipe),
+            },
             '^' => {
                 if self.peek() == Some('=') {
                     self.next_char();
@@ -595,8 +734,14 @@ impl<'a, R: Read> Iterator for Lexer<'a, R> {
             '#' => Ok(Token::Hash),
             '$' => Ok(Token::Dollar),
             _ => Err(LexicalError::UnknownCharacter(c)),
-        };
+        }
+    }
+}
 
-        Some(Self::Item { location, data })
+impl<'a, R: Read> Iterator for Lexer<'a, R> {
+    type Item = Locatable<'a, Result<Token, LexicalError>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_raw().map(|raw| raw.token)
     }
 }
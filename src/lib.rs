@@ -33,9 +33,20 @@
 //! assert!(tokens[4].data.as_ref().unwrap() == &Token::Semicolon);
 //! ```
 
+pub mod ast;
 pub mod data;
+pub mod fold;
+pub mod format;
+pub mod intern;
 pub mod lex;
+pub mod module;
+pub mod operator;
 pub mod parser;
+pub mod printer;
+pub mod resolve;
+pub mod sexp;
+pub mod syntax;
+pub mod visit;
 
 #[cfg(test)]
 mod tests {
@@ -1,16 +1,28 @@
 use std::env;
+use std::fs;
 use std::fs::File;
-use std::io::{self, BufReader};
+use std::io::{self, BufReader, Read};
 use std::process;
 
-use lex::Lexer;
-
-mod data;
-mod lex;
+use rus::format::{FormatOptions, format_source};
+use rus::lex::Lexer;
+use rus::parser::Parser;
+use rus::sexp::{dump_program, dump_tokens};
 
 fn main() {
     let args: Vec<String> = env::args().collect();
 
+    // 格式化子命令
+    if args.len() > 1 && args[1] == "fmt" {
+        format_command(&args[0], &args[2..]);
+        return;
+    }
+    // 输出记号流或语法树
+    if args.len() > 1 && args[1] == "dump" {
+        dump_command(&args[0], &args[2..]);
+        return;
+    }
+
     match args.len() {
         // 没有参数，从标准输入读取
         1 => {
@@ -58,7 +70,170 @@ fn main() {
         // 参数过多
         _ => {
             eprintln!("Usage: {} [filename]", args[0]);
+            eprintln!(
+                "       {} fmt [--check] [--line-width N] [files...]",
+                args[0]
+            );
+            eprintln!(
+                "       {} dump [--tokens] [--sexp | --json] [filename]",
+                args[0]
+            );
+            process::exit(1);
+        }
+    }
+}
+
+/// `rus fmt [--check] [--line-width N] [files...]`
+///
+/// 没有文件时格式化标准输入并写到标准输出；`--check` 只检查不写入，有文件需要格式化时以 1 退出
+fn format_command(program: &str, args: &[String]) {
+    let mut check = false;
+    let mut options = FormatOptions::default();
+    let mut files = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--check" => check = true,
+            "--line-width" => match args.next().and_then(|width| width.parse().ok()) {
+                Some(width) => options.line_width = width,
+                None => {
+                    eprintln!(
+                        "Usage: {} fmt [--check] [--line-width N] [files...]",
+                        program
+                    );
+                    process::exit(1);
+                }
+            },
+            _ => files.push(arg.clone()),
+        }
+    }
+
+    if files.is_empty() {
+        let mut source = String::new();
+        if let Err(e) = io::stdin().read_to_string(&mut source) {
+            eprintln!("Failed to read stdin: {}", e);
+            process::exit(1);
+        }
+        let formatted = format_or_exit("<stdin>", &source, &options);
+        if check {
+            if formatted != source {
+                eprintln!("Would reformat <stdin>");
+                process::exit(1);
+            }
+        } else {
+            print!("{}", formatted);
+        }
+        return;
+    }
+
+    let mut unformatted = false;
+    for file in &files {
+        let source = match fs::read_to_string(file) {
+            Ok(source) => source,
+            Err(e) => {
+                eprintln!("Failed to open file '{}': {}", file, e);
+                process::exit(1);
+            }
+        };
+        let formatted = format_or_exit(file, &source, &options);
+        if formatted == source {
+            continue;
+        }
+        if check {
+            println!("Would reformat {}", file);
+            unformatted = true;
+        } else if let Err(e) = fs::write(file, formatted) {
+            eprintln!("Failed to write file '{}': {}", file, e);
+            process::exit(1);
+        }
+    }
+    if unformatted {
+        process::exit(1);
+    }
+}
+
+fn format_or_exit(file: &str, source: &str, options: &FormatOptions) -> String {
+    match format_source(file, source, options) {
+        Ok(formatted) => formatted,
+        Err(e) => {
+            eprintln!("Error at {}", e);
             process::exit(1);
         }
     }
 }
+
+/// `rus dump [--tokens] [--sexp | --json] [filename]`
+///
+/// 输出文件（没有文件名时是标准输入）的语法树，`--tokens` 输出记号流。
+/// 默认输出 S 表达式，`--json` 需要启用 `serde` 特性
+fn dump_command(program: &str, args: &[String]) {
+    let usage = || {
+        eprintln!(
+            "Usage: {} dump [--tokens] [--sexp | --json] [filename]",
+            program
+        );
+        process::exit(1);
+    };
+    let mut tokens = false;
+    let mut json = false;
+    let mut filename = None;
+    for arg in args {
+        match arg.as_str() {
+            "--tokens" => tokens = true,
+            "--json" => json = true,
+            "--sexp" => json = false,
+            _ if filename.is_none() => filename = Some(arg.clone()),
+            _ => usage(),
+        }
+    }
+    if json && !cfg!(feature = "serde") {
+        eprintln!("JSON output requires building with `--features serde`");
+        process::exit(1);
+    }
+
+    let source = match &filename {
+        Some(filename) => fs::read_to_string(filename).unwrap_or_else(|e| {
+            eprintln!("Failed to open file '{}': {}", filename, e);
+            process::exit(1);
+        }),
+        None => {
+            let mut source = String::new();
+            if let Err(e) = io::stdin().read_to_string(&mut source) {
+                eprintln!("Failed to read stdin: {}", e);
+                process::exit(1);
+            }
+            source
+        }
+    };
+    let filename = filename.as_deref().unwrap_or("<stdin>");
+    let lexer = Lexer::new(filename, BufReader::new(source.as_bytes()));
+
+    if tokens {
+        if json {
+            #[cfg(feature = "serde")]
+            println!("{}", to_json(&lexer.collect::<Vec<_>>()));
+        } else {
+            print!("{}", dump_tokens(lexer));
+        }
+        return;
+    }
+
+    let statements = match Parser::new(lexer).parse() {
+        Ok(statements) => statements,
+        Err(e) => {
+            eprintln!("Error at {}", e);
+            process::exit(1);
+        }
+    };
+    if json {
+        #[cfg(feature = "serde")]
+        println!("{}", to_json(&statements));
+    } else {
+        print!("{}", dump_program(&statements));
+    }
+}
+
+#[cfg(feature = "serde")]
+fn to_json<T: serde::Serialize>(value: &T) -> String {
+    serde_json::to_string_pretty(value).expect("syntax trees always serialize")
+}
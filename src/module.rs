@@ -0,0 +1,210 @@
+//! 模块加载模块
+//!
+//! `mod name;` 声明的模块内容位于其他文件中，语法分析阶段只记录模块名。
+//! 本模块按照与Rust相同的规则找到对应的文件，解析后填入 `ModuleBody::File`：
+//! - 入口文件和 `mod.<ext>` 文件中的声明在文件所在的目录下查找 `name.<ext>` 或 `name/mod.<ext>`
+//! - 其他文件 `dir/a.<ext>` 中的声明在 `dir/a/` 目录下查找
+//! - 内联模块 `mod a { mod b; }` 中的声明在 `a/` 子目录下查找
+//!
+//! 模块文件的扩展名与入口文件相同。
+
+use crate::ast::assign_node_ids;
+use crate::data::Span;
+use crate::lex::Lexer;
+use crate::parser::{ModuleBody, ParseError, Parser, Stmt};
+use std::fmt;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+
+/// 入口文件没有扩展名时使用的默认扩展名
+pub const DEFAULT_EXTENSION: &str = "rus";
+
+/// 模块加载错误
+#[derive(Debug, PartialEq)]
+pub enum ModuleError {
+    /// 找不到模块对应的文件，candidates 是查找过的路径
+    NotFound {
+        span: Span,
+        module: String,
+        candidates: Vec<String>,
+    },
+    /// name.<ext> 和 name/mod.<ext> 同时存在
+    Ambiguous {
+        span: Span,
+        module: String,
+        candidates: Vec<String>,
+    },
+    /// 模块文件无法读取
+    Io {
+        span: Span,
+        path: String,
+        message: String,
+    },
+    /// 模块文件中的词法或语法错误，位置在模块文件中
+    Parse { error: ParseError },
+}
+
+impl fmt::Display for ModuleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ModuleError::NotFound {
+                span,
+                module,
+                candidates,
+            } => write!(
+                f,
+                "{}:{}:{}: File for module '{}' not found, expected one of: {}",
+                span.file,
+                span.line,
+                span.column,
+                module,
+                candidates.join(", ")
+            ),
+            ModuleError::Ambiguous {
+                span,
+                module,
+                candidates,
+            } => write!(
+                f,
+                "{}:{}:{}: File for module '{}' found at both {}",
+                span.file,
+                span.line,
+                span.column,
+                module,
+                candidates.join(" and ")
+            ),
+            ModuleError::Io {
+                span,
+                path,
+                message,
+            } => write!(
+                f,
+                "{}:{}:{}: Failed to read module file '{}': {}",
+                span.file, span.line, span.column, path, message
+            ),
+            ModuleError::Parse { error } => write!(f, "{}", error),
+        }
+    }
+}
+
+/// 加载 file 中 `mod name;` 声明的模块，包括模块文件中再次声明的子模块
+///
+/// file 是入口文件的路径，statements 是它的语法分析结果。
+/// 加载失败的模块保持为空，错误作为列表返回。
+/// 加载后整棵语法树重新编号，各文件中的节点编号互不相同
+pub fn load_modules(statements: &mut [Stmt], file: &Path) -> Vec<ModuleError> {
+    let directory = file.parent().unwrap_or(Path::new(""));
+    let extension = file
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .unwrap_or(DEFAULT_EXTENSION);
+
+    let mut loader = ModuleLoader {
+        extension,
+        errors: Vec::new(),
+    };
+    loader.load_in_directory(statements, directory);
+    assign_node_ids(statements);
+    loader.errors
+}
+
+/// 在目录树中查找并解析模块文件
+struct ModuleLoader<'e> {
+    extension: &'e str,
+    errors: Vec<ModuleError>,
+}
+
+impl ModuleLoader<'_> {
+    /// 加载 statements 中声明的模块，模块文件在 directory 下查找
+    fn load_in_directory(&mut self, statements: &mut [Stmt], directory: &Path) {
+        for statement in statements {
+            let Stmt::Module {
+                span,
+                declaration,
+                ..
+            } = statement
+            else {
+                continue;
+            };
+            let span = *span;
+
+            let name = &declaration.name;
+            match &mut declaration.body {
+                ModuleBody::Inline(items) => {
+                    self.load_in_directory(items, &directory.join(name.as_str()))
+                }
+                // 已经加载过的模块不再重复加载
+                ModuleBody::File { path: Some(_), .. } => {}
+                ModuleBody::File { path, items } => {
+                    if let Some((file, nested_directory)) =
+                        self.find_file(name, directory, span)
+                        && let Some(mut parsed) = self.parse_file(&file, span)
+                    {
+                        self.load_in_directory(&mut parsed, &nested_directory);
+                        *path = Some(file.display().to_string());
+                        *items = parsed;
+                    }
+                }
+            }
+        }
+    }
+
+    /// 查找模块文件，返回文件路径和其子模块所在的目录
+    fn find_file(
+        &mut self,
+        name: &str,
+        directory: &Path,
+        span: Span,
+    ) -> Option<(PathBuf, PathBuf)> {
+        let flat = directory.join(format!("{}.{}", name, self.extension));
+        let nested = directory.join(name).join(format!("mod.{}", self.extension));
+        let candidates = || vec![flat.display().to_string(), nested.display().to_string()];
+
+        match (flat.is_file(), nested.is_file()) {
+            (true, false) => Some((flat.clone(), directory.join(name))),
+            (false, true) => Some((nested.clone(), directory.join(name))),
+            (found_flat, _) => {
+                let error = if found_flat {
+                    ModuleError::Ambiguous {
+                        span,
+                        module: name.to_string(),
+                        candidates: candidates(),
+                    }
+                } else {
+                    ModuleError::NotFound {
+                        span,
+                        module: name.to_string(),
+                        candidates: candidates(),
+                    }
+                };
+                self.errors.push(error);
+                None
+            }
+        }
+    }
+
+    /// 对模块文件进行词法分析和语法分析
+    fn parse_file(&mut self, file: &Path, span: Span) -> Option<Vec<Stmt>> {
+        let path = file.display().to_string();
+        let source = match File::open(file) {
+            Ok(source) => source,
+            Err(error) => {
+                self.errors.push(ModuleError::Io {
+                    span,
+                    path,
+                    message: error.to_string(),
+                });
+                return None;
+            }
+        };
+
+        match Parser::new(Lexer::new(&path, BufReader::new(source))).parse() {
+            Ok(statements) => Some(statements),
+            Err(error) => {
+                self.errors.push(ModuleError::Parse { error });
+                None
+            }
+        }
+    }
+}
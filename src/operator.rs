@@ -0,0 +1,196 @@
+//! 运算符表模块
+//!
+//! 所有表达式运算符的绑定强度、结合性和位置（前缀、中缀、后缀）集中在 `OPERATORS` 一张表中，
+//! 语法分析器的 Pratt 算法和语法树打印都按这张表决定如何结合和何时加括号，与 Rust 保持一致：
+//!
+//! | 绑定强度 | 运算符                            | 位置 | 结合性   |
+//! |----------|-----------------------------------|------|----------|
+//! | 14       | 调用 `f()`、索引 `a[i]`、`.` `?`  | 后缀 | 左结合   |
+//! | 13       | `-` `!` `*` `&` `&mut` `&&`       | 前缀 | 右结合   |
+//! | 12       | `as`                              | 中缀 | 左结合   |
+//! | 11       | `*` `/` `%`                       | 中缀 | 左结合   |
+//! | 10       | `+` `-`                           | 中缀 | 左结合   |
+//! | 9        | `<<` `>>`                         | 中缀 | 左结合   |
+//! | 8        | `&`                               | 中缀 | 左结合   |
+//! | 7        | `^`                               | 中缀 | 左结合   |
+//! | 6        | `\|`                              | 中缀 | 左结合   |
+//! | 5        | `==` `!=` `<` `<=` `>` `>=`       | 中缀 | 不可结合 |
+//! | 4        | `&&`                              | 中缀 | 左结合   |
+//! | 3        | `\|\|`                            | 中缀 | 左结合   |
+//! | 2        | `..` `..=`                        | 前缀和中缀 | 不可结合 |
+//! | 1        | `=` `+=` `-=` ... `<<=` `>>=`     | 中缀 | 右结合   |
+//!
+//! 数值越大结合越紧。左结合的中缀运算符的右侧以高一级的绑定强度解析，右结合的以相同的绑定强度解析；
+//! 不可结合的运算符不能连用（`a < b < c`、`a..b..c` 是语法错误）
+
+use crate::data::Token;
+use crate::parser::{AssignmentOperator, BinaryOperator, UnaryOperator};
+
+pub(crate) const PRECEDENCE_ASSIGNMENT: u8 = 1;
+pub(crate) const PRECEDENCE_RANGE: u8 = 2;
+pub(crate) const PRECEDENCE_OR: u8 = 3;
+pub(crate) const PRECEDENCE_AND: u8 = 4;
+pub(crate) const PRECEDENCE_COMPARISON: u8 = 5;
+pub(crate) const PRECEDENCE_BIT_OR: u8 = 6;
+pub(crate) const PRECEDENCE_BIT_XOR: u8 = 7;
+pub(crate) const PRECEDENCE_BIT_AND: u8 = 8;
+pub(crate) const PRECEDENCE_SHIFT: u8 = 9;
+pub(crate) const PRECEDENCE_ADDITIVE: u8 = 10;
+pub(crate) const PRECEDENCE_MULTIPLICATIVE: u8 = 11;
+pub(crate) const PRECEDENCE_CAST: u8 = 12;
+pub(crate) const PRECEDENCE_PREFIX: u8 = 13;
+pub(crate) const PRECEDENCE_POSTFIX: u8 = 14;
+
+/// 运算符相对于操作数的位置
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fixity {
+    Prefix,
+    Infix,
+    Postfix,
+}
+
+/// 运算符的结合性
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Associativity {
+    Left,
+    Right,
+    /// 不能连用
+    None,
+}
+
+/// 运算符构造的表达式
+#[derive(Debug, Clone, PartialEq)]
+pub enum OperatorKind {
+    Binary(BinaryOperator),
+    Assign(AssignmentOperator),
+    /// 前缀 `&` 后面跟着 `mut` 时是 `&mut`
+    Unary(UnaryOperator),
+    /// 前缀位置的 `&&` 是两次借用：`&&x` 等价于 `&(&x)`
+    DoubleReference,
+    Range {
+        inclusive: bool,
+    },
+    /// `x as T`，右侧是类型
+    Cast,
+    /// 调用 `f(x)`
+    Call,
+    /// 索引 `a[i]`
+    Index,
+    /// `.` 之后的字段 `a.b`、元组字段 `t.0`、方法调用 `a.b()` 或 `.await`
+    Member,
+    /// `a?`
+    Try,
+}
+
+/// 运算符表中的一项
+#[derive(Debug, Clone, PartialEq)]
+pub struct Operator {
+    pub token: Token,
+    pub fixity: Fixity,
+    pub binding_power: u8,
+    pub associativity: Associativity,
+    pub kind: OperatorKind,
+}
+
+impl Operator {
+    /// 运算符右侧（前缀运算符的操作数）解析时要求的最低绑定强度
+    pub fn right_binding_power(&self) -> u8 {
+        match self.associativity {
+            Associativity::Right => self.binding_power,
+            Associativity::Left | Associativity::None => self.binding_power + 1,
+        }
+    }
+
+    /// 运算符左侧的操作数要求的最低绑定强度，左结合时可以是同一级的运算
+    pub fn left_binding_power(&self) -> u8 {
+        match self.associativity {
+            Associativity::Left => self.binding_power,
+            Associativity::Right | Associativity::None => self.binding_power + 1,
+        }
+    }
+}
+
+macro_rules! operators {
+    ($($token:ident $fixity:ident $binding_power:ident $associativity:ident $kind:expr;)*) => {
+        &[$(Operator {
+            token: Token::$token,
+            fixity: Fixity::$fixity,
+            binding_power: $binding_power,
+            associativity: Associativity::$associativity,
+            kind: $kind,
+        }),*]
+    };
+}
+
+/// 全部运算符
+pub static OPERATORS: &[Operator] = operators! {
+    LParen         Postfix PRECEDENCE_POSTFIX        Left  OperatorKind::Call;
+    LBracket       Postfix PRECEDENCE_POSTFIX        Left  OperatorKind::Index;
+    Dot            Postfix PRECEDENCE_POSTFIX        Left  OperatorKind::Member;
+    Question       Postfix PRECEDENCE_POSTFIX        Left  OperatorKind::Try;
+
+    Minus          Prefix  PRECEDENCE_PREFIX         Right OperatorKind::Unary(UnaryOperator::Negate);
+    Bang           Prefix  PRECEDENCE_PREFIX         Right OperatorKind::Unary(UnaryOperator::Not);
+    Star           Prefix  PRECEDENCE_PREFIX         Right OperatorKind::Unary(UnaryOperator::Dereference);
+    Ampersand      Prefix  PRECEDENCE_PREFIX         Right OperatorKind::Unary(UnaryOperator::Reference);
+    MutRef         Prefix  PRECEDENCE_PREFIX         Right OperatorKind::Unary(UnaryOperator::MutableReference);
+    And            Prefix  PRECEDENCE_PREFIX         Right OperatorKind::DoubleReference;
+
+    As             Infix   PRECEDENCE_CAST           Left  OperatorKind::Cast;
+
+    Star           Infix   PRECEDENCE_MULTIPLICATIVE Left  OperatorKind::Binary(BinaryOperator::Multiply);
+    Slash          Infix   PRECEDENCE_MULTIPLICATIVE Left  OperatorKind::Binary(BinaryOperator::Divide);
+    Percent        Infix   PRECEDENCE_MULTIPLICATIVE Left  OperatorKind::Binary(BinaryOperator::Modulo);
+
+    Plus           Infix   PRECEDENCE_ADDITIVE       Left  OperatorKind::Binary(BinaryOperator::Add);
+    Minus          Infix   PRECEDENCE_ADDITIVE       Left  OperatorKind::Binary(BinaryOperator::Subtract);
+
+    Shl            Infix   PRECEDENCE_SHIFT          Left  OperatorKind::Binary(BinaryOperator::Shl);
+    Shr            Infix   PRECEDENCE_SHIFT          Left  OperatorKind::Binary(BinaryOperator::Shr);
+
+    Ampersand      Infix   PRECEDENCE_BIT_AND        Left  OperatorKind::Binary(BinaryOperator::BitAnd);
+    Caret          Infix   PRECEDENCE_BIT_XOR        Left  OperatorKind::Binary(BinaryOperator::BitXor);
+    Pipe           Infix   PRECEDENCE_BIT_OR         Left  OperatorKind::Binary(BinaryOperator::BitOr);
+
+    EqualEqual     Infix   PRECEDENCE_COMPARISON     None  OperatorKind::Binary(BinaryOperator::Equal);
+    BangEqual      Infix   PRECEDENCE_COMPARISON     None  OperatorKind::Binary(BinaryOperator::NotEqual);
+    Less           Infix   PRECEDENCE_COMPARISON     None  OperatorKind::Binary(BinaryOperator::Less);
+    LessEqual      Infix   PRECEDENCE_COMPARISON     None  OperatorKind::Binary(BinaryOperator::LessEqual);
+    Greater        Infix   PRECEDENCE_COMPARISON     None  OperatorKind::Binary(BinaryOperator::Greater);
+    GreaterEqual   Infix   PRECEDENCE_COMPARISON     None  OperatorKind::Binary(BinaryOperator::GreaterEqual);
+
+    And            Infix   PRECEDENCE_AND            Left  OperatorKind::Binary(BinaryOperator::And);
+    Or             Infix   PRECEDENCE_OR             Left  OperatorKind::Binary(BinaryOperator::Or);
+
+    Range          Prefix  PRECEDENCE_RANGE          None  OperatorKind::Range { inclusive: false };
+    RangeInclusive Prefix  PRECEDENCE_RANGE          None  OperatorKind::Range { inclusive: true };
+    Range          Infix   PRECEDENCE_RANGE          None  OperatorKind::Range { inclusive: false };
+    RangeInclusive Infix   PRECEDENCE_RANGE          None  OperatorKind::Range { inclusive: true };
+
+    Equal          Infix   PRECEDENCE_ASSIGNMENT     Right OperatorKind::Assign(AssignmentOperator::Assign);
+    PlusEqual      Infix   PRECEDENCE_ASSIGNMENT     Right OperatorKind::Assign(AssignmentOperator::AddAssign);
+    MinusEqual     Infix   PRECEDENCE_ASSIGNMENT     Right OperatorKind::Assign(AssignmentOperator::SubtractAssign);
+    StarEqual      Infix   PRECEDENCE_ASSIGNMENT     Right OperatorKind::Assign(AssignmentOperator::MultiplyAssign);
+    SlashEqual     Infix   PRECEDENCE_ASSIGNMENT     Right OperatorKind::Assign(AssignmentOperator::DivideAssign);
+    PercentEqual   Infix   PRECEDENCE_ASSIGNMENT     Right OperatorKind::Assign(AssignmentOperator::ModuloAssign);
+    AmpersandEqual Infix   PRECEDENCE_ASSIGNMENT     Right OperatorKind::Assign(AssignmentOperator::BitAndAssign);
+    PipeEqual      Infix   PRECEDENCE_ASSIGNMENT     Right OperatorKind::Assign(AssignmentOperator::BitOrAssign);
+    CaretEqual     Infix   PRECEDENCE_ASSIGNMENT     Right OperatorKind::Assign(AssignmentOperator::BitXorAssign);
+    ShlEqual       Infix   PRECEDENCE_ASSIGNMENT     Right OperatorKind::Assign(AssignmentOperator::ShlAssign);
+    ShrEqual       Infix   PRECEDENCE_ASSIGNMENT     Right OperatorKind::Assign(AssignmentOperator::ShrAssign);
+};
+
+/// 记号在给定位置上作为运算符时的表项
+pub fn lookup(token: &Token, fixity: Fixity) -> Option<&'static Operator> {
+    OPERATORS
+        .iter()
+        .find(|operator| operator.fixity == fixity && operator.token == *token)
+}
+
+/// 二元运算符的表项
+pub fn binary(operator: &BinaryOperator) -> &'static Operator {
+    OPERATORS
+        .iter()
+        .find(|entry| matches!(&entry.kind, OperatorKind::Binary(kind) if kind == operator))
+        .expect("every binary operator is in the operator table")
+}
@@ -3,26 +3,139 @@
 //! 将词法分析器生成的Token流转换为抽象语法树(AST)
 //! 实现递归下降解析算法和Pratt解析算法处理运算符优先级
 
-use crate::data::{Locatable, Token};
+use crate::ast::{NodeId, assign_node_ids};
+use crate::data::{LexicalError, Locatable, Location, Span, Token};
+use crate::intern::Symbol;
+use crate::operator::{self, Associativity, Fixity, Operator, OperatorKind};
+use crate::syntax::SyntaxKind;
+use std::collections::VecDeque;
 use std::fmt;
 
-/// 解析错误类型
-#[derive(Debug, PartialEq)]
-pub enum ParseError {
-    /// 意外的Token
-    UnexpectedToken(String),
-    /// 缺少必需的Token
-    MissingToken(String),
-    /// 无效的表达式
-    InvalidExpression,
+/// 解析错误：出错的位置、实际遇到的记号和该位置可以接受的记号，错误信息由这些数据生成
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ParseError {
+    pub kind: ParseErrorKind,
+    /// 出错的区间，覆盖 found 记号；输入结束时是最后一个记号之后的空区间
+    pub span: Span,
+    /// 实际遇到的记号，None 表示输入已结束
+    pub found: Option<Token>,
+    /// 该位置可以接受的记号或语法成分，按尝试的顺序排列
+    pub expected: Vec<Expected>,
 }
 
-impl fmt::Display for ParseError {
+/// 解析错误的种类
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum ParseErrorKind {
+    /// 遇到的记号不在期望的集合中
+    UnexpectedToken,
+    /// 赋值运算符（found）左侧不是可赋值的位置
+    InvalidAssignmentTarget,
+    /// 比较运算符被链式使用：previous 是前一个比较运算符，found 是当前的
+    ChainedComparison { previous: BinaryOperator },
+    /// 范围运算符被链式使用
+    ChainedRange,
+    /// 处理器中重复的 return 或 finally 子句（found）
+    DuplicateClause,
+    /// 契约子句（found）不能用在这种声明上
+    ClauseNotAllowed(ContractOwner),
+    /// 行变量只能出现在函数的效果列表中
+    RowVariableNotAllowed,
+    /// 效果行中出现了第二个行变量
+    DuplicateRowVariable,
+    /// 枚举变体的字段不能带可见性修饰
+    VisibilityNotAllowed,
+    /// 记号流中的词法错误，span 是出错的位置
+    Lexical(LexicalError),
+}
+
+/// 解析错误中期望出现的内容
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum Expected {
+    /// 某个具体的记号
+    Token(Token),
+    /// 任意标识符
+    Identifier,
+    /// 一类语法成分，例如 "expression"、"type"、"function name"
+    Syntax(&'static str),
+}
+
+impl fmt::Display for Expected {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            ParseError::UnexpectedToken(token) => write!(f, "Unexpected token: {}", token),
-            ParseError::MissingToken(token) => write!(f, "Missing token: {}", token),
-            ParseError::InvalidExpression => write!(f, "Invalid expression"),
+            Expected::Token(token) => write!(f, "`{}`", token),
+            Expected::Identifier => write!(f, "identifier"),
+            Expected::Syntax(description) => write!(f, "{}", description),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}:{}: ",
+            self.span.file, self.span.line, self.span.column
+        )?;
+
+        let found = match &self.found {
+            Some(token) => format!("`{}`", token),
+            None => "end of input".to_string(),
+        };
+        match &self.kind {
+            ParseErrorKind::UnexpectedToken => match self.expected.as_slice() {
+                [] => write!(f, "unexpected {}", found),
+                [expected] => write!(f, "expected {}, found {}", expected, found),
+                [init @ .., last] => {
+                    let init: Vec<String> = init.iter().map(Expected::to_string).collect();
+                    let separator = if init.len() == 1 { " or " } else { ", or " };
+                    write!(
+                        f,
+                        "expected {}{}{}, found {}",
+                        init.join(", "),
+                        separator,
+                        last,
+                        found
+                    )
+                }
+            },
+            ParseErrorKind::InvalidAssignmentTarget => {
+                write!(f, "left-hand side of {} must be an assignable place", found)
+            }
+            ParseErrorKind::ChainedComparison { previous } => {
+                let current = self.found.as_ref().map(Token::lexeme).unwrap_or_default();
+                write!(
+                    f,
+                    "comparison operators cannot be chained, write `(a {} b) && (b {} c)` instead",
+                    previous, current
+                )
+            }
+            ParseErrorKind::ChainedRange => write!(
+                f,
+                "range operators are non-associative, use parentheses to group them"
+            ),
+            ParseErrorKind::DuplicateClause => {
+                write!(f, "handler already has a {} clause", found)
+            }
+            ParseErrorKind::ClauseNotAllowed(owner) => {
+                let owner = match owner {
+                    ContractOwner::Function => "functions",
+                    ContractOwner::Type => "type declarations",
+                };
+                write!(f, "{} clauses are not allowed on {}", found, owner)
+            }
+            ParseErrorKind::RowVariableNotAllowed => {
+                write!(f, "row variables are only allowed in function effect lists")
+            }
+            ParseErrorKind::DuplicateRowVariable => {
+                write!(f, "an effect row can have only one row variable")
+            }
+            ParseErrorKind::VisibilityNotAllowed => {
+                write!(f, "enum variant fields cannot have visibility modifiers")
+            }
+            ParseErrorKind::Lexical(error) => write!(f, "{}", error),
         }
     }
 }
@@ -30,11 +143,12 @@ impl fmt::Display for ParseError {
 /// AST节点基本特质
 pub trait AstNode {
     /// 获取节点在源代码中的位置信息
-    fn location(&self) -> &crate::data::Location;
+    fn location(&self) -> &crate::data::Location<'_>;
 }
 
 /// 字面量表达式
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum Literal {
     Integer(String),
     Float(String),
@@ -45,6 +159,7 @@ pub enum Literal {
 
 /// 二元操作符
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum BinaryOperator {
     // 算术操作符
     Add,      // +
@@ -64,812 +179,2950 @@ pub enum BinaryOperator {
     // 逻辑操作符
     And, // &&
     Or,  // ||
+
+    // 位操作符
+    BitAnd, // &
+    BitOr,  // |
+    BitXor, // ^
+    Shl,    // <<
+    Shr,    // >>
+}
+
+impl BinaryOperator {
+    /// 是否是比较操作符（比较操作符不可结合，不能链式使用）
+    pub fn is_comparison(&self) -> bool {
+        matches!(
+            self,
+            BinaryOperator::Equal
+                | BinaryOperator::NotEqual
+                | BinaryOperator::Less
+                | BinaryOperator::LessEqual
+                | BinaryOperator::Greater
+                | BinaryOperator::GreaterEqual
+        )
+    }
+
+    /// 操作符的优先级，数值越大结合越紧（见 `operator` 模块中的运算符表）
+    pub(crate) fn precedence(&self) -> u8 {
+        operator::binary(self).binding_power
+    }
+}
+
+impl fmt::Display for BinaryOperator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let symbol = match self {
+            BinaryOperator::Add => "+",
+            BinaryOperator::Subtract => "-",
+            BinaryOperator::Multiply => "*",
+            BinaryOperator::Divide => "/",
+            BinaryOperator::Modulo => "%",
+            BinaryOperator::Equal => "==",
+            BinaryOperator::NotEqual => "!=",
+            BinaryOperator::Less => "<",
+            BinaryOperator::LessEqual => "<=",
+            BinaryOperator::Greater => ">",
+            BinaryOperator::GreaterEqual => ">=",
+            BinaryOperator::And => "&&",
+            BinaryOperator::Or => "||",
+            BinaryOperator::BitAnd => "&",
+            BinaryOperator::BitOr => "|",
+            BinaryOperator::BitXor => "^",
+            BinaryOperator::Shl => "<<",
+            BinaryOperator::Shr => ">>",
+        };
+        write!(f, "{}", symbol)
+    }
+}
+
+/// 赋值操作符
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum AssignmentOperator {
+    Assign,         // =
+    AddAssign,      // +=
+    SubtractAssign, // -=
+    MultiplyAssign, // *=
+    DivideAssign,   // /=
+    ModuloAssign,   // %=
+    BitAndAssign,   // &=
+    BitOrAssign,    // |=
+    BitXorAssign,   // ^=
+    ShlAssign,      // <<=
+    ShrAssign,      // >>=
+}
+
+impl fmt::Display for AssignmentOperator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let symbol = match self {
+            AssignmentOperator::Assign => "=",
+            AssignmentOperator::AddAssign => "+=",
+            AssignmentOperator::SubtractAssign => "-=",
+            AssignmentOperator::MultiplyAssign => "*=",
+            AssignmentOperator::DivideAssign => "/=",
+            AssignmentOperator::ModuloAssign => "%=",
+            AssignmentOperator::BitAndAssign => "&=",
+            AssignmentOperator::BitOrAssign => "|=",
+            AssignmentOperator::BitXorAssign => "^=",
+            AssignmentOperator::ShlAssign => "<<=",
+            AssignmentOperator::ShrAssign => ">>=",
+        };
+        write!(f, "{}", symbol)
+    }
 }
 
 /// 一元操作符
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum UnaryOperator {
-    Negate, // -
-    Not,    // !
+    Negate,           // -
+    Not,              // !
+    Dereference,      // *
+    Reference,        // &
+    MutableReference, // &mut
+}
+
+impl fmt::Display for UnaryOperator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let symbol = match self {
+            UnaryOperator::Negate => "-",
+            UnaryOperator::Not => "!",
+            UnaryOperator::Dereference => "*",
+            UnaryOperator::Reference => "&",
+            UnaryOperator::MutableReference => "&mut",
+        };
+        write!(f, "{}", symbol)
+    }
 }
 
 /// 效果声明中的操作符
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct EffectOperation {
-    pub name: String,
+    pub name: Symbol,
     pub parameters: Vec<(String, String)>, // (参数名, 类型)
     pub return_type: Option<String>,
 }
 
 /// 效果声明
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct EffectDeclaration {
-    pub name: String,
+    pub name: Symbol,
+    pub generics: Vec<GenericParameter>, // effect State<S> 中的 S
     pub operations: Vec<EffectOperation>,
 }
 
 /// 处理器子句
+///
+/// 参数可以带类型标注，由名称解析阶段与效果声明中的操作参数核对。
+/// 比操作声明多出的最后一个参数是续延 (read_file(path, k))，名称解析阶段会把它移入 continuation
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct HandlerClause {
-    pub operation: String,
-    pub parameters: Vec<String>,
+    pub span: Span,
+    pub operation: Symbol,
+    pub parameters: Vec<Parameter>,
+    pub continuation: Option<Symbol>,
+    pub body: Vec<Stmt>,
+}
+
+/// 处理器的 return(x) 子句，变换被处理代码块的最终结果
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ReturnClause {
+    pub parameter: Parameter,
     pub body: Vec<Stmt>,
 }
 
 /// 处理器声明
+///
+/// 具名处理器 (handle FileSystem as LocalFs { ... }) 可以在with表达式和处理器组中按名称引用
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct HandlerDeclaration {
-    pub effect: String,
+    pub name: Option<Symbol>,
+    pub effect: EffectName,
     pub clauses: Vec<HandlerClause>,
+    pub return_clause: Option<ReturnClause>,
+    pub finally_clause: Option<Vec<Stmt>>, // 处理器退出时执行的清理代码
+}
+
+/// with表达式安装的处理器
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum HandlerReference {
+    /// 按名称引用的处理器或处理器组
+    Named(Symbol),
+    /// 内联的匿名处理器 (with handle Log { ... } { ... })
+    Inline(HandlerDeclaration),
 }
 
-/// 效果组声明
+/// 效果组声明 (effect_group Safe = IO, Log - Network;)
+///
+/// effects 中的成员可以是效果或其他效果组，excluded 从整个组中减去。
+/// flattened_effects 在语法分析阶段为空，由名称解析阶段展开嵌套的组后填写
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct EffectGroupDeclaration {
-    pub name: String,
-    pub effects: Vec<String>,
+    pub name: Symbol,
+    pub effects: Vec<EffectName>,
+    pub excluded: Vec<EffectName>,
+    pub flattened_effects: Vec<EffectName>,
+}
+
+/// 函数签名中的效果行 (effects IO - Network, Log, ..e)
+///
+/// 与效果组相同，excluded 从整行中减去；row_variable 是行变量 ..e，用于效果多态。
+/// flattened_effects 由名称解析阶段填写
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct EffectRow {
+    pub effects: Vec<EffectName>,
+    pub excluded: Vec<EffectName>,
+    pub row_variable: Option<Symbol>,
+    pub flattened_effects: Vec<EffectName>,
+}
+
+/// 效果名称，可以带路径和类型实参 (std::io::Console、State<i32>)
+///
+/// 与源代码文本比较时按规范化的写法比较：`State<i32>` 等于 "State<i32>"
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct EffectName {
+    /// 效果或效果组的名称，带路径时包括路径 (std::io::Console)
+    pub name: Symbol,
+    /// 实例化泛型效果的类型实参，每一项都是一个完整的类型
+    pub type_arguments: Vec<String>,
+}
+
+impl EffectName {
+    /// 不带类型实参的效果名称
+    pub fn new(name: impl Into<Symbol>) -> EffectName {
+        EffectName {
+            name: name.into(),
+            type_arguments: Vec::new(),
+        }
+    }
+
+    /// 路径的最后一段：`std::io::Console` 的 `Console`
+    pub fn base_name(&self) -> &str {
+        let name = self.name.as_str();
+        name.rsplit("::").next().unwrap_or(name)
+    }
+}
+
+impl From<&str> for EffectName {
+    fn from(name: &str) -> Self {
+        EffectName::new(name)
+    }
+}
+
+impl fmt::Display for EffectName {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name)?;
+        if !self.type_arguments.is_empty() {
+            write!(f, "<{}>", self.type_arguments.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+impl PartialEq<str> for EffectName {
+    fn eq(&self, other: &str) -> bool {
+        let Some(mut rest) = other.strip_prefix(self.name.as_str()) else {
+            return false;
+        };
+        if self.type_arguments.is_empty() {
+            return rest.is_empty();
+        }
+
+        for (index, argument) in self.type_arguments.iter().enumerate() {
+            let separator = if index == 0 { "<" } else { ", " };
+            match rest
+                .strip_prefix(separator)
+                .and_then(|rest| rest.strip_prefix(argument.as_str()))
+            {
+                Some(remaining) => rest = remaining,
+                None => return false,
+            }
+        }
+        rest == ">"
+    }
+}
+
+impl PartialEq<&str> for EffectName {
+    fn eq(&self, other: &&str) -> bool {
+        *self == **other
+    }
+}
+
+impl PartialEq<String> for EffectName {
+    fn eq(&self, other: &String) -> bool {
+        *self == *other.as_str()
+    }
 }
 
-/// 处理器组声明
+/// 处理器组声明 (handler_group Defaults = ConsoleLogger, OtherGroup;)
+///
+/// handlers 中的成员可以是具名处理器或其他处理器组。
+/// discharged_effects 由名称解析阶段展开嵌套的组后填写
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct HandlerGroupDeclaration {
-    pub name: String,
-    pub handlers: Vec<String>,
+    pub name: Symbol,
+    pub handlers: Vec<Symbol>,
+    pub discharged_effects: Vec<EffectName>,
+}
+
+/// 泛型参数
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct GenericParameter {
+    pub name: Symbol,
+    pub bounds: Vec<String>, // T: Clone + Debug 中的 ["Clone", "Debug"]
+}
+
+/// where子句中的约束 (Vec<T>: Debug)
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct WherePredicate {
+    pub bounded_type: String,
+    pub bounds: Vec<String>,
+}
+
+/// 函数参数
+///
+/// 接收者参数 self、&self 和 &mut self 的类型分别记为 Self、&Self 和 &mut Self
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Parameter {
+    pub name: Symbol,
+    pub type_annotation: Option<String>,
+}
+
+/// 契约子句
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum ContractClause {
+    /// 前置条件 requires expr
+    Requires(Expr),
+    /// 后置条件 ensures expr，条件中可以使用 old(x) 和 result
+    Ensures(Expr),
+    /// 不变式 invariant expr
+    Invariant(Expr),
+    /// 引用具名契约 contract Name
+    Named(Symbol),
+}
+
+/// 具名契约声明 (contract SafeDivision(a, b) { requires b != 0; })
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ContractDeclaration {
+    pub name: Symbol,
+    pub parameters: Vec<Parameter>,
+    pub clauses: Vec<ContractClause>,
+}
+
+/// 函数签名，由函数声明和trait方法共用
+///
+/// is_async 对应 async fn，是执行内置 Async 效果的语法糖，名称解析阶段把 Async 加入展开后的效果行
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct FunctionSignature {
+    pub is_async: bool,
+    pub name: Symbol,
+    pub generics: Vec<GenericParameter>,
+    pub parameters: Vec<Parameter>,
+    pub return_type: Option<String>,
+    pub effects: EffectRow,
+    pub where_clause: Vec<WherePredicate>,
+    pub contracts: Vec<ContractClause>,
+}
+
+/// trait中的成员
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum TraitItem {
+    /// 方法，default_body 为 None 时是必须由实现提供的方法
+    Method {
+        signature: FunctionSignature,
+        default_body: Option<Vec<Stmt>>,
+    },
+    /// 关联类型 (type Output: Clone = i32;)
+    AssociatedType {
+        name: Symbol,
+        bounds: Vec<String>,
+        default: Option<String>,
+    },
+}
+
+/// trait声明
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct TraitDeclaration {
+    pub name: Symbol,
+    pub generics: Vec<GenericParameter>,
+    pub supertraits: Vec<Symbol>,
+    pub where_clause: Vec<WherePredicate>,
+    pub contracts: Vec<ContractClause>, // 不变式和具名契约
+    pub items: Vec<TraitItem>,
+}
+
+/// impl块声明
+///
+/// 固有实现 impl T { ... } 的 trait_name 为 None；
+/// 成员只能是函数声明和关联类型的定义 (Stmt::TypeAlias)
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ImplDeclaration {
+    pub generics: Vec<GenericParameter>,
+    pub trait_name: Option<Symbol>,
+    pub self_type: Symbol,
+    pub where_clause: Vec<WherePredicate>,
+    pub items: Vec<Stmt>,
+}
+
+/// 命名字段声明
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct FieldDeclaration {
+    pub visibility: Visibility,
+    pub name: Symbol,
+    pub field_type: String,
+}
+
+/// 元组字段声明
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct TupleField {
+    pub visibility: Visibility,
+    pub field_type: String,
+}
+
+/// 结构体或枚举变体携带的数据
+///
+/// 结构体的字段可以带可见性，枚举变体的字段总是与枚举相同，可见性为 Private
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum StructFields {
+    /// 命名字段 { a: A, b: B }
+    Named(Vec<FieldDeclaration>),
+    /// 元组字段 (A, B)
+    Tuple(Vec<TupleField>),
+    /// 没有字段
+    Unit,
+}
+
+/// 结构体声明
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct StructDeclaration {
+    pub name: Symbol,
+    pub generics: Vec<GenericParameter>,
+    pub fields: StructFields,
+    pub contracts: Vec<ContractClause>, // 不变式和具名契约
+}
+
+/// 枚举变体
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct EnumVariant {
+    pub name: Symbol,
+    pub fields: StructFields,
+}
+
+/// 枚举声明
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct EnumDeclaration {
+    pub name: Symbol,
+    pub generics: Vec<GenericParameter>,
+    pub variants: Vec<EnumVariant>,
+}
+
+/// 类型别名声明
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct TypeAliasDeclaration {
+    pub name: Symbol,
+    pub generics: Vec<GenericParameter>,
+    pub aliased_type: String,
+}
+
+/// use声明中的导入树
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum UseTree {
+    /// 导入单个名称，可以重命名 (a::b, a::b as c)
+    Simple {
+        path: Vec<Symbol>,
+        alias: Option<Symbol>,
+    },
+    /// 通配导入 (a::*)
+    Glob { path: Vec<Symbol> },
+    /// 分组导入 (a::{b, c as d, e::*})
+    Group {
+        path: Vec<Symbol>,
+        items: Vec<UseTree>,
+    },
+}
+
+impl UseTree {
+    /// 展开为 (本地名称, 完整路径) 列表，通配导入不引入具体名称因而被跳过
+    ///
+    /// 分组中的 `self` 表示分组前缀本身，例如 `a::{self, b}` 引入 `a` 和 `b`
+    pub fn bindings(&self) -> Vec<(Symbol, Vec<Symbol>)> {
+        let mut bindings = Vec::new();
+        self.collect_bindings(&[], &mut bindings);
+        bindings
+    }
+
+    fn collect_bindings(&self, prefix: &[Symbol], bindings: &mut Vec<(Symbol, Vec<Symbol>)>) {
+        match self {
+            UseTree::Simple { path, alias } => {
+                let mut full_path = prefix.to_vec();
+                if path.len() != 1 || path[0] != "self" {
+                    full_path.extend(path.iter().copied());
+                }
+                if let Some(name) = alias.or(full_path.last().copied()) {
+                    bindings.push((name, full_path.clone()));
+                }
+            }
+            UseTree::Glob { .. } => {}
+            UseTree::Group { path, items } => {
+                let mut full_prefix = prefix.to_vec();
+                full_prefix.extend(path.iter().copied());
+                for item in items {
+                    item.collect_bindings(&full_prefix, bindings);
+                }
+            }
+        }
+    }
+}
+
+/// use声明
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct UseDeclaration {
+    pub tree: UseTree,
+}
+
+/// 声明的可见性
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum Visibility {
+    /// 没有修饰：只在声明所在的模块内可见
+    #[default]
+    Private,
+    /// pub(crate)：在整个程序内可见
+    Crate,
+    /// pub：作为公开API对外可见
+    Public,
+}
+
+/// 模块声明
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ModuleDeclaration {
+    pub name: Symbol,
+    pub body: ModuleBody,
+}
+
+/// 模块的内容
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum ModuleBody {
+    /// 内联模块 mod name { ... }
+    Inline(Vec<Stmt>),
+    /// 文件模块 mod name;：语法分析阶段 path 为 None、items 为空，
+    /// 由模块加载器（module模块）找到对应的文件并解析后填写
+    File {
+        path: Option<String>,
+        items: Vec<Stmt>,
+    },
+}
+
+impl ModuleBody {
+    /// 模块中的声明
+    pub fn items(&self) -> &[Stmt] {
+        match self {
+            ModuleBody::Inline(items) | ModuleBody::File { items, .. } => items,
+        }
+    }
+
+    pub fn items_mut(&mut self) -> &mut Vec<Stmt> {
+        match self {
+            ModuleBody::Inline(items) | ModuleBody::File { items, .. } => items,
+        }
+    }
+}
+
+/// 枚举构造表达式携带的数据
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum VariantArguments {
+    /// 单元变体 Shape::Empty
+    Unit,
+    /// 元组变体 Shape::Circle(r)
+    Tuple(Vec<Expr>),
+    /// 结构体变体 Shape::Rect { w: 1, h: 2 }
+    Named(Vec<(Symbol, Expr)>),
+}
+
+/// 闭包捕获变量的方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum CaptureMode {
+    /// 共享借用 &x
+    Borrow,
+    /// 可变借用 &mut x：闭包体中对变量赋值或取 &mut
+    BorrowMut,
+    /// 移动所有权：move 闭包捕获的所有变量
+    Move,
+}
+
+/// 闭包捕获的外部变量
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Capture {
+    pub name: Symbol,
+    pub mode: CaptureMode,
 }
 
 /// 表达式节点
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum Expr {
     /// 字面量
     Literal {
-        location_line: usize,
-        location_column: usize,
-        location_file: String,
+        id: NodeId,
+        span: Span,
         value: Literal,
     },
 
     /// 标识符
     Identifier {
-        location_line: usize,
-        location_column: usize,
-        location_file: String,
-        name: String,
+        id: NodeId,
+        span: Span,
+        name: Symbol,
+    },
+
+    /// 路径表达式 (FileSystem::read_file, std::io::Console)
+    Path {
+        id: NodeId,
+        span: Span,
+        segments: Vec<Symbol>,
     },
 
     /// 二元表达式
     Binary {
-        location_line: usize,
-        location_column: usize,
-        location_file: String,
+        id: NodeId,
+        span: Span,
         left: Box<Expr>,
         operator: BinaryOperator,
         right: Box<Expr>,
     },
 
+    /// 赋值表达式 (x = 1, x += 1)
+    Assign {
+        id: NodeId,
+        span: Span,
+        target: Box<Expr>,
+        operator: AssignmentOperator,
+        value: Box<Expr>,
+    },
+
     /// 一元表达式
     Unary {
-        location_line: usize,
-        location_column: usize,
-        location_file: String,
+        id: NodeId,
+        span: Span,
         operator: UnaryOperator,
         operand: Box<Expr>,
     },
 
+    /// 类型转换表达式 (x as T)
+    Cast {
+        id: NodeId,
+        span: Span,
+        expression: Box<Expr>,
+        target_type: String,
+    },
+
+    /// 范围表达式 (a..b, a..=b, a.., ..b, ..)
+    Range {
+        id: NodeId,
+        span: Span,
+        start: Option<Box<Expr>>,
+        end: Option<Box<Expr>>,
+        inclusive: bool,
+    },
+
     /// 函数调用
     Call {
-        location_line: usize,
-        location_column: usize,
-        location_file: String,
+        id: NodeId,
+        span: Span,
         function: Box<Expr>,
         arguments: Vec<Expr>,
     },
 
     /// 分组表达式 (...)
     Grouping {
-        location_line: usize,
-        location_column: usize,
-        location_file: String,
+        id: NodeId,
+        span: Span,
         expression: Box<Expr>,
     },
 
-    /// 效果操作调用
-    EffectOperation {
-        location_line: usize,
-        location_column: usize,
-        location_file: String,
-        effect: String,
-        operation: String,
-        arguments: Vec<Expr>,
+    /// 元组表达式 (a, b)、(a,)，没有元素时是单元值 ()
+    Tuple {
+        id: NodeId,
+        span: Span,
+        elements: Vec<Expr>,
     },
-}
-
-impl Expr {
-    pub fn location(&self) -> crate::data::Location {
-        match self {
-            Expr::Literal {
-                location_line,
-                location_column,
-                location_file,
-                ..
-            } => crate::data::Location {
-                line: *location_line,
-                column: *location_column,
-                file: location_file.as_str(),
-            },
-            Expr::Identifier {
-                location_line,
-                location_column,
-                location_file,
-                ..
-            } => crate::data::Location {
-                line: *location_line,
-                column: *location_column,
-                file: location_file.as_str(),
-            },
-            Expr::Binary {
-                location_line,
-                location_column,
-                location_file,
-                ..
-            } => crate::data::Location {
-                line: *location_line,
-                column: *location_column,
-                file: location_file.as_str(),
-            },
-            Expr::Unary {
-                location_line,
-                location_column,
-                location_file,
-                ..
-            } => crate::data::Location {
-                line: *location_line,
-                column: *location_column,
-                file: location_file.as_str(),
-            },
-            Expr::Call {
-                location_line,
-                location_column,
-                location_file,
-                ..
-            } => crate::data::Location {
-                line: *location_line,
-                column: *location_column,
-                file: location_file.as_str(),
-            },
-            Expr::Grouping {
-                location_line,
-                location_column,
-                location_file,
-                ..
-            } => crate::data::Location {
-                line: *location_line,
-                column: *location_column,
-                file: location_file.as_str(),
-            },
-            Expr::EffectOperation {
-                location_line,
-                location_column,
-                location_file,
-                ..
-            } => crate::data::Location {
-                line: *location_line,
-                column: *location_column,
-                file: location_file.as_str(),
-            },
-        }
-    }
-}
 
-/// 语句节点
-#[derive(Debug, Clone, PartialEq)]
-pub enum Stmt {
-    /// 表达式语句
-    Expression {
-        location_line: usize,
-        location_column: usize,
-        location_file: String,
-        expression: Expr,
+    /// 数组表达式 [1, 2, 3]
+    Array {
+        id: NodeId,
+        span: Span,
+        elements: Vec<Expr>,
     },
 
-    /// let声明语句
-    Let {
-        location_line: usize,
-        location_column: usize,
-        location_file: String,
-        identifier: String,
-        initializer: Option<Expr>,
+    /// 重复数组表达式 [0; N]
+    ArrayRepeat {
+        id: NodeId,
+        span: Span,
+        value: Box<Expr>,
+        count: Box<Expr>,
     },
 
-    /// var声明语句
-    Var {
-        location_line: usize,
-        location_column: usize,
-        location_file: String,
-        identifier: String,
-        initializer: Option<Expr>,
+    /// async块 async { ... }：延迟执行的计算，执行时可以使用内置 Async 效果。
+    /// effects 在语法分析阶段为空，由名称解析阶段记录内置的 Async 效果
+    Async {
+        id: NodeId,
+        span: Span,
+        statements: Vec<Stmt>,
+        effects: Vec<EffectName>,
     },
 
-    /// 函数声明语句
-    Function {
-        location_line: usize,
-        location_column: usize,
-        location_file: String,
-        name: String,
-        parameters: Vec<String>,
-        body: Vec<Stmt>,
+    /// try块 try { ... }：为块安装内置 Exception 效果的处理器，
+    /// 块正常结束时得到 Ok(值)，块中的 `e?` 传播错误时得到 Err(错误)。
+    /// discharged_effects 在语法分析阶段为空，由名称解析阶段记录被处理的 Exception 效果
+    TryBlock {
+        id: NodeId,
+        span: Span,
+        statements: Vec<Stmt>,
+        discharged_effects: Vec<EffectName>,
     },
 
-    /// 块语句
+    /// 块表达式 { ...; value }，值是最后一条不带分号的表达式语句（见 Stmt::block_tail）
     Block {
-        location_line: usize,
-        location_column: usize,
-        location_file: String,
+        id: NodeId,
+        span: Span,
         statements: Vec<Stmt>,
     },
 
-    /// 效果声明语句
-    Effect {
-        location_line: usize,
-        location_column: usize,
-        location_file: String,
-        declaration: EffectDeclaration,
+    /// 字段访问 (a.b, t.0)
+    Field {
+        id: NodeId,
+        span: Span,
+        object: Box<Expr>,
+        field: Symbol,
     },
 
-    /// 处理器声明语句
-    Handler {
-        location_line: usize,
-        location_column: usize,
-        location_file: String,
-        declaration: HandlerDeclaration,
+    /// 方法调用 (a.b(...))
+    MethodCall {
+        id: NodeId,
+        span: Span,
+        receiver: Box<Expr>,
+        method: Symbol,
+        arguments: Vec<Expr>,
     },
 
-    /// 效果组声明语句
-    EffectGroup {
-        location_line: usize,
-        location_column: usize,
-        location_file: String,
-        declaration: EffectGroupDeclaration,
+    /// 索引表达式 (a[i])
+    Index {
+        id: NodeId,
+        span: Span,
+        object: Box<Expr>,
+        index: Box<Expr>,
     },
 
-    /// 处理器组声明语句
-    HandlerGroup {
-        location_line: usize,
-        location_column: usize,
-        location_file: String,
-        declaration: HandlerGroupDeclaration,
+    /// 错误传播表达式 (a?)
+    Try {
+        id: NodeId,
+        span: Span,
+        expression: Box<Expr>,
     },
-}
 
-impl Stmt {
-    pub fn location(&self) -> crate::data::Location {
-        match self {
+    /// 等待表达式 (a.await)
+    ///
+    /// 是内置 Async 效果的 await 操作的语法糖，名称解析阶段改写为 `Expr::EffectOperation`，
+    /// 因此可以被用户定义的 Async 处理器处理
+    Await {
+        id: NodeId,
+        span: Span,
+        expression: Box<Expr>,
+    },
+
+    /// 结构体字面量 (Point { x: 1, y }, Point { x: 1, ..origin })
+    StructLiteral {
+        id: NodeId,
+        span: Span,
+        path: Vec<Symbol>,
+        fields: Vec<(Symbol, Expr)>, // (字段名, 值)，简写形式 { y } 的值是标识符 y
+        base: Option<Box<Expr>>,
+    },
+
+    /// 枚举构造表达式
+    ///
+    /// 语法分析阶段把枚举构造解析为路径、调用或结构体字面量，
+    /// 名称解析阶段确认路径指向已声明的枚举变体后才改写为此节点
+    EnumConstructor {
+        id: NodeId,
+        span: Span,
+        enum_name: Symbol,
+        variant: Symbol,
+        arguments: VariantArguments,
+    },
+
+    /// with表达式：在安装的处理器下执行代码块
+    ///
+    /// discharged_effects 记录被处理的效果。语法分析阶段只能确定内联处理器的效果，
+    /// 按名称引用的处理器和处理器组由名称解析阶段补全
+    With {
+        id: NodeId,
+        span: Span,
+        handlers: Vec<HandlerReference>,
+        body: Vec<Stmt>,
+        discharged_effects: Vec<EffectName>,
+    },
+
+    /// 处理器子句中的 resume!(value)：以 value 恢复被挂起的计算
+    Resume {
+        id: NodeId,
+        span: Span,
+        value: Option<Box<Expr>>,
+    },
+
+    /// 后置条件中的 old(x)：表达式在函数入口处的值
+    Old {
+        id: NodeId,
+        span: Span,
+        expression: Box<Expr>,
+    },
+
+    /// 后置条件中的 result：函数的返回值
+    Result { id: NodeId, span: Span },
+
+    /// 闭包表达式 (|x, y| x + y、move |x: i32| -> i32 effects Log { ... })
+    ///
+    /// captures 在语法分析阶段为空，由名称解析阶段根据闭包体对外部变量的使用填写
+    Closure {
+        id: NodeId,
+        span: Span,
+        is_move: bool,
+        parameters: Vec<Parameter>,
+        return_type: Option<String>,
+        effects: EffectRow,
+        body: Box<Expr>,
+        captures: Vec<Capture>,
+    },
+
+    /// 效果操作调用
+    ///
+    /// 语法分析阶段把 `effect.operation(...)` 解析为方法调用，
+    /// 名称解析阶段确认接收者是已声明的效果后才改写为此节点
+    EffectOperation {
+        id: NodeId,
+        span: Span,
+        effect: Symbol,
+        operation: Symbol,
+        arguments: Vec<Expr>,
+    },
+}
+
+impl Expr {
+    /// 节点编号，解析完成前是 `NodeId::DUMMY`
+    pub fn id(&self) -> NodeId {
+        match self {
+            Expr::Literal { id, .. }
+            | Expr::Identifier { id, .. }
+            | Expr::Path { id, .. }
+            | Expr::Binary { id, .. }
+            | Expr::Assign { id, .. }
+            | Expr::Unary { id, .. }
+            | Expr::Cast { id, .. }
+            | Expr::Range { id, .. }
+            | Expr::Call { id, .. }
+            | Expr::Grouping { id, .. }
+            | Expr::Tuple { id, .. }
+            | Expr::Array { id, .. }
+            | Expr::ArrayRepeat { id, .. }
+            | Expr::Async { id, .. }
+            | Expr::TryBlock { id, .. }
+            | Expr::Block { id, .. }
+            | Expr::Field { id, .. }
+            | Expr::MethodCall { id, .. }
+            | Expr::Index { id, .. }
+            | Expr::Try { id, .. }
+            | Expr::Await { id, .. }
+            | Expr::StructLiteral { id, .. }
+            | Expr::EnumConstructor { id, .. }
+            | Expr::With { id, .. }
+            | Expr::Resume { id, .. }
+            | Expr::Old { id, .. }
+            | Expr::Result { id, .. }
+            | Expr::Closure { id, .. }
+            | Expr::EffectOperation { id, .. } => *id,
+        }
+    }
+
+    pub fn set_id(&mut self, new_id: NodeId) {
+        match self {
+            Expr::Literal { id, .. }
+            | Expr::Identifier { id, .. }
+            | Expr::Path { id, .. }
+            | Expr::Binary { id, .. }
+            | Expr::Assign { id, .. }
+            | Expr::Unary { id, .. }
+            | Expr::Cast { id, .. }
+            | Expr::Range { id, .. }
+            | Expr::Call { id, .. }
+            | Expr::Grouping { id, .. }
+            | Expr::Tuple { id, .. }
+            | Expr::Array { id, .. }
+            | Expr::ArrayRepeat { id, .. }
+            | Expr::Async { id, .. }
+            | Expr::TryBlock { id, .. }
+            | Expr::Block { id, .. }
+            | Expr::Field { id, .. }
+            | Expr::MethodCall { id, .. }
+            | Expr::Index { id, .. }
+            | Expr::Try { id, .. }
+            | Expr::Await { id, .. }
+            | Expr::StructLiteral { id, .. }
+            | Expr::EnumConstructor { id, .. }
+            | Expr::With { id, .. }
+            | Expr::Resume { id, .. }
+            | Expr::Old { id, .. }
+            | Expr::Result { id, .. }
+            | Expr::Closure { id, .. }
+            | Expr::EffectOperation { id, .. } => *id = new_id,
+        }
+    }
+
+    /// 节点在源代码中的区间
+    pub fn span(&self) -> Span {
+        match self {
+            Expr::Literal { span, .. }
+            | Expr::Identifier { span, .. }
+            | Expr::Path { span, .. }
+            | Expr::Binary { span, .. }
+            | Expr::Assign { span, .. }
+            | Expr::Unary { span, .. }
+            | Expr::Cast { span, .. }
+            | Expr::Range { span, .. }
+            | Expr::Call { span, .. }
+            | Expr::Grouping { span, .. }
+            | Expr::Tuple { span, .. }
+            | Expr::Array { span, .. }
+            | Expr::ArrayRepeat { span, .. }
+            | Expr::Block { span, .. }
+            | Expr::Async { span, .. }
+            | Expr::TryBlock { span, .. }
+            | Expr::Field { span, .. }
+            | Expr::MethodCall { span, .. }
+            | Expr::Index { span, .. }
+            | Expr::Try { span, .. }
+            | Expr::Await { span, .. }
+            | Expr::StructLiteral { span, .. }
+            | Expr::EnumConstructor { span, .. }
+            | Expr::With { span, .. }
+            | Expr::Resume { span, .. }
+            | Expr::Old { span, .. }
+            | Expr::Closure { span, .. }
+            | Expr::Result { span, .. }
+            | Expr::EffectOperation { span, .. } => *span,
+        }
+    }
+
+    /// 节点在源代码中的起始位置
+    pub fn location(&self) -> Location<'static> {
+        self.span().start()
+    }
+}
+
+/// 语句节点
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum Stmt {
+    /// 表达式语句
+    ///
+    /// 块中最后一条没有分号的表达式语句是块的值（尾表达式）
+    Expression {
+        id: NodeId,
+        span: Span,
+        expression: Expr,
+        has_semicolon: bool,
+    },
+
+    /// let声明语句
+    Let {
+        id: NodeId,
+        span: Span,
+        identifier: Symbol,
+        initializer: Option<Expr>,
+    },
+
+    /// var声明语句
+    Var {
+        id: NodeId,
+        span: Span,
+        identifier: Symbol,
+        initializer: Option<Expr>,
+    },
+
+    /// 函数声明语句，is_async 的含义见 FunctionSignature
+    Function {
+        id: NodeId,
+        span: Span,
+        visibility: Visibility,
+        is_async: bool,
+        name: Symbol,
+        generics: Vec<GenericParameter>,
+        parameters: Vec<Parameter>,
+        return_type: Option<String>,
+        effects: EffectRow,
+        where_clause: Vec<WherePredicate>,
+        contracts: Vec<ContractClause>,
+        body: Vec<Stmt>,
+    },
+
+    /// 块语句
+    Block {
+        id: NodeId,
+        span: Span,
+        statements: Vec<Stmt>,
+    },
+
+    /// 效果声明语句
+    Effect {
+        id: NodeId,
+        span: Span,
+        visibility: Visibility,
+        declaration: EffectDeclaration,
+    },
+
+    /// 处理器声明语句
+    Handler {
+        id: NodeId,
+        span: Span,
+        visibility: Visibility,
+        declaration: HandlerDeclaration,
+    },
+
+    /// 效果组声明语句
+    EffectGroup {
+        id: NodeId,
+        span: Span,
+        visibility: Visibility,
+        declaration: EffectGroupDeclaration,
+    },
+
+    /// 处理器组声明语句
+    HandlerGroup {
+        id: NodeId,
+        span: Span,
+        visibility: Visibility,
+        declaration: HandlerGroupDeclaration,
+    },
+
+    /// use导入声明语句
+    Use {
+        id: NodeId,
+        span: Span,
+        visibility: Visibility,
+        declaration: UseDeclaration,
+    },
+
+    /// 结构体声明语句
+    Struct {
+        id: NodeId,
+        span: Span,
+        visibility: Visibility,
+        declaration: StructDeclaration,
+    },
+
+    /// 枚举声明语句
+    Enum {
+        id: NodeId,
+        span: Span,
+        visibility: Visibility,
+        declaration: EnumDeclaration,
+    },
+
+    /// 类型别名声明语句
+    TypeAlias {
+        id: NodeId,
+        span: Span,
+        visibility: Visibility,
+        declaration: TypeAliasDeclaration,
+    },
+
+    /// trait声明语句
+    Trait {
+        id: NodeId,
+        span: Span,
+        visibility: Visibility,
+        declaration: TraitDeclaration,
+    },
+
+    /// impl块语句
+    Impl {
+        id: NodeId,
+        span: Span,
+        declaration: ImplDeclaration,
+    },
+
+    /// 具名契约声明语句
+    Contract {
+        id: NodeId,
+        span: Span,
+        visibility: Visibility,
+        declaration: ContractDeclaration,
+    },
+
+    /// 模块声明语句
+    Module {
+        id: NodeId,
+        span: Span,
+        visibility: Visibility,
+        declaration: ModuleDeclaration,
+    },
+
+    /// 错误节点：解析失败后被跳过的语句，错误本身记录在解析器的错误列表中
+    Error { id: NodeId, span: Span },
+}
+
+impl Stmt {
+    /// 块的尾表达式：最后一条语句是没有分号的表达式语句时，返回该表达式作为块的值
+    pub fn block_tail(statements: &[Stmt]) -> Option<&Expr> {
+        match statements.last()? {
             Stmt::Expression {
-                location_line,
-                location_column,
-                location_file,
-                ..
-            } => crate::data::Location {
-                line: *location_line,
-                column: *location_column,
-                file: location_file.as_str(),
-            },
-            Stmt::Let {
-                location_line,
-                location_column,
-                location_file,
-                ..
-            } => crate::data::Location {
-                line: *location_line,
-                column: *location_column,
-                file: location_file.as_str(),
-            },
-            Stmt::Var {
-                location_line,
-                location_column,
-                location_file,
+                expression,
+                has_semicolon: false,
                 ..
-            } => crate::data::Location {
-                line: *location_line,
-                column: *location_column,
-                file: location_file.as_str(),
-            },
+            } => Some(expression),
+            _ => None,
+        }
+    }
+
+    /// 节点编号，解析完成前是 `NodeId::DUMMY`
+    pub fn id(&self) -> NodeId {
+        match self {
+            Stmt::Expression { id, .. }
+            | Stmt::Let { id, .. }
+            | Stmt::Var { id, .. }
+            | Stmt::Function { id, .. }
+            | Stmt::Block { id, .. }
+            | Stmt::Effect { id, .. }
+            | Stmt::Handler { id, .. }
+            | Stmt::EffectGroup { id, .. }
+            | Stmt::HandlerGroup { id, .. }
+            | Stmt::Use { id, .. }
+            | Stmt::Struct { id, .. }
+            | Stmt::Enum { id, .. }
+            | Stmt::TypeAlias { id, .. }
+            | Stmt::Trait { id, .. }
+            | Stmt::Impl { id, .. }
+            | Stmt::Contract { id, .. }
+            | Stmt::Module { id, .. }
+            | Stmt::Error { id, .. } => *id,
+        }
+    }
+
+    pub fn set_id(&mut self, new_id: NodeId) {
+        match self {
+            Stmt::Expression { id, .. }
+            | Stmt::Let { id, .. }
+            | Stmt::Var { id, .. }
+            | Stmt::Function { id, .. }
+            | Stmt::Block { id, .. }
+            | Stmt::Effect { id, .. }
+            | Stmt::Handler { id, .. }
+            | Stmt::EffectGroup { id, .. }
+            | Stmt::HandlerGroup { id, .. }
+            | Stmt::Use { id, .. }
+            | Stmt::Struct { id, .. }
+            | Stmt::Enum { id, .. }
+            | Stmt::TypeAlias { id, .. }
+            | Stmt::Trait { id, .. }
+            | Stmt::Impl { id, .. }
+            | Stmt::Contract { id, .. }
+            | Stmt::Module { id, .. }
+            | Stmt::Error { id, .. } => *id = new_id,
+        }
+    }
+
+    /// 节点在源代码中的区间
+    pub fn span(&self) -> Span {
+        match self {
+            Stmt::Expression { span, .. }
+            | Stmt::Let { span, .. }
+            | Stmt::Var { span, .. }
+            | Stmt::Function { span, .. }
+            | Stmt::Block { span, .. }
+            | Stmt::Effect { span, .. }
+            | Stmt::Handler { span, .. }
+            | Stmt::EffectGroup { span, .. }
+            | Stmt::HandlerGroup { span, .. }
+            | Stmt::Use { span, .. }
+            | Stmt::Struct { span, .. }
+            | Stmt::Enum { span, .. }
+            | Stmt::TypeAlias { span, .. }
+            | Stmt::Trait { span, .. }
+            | Stmt::Impl { span, .. }
+            | Stmt::Contract { span, .. }
+            | Stmt::Module { span, .. }
+            | Stmt::Error { span, .. } => *span,
+        }
+    }
+
+    /// 节点在源代码中的起始位置
+    pub fn location(&self) -> Location<'static> {
+        self.span().start()
+    }
+
+    /// 声明的可见性；表达式、块、变量声明和impl块没有可见性，返回 None
+    pub fn visibility(&self) -> Option<Visibility> {
+        match self {
+            Stmt::Function { visibility, .. }
+            | Stmt::Effect { visibility, .. }
+            | Stmt::Handler { visibility, .. }
+            | Stmt::EffectGroup { visibility, .. }
+            | Stmt::HandlerGroup { visibility, .. }
+            | Stmt::Use { visibility, .. }
+            | Stmt::Struct { visibility, .. }
+            | Stmt::Enum { visibility, .. }
+            | Stmt::TypeAlias { visibility, .. }
+            | Stmt::Trait { visibility, .. }
+            | Stmt::Contract { visibility, .. }
+            | Stmt::Module { visibility, .. } => Some(*visibility),
+            Stmt::Expression { .. }
+            | Stmt::Let { .. }
+            | Stmt::Var { .. }
+            | Stmt::Block { .. }
+            | Stmt::Impl { .. }
+            | Stmt::Error { .. } => None,
+        }
+    }
+
+    /// 设置声明的可见性，并把声明的起始位置移到可见性修饰上，语句没有可见性时返回 false
+    fn set_visibility(&mut self, new_visibility: Visibility, start: Location<'_>) -> bool {
+        match self {
             Stmt::Function {
-                location_line,
-                location_column,
-                location_file,
-                ..
-            } => crate::data::Location {
-                line: *location_line,
-                column: *location_column,
-                file: location_file.as_str(),
-            },
-            Stmt::Block {
-                location_line,
-                location_column,
-                location_file,
-                ..
-            } => crate::data::Location {
-                line: *location_line,
-                column: *location_column,
-                file: location_file.as_str(),
-            },
-            Stmt::Effect {
-                location_line,
-                location_column,
-                location_file,
-                ..
-            } => crate::data::Location {
-                line: *location_line,
-                column: *location_column,
-                file: location_file.as_str(),
-            },
-            Stmt::Handler {
-                location_line,
-                location_column,
-                location_file,
-                ..
-            } => crate::data::Location {
-                line: *location_line,
-                column: *location_column,
-                file: location_file.as_str(),
-            },
-            Stmt::EffectGroup {
-                location_line,
-                location_column,
-                location_file,
-                ..
-            } => crate::data::Location {
-                line: *location_line,
-                column: *location_column,
-                file: location_file.as_str(),
-            },
-            Stmt::HandlerGroup {
-                location_line,
-                location_column,
-                location_file,
-                ..
-            } => crate::data::Location {
-                line: *location_line,
-                column: *location_column,
-                file: location_file.as_str(),
-            },
+                visibility, span, ..
+            }
+            | Stmt::Effect {
+                visibility, span, ..
+            }
+            | Stmt::Handler {
+                visibility, span, ..
+            }
+            | Stmt::EffectGroup {
+                visibility, span, ..
+            }
+            | Stmt::HandlerGroup {
+                visibility, span, ..
+            }
+            | Stmt::Use {
+                visibility, span, ..
+            }
+            | Stmt::Struct {
+                visibility, span, ..
+            }
+            | Stmt::Enum {
+                visibility, span, ..
+            }
+            | Stmt::TypeAlias {
+                visibility, span, ..
+            }
+            | Stmt::Trait {
+                visibility, span, ..
+            }
+            | Stmt::Contract {
+                visibility, span, ..
+            }
+            | Stmt::Module {
+                visibility, span, ..
+            } => {
+                *visibility = new_visibility;
+                *span = Span::between(start, span.end());
+                true
+            }
+            Stmt::Expression { .. }
+            | Stmt::Let { .. }
+            | Stmt::Var { .. }
+            | Stmt::Block { .. }
+            | Stmt::Impl { .. }
+            | Stmt::Error { .. } => false,
         }
     }
 }
 
+/// 向前看的记号个数：当前记号和它之后的一个记号
+const LOOKAHEAD: usize = 2;
+
+/// 记号流的元素，与词法分析器产生的相同
+type TokenResult<'a> = Locatable<'a, Result<Token, LexicalError>>;
+
 /// 语法分析器
+///
+/// 记号按需从记号流中读取，只缓存向前看需要的记号和上一个消费的记号
 pub struct Parser<'a> {
-    tokens: Vec<Locatable<'a, Token>>,
+    /// 还没有读取的记号
+    source: Box<dyn Iterator<Item = TokenResult<'a>> + 'a>,
+    /// 已读取、还没有消费的记号，读到输入末尾后以 Eof 结尾
+    lookahead: VecDeque<Locatable<'a, Token>>,
+    /// 上一个消费的记号
+    previous: Option<Locatable<'a, Token>>,
+    /// 最后读取的记号之后的位置，用作 Eof 的位置
+    end: Location<'a>,
+    /// 已消费的记号个数，即当前记号在记号流中的下标（不计词法错误）
     current: usize,
+    /// 表达式后紧跟 `{` 的上下文（例如契约子句）中禁止结构体字面量
+    no_struct_literal: bool,
+    /// 是否在后置条件中，决定 old(x) 和 result 是否有特殊含义
+    in_postcondition: bool,
+    /// 恢复后继续解析时收集的错误，按出现顺序排列
+    errors: Vec<ParseError>,
+    /// 无损语法树的节点：(种类, 第一个记号的下标, 最后一个记号之后的下标)，按完成的顺序排列。
+    /// 只有请求了语法树时才记录，流式解析不会累积节点
+    syntax_nodes: Option<Vec<(SyntaxKind, usize, usize)>>,
+}
+
+/// 契约子句所属的声明，决定允许哪些子句
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum ContractOwner {
+    /// 函数和trait方法：requires 和 ensures
+    Function,
+    /// 结构体和trait：invariant
+    Type,
 }
 
 impl<'a> Parser<'a> {
     /// 创建新的语法分析器实例
-    pub fn new(tokens: Vec<Locatable<'a, Token>>) -> Self {
-        Parser { tokens, current: 0 }
+    ///
+    /// 记号流可以是词法分析器本身，也可以是已经去掉词法错误的记号序列；
+    /// 记号流中的词法错误作为 `ParseErrorKind::Lexical` 与语法错误一起报告
+    pub fn new<I>(tokens: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<TokenResult<'a>> + 'a,
+        I::IntoIter: 'a,
+    {
+        let mut parser = Parser {
+            source: Box::new(tokens.into_iter().map(Into::into)),
+            lookahead: VecDeque::with_capacity(LOOKAHEAD),
+            previous: None,
+            end: Location {
+                line: 1,
+                column: 1,
+                file: "",
+            },
+            current: 0,
+            no_struct_literal: false,
+            in_postcondition: false,
+            errors: Vec::new(),
+            syntax_nodes: None,
+        };
+        parser.fill_lookahead();
+        parser
+    }
+
+    /// 从记号流中读取记号直到缓存了 LOOKAHEAD 个记号或读到输入末尾
+    ///
+    /// 记号流总是以 Eof 结尾，在输入末尾报告错误时也有位置可用
+    fn fill_lookahead(&mut self) {
+        while self.lookahead.len() < LOOKAHEAD
+            && self
+                .lookahead
+                .back()
+                .is_none_or(|token| token.data != Token::Eof)
+        {
+            let token = match self.source.next() {
+                Some(Locatable {
+                    location,
+                    data: Ok(data),
+                }) => Locatable { location, data },
+                Some(Locatable {
+                    location,
+                    data: Err(error),
+                }) => {
+                    self.errors.push(ParseError {
+                        kind: ParseErrorKind::Lexical(error),
+                        span: Span::empty(location.file, location.line, location.column),
+                        found: None,
+                        expected: Vec::new(),
+                    });
+                    continue;
+                }
+                None => Locatable {
+                    location: self.end,
+                    data: Token::Eof,
+                },
+            };
+            self.end = Location {
+                column: token.location.column + token.data.lexeme().chars().count(),
+                ..token.location
+            };
+            self.lookahead.push_back(token);
+        }
     }
 
-    /// 解析入口点 - 解析整个程序
+    /// 解析入口点 - 解析整个程序，返回遇到的第一个错误
     pub fn parse(&mut self) -> Result<Vec<Stmt>, ParseError> {
+        let (statements, mut errors) = self.parse_with_recovery();
+        if errors.is_empty() {
+            Ok(statements)
+        } else {
+            Err(errors.remove(0))
+        }
+    }
+
+    /// 解析整个程序，遇到错误时恢复并继续解析，返回部分语法树和所有错误
+    ///
+    /// 解析失败的语句在语法树中替换为 Stmt::Error，
+    /// 解析器跳过记号直到 `;` 之后、`}` 之前或下一个声明关键字（panic mode）。
+    /// 返回的语法树已经按先序遍历编号（见 `ast::assign_node_ids`）
+    pub fn parse_with_recovery(&mut self) -> (Vec<Stmt>, Vec<ParseError>) {
         let mut statements = Vec::new();
 
         while !self.is_at_end() {
-            statements.push(self.parse_declaration()?);
+            let statement = self.recover(Self::parse_declaration);
+            statements.push(statement);
         }
+        assign_node_ids(&mut statements);
 
-        Ok(statements)
+        // 词法错误在读入向前看的记号时就已记录，按位置与语法错误合并
+        let mut errors = std::mem::take(&mut self.errors);
+        errors.sort_by_key(|error| (error.span.line, error.span.column));
+        (statements, errors)
+    }
+
+    /// 解析时记录无损语法树的节点，供 syntax 模块构建语法树
+    pub(crate) fn record_syntax_nodes(&mut self) {
+        self.syntax_nodes = Some(Vec::new());
+    }
+
+    /// 解析完整程序后取出记录的节点
+    pub(crate) fn take_syntax_nodes(&mut self) -> Vec<(SyntaxKind, usize, usize)> {
+        self.syntax_nodes.take().unwrap_or_default()
+    }
+
+    /// 从 start 开始、到上一个消费的记号之后结束的区间
+    fn span_from(&self, start: Location<'_>) -> Span {
+        match &self.previous {
+            Some(token)
+                if (token.location.line, token.location.column) >= (start.line, start.column) =>
+            {
+                Span::between(start, token.location.after(&token.data))
+            }
+            _ => Span::between(start, start),
+        }
+    }
+
+    /// 记录覆盖从 start 到当前位置的记号的节点，没有请求语法树时什么也不做
+    fn node(&mut self, kind: SyntaxKind, start: usize) {
+        if let Some(nodes) = &mut self.syntax_nodes {
+            nodes.push((kind, start, self.current));
+        }
+    }
+
+    /// 解析一条语句，失败时记录错误、同步到下一个恢复点并返回错误节点
+    fn recover(&mut self, parse: impl FnOnce(&mut Self) -> Result<Stmt, ParseError>) -> Stmt {
+        let start = self.current;
+        let location = self.peek().location;
+
+        match parse(self) {
+            Ok(statement) => {
+                self.node(SyntaxKind::of_stmt(&statement), start);
+                statement
+            }
+            Err(error) => {
+                self.errors.push(error);
+                self.synchronize(start);
+                self.node(SyntaxKind::Error, start);
+                Stmt::Error {
+                    id: NodeId::DUMMY,
+                    span: self.span_from(location),
+                }
+            }
+        }
+    }
+
+    /// 跳过记号直到恢复点：`;` 之后、不匹配的 `}` 之前、闭合的 `{ ... }` 之后或声明关键字之前
+    ///
+    /// 至少跳过一个记号，保证从 start 开始的解析不会在同一位置反复失败
+    fn synchronize(&mut self, start: usize) {
+        if self.current == start && !self.is_at_end() {
+            if self.check(&Token::RBrace) {
+                // 语句开头的 } 只可能是多余的，整体跳过
+                self.advance();
+                return;
+            }
+            let token = self.advance().data.clone();
+            match token {
+                Token::Semicolon => return,
+                Token::LBrace => return self.skip_to_matching_brace(),
+                _ => {}
+            }
+        }
+
+        while !self.is_at_end() {
+            if self.is_declaration_start() {
+                return;
+            }
+            match self.peek().data {
+                Token::RBrace => return,
+                Token::Semicolon => {
+                    self.advance();
+                    return;
+                }
+                Token::LBrace => {
+                    self.advance();
+                    return self.skip_to_matching_brace();
+                }
+                _ => {
+                    self.advance();
+                }
+            }
+        }
+    }
+
+    /// 在 `{` 之后跳过记号直到与之匹配的 `}`（包括该 `}`）
+    fn skip_to_matching_brace(&mut self) {
+        let mut depth = 1;
+        while !self.is_at_end() {
+            match self.advance().data {
+                Token::LBrace => depth += 1,
+                Token::RBrace => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return;
+                    }
+                }
+                _ => {}
+            }
+        }
     }
 
     /// 解析声明
     fn parse_declaration(&mut self) -> Result<Stmt, ParseError> {
-        if self.match_token(&[
-            Token::Let,
-            Token::Var,
-            Token::Fn,
-            Token::Effect,
-            Token::Handle,
-            Token::EffectGroup,
-            Token::HandlerGroup,
-        ]) {
-            // 回退一个token以便在具体解析函数中处理
-            self.current -= 1;
+        if self.is_declaration_start() {
             self.parse_declaration_statement()
         } else {
             self.parse_statement()
         }
     }
 
+    /// 检查当前token是否是声明的开始
+    fn is_declaration_start(&self) -> bool {
+        matches!(
+            self.peek_data(),
+            Some(
+                Token::Let
+                    | Token::Var
+                    | Token::Fn
+                    | Token::Effect
+                    | Token::Handle
+                    | Token::EffectGroup
+                    | Token::HandlerGroup
+                    | Token::Use
+                    | Token::Struct
+                    | Token::Enum
+                    | Token::Type
+                    | Token::Trait
+                    | Token::Impl
+                    | Token::Contract
+                    | Token::Mod
+                    | Token::Pub
+            )
+        ) || (self.check(&Token::Async) && matches!(self.peek_next(), Some(Token::Fn)))
+    }
+
     /// 解析声明语句
     fn parse_declaration_statement(&mut self) -> Result<Stmt, ParseError> {
         // 根据当前token类型决定解析哪种声明
         match self.peek().data {
             Token::Let => self.parse_let_declaration(),
             Token::Var => self.parse_var_declaration(),
-            Token::Fn => self.parse_function_declaration(),
+            Token::Pub => self.parse_public_declaration(),
+            Token::Mod => self.parse_module_declaration(),
+            Token::Fn | Token::Async => self.parse_function_declaration(),
             Token::Effect => self.parse_effect_declaration(),
             Token::Handle => self.parse_handler_declaration(),
             Token::EffectGroup => self.parse_effect_group_declaration(),
             Token::HandlerGroup => self.parse_handler_group_declaration(),
-            _ => Err(ParseError::UnexpectedToken(format!(
-                "{:?}",
-                self.peek().data
-            ))),
+            Token::Use => self.parse_use_declaration(),
+            Token::Struct => self.parse_struct_declaration(),
+            Token::Enum => self.parse_enum_declaration(),
+            Token::Type => self.parse_type_alias_declaration(),
+            Token::Trait => self.parse_trait_declaration(),
+            Token::Impl => self.parse_impl_declaration(),
+            Token::Contract => self.parse_contract_declaration(),
+            _ => Err(self.expected("declaration")),
+        }
+    }
+
+    /// 解析带可见性修饰的声明：pub fn ...、pub(crate) struct ...
+    fn parse_public_declaration(&mut self) -> Result<Stmt, ParseError> {
+        let location = self.peek().location;
+        let visibility = self.parse_visibility()?;
+
+        if !self.is_declaration_start()
+            || matches!(
+                self.peek_data(),
+                Some(Token::Let | Token::Var | Token::Impl | Token::Pub)
+            )
+        {
+            return Err(self.expected("item"));
+        }
+
+        let mut statement = self.parse_declaration_statement()?;
+        statement.set_visibility(visibility, location);
+        Ok(statement)
+    }
+
+    /// 解析可见性修饰：没有修饰、pub 或 pub(crate)
+    fn parse_visibility(&mut self) -> Result<Visibility, ParseError> {
+        if !self.match_token(&[Token::Pub]) {
+            return Ok(Visibility::Private);
+        }
+
+        if self.match_token(&[Token::LParen]) {
+            match &self.peek().data {
+                Token::Identifier(name) if name == "crate" => {
+                    self.advance(); // 消费crate
+                }
+                _ => {
+                    return Err(self.unexpected(vec![Expected::Token(Token::Identifier(
+                        "crate".to_string(),
+                    ))]));
+                }
+            }
+            self.consume(&Token::RParen)?;
+            return Ok(Visibility::Crate);
         }
+
+        Ok(Visibility::Public)
+    }
+
+    /// 解析模块声明：mod name; 或 mod name { ... }
+    fn parse_module_declaration(&mut self) -> Result<Stmt, ParseError> {
+        let token = self.consume(&Token::Mod)?;
+        let location = token.location;
+
+        let name = if let Token::Identifier(name) = &self.peek().data {
+            name.clone()
+        } else {
+            return Err(self.expected("module name"));
+        };
+
+        self.advance(); // 消费模块名
+
+        let body = if self.match_token(&[Token::LBrace]) {
+            let mut items = Vec::new();
+            while !self.check(&Token::RBrace) && !self.is_at_end() {
+                let item = self.recover(|parser| {
+                    // 模块内只能出现声明，不能出现语句和变量声明
+                    if !parser.is_declaration_start()
+                        || matches!(parser.peek_data(), Some(Token::Let | Token::Var))
+                    {
+                        return Err(parser.expected("item"));
+                    }
+                    parser.parse_declaration_statement()
+                });
+                items.push(item);
+            }
+            self.consume(&Token::RBrace)?;
+            ModuleBody::Inline(items)
+        } else {
+            if !self.match_token(&[Token::Semicolon]) {
+                return Err(self.unexpected(vec![
+                    Expected::Token(Token::LBrace),
+                    Expected::Token(Token::Semicolon),
+                ]));
+            }
+            ModuleBody::File {
+                path: None,
+                items: Vec::new(),
+            }
+        };
+
+        Ok(Stmt::Module {
+            id: NodeId::DUMMY,
+            span: self.span_from(location),
+            visibility: Visibility::Private,
+            declaration: ModuleDeclaration {
+                name: name.into(),
+                body,
+            },
+        })
     }
 
     /// 解析let声明
     fn parse_let_declaration(&mut self) -> Result<Stmt, ParseError> {
-        let token = self.consume(&Token::Let, "Expected 'let' keyword")?;
-        let location_line = token.location.line;
-        let location_column = token.location.column;
-        let location_file = token.location.file.to_string();
+        let token = self.consume(&Token::Let)?;
+        let location = token.location;
+
+        let identifier = if let Token::Identifier(name) = &self.peek().data {
+            name.clone()
+        } else {
+            return Err(self.unexpected(vec![Expected::Identifier]));
+        };
+
+        self.advance(); // 消费标识符
+
+        let initializer = if self.match_token(&[Token::Equal]) {
+            Some(self.parse_expression()?)
+        } else {
+            None
+        };
+
+        self.consume(&Token::Semicolon)?;
+
+        Ok(Stmt::Let {
+            id: NodeId::DUMMY,
+            span: self.span_from(location),
+            identifier: identifier.into(),
+            initializer,
+        })
+    }
+
+    /// 解析var声明
+    fn parse_var_declaration(&mut self) -> Result<Stmt, ParseError> {
+        let token = self.consume(&Token::Var)?;
+        let location = token.location;
+
+        let identifier = if let Token::Identifier(name) = &self.peek().data {
+            name.clone()
+        } else {
+            return Err(self.unexpected(vec![Expected::Identifier]));
+        };
+
+        self.advance(); // 消费标识符
+
+        let initializer = if self.match_token(&[Token::Equal]) {
+            Some(self.parse_expression()?)
+        } else {
+            None
+        };
+
+        self.consume(&Token::Semicolon)?;
+
+        Ok(Stmt::Var {
+            id: NodeId::DUMMY,
+            span: self.span_from(location),
+            identifier: identifier.into(),
+            initializer,
+        })
+    }
+
+    /// 解析函数声明
+    fn parse_function_declaration(&mut self) -> Result<Stmt, ParseError> {
+        let location = self.peek().location;
+
+        let is_async = self.match_token(&[Token::Async]);
+        self.consume(&Token::Fn)?;
+        let signature = self.parse_function_signature()?;
+
+        self.consume(&Token::LBrace)?;
+
+        let body = self.parse_block_body()?;
+        Ok(Stmt::Function {
+            id: NodeId::DUMMY,
+            span: self.span_from(location),
+            visibility: Visibility::Private,
+            is_async,
+            name: signature.name,
+            generics: signature.generics,
+            parameters: signature.parameters,
+            return_type: signature.return_type,
+            effects: signature.effects,
+            where_clause: signature.where_clause,
+            contracts: signature.contracts,
+            body,
+        })
+    }
+
+    /// 解析函数签名：名称、泛型参数、参数列表、返回类型、效果列表和where子句
+    ///
+    /// fn关键字已被消费，函数体或结尾的分号由调用者处理
+    fn parse_function_signature(&mut self) -> Result<FunctionSignature, ParseError> {
+        let name = if let Token::Identifier(name) = &self.peek().data {
+            name.clone()
+        } else {
+            return Err(self.expected("function name"));
+        };
+
+        self.advance(); // 消费函数名
+
+        let generics = self.parse_generic_parameters()?;
+
+        self.consume(&Token::LParen)?;
+
+        let mut parameters = Vec::new();
+        while !self.check(&Token::RParen) && !self.is_at_end() {
+            parameters.push(self.parse_parameter()?);
+
+            if !self.match_token(&[Token::Comma]) {
+                break;
+            }
+        }
+
+        self.consume(&Token::RParen)?;
+
+        // 检查是否有返回类型
+        let return_type = if self.match_token(&[Token::Arrow]) {
+            Some(self.parse_type()?)
+        } else {
+            None
+        };
+
+        // 检查是否有effects关键字
+        let mut effects = EffectRow::default();
+        if let Some(Token::Identifier(ident)) = self.peek_data()
+            && ident == "effects"
+        {
+            self.advance(); // 消费effects关键字
+            effects = self.parse_effect_row(true)?;
+        }
+
+        let where_clause = self.parse_where_clause()?;
+        let contracts = self.parse_contract_clauses(ContractOwner::Function)?;
+
+        Ok(FunctionSignature {
+            is_async: false,
+            name: name.into(),
+            generics,
+            parameters,
+            return_type,
+            effects,
+            where_clause,
+            contracts,
+        })
+    }
+
+    /// 解析单个函数参数：name、name: Type 或接收者 self、&self、&mut self
+    fn parse_parameter(&mut self) -> Result<Parameter, ParseError> {
+        let start = self.current;
+        let parameter = self.parse_parameter_parts()?;
+        self.node(SyntaxKind::Parameter, start);
+        Ok(parameter)
+    }
+
+    /// 解析函数参数的各个部分，由 parse_parameter 记录语法树节点
+    fn parse_parameter_parts(&mut self) -> Result<Parameter, ParseError> {
+        let receiver_type = if self.match_token(&[Token::MutRef]) {
+            Some("&mut Self")
+        } else if self.match_token(&[Token::Ampersand]) {
+            if self.match_token(&[Token::Mut]) {
+                Some("&mut Self")
+            } else {
+                Some("&Self")
+            }
+        } else {
+            None
+        };
+
+        let name = if let Token::Identifier(name) = &self.peek().data {
+            name.clone()
+        } else {
+            return Err(self.expected("parameter name"));
+        };
+
+        if let Some(receiver_type) = receiver_type {
+            if name != "self" {
+                return Err(
+                    self.unexpected(vec![Expected::Token(Token::Identifier("self".to_string()))])
+                );
+            }
+            self.advance(); // 消费self
+            return Ok(Parameter {
+                name: name.into(),
+                type_annotation: Some(receiver_type.to_string()),
+            });
+        }
+
+        self.advance(); // 消费参数名
+
+        let type_annotation = if self.match_token(&[Token::Colon]) {
+            Some(self.parse_type()?)
+        } else if name == "self" {
+            Some("Self".to_string())
+        } else {
+            None
+        };
+
+        Ok(Parameter {
+            name: name.into(),
+            type_annotation,
+        })
+    }
+
+    /// 解析trait声明
+    fn parse_trait_declaration(&mut self) -> Result<Stmt, ParseError> {
+        let token = self.consume(&Token::Trait)?;
+        let location = token.location;
+
+        let name = if let Token::Identifier(name) = &self.peek().data {
+            name.clone()
+        } else {
+            return Err(self.expected("trait name"));
+        };
+
+        self.advance(); // 消费trait名
+
+        let generics = self.parse_generic_parameters()?;
+
+        let supertraits = if self.match_token(&[Token::Colon]) {
+            self.parse_bounds()?.iter().map(Symbol::from).collect()
+        } else {
+            Vec::new()
+        };
+
+        let where_clause = self.parse_where_clause()?;
+        let contracts = self.parse_contract_clauses(ContractOwner::Type)?;
+
+        self.consume(&Token::LBrace)?;
+
+        let mut items = Vec::new();
+        while !self.check(&Token::RBrace) && !self.is_at_end() {
+            let start = self.current;
+            if self.match_token(&[Token::Type]) {
+                items.push(self.parse_associated_type()?);
+                self.node(SyntaxKind::AssociatedType, start);
+                continue;
+            }
+
+            let is_async = self.match_token(&[Token::Async]);
+            if !self.match_token(&[Token::Fn]) {
+                return Err(self.unexpected(vec![
+                    Expected::Token(Token::Type),
+                    Expected::Token(Token::Async),
+                    Expected::Token(Token::Fn),
+                ]));
+            }
+            let mut signature = self.parse_function_signature()?;
+            signature.is_async = is_async;
+
+            let default_body = if self.match_token(&[Token::LBrace]) {
+                Some(self.parse_block_body()?)
+            } else {
+                if !self.match_token(&[Token::Semicolon]) {
+                    return Err(self.unexpected(vec![
+                        Expected::Token(Token::LBrace),
+                        Expected::Token(Token::Semicolon),
+                    ]));
+                }
+                None
+            };
+
+            items.push(TraitItem::Method {
+                signature,
+                default_body,
+            });
+            self.node(SyntaxKind::TraitMethod, start);
+        }
+
+        self.consume(&Token::RBrace)?;
+
+        Ok(Stmt::Trait {
+            id: NodeId::DUMMY,
+            span: self.span_from(location),
+            visibility: Visibility::Private,
+            declaration: TraitDeclaration {
+                name: name.into(),
+                generics,
+                supertraits,
+                where_clause,
+                contracts,
+                items,
+            },
+        })
+    }
+
+    /// 解析trait中的关联类型，type关键字已被消费
+    fn parse_associated_type(&mut self) -> Result<TraitItem, ParseError> {
+        let name = if let Token::Identifier(name) = &self.peek().data {
+            name.clone()
+        } else {
+            return Err(self.expected("associated type name"));
+        };
+
+        self.advance(); // 消费关联类型名
+
+        let bounds = if self.match_token(&[Token::Colon]) {
+            self.parse_bounds()?
+        } else {
+            Vec::new()
+        };
+
+        let default = if self.match_token(&[Token::Equal]) {
+            Some(self.parse_type()?)
+        } else {
+            None
+        };
+
+        self.consume(&Token::Semicolon)?;
+
+        Ok(TraitItem::AssociatedType {
+            name: name.into(),
+            bounds,
+            default,
+        })
+    }
+
+    /// 解析impl块：impl<T> Type { ... } 或 impl<T> Trait for Type { ... }
+    fn parse_impl_declaration(&mut self) -> Result<Stmt, ParseError> {
+        let token = self.consume(&Token::Impl)?;
+        let location = token.location;
+
+        let generics = self.parse_generic_parameters()?;
+
+        let first_type = self.parse_type()?;
+        let (trait_name, self_type) = if self.match_token(&[Token::For]) {
+            (Some(first_type.into()), self.parse_type()?.into())
+        } else {
+            (None, first_type.into())
+        };
+
+        let where_clause = self.parse_where_clause()?;
+
+        self.consume(&Token::LBrace)?;
+
+        let mut items = Vec::new();
+        while !self.check(&Token::RBrace) && !self.is_at_end() {
+            let start = self.current;
+            let location = self.peek().location;
+            let visibility = self.parse_visibility()?;
+            if !matches!(
+                self.peek_data(),
+                Some(Token::Fn | Token::Async | Token::Type)
+            ) {
+                return Err(self.unexpected(vec![
+                    Expected::Token(Token::Fn),
+                    Expected::Token(Token::Async),
+                    Expected::Token(Token::Type),
+                ]));
+            }
+
+            let mut item = self.parse_declaration_statement()?;
+            if visibility != Visibility::Private {
+                item.set_visibility(visibility, location);
+            }
+            // 实现中的方法和关联类型也是语法树中的语句节点
+            self.node(SyntaxKind::of_stmt(&item), start);
+            items.push(item);
+        }
+
+        self.consume(&Token::RBrace)?;
+
+        Ok(Stmt::Impl {
+            id: NodeId::DUMMY,
+            span: self.span_from(location),
+            declaration: ImplDeclaration {
+                generics,
+                trait_name,
+                self_type,
+                where_clause,
+                items,
+            },
+        })
+    }
+
+    /// 解析具名契约声明：contract Name(params) { 子句; ... }
+    fn parse_contract_declaration(&mut self) -> Result<Stmt, ParseError> {
+        let token = self.consume(&Token::Contract)?;
+        let location = token.location;
+
+        let name = if let Token::Identifier(name) = &self.peek().data {
+            name.clone()
+        } else {
+            return Err(self.expected("contract name"));
+        };
+
+        self.advance(); // 消费契约名
+
+        let mut parameters = Vec::new();
+        if self.match_token(&[Token::LParen]) {
+            while !self.check(&Token::RParen) && !self.is_at_end() {
+                parameters.push(self.parse_parameter()?);
+
+                if !self.match_token(&[Token::Comma]) {
+                    break;
+                }
+            }
+
+            self.consume(&Token::RParen)?;
+        }
+
+        self.consume(&Token::LBrace)?;
+
+        let mut clauses = Vec::new();
+        while !self.check(&Token::RBrace) && !self.is_at_end() {
+            match self.parse_contract_clause()? {
+                Some(clause) => clauses.push(clause),
+                None => {
+                    return Err(self.unexpected(vec![
+                        Expected::Token(Token::Identifier("requires".to_string())),
+                        Expected::Token(Token::Identifier("ensures".to_string())),
+                        Expected::Token(Token::Identifier("invariant".to_string())),
+                        Expected::Token(Token::Contract),
+                    ]));
+                }
+            }
+
+            self.consume(&Token::Semicolon)?;
+        }
+
+        self.consume(&Token::RBrace)?;
+
+        Ok(Stmt::Contract {
+            id: NodeId::DUMMY,
+            span: self.span_from(location),
+            visibility: Visibility::Private,
+            declaration: ContractDeclaration {
+                name: name.into(),
+                parameters,
+                clauses,
+            },
+        })
+    }
+
+    /// 解析声明头部的契约子句，子句之后紧跟 `{` 或 `;`
+    fn parse_contract_clauses(
+        &mut self,
+        owner: ContractOwner,
+    ) -> Result<Vec<ContractClause>, ParseError> {
+        let previous = std::mem::replace(&mut self.no_struct_literal, true);
+        let mut clauses = Vec::new();
+        let result = loop {
+            let keyword = self.peek().clone();
+            match self.parse_contract_clause() {
+                Ok(Some(clause)) => {
+                    let misplaced = matches!(
+                        (owner, &clause),
+                        (ContractOwner::Function, ContractClause::Invariant(_))
+                            | (ContractOwner::Type, ContractClause::Requires(_))
+                            | (ContractOwner::Type, ContractClause::Ensures(_))
+                    );
+                    if misplaced {
+                        let kind = ParseErrorKind::ClauseNotAllowed(owner);
+                        break Err(self.error_at(&keyword, kind, Vec::new()));
+                    }
+                    clauses.push(clause);
+                }
+                Ok(None) => break Ok(()),
+                Err(error) => break Err(error),
+            }
+        };
+        self.no_struct_literal = previous;
+        result?;
+
+        Ok(clauses)
+    }
+
+    /// 解析单个契约子句，当前位置不是契约子句时返回 None
+    fn parse_contract_clause(&mut self) -> Result<Option<ContractClause>, ParseError> {
+        if !self.is_contract_clause_start() {
+            return Ok(None);
+        }
+
+        let start = self.current;
+        if self.match_token(&[Token::Contract]) {
+            let name = self.parse_path_name("contract name")?;
+            self.node(SyntaxKind::ContractClause, start);
+            return Ok(Some(ContractClause::Named(name)));
+        }
+
+        let keyword = self.advance().data.clone();
+        let clause = match keyword {
+            Token::Identifier(keyword) if keyword == "requires" => {
+                ContractClause::Requires(self.parse_expression()?)
+            }
+            Token::Identifier(keyword) if keyword == "ensures" => {
+                let previous = std::mem::replace(&mut self.in_postcondition, true);
+                let condition = self.parse_expression();
+                self.in_postcondition = previous;
+                ContractClause::Ensures(condition?)
+            }
+            _ => ContractClause::Invariant(self.parse_expression()?),
+        };
+
+        self.node(SyntaxKind::ContractClause, start);
+        Ok(Some(clause))
+    }
+
+    /// 检查当前token是否是契约子句的开始（requires、ensures、invariant 或 contract）
+    fn is_contract_clause_start(&self) -> bool {
+        match self.peek_data() {
+            Some(Token::Contract) => true,
+            Some(Token::Identifier(keyword)) => {
+                matches!(keyword.as_str(), "requires" | "ensures" | "invariant")
+            }
+            _ => false,
+        }
+    }
+
+    /// 解析以 + 分隔的约束列表 (Clone + Into<String>)
+    fn parse_bounds(&mut self) -> Result<Vec<String>, ParseError> {
+        let mut bounds = vec![self.parse_type()?];
+        while self.match_token(&[Token::Plus]) {
+            bounds.push(self.parse_type()?);
+        }
+        Ok(bounds)
+    }
+
+    /// 解析where子句，没有where关键字时返回空列表
+    fn parse_where_clause(&mut self) -> Result<Vec<WherePredicate>, ParseError> {
+        let mut predicates = Vec::new();
+        if !self.match_token(&[Token::Where]) {
+            return Ok(predicates);
+        }
+
+        // 约束列表在 {、; 或契约子句之前结束，允许尾随逗号
+        while !self.check(&Token::LBrace)
+            && !self.check(&Token::Semicolon)
+            && !self.is_contract_clause_start()
+            && !self.is_at_end()
+        {
+            let start = self.current;
+            let bounded_type = self.parse_type()?;
+            self.consume(&Token::Colon)?;
+            let bounds = self.parse_bounds()?;
+            predicates.push(WherePredicate {
+                bounded_type,
+                bounds,
+            });
+            self.node(SyntaxKind::WherePredicate, start);
+
+            if !self.match_token(&[Token::Comma]) {
+                break;
+            }
+        }
+
+        Ok(predicates)
+    }
+
+    /// 解析效果声明
+    fn parse_effect_declaration(&mut self) -> Result<Stmt, ParseError> {
+        let token = self.consume(&Token::Effect)?;
+        let location = token.location;
+
+        let name = if let Token::Identifier(name) = &self.peek().data {
+            name.clone()
+        } else {
+            return Err(self.expected("effect name"));
+        };
+
+        self.advance(); // 消费效果名
+
+        let generics = self.parse_generic_parameters()?;
+
+        self.consume(&Token::LBrace)?;
+
+        let mut operations = Vec::new();
+        while !self.check(&Token::RBrace) && !self.is_at_end() {
+            let start = self.current;
+            self.consume(&Token::Fn)?;
+
+            let op_name = if let Token::Identifier(name) = &self.peek().data {
+                name.clone()
+            } else {
+                return Err(self.expected("operation name"));
+            };
+
+            self.advance(); // 消费操作名
+
+            self.consume(&Token::LParen)?;
+
+            let mut parameters = Vec::new();
+            if !self.check(&Token::RParen) {
+                loop {
+                    let parameter_start = self.current;
+                    let param_name = if let Token::Identifier(name) = &self.peek().data {
+                        name.clone()
+                    } else {
+                        return Err(self.expected("parameter name"));
+                    };
+
+                    self.advance(); // 消费参数名
+
+                    self.consume(&Token::Colon)?;
+
+                    let param_type = self.parse_type()?;
+
+                    parameters.push((param_name, param_type));
+                    self.node(SyntaxKind::Parameter, parameter_start);
+
+                    // 与函数参数一样允许末尾的逗号
+                    if !self.match_token(&[Token::Comma]) || self.check(&Token::RParen) {
+                        break;
+                    }
+                }
+            }
+
+            self.consume(&Token::RParen)?;
+
+            let return_type = if self.match_token(&[Token::Arrow]) {
+                Some(self.parse_type()?)
+            } else {
+                None
+            };
+
+            self.consume(&Token::Semicolon)?;
+
+            operations.push(EffectOperation {
+                name: op_name.into(),
+                parameters,
+                return_type,
+            });
+            self.node(SyntaxKind::EffectOperation, start);
+        }
+
+        self.consume(&Token::RBrace)?;
+
+        Ok(Stmt::Effect {
+            id: NodeId::DUMMY,
+            span: self.span_from(location),
+            visibility: Visibility::Private,
+            declaration: EffectDeclaration {
+                name: name.into(),
+                generics,
+                operations,
+            },
+        })
+    }
+
+    /// 解析处理器声明
+    fn parse_handler_declaration(&mut self) -> Result<Stmt, ParseError> {
+        let token = self.consume(&Token::Handle)?;
+        let location = token.location;
+
+        let declaration = self.parse_handler_body()?;
+
+        Ok(Stmt::Handler {
+            id: NodeId::DUMMY,
+            span: self.span_from(location),
+            visibility: Visibility::Private,
+            declaration,
+        })
+    }
+
+    /// 解析handle关键字之后的处理器：效果名、可选的 as 名称和子句列表
+    fn parse_handler_body(&mut self) -> Result<HandlerDeclaration, ParseError> {
+        let effect = self.parse_effect_name()?;
+
+        let name = if self.match_token(&[Token::As]) {
+            if let Token::Identifier(name) = &self.peek().data {
+                let name = Symbol::intern(name);
+                self.advance(); // 消费处理器名
+                Some(name)
+            } else {
+                return Err(self.expected("handler name"));
+            }
+        } else {
+            None
+        };
+
+        self.consume(&Token::LBrace)?;
+
+        let mut clauses = Vec::new();
+        let mut return_clause = None;
+        let mut finally_clause = None;
+        while !self.check(&Token::RBrace) && !self.is_at_end() {
+            let start = self.current;
+            // return(x) { ... } 子句
+            if self.match_token(&[Token::Return]) {
+                if return_clause.is_some() {
+                    return Err(self.error_at(
+                        self.previous(),
+                        ParseErrorKind::DuplicateClause,
+                        Vec::new(),
+                    ));
+                }
+
+                self.consume(&Token::LParen)?;
+                let parameter = self.parse_parameter()?;
+                self.consume(&Token::RParen)?;
+                self.consume(&Token::LBrace)?;
+                let body = self.parse_block_body()?;
+                return_clause = Some(ReturnClause { parameter, body });
+                self.node(SyntaxKind::ReturnClause, start);
+                continue;
+            }
+
+            let token = self.peek().clone();
+            let operation = if let Token::Identifier(name) = &token.data {
+                name.clone()
+            } else if token.data == Token::Await {
+                // 内置 Async 效果的 await 操作
+                "await".to_string()
+            } else {
+                return Err(self.expected("operation name"));
+            };
+
+            self.advance(); // 消费操作名
+
+            // finally { ... } 子句
+            if operation == "finally" && self.match_token(&[Token::LBrace]) {
+                if finally_clause.is_some() {
+                    return Err(self.error_at(&token, ParseErrorKind::DuplicateClause, Vec::new()));
+                }
+
+                finally_clause = Some(self.parse_block_body()?);
+                self.node(SyntaxKind::FinallyClause, start);
+                continue;
+            }
+
+            self.consume(&Token::LParen)?;
+
+            let mut parameters = Vec::new();
+            while !self.check(&Token::RParen) && !self.is_at_end() {
+                parameters.push(self.parse_parameter()?);
+
+                if !self.match_token(&[Token::Comma]) {
+                    break;
+                }
+            }
+
+            self.consume(&Token::RParen)?;
+            self.consume(&Token::LBrace)?;
+
+            let body = self.parse_block_body()?;
+            clauses.push(HandlerClause {
+                span: self.span_from(token.location),
+                operation: operation.into(),
+                parameters,
+                continuation: None,
+                body,
+            });
+            self.node(SyntaxKind::HandlerClause, start);
+        }
+
+        self.consume(&Token::RBrace)?;
+
+        Ok(HandlerDeclaration {
+            name,
+            effect,
+            clauses,
+            return_clause,
+            finally_clause,
+        })
+    }
+
+    /// 解析代码块中的语句直到右花括号（包括右花括号），左花括号已被消费
+    fn parse_block_body(&mut self) -> Result<Vec<Stmt>, ParseError> {
+        let start = self.current - 1;
+        let mut statements = Vec::new();
+
+        // 块内部可以使用结构体字面量，即使块本身位于 if 条件之类禁止结构体字面量的位置
+        let no_struct_literal = std::mem::replace(&mut self.no_struct_literal, false);
+        while !self.check(&Token::RBrace) && !self.is_at_end() {
+            // 在块中，我们可以解析声明或语句
+            let statement = self.recover(Self::parse_declaration);
+            statements.push(statement);
+        }
+        self.no_struct_literal = no_struct_literal;
+
+        self.consume(&Token::RBrace)?;
+        self.node(SyntaxKind::Block, start);
+        Ok(statements)
+    }
+
+    /// 解析with表达式：with H1, H2, handle E { ... } { body }
+    fn parse_with_expression(&mut self) -> Result<Expr, ParseError> {
+        let token = self.consume(&Token::With)?;
+        let location = token.location;
+
+        let mut handlers = Vec::new();
+        let mut discharged_effects = Vec::new();
+        loop {
+            let start = self.current;
+            if self.match_token(&[Token::Handle]) {
+                let handler = self.parse_handler_body()?;
+                self.node(SyntaxKind::InlineHandler, start);
+                discharged_effects.push(handler.effect.clone());
+                handlers.push(HandlerReference::Inline(handler));
+            } else {
+                let name = self.parse_path_name("handler name")?;
+                handlers.push(HandlerReference::Named(name));
+            }
+
+            if !self.match_token(&[Token::Comma]) {
+                break;
+            }
+        }
+
+        self.consume(&Token::LBrace)?;
+        let body = self.parse_block_body()?;
+
+        Ok(Expr::With {
+            id: NodeId::DUMMY,
+            span: self.span_from(location),
+            handlers,
+            body,
+            discharged_effects,
+        })
+    }
+
+    /// 解析效果组声明
+    fn parse_effect_group_declaration(&mut self) -> Result<Stmt, ParseError> {
+        // 消费effect_group关键字
+        let token = self.consume(&Token::EffectGroup)?;
+        let location = token.location;
+
+        let name = if let Token::Identifier(name) = &self.peek().data {
+            name.clone()
+        } else {
+            return Err(self.expected("effect group name"));
+        };
+
+        self.advance(); // 消费组名
+
+        self.consume(&Token::Equal)?;
+
+        let row = self.parse_effect_row(false)?;
+
+        self.consume(&Token::Semicolon)?;
+
+        Ok(Stmt::EffectGroup {
+            id: NodeId::DUMMY,
+            span: self.span_from(location),
+            visibility: Visibility::Private,
+            declaration: EffectGroupDeclaration {
+                name: name.into(),
+                effects: row.effects,
+                excluded: row.excluded,
+                flattened_effects: Vec::new(),
+            },
+        })
+    }
+
+    /// 解析处理器组声明
+    fn parse_handler_group_declaration(&mut self) -> Result<Stmt, ParseError> {
+        // 消费handler_group关键字
+        let token = self.consume(&Token::HandlerGroup)?;
+        let location = token.location;
 
-        let identifier = if let Token::Identifier(name) = &self.peek().data {
+        let name = if let Token::Identifier(name) = &self.peek().data {
             name.clone()
         } else {
-            return Err(ParseError::UnexpectedToken(
-                "Expected identifier".to_string(),
-            ));
+            return Err(self.expected("handler group name"));
         };
 
-        self.advance(); // 消费标识符
+        self.advance(); // 消费组名
 
-        let initializer = if self.match_token(&[Token::Equal]) {
-            Some(self.parse_expression()?)
-        } else {
-            None
-        };
+        self.consume(&Token::Equal)?;
 
-        self.consume(&Token::Semicolon, "Expected ';' after let declaration")?;
+        let mut handlers = Vec::new();
+        loop {
+            handlers.push(self.parse_path_name("handler name")?);
 
-        Ok(Stmt::Let {
-            location_line,
-            location_column,
-            location_file,
-            identifier,
-            initializer,
+            if !self.match_token(&[Token::Comma]) {
+                break;
+            }
+        }
+
+        self.consume(&Token::Semicolon)?;
+
+        Ok(Stmt::HandlerGroup {
+            id: NodeId::DUMMY,
+            span: self.span_from(location),
+            visibility: Visibility::Private,
+            declaration: HandlerGroupDeclaration {
+                name: name.into(),
+                handlers,
+                discharged_effects: Vec::new(),
+            },
         })
     }
 
-    /// 解析var声明
-    fn parse_var_declaration(&mut self) -> Result<Stmt, ParseError> {
-        let token = self.consume(&Token::Var, "Expected 'var' keyword")?;
-        let location_line = token.location.line;
-        let location_column = token.location.column;
-        let location_file = token.location.file.to_string();
+    /// 解析结构体声明：命名字段、元组和单元三种形式
+    fn parse_struct_declaration(&mut self) -> Result<Stmt, ParseError> {
+        let token = self.consume(&Token::Struct)?;
+        let location = token.location;
 
-        let identifier = if let Token::Identifier(name) = &self.peek().data {
+        let name = if let Token::Identifier(name) = &self.peek().data {
             name.clone()
         } else {
-            return Err(ParseError::UnexpectedToken(
-                "Expected identifier".to_string(),
-            ));
+            return Err(self.expected("struct name"));
         };
 
-        self.advance(); // 消费标识符
+        self.advance(); // 消费结构体名
 
-        let initializer = if self.match_token(&[Token::Equal]) {
-            Some(self.parse_expression()?)
+        let generics = self.parse_generic_parameters()?;
+
+        // 不变式写在结构体体之前：struct A invariant ... { }、struct B(T) invariant ...;
+        let mut contracts = self.parse_contract_clauses(ContractOwner::Type)?;
+
+        let fields = if self.check(&Token::LBrace) {
+            self.parse_struct_fields(true)?
         } else {
-            None
+            let fields = self.parse_struct_fields(true)?;
+            contracts.extend(self.parse_contract_clauses(ContractOwner::Type)?);
+            self.consume(&Token::Semicolon)?;
+            fields
         };
 
-        self.consume(&Token::Semicolon, "Expected ';' after var declaration")?;
-
-        Ok(Stmt::Var {
-            location_line,
-            location_column,
-            location_file,
-            identifier,
-            initializer,
+        Ok(Stmt::Struct {
+            id: NodeId::DUMMY,
+            span: self.span_from(location),
+            visibility: Visibility::Private,
+            declaration: StructDeclaration {
+                name: name.into(),
+                generics,
+                fields,
+                contracts,
+            },
         })
     }
 
-    /// 解析函数声明
-    fn parse_function_declaration(&mut self) -> Result<Stmt, ParseError> {
-        let token = self.consume(&Token::Fn, "Expected 'fn' keyword")?;
-        let location_line = token.location.line;
-        let location_column = token.location.column;
-        let location_file = token.location.file.to_string();
+    /// 解析枚举声明
+    fn parse_enum_declaration(&mut self) -> Result<Stmt, ParseError> {
+        let token = self.consume(&Token::Enum)?;
+        let location = token.location;
 
         let name = if let Token::Identifier(name) = &self.peek().data {
             name.clone()
         } else {
-            return Err(ParseError::UnexpectedToken(
-                "Expected function name".to_string(),
-            ));
+            return Err(self.expected("enum name"));
         };
 
-        self.advance(); // 消费函数名
-
-        self.consume(&Token::LParen, "Expected '(' after function name")?;
-
-        let mut parameters = Vec::new();
-        if !self.check(&Token::RParen) {
-            loop {
-                if let Token::Identifier(name) = &self.peek().data {
-                    parameters.push(name.clone());
-                    self.advance();
-                } else {
-                    return Err(ParseError::UnexpectedToken(
-                        "Expected parameter name".to_string(),
-                    ));
-                }
+        self.advance(); // 消费枚举名
 
-                if !self.match_token(&[Token::Comma]) {
-                    break;
-                }
-            }
-        }
+        let generics = self.parse_generic_parameters()?;
 
-        self.consume(&Token::RParen, "Expected ')' after parameters")?;
+        self.consume(&Token::LBrace)?;
 
-        // 检查是否有返回类型
-        if self.match_token(&[Token::Arrow]) {
-            // 解析返回类型（这里我们只是消费它，因为我们还没有实现完整的类型系统）
-            if let Token::Identifier(_) = &self.peek().data {
-                self.advance(); // 消费返回类型
+        let mut variants = Vec::new();
+        while !self.check(&Token::RBrace) && !self.is_at_end() {
+            let start = self.current;
+            let variant_name = if let Token::Identifier(name) = &self.peek().data {
+                name.clone()
             } else {
-                return Err(ParseError::UnexpectedToken(
-                    "Expected return type".to_string(),
-                ));
-            }
-        }
+                return Err(self.expected("variant name"));
+            };
 
-        // 检查是否有effects关键字
-        let mut effect_list = Vec::new();
-        if let Token::Identifier(ref ident) = self.peek().data
-            && ident == "effects"
-        {
-            self.advance(); // 消费effects关键字
+            self.advance(); // 消费变体名
 
-            // 解析效果列表
-            loop {
-                if let Token::Identifier(effect_name) = &self.peek().data {
-                    effect_list.push(effect_name.clone());
-                    self.advance(); // 消费效果名
-                } else {
-                    return Err(ParseError::UnexpectedToken(
-                        "Expected effect name".to_string(),
-                    ));
-                }
+            let fields = self.parse_struct_fields(false)?;
+            variants.push(EnumVariant {
+                name: variant_name.into(),
+                fields,
+            });
+            self.node(SyntaxKind::Variant, start);
 
-                if !self.match_token(&[Token::Comma]) {
-                    break;
-                }
+            if !self.match_token(&[Token::Comma]) {
+                break;
             }
         }
 
-        self.consume(&Token::LBrace, "Expected '{' before function body")?;
+        self.consume(&Token::RBrace)?;
 
-        let body = self.parse_block_statement()?;
-        if let Stmt::Block { statements, .. } = body {
-            // 注意：目前我们没有在AST中存储effect_list和返回类型，因为我们还没有实现完整的类型系统
-            // 这将在后续阶段实现
-            Ok(Stmt::Function {
-                location_line,
-                location_column,
-                location_file,
-                name,
-                parameters,
-                body: statements,
-            })
-        } else {
-            // 这不应该发生，因为parse_block_statement总是返回Block语句
-            Err(ParseError::InvalidExpression)
-        }
+        Ok(Stmt::Enum {
+            id: NodeId::DUMMY,
+            span: self.span_from(location),
+            visibility: Visibility::Private,
+            declaration: EnumDeclaration {
+                name: name.into(),
+                generics,
+                variants,
+            },
+        })
     }
 
-    /// 解析效果声明
-    fn parse_effect_declaration(&mut self) -> Result<Stmt, ParseError> {
-        let token = self.consume(&Token::Effect, "Expected 'effect' keyword")?;
-        let location_line = token.location.line;
-        let location_column = token.location.column;
-        let location_file = token.location.file.to_string();
+    /// 解析类型别名声明
+    fn parse_type_alias_declaration(&mut self) -> Result<Stmt, ParseError> {
+        let token = self.consume(&Token::Type)?;
+        let location = token.location;
 
         let name = if let Token::Identifier(name) = &self.peek().data {
             name.clone()
         } else {
-            return Err(ParseError::UnexpectedToken(
-                "Expected effect name".to_string(),
-            ));
+            return Err(self.expected("type alias name"));
         };
 
-        self.advance(); // 消费效果名
+        self.advance(); // 消费别名
 
-        self.consume(&Token::LBrace, "Expected '{' after effect name")?;
+        let generics = self.parse_generic_parameters()?;
 
-        let mut operations = Vec::new();
-        while !self.check(&Token::RBrace) && !self.is_at_end() {
-            self.consume(&Token::Fn, "Expected 'fn' keyword")?;
+        self.consume(&Token::Equal)?;
+        let aliased_type = self.parse_type()?;
+        self.consume(&Token::Semicolon)?;
 
-            let op_name = if let Token::Identifier(name) = &self.peek().data {
+        Ok(Stmt::TypeAlias {
+            id: NodeId::DUMMY,
+            span: self.span_from(location),
+            visibility: Visibility::Private,
+            declaration: TypeAliasDeclaration {
+                name: name.into(),
+                generics,
+                aliased_type,
+            },
+        })
+    }
+
+    /// 解析泛型参数列表 <T: Clone, U>，没有泛型参数时返回空列表
+    fn parse_generic_parameters(&mut self) -> Result<Vec<GenericParameter>, ParseError> {
+        let mut generics = Vec::new();
+        let list_start = self.current;
+        if !self.match_token(&[Token::Less]) {
+            return Ok(generics);
+        }
+
+        while !self.check_generic_close() && !self.is_at_end() {
+            let start = self.current;
+            let name = if let Token::Identifier(name) = &self.peek().data {
                 name.clone()
             } else {
-                return Err(ParseError::UnexpectedToken(
-                    "Expected operation name".to_string(),
-                ));
+                return Err(self.expected("generic parameter name"));
             };
 
-            self.advance(); // 消费操作名
-
-            self.consume(&Token::LParen, "Expected '(' after operation name")?;
+            self.advance(); // 消费泛型参数名
 
-            let mut parameters = Vec::new();
-            if !self.check(&Token::RParen) {
-                loop {
-                    let param_name = if let Token::Identifier(name) = &self.peek().data {
-                        name.clone()
-                    } else {
-                        return Err(ParseError::UnexpectedToken(
-                            "Expected parameter name".to_string(),
-                        ));
-                    };
+            let bounds = if self.match_token(&[Token::Colon]) {
+                self.parse_bounds()?
+            } else {
+                Vec::new()
+            };
+            generics.push(GenericParameter {
+                name: name.into(),
+                bounds,
+            });
+            self.node(SyntaxKind::GenericParameter, start);
 
-                    self.advance(); // 消费参数名
+            if !self.match_token(&[Token::Comma]) {
+                break;
+            }
+        }
 
-                    self.consume(&Token::Colon, "Expected ':' after parameter name")?;
+        self.consume_generic_close()?;
+        self.node(SyntaxKind::GenericParameterList, list_start);
+        Ok(generics)
+    }
 
-                    let param_type = if let Token::Identifier(type_name) = &self.peek().data {
-                        type_name.clone()
-                    } else {
-                        return Err(ParseError::UnexpectedToken(
-                            "Expected parameter type".to_string(),
-                        ));
-                    };
+    /// 解析结构体或枚举变体的字段：{ a: A, b: B }、(A, B) 或者没有字段
+    ///
+    /// allow_visibility 为 true 时字段可以带 pub 或 pub(crate)（结构体），否则不允许（枚举变体）
+    fn parse_struct_fields(&mut self, allow_visibility: bool) -> Result<StructFields, ParseError> {
+        let list_start = self.current;
+        if self.match_token(&[Token::LBrace]) {
+            let mut fields = Vec::new();
+            while !self.check(&Token::RBrace) && !self.is_at_end() {
+                let start = self.current;
+                let visibility = self.parse_field_visibility(allow_visibility)?;
+                let name = if let Token::Identifier(name) = &self.peek().data {
+                    name.clone()
+                } else {
+                    return Err(self.expected("field name"));
+                };
 
-                    self.advance(); // 消费参数类型
+                self.advance(); // 消费字段名
 
-                    parameters.push((param_name, param_type));
+                self.consume(&Token::Colon)?;
+                let field_type = self.parse_type()?;
+                fields.push(FieldDeclaration {
+                    visibility,
+                    name: name.into(),
+                    field_type,
+                });
+                self.node(SyntaxKind::NamedField, start);
 
-                    if !self.match_token(&[Token::Comma]) {
-                        break;
-                    }
+                if !self.match_token(&[Token::Comma]) {
+                    break;
                 }
             }
 
-            self.consume(&Token::RParen, "Expected ')' after parameters")?;
+            self.consume(&Token::RBrace)?;
+            self.node(SyntaxKind::NamedFields, list_start);
+            Ok(StructFields::Named(fields))
+        } else if self.match_token(&[Token::LParen]) {
+            let mut fields = Vec::new();
+            while !self.check(&Token::RParen) && !self.is_at_end() {
+                let start = self.current;
+                let visibility = self.parse_field_visibility(allow_visibility)?;
+                let field_type = self.parse_type()?;
+                fields.push(TupleField {
+                    visibility,
+                    field_type,
+                });
+                self.node(SyntaxKind::TupleField, start);
 
-            let return_type = if self.match_token(&[Token::Arrow]) {
-                if let Token::Identifier(type_name) = &self.peek().data {
-                    let type_name = type_name.clone();
-                    self.advance(); // 消费返回类型
-                    Some(type_name)
-                } else {
-                    return Err(ParseError::UnexpectedToken(
-                        "Expected return type".to_string(),
-                    ));
+                if !self.match_token(&[Token::Comma]) {
+                    break;
                 }
-            } else {
-                None
-            };
-
-            self.consume(&Token::Semicolon, "Expected ';' after operation")?;
+            }
 
-            operations.push(EffectOperation {
-                name: op_name,
-                parameters,
-                return_type,
-            });
+            self.consume(&Token::RParen)?;
+            self.node(SyntaxKind::TupleFields, list_start);
+            Ok(StructFields::Tuple(fields))
+        } else {
+            Ok(StructFields::Unit)
         }
-
-        self.consume(&Token::RBrace, "Expected '}' after effect operations")?;
-
-        Ok(Stmt::Effect {
-            location_line,
-            location_column,
-            location_file,
-            declaration: EffectDeclaration { name, operations },
-        })
     }
 
-    /// 解析处理器声明
-    fn parse_handler_declaration(&mut self) -> Result<Stmt, ParseError> {
-        let token = self.consume(&Token::Handle, "Expected 'handle' keyword")?;
-        let location_line = token.location.line;
-        let location_column = token.location.column;
-        let location_file = token.location.file.to_string();
+    /// 解析字段的可见性修饰
+    fn parse_field_visibility(&mut self, allow_visibility: bool) -> Result<Visibility, ParseError> {
+        if !allow_visibility && self.check(&Token::Pub) {
+            return Err(self.error(ParseErrorKind::VisibilityNotAllowed, Vec::new()));
+        }
+        self.parse_visibility()
+    }
 
-        let effect = if let Token::Identifier(name) = &self.peek().data {
-            name.clone()
-        } else {
-            return Err(ParseError::UnexpectedToken(
-                "Expected effect name".to_string(),
-            ));
-        };
+    /// 解析use声明
+    fn parse_use_declaration(&mut self) -> Result<Stmt, ParseError> {
+        let token = self.consume(&Token::Use)?;
+        let location = token.location;
 
-        self.advance(); // 消费效果名
+        let tree = self.parse_use_tree()?;
 
-        self.consume(&Token::LBrace, "Expected '{' after effect name")?;
+        self.consume(&Token::Semicolon)?;
 
-        let mut clauses = Vec::new();
-        while !self.check(&Token::RBrace) && !self.is_at_end() {
-            let operation = if let Token::Identifier(name) = &self.peek().data {
-                name.clone()
-            } else {
-                return Err(ParseError::UnexpectedToken(
-                    "Expected operation name".to_string(),
-                ));
-            };
+        Ok(Stmt::Use {
+            id: NodeId::DUMMY,
+            span: self.span_from(location),
+            visibility: Visibility::Private,
+            declaration: UseDeclaration { tree },
+        })
+    }
 
-            self.advance(); // 消费操作名
+    /// 解析导入树：a::b、a::b as c、a::*、a::{b, c::d}
+    fn parse_use_tree(&mut self) -> Result<UseTree, ParseError> {
+        let start = self.current;
+        let tree = self.parse_use_tree_parts()?;
+        self.node(SyntaxKind::UseTree, start);
+        Ok(tree)
+    }
 
-            self.consume(&Token::LParen, "Expected '(' after operation name")?;
+    /// 解析导入树的各个部分，由 parse_use_tree 记录语法树节点
+    fn parse_use_tree_parts(&mut self) -> Result<UseTree, ParseError> {
+        let mut path = Vec::new();
 
-            let mut parameters = Vec::new();
-            if !self.check(&Token::RParen) {
-                loop {
-                    let param_name = if let Token::Identifier(name) = &self.peek().data {
-                        name.clone()
-                    } else {
-                        return Err(ParseError::UnexpectedToken(
-                            "Expected parameter name".to_string(),
-                        ));
-                    };
+        loop {
+            if self.match_token(&[Token::Star]) {
+                if path.is_empty() {
+                    return Err(self.error_at(
+                        self.previous(),
+                        ParseErrorKind::UnexpectedToken,
+                        vec![Expected::Identifier],
+                    ));
+                }
+                return Ok(UseTree::Glob { path });
+            }
 
-                    self.advance(); // 消费参数名
-                    parameters.push(param_name);
+            if self.match_token(&[Token::LBrace]) {
+                let mut items = Vec::new();
+                while !self.check(&Token::RBrace) && !self.is_at_end() {
+                    items.push(self.parse_use_tree()?);
 
                     if !self.match_token(&[Token::Comma]) {
                         break;
                     }
                 }
+                self.consume(&Token::RBrace)?;
+                return Ok(UseTree::Group { path, items });
             }
 
-            self.consume(&Token::RParen, "Expected ')' after parameters")?;
-            self.consume(&Token::LBrace, "Expected '{' before clause body")?;
-
-            let body = self.parse_block_statement()?;
-            if let Stmt::Block { statements, .. } = body {
-                clauses.push(HandlerClause {
-                    operation,
-                    parameters,
-                    body: statements,
-                });
+            if let Token::Identifier(segment) = &self.peek().data {
+                path.push(Symbol::intern(segment));
+                self.advance(); // 消费路径段
             } else {
-                return Err(ParseError::InvalidExpression);
+                return Err(self.unexpected(vec![
+                    Expected::Identifier,
+                    Expected::Token(Token::LBrace),
+                    Expected::Token(Token::Star),
+                ]));
+            }
+
+            if !self.match_token(&[Token::PathSep]) {
+                break;
             }
         }
 
-        self.consume(&Token::RBrace, "Expected '}' after handler clauses")?;
+        let alias = if self.match_token(&[Token::As]) {
+            if let Token::Identifier(alias) = &self.peek().data {
+                let alias = Symbol::intern(alias);
+                self.advance(); // 消费别名
+                Some(alias)
+            } else {
+                return Err(self.expected("alias"));
+            }
+        } else {
+            None
+        };
 
-        Ok(Stmt::Handler {
-            location_line,
-            location_column,
-            location_file,
-            declaration: HandlerDeclaration { effect, clauses },
-        })
+        Ok(UseTree::Simple { path, alias })
     }
 
-    /// 解析效果组声明
-    fn parse_effect_group_declaration(&mut self) -> Result<Stmt, ParseError> {
-        // 消费effect_group关键字
-        let token = self.consume(&Token::EffectGroup, "Expected 'effect_group' keyword")?;
-        let location_line = token.location.line;
-        let location_column = token.location.column;
-        let location_file = token.location.file.to_string();
-
-        let name = if let Token::Identifier(name) = &self.peek().data {
+    /// 解析以 :: 分隔的名称路径（如 std::io::Console），返回拼接后的文本
+    fn parse_path_name(&mut self, description: &'static str) -> Result<Symbol, ParseError> {
+        let mut path = if let Token::Identifier(name) = &self.peek().data {
             name.clone()
         } else {
-            return Err(ParseError::UnexpectedToken(
-                "Expected effect group name".to_string(),
-            ));
+            return Err(self.expected(description));
         };
+        self.advance(); // 消费名称
 
-        self.advance(); // 消费组名
+        while self.match_token(&[Token::PathSep]) {
+            if let Token::Identifier(segment) = &self.peek().data {
+                path.push_str("::");
+                path.push_str(segment);
+                self.advance(); // 消费路径段
+            } else {
+                return Err(self.unexpected(vec![Expected::Identifier]));
+            }
+        }
 
-        self.consume(&Token::Equal, "Expected '=' after effect group name")?;
+        Ok(Symbol::intern(&path))
+    }
 
-        let mut effects = Vec::new();
+    /// 解析以逗号分隔的效果行：效果或效果组、差集 `- Name` 和行变量 `..e`
+    ///
+    /// 差集作用于整行：`IO, Log - Network` 表示 (IO ∪ Log) - Network
+    fn parse_effect_row(&mut self, allow_row_variable: bool) -> Result<EffectRow, ParseError> {
+        let start = self.current;
+        let mut row = EffectRow::default();
         loop {
-            if let Token::Identifier(effect_name) = &self.peek().data {
-                effects.push(effect_name.clone());
-                self.advance(); // 消费效果名
+            if self.match_token(&[Token::Range]) {
+                if !allow_row_variable {
+                    return Err(self.error_at(
+                        self.previous(),
+                        ParseErrorKind::RowVariableNotAllowed,
+                        Vec::new(),
+                    ));
+                }
+                if row.row_variable.is_some() {
+                    return Err(self.error_at(
+                        self.previous(),
+                        ParseErrorKind::DuplicateRowVariable,
+                        Vec::new(),
+                    ));
+                }
+
+                if let Token::Identifier(name) = &self.peek().data {
+                    row.row_variable = Some(Symbol::intern(name));
+                    self.advance(); // 消费行变量名
+                } else {
+                    return Err(self.expected("row variable name"));
+                }
             } else {
-                return Err(ParseError::UnexpectedToken(
-                    "Expected effect name".to_string(),
-                ));
+                row.effects.push(self.parse_effect_name()?);
+            }
+
+            while self.match_token(&[Token::Minus]) {
+                row.excluded.push(self.parse_effect_name()?);
             }
 
             if !self.match_token(&[Token::Comma]) {
@@ -877,66 +3130,180 @@ impl<'a> Parser<'a> {
             }
         }
 
-        self.consume(
-            &Token::Semicolon,
-            "Expected ';' after effect group declaration",
-        )?;
+        self.node(SyntaxKind::EffectRow, start);
+        Ok(row)
+    }
 
-        Ok(Stmt::EffectGroup {
-            location_line,
-            location_column,
-            location_file,
-            declaration: EffectGroupDeclaration { name, effects },
-        })
+    /// 解析效果名称，可以带路径和类型实参 (std::io::Console、State<i32>)
+    fn parse_effect_name(&mut self) -> Result<EffectName, ParseError> {
+        let start = self.current;
+        let mut effect = EffectName::new(self.parse_path_name("effect name")?);
+
+        if self.match_token(&[Token::Less]) {
+            while !self.check_generic_close() && !self.is_at_end() {
+                effect.type_arguments.push(self.parse_type()?);
+                if !self.match_token(&[Token::Comma]) {
+                    break;
+                }
+            }
+            self.consume_generic_close()?;
+        }
+
+        self.node(SyntaxKind::EffectName, start);
+        Ok(effect)
     }
 
-    /// 解析处理器组声明
-    fn parse_handler_group_declaration(&mut self) -> Result<Stmt, ParseError> {
-        // 消费handler_group关键字
-        let token = self.consume(&Token::HandlerGroup, "Expected 'handler_group' keyword")?;
-        let location_line = token.location.line;
-        let location_column = token.location.column;
-        let location_file = token.location.file.to_string();
+    /// 解析类型注解
+    ///
+    /// 在完整的类型系统实现之前，类型以规范化的源代码文本表示，
+    /// 例如 `Vec<Vec<i32>>`、`&mut T`、`(A, B)`、`[u8; 4]`、`fn(i32) -> bool`
+    pub(crate) fn parse_type(&mut self) -> Result<String, ParseError> {
+        let start = self.current;
+        let parsed_type = self.parse_type_parts()?;
+        self.node(SyntaxKind::Type, start);
+        Ok(parsed_type)
+    }
 
-        let name = if let Token::Identifier(name) = &self.peek().data {
+    /// 解析类型注解的各个部分，由 parse_type 记录语法树节点
+    fn parse_type_parts(&mut self) -> Result<String, ParseError> {
+        if self.match_token(&[Token::Ampersand]) {
+            if self.match_token(&[Token::Mut]) {
+                return Ok(format!("&mut {}", self.parse_type()?));
+            }
+            return Ok(format!("&{}", self.parse_type()?));
+        }
+
+        if self.match_token(&[Token::MutRef]) {
+            return Ok(format!("&mut {}", self.parse_type()?));
+        }
+
+        if self.match_token(&[Token::And]) {
+            return Ok(format!("&&{}", self.parse_type()?));
+        }
+
+        if self.match_token(&[Token::LParen]) {
+            let mut elements = Vec::new();
+            let mut trailing_comma = false;
+            while !self.check(&Token::RParen) && !self.is_at_end() {
+                elements.push(self.parse_type()?);
+                trailing_comma = self.match_token(&[Token::Comma]);
+                if !trailing_comma {
+                    break;
+                }
+            }
+            self.consume(&Token::RParen)?;
+
+            return Ok(match elements.len() {
+                // (T) 只是带括号的T，(T,) 才是单元素元组
+                1 if !trailing_comma => elements.remove(0),
+                1 => format!("({},)", elements[0]),
+                _ => format!("({})", elements.join(", ")),
+            });
+        }
+
+        if self.match_token(&[Token::LBracket]) {
+            let element = self.parse_type()?;
+            let array_type = if self.match_token(&[Token::Semicolon]) {
+                let size = match &self.peek().data {
+                    Token::IntegerLiteral(size) | Token::Identifier(size) => size.clone(),
+                    _ => {
+                        return Err(self.expected("array length"));
+                    }
+                };
+                self.advance(); // 消费数组长度
+                format!("[{}; {}]", element, size)
+            } else {
+                format!("[{}]", element)
+            };
+            self.consume(&Token::RBracket)?;
+            return Ok(array_type);
+        }
+
+        if self.match_token(&[Token::Fn]) {
+            self.consume(&Token::LParen)?;
+            let mut parameters = Vec::new();
+            while !self.check(&Token::RParen) && !self.is_at_end() {
+                parameters.push(self.parse_type()?);
+                if !self.match_token(&[Token::Comma]) {
+                    break;
+                }
+            }
+            self.consume(&Token::RParen)?;
+
+            let mut function_type = format!("fn({})", parameters.join(", "));
+            if self.match_token(&[Token::Arrow]) {
+                function_type.push_str(" -> ");
+                function_type.push_str(&self.parse_type()?);
+            }
+            return Ok(function_type);
+        }
+
+        let mut path = if let Token::Identifier(name) = &self.peek().data {
             name.clone()
         } else {
-            return Err(ParseError::UnexpectedToken(
-                "Expected handler group name".to_string(),
-            ));
+            return Err(self.expected("type"));
         };
+        self.advance(); // 消费类型名
 
-        self.advance(); // 消费组名
-
-        self.consume(&Token::Equal, "Expected '=' after handler group name")?;
-
-        let mut handlers = Vec::new();
         loop {
-            if let Token::Identifier(handler_name) = &self.peek().data {
-                handlers.push(handler_name.clone());
-                self.advance(); // 消费处理器名
+            if self.match_token(&[Token::PathSep]) {
+                if let Token::Identifier(segment) = &self.peek().data {
+                    path.push_str("::");
+                    path.push_str(segment);
+                    self.advance(); // 消费路径段
+                } else {
+                    return Err(self.unexpected(vec![Expected::Identifier]));
+                }
+            } else if self.match_token(&[Token::Less]) {
+                let mut arguments = Vec::new();
+                loop {
+                    arguments.push(self.parse_type()?);
+                    if !self.match_token(&[Token::Comma]) || self.check_generic_close() {
+                        break;
+                    }
+                }
+                self.consume_generic_close()?;
+                path.push('<');
+                path.push_str(&arguments.join(", "));
+                path.push('>');
             } else {
-                return Err(ParseError::UnexpectedToken(
-                    "Expected handler name".to_string(),
-                ));
-            }
-
-            if !self.match_token(&[Token::Comma]) {
                 break;
             }
         }
 
-        self.consume(
-            &Token::Semicolon,
-            "Expected ';' after handler group declaration",
-        )?;
+        Ok(path)
+    }
 
-        Ok(Stmt::HandlerGroup {
-            location_line,
-            location_column,
-            location_file,
-            declaration: HandlerGroupDeclaration { name, handlers },
-        })
+    /// 检查当前token是否以 '>' 开始（包括 >>、>=、>>=）
+    fn check_generic_close(&self) -> bool {
+        self.check(&Token::Greater)
+            || self.check(&Token::Shr)
+            || self.check(&Token::GreaterEqual)
+            || self.check(&Token::ShrEqual)
+    }
+
+    /// 消费泛型参数列表的结束符 '>'
+    ///
+    /// 词法分析器会把 `Vec<Vec<i32>>` 末尾的 `>>` 识别为一个记号，
+    /// 此时只消费第一个 '>'，剩余部分留在原位置
+    fn consume_generic_close(&mut self) -> Result<(), ParseError> {
+        let rest = match self.peek_data() {
+            Some(Token::Greater) => {
+                self.advance();
+                return Ok(());
+            }
+            Some(Token::Shr) => Token::Greater,
+            Some(Token::GreaterEqual) => Token::Equal,
+            Some(Token::ShrEqual) => Token::GreaterEqual,
+            _ => {
+                return Err(self.unexpected(vec![Expected::Token(Token::Greater)]));
+            }
+        };
+
+        let token = &mut self.lookahead[0];
+        token.data = rest;
+        token.location.column += 1;
+        Ok(())
     }
 
     /// 解析语句
@@ -950,54 +3317,40 @@ impl<'a> Parser<'a> {
 
     /// 解析块语句
     fn parse_block_statement(&mut self) -> Result<Stmt, ParseError> {
-        let mut statements = Vec::new();
-
-        while !self.check(&Token::RBrace) && !self.is_at_end() {
-            // 在块中，我们可以解析声明或语句
-            if self.match_token(&[
-                Token::Let,
-                Token::Var,
-                Token::Fn,
-                Token::Effect,
-                Token::Handle,
-                Token::EffectGroup,
-                Token::HandlerGroup,
-            ]) {
-                self.current -= 1; // 回退token
-                statements.push(self.parse_declaration_statement()?);
-            } else {
-                statements.push(self.parse_statement()?);
-            }
-        }
+        let statements = self.parse_block_body()?;
 
-        let token = self.consume(&Token::RBrace, "Expected '}' after block")?;
-        let location_line = token.location.line;
-        let location_column = token.location.column;
-        let location_file = token.location.file.to_string();
+        let token = self.previous();
+        let location = token.location;
 
         Ok(Stmt::Block {
-            location_line,
-            location_column,
-            location_file,
+            id: NodeId::DUMMY,
+            span: self.span_from(location),
             statements,
         })
     }
 
     /// 解析表达式语句
     fn parse_expression_statement(&mut self) -> Result<Stmt, ParseError> {
-        let expression = self.parse_expression()?;
+        // 以块结尾的表达式在语句位置自成一条语句：分号可选，也不与后面的记号组成二元表达式
+        let block_like = self.is_block_like_expression_start();
+        let expression = if block_like {
+            self.parse_primary_expression()?
+        } else {
+            self.parse_expression()?
+        };
         let location = expression.location();
-        let location_line = location.line;
-        let location_column = location.column;
-        let location_file = location.file.to_string();
 
-        self.consume(&Token::Semicolon, "Expected ';' after expression")?;
+        // 块末尾的表达式可以省略分号，作为块的值
+        let has_semicolon = self.match_token(&[Token::Semicolon]);
+        if !has_semicolon && !block_like && !self.check(&Token::RBrace) {
+            return Err(self.unexpected(vec![Expected::Token(Token::Semicolon)]));
+        }
 
         Ok(Stmt::Expression {
-            location_line,
-            location_column,
-            location_file,
+            id: NodeId::DUMMY,
+            span: self.span_from(location),
             expression,
+            has_semicolon,
         })
     }
 
@@ -1006,69 +3359,501 @@ impl<'a> Parser<'a> {
         self.parse_binary_expression(0)
     }
 
-    /// 解析二元表达式（Pratt解析算法核心）
-    fn parse_binary_expression(&mut self, precedence: u8) -> Result<Expr, ParseError> {
+    /// 解析数组表达式的剩余部分：[a, b, c] 或 [value; count]，'[' 已被消费
+    fn parse_array_expression(&mut self) -> Result<Expr, ParseError> {
+        let location = self.previous().location;
+
+        let mut elements = Vec::new();
+        if !self.check(&Token::RBracket) {
+            let first = self.parse_nested_expression()?;
+            if self.match_token(&[Token::Semicolon]) {
+                let count = self.parse_nested_expression()?;
+                self.consume(&Token::RBracket)?;
+                return Ok(Expr::ArrayRepeat {
+                    id: NodeId::DUMMY,
+                    span: self.span_from(location),
+                    value: Box::new(first),
+                    count: Box::new(count),
+                });
+            }
+
+            elements.push(first);
+            while self.match_token(&[Token::Comma]) && !self.check(&Token::RBracket) {
+                elements.push(self.parse_nested_expression()?);
+            }
+        }
+        self.consume(&Token::RBracket)?;
+
+        Ok(Expr::Array {
+            id: NodeId::DUMMY,
+            span: self.span_from(location),
+            elements,
+        })
+    }
+
+    /// 解析二元表达式（Pratt解析算法核心），只结合绑定强度不低于 min_binding_power 的中缀运算符
+    fn parse_binary_expression(&mut self, min_binding_power: u8) -> Result<Expr, ParseError> {
+        let start = self.current;
         let mut left = self.parse_unary_expression()?;
 
-        while let Some(current_precedence) = self.get_precedence() {
-            if current_precedence < precedence {
+        while let Some(operator) = self.peek_operator(Fixity::Infix) {
+            if operator.binding_power < min_binding_power {
                 break;
             }
 
-            let operator = self.parse_binary_operator()?;
-            self.advance(); // 消费操作符
-
-            let right = self.parse_binary_expression(current_precedence + 1)?;
+            // 不可结合的运算符：左侧已经是同一绑定强度的运算时不能再连用
+            if operator.associativity == Associativity::None
+                && let Some(kind) = Self::chained_operator(&left, operator)
+            {
+                return Err(self.error(kind, Vec::new()));
+            }
 
             let location = left.location();
-            let location_line = location.line;
-            let location_column = location.column;
-            let location_file = location.file.to_string();
-
-            left = Expr::Binary {
-                location_line,
-                location_column,
-                location_file,
-                left: Box::new(left),
-                operator,
-                right: Box::new(right),
+
+            left = match &operator.kind {
+                OperatorKind::Assign(assignment) => {
+                    if !Self::is_place_expression(&left) {
+                        return Err(self.error(ParseErrorKind::InvalidAssignmentTarget, Vec::new()));
+                    }
+                    self.advance(); // 消费赋值操作符
+
+                    // 赋值是右结合的：a = b = c 解析为 a = (b = c)
+                    let value = self.parse_binary_expression(operator.right_binding_power())?;
+                    Expr::Assign {
+                        id: NodeId::DUMMY,
+                        span: self.span_from(location),
+                        target: Box::new(left),
+                        operator: assignment.clone(),
+                        value: Box::new(value),
+                    }
+                }
+                OperatorKind::Cast => {
+                    self.advance(); // 消费as
+                    let target_type = self.parse_type()?;
+                    Expr::Cast {
+                        id: NodeId::DUMMY,
+                        span: self.span_from(location),
+                        expression: Box::new(left),
+                        target_type,
+                    }
+                }
+                OperatorKind::Range { .. } => self.parse_range_expression(Some(left), operator)?,
+                OperatorKind::Binary(binary) => {
+                    self.advance(); // 消费操作符
+                    let right = self.parse_binary_expression(operator.right_binding_power())?;
+                    Expr::Binary {
+                        id: NodeId::DUMMY,
+                        span: self.span_from(location),
+                        left: Box::new(left),
+                        operator: binary.clone(),
+                        right: Box::new(right),
+                    }
+                }
+                _ => unreachable!("中缀运算符只有赋值、类型转换、范围和二元运算"),
             };
+            self.node(SyntaxKind::of_expr(&left), start);
         }
 
         Ok(left)
     }
 
+    /// 当前记号在给定位置上作为运算符时的表项
+    fn peek_operator(&self, fixity: Fixity) -> Option<&'static Operator> {
+        if self.is_at_end() {
+            return None;
+        }
+        operator::lookup(&self.peek().data, fixity)
+    }
+
+    /// 不可结合的运算符连用时的错误：左侧是同一绑定强度的比较或范围
+    fn chained_operator(left: &Expr, operator: &Operator) -> Option<ParseErrorKind> {
+        match left {
+            Expr::Binary {
+                operator: previous, ..
+            } if previous.precedence() == operator.binding_power => {
+                Some(ParseErrorKind::ChainedComparison {
+                    previous: previous.clone(),
+                })
+            }
+            Expr::Range { .. } if matches!(operator.kind, OperatorKind::Range { .. }) => {
+                Some(ParseErrorKind::ChainedRange)
+            }
+            _ => None,
+        }
+    }
+
+    /// 解析范围表达式，start为None时是前缀形式（..b 或 ..）
+    fn parse_range_expression(
+        &mut self,
+        start: Option<Expr>,
+        operator: &Operator,
+    ) -> Result<Expr, ParseError> {
+        let token = self.advance().clone(); // 消费 .. 或 ..=
+        let inclusive = matches!(operator.kind, OperatorKind::Range { inclusive: true });
+
+        let end = if self.can_start_expression() {
+            Some(Box::new(
+                self.parse_binary_expression(operator.right_binding_power())?,
+            ))
+        } else if inclusive {
+            return Err(self.expected("expression"));
+        } else {
+            None
+        };
+
+        let location = match &start {
+            Some(start) => start.location(),
+            None => token.location,
+        };
+
+        Ok(Expr::Range {
+            id: NodeId::DUMMY,
+            span: self.span_from(location),
+            start: start.map(Box::new),
+            end,
+            inclusive,
+        })
+    }
+
     /// 解析一元表达式
     fn parse_unary_expression(&mut self) -> Result<Expr, ParseError> {
-        if self.match_token(&[Token::Minus, Token::Bang]) {
-            let operator_token = self.previous().data.clone();
-            let operator = match operator_token {
-                Token::Minus => UnaryOperator::Negate,
-                Token::Bang => UnaryOperator::Not,
-                _ => unreachable!(), // 因为上面已经匹配过了
-            };
+        let start = self.current;
+        let Some(operator) = self.peek_operator(Fixity::Prefix) else {
+            if self.is_closure_start() {
+                return self.parse_closure_expression();
+            }
+            let primary = self.parse_primary_expression()?;
+            return self.parse_postfix_expression(primary, start);
+        };
+        if let OperatorKind::Range { .. } = operator.kind {
+            let range = self.parse_range_expression(None, operator)?;
+            self.node(SyntaxKind::Range, start);
+            return Ok(range);
+        }
 
-            let operand = self.parse_unary_expression()?;
+        let operator_token = self.advance().clone();
+        let location = operator_token.location;
 
-            let location = self.previous().location;
-            let location_line = location.line;
-            let location_column = location.column;
-            let location_file = location.file.to_string();
-
-            Ok(Expr::Unary {
-                location_line,
-                location_column,
-                location_file,
-                operator,
+        let unary = match &operator.kind {
+            // & mut x（中间有空白时不会被词法分析为&mut）
+            OperatorKind::Unary(UnaryOperator::Reference) | OperatorKind::DoubleReference
+                if self.match_token(&[Token::Mut]) =>
+            {
+                UnaryOperator::MutableReference
+            }
+            OperatorKind::Unary(unary) => unary.clone(),
+            OperatorKind::DoubleReference => UnaryOperator::Reference,
+            _ => unreachable!("前缀运算符只有一元运算和范围"),
+        };
+
+        let mut operand = self.parse_binary_expression(operator.right_binding_power())?;
+
+        self.node(SyntaxKind::Unary, start);
+
+        // 前缀位置的 && 是两次借用：&&x 等价于 &(&x)，语法树中只有一个节点
+        if operator.kind == OperatorKind::DoubleReference {
+            operand = Expr::Unary {
+                id: NodeId::DUMMY,
+                span: self.span_from(Location {
+                    column: location.column + 1,
+                    ..location
+                }),
+                operator: unary,
                 operand: Box::new(operand),
-            })
+            };
+            return Ok(Expr::Unary {
+                id: NodeId::DUMMY,
+                span: self.span_from(location),
+                operator: UnaryOperator::Reference,
+                operand: Box::new(operand),
+            });
+        }
+
+        Ok(Expr::Unary {
+            id: NodeId::DUMMY,
+            span: self.span_from(location),
+            operator: unary,
+            operand: Box::new(operand),
+        })
+    }
+
+    /// 当前位置是否是闭包的开始：|...|、|| 或 move 后跟二者之一
+    fn is_closure_start(&self) -> bool {
+        match &self.peek().data {
+            Token::Pipe | Token::Or => true,
+            Token::Identifier(name) if name == "move" => {
+                matches!(self.peek_next(), Some(Token::Pipe | Token::Or))
+            }
+            _ => false,
+        }
+    }
+
+    /// 解析闭包表达式：[move] |参数| [-> 返回类型] [effects 效果行] 闭包体
+    ///
+    /// 标注了返回类型或效果时闭包体必须是代码块；否则闭包体是一直延伸到最右侧的表达式
+    fn parse_closure_expression(&mut self) -> Result<Expr, ParseError> {
+        let start = self.current;
+        let token = self.peek().clone();
+        let location = token.location;
+
+        let is_move = matches!(&token.data, Token::Identifier(name) if name == "move");
+        if is_move {
+            self.advance(); // 消费move
+        }
+
+        let mut parameters = Vec::new();
+        if !self.match_token(&[Token::Or]) {
+            self.consume(&Token::Pipe)?;
+            while !self.check(&Token::Pipe) {
+                parameters.push(self.parse_parameter()?);
+                if !self.match_token(&[Token::Comma]) {
+                    break;
+                }
+            }
+            self.consume(&Token::Pipe)?;
+        }
+
+        let return_type = if self.match_token(&[Token::Arrow]) {
+            Some(self.parse_type()?)
+        } else {
+            None
+        };
+
+        let mut effects = EffectRow::default();
+        if let Some(Token::Identifier(ident)) = self.peek_data()
+            && ident == "effects"
+        {
+            self.advance(); // 消费effects关键字
+            effects = self.parse_effect_row(true)?;
+        }
+
+        let annotated =
+            return_type.is_some() || !effects.effects.is_empty() || effects.row_variable.is_some();
+        let body = if !annotated {
+            self.parse_expression()?
+        } else if self.check(&Token::LBrace) {
+            self.parse_primary_expression()?
         } else {
-            self.parse_primary_expression()
+            return Err(self.unexpected(vec![Expected::Token(Token::LBrace)]));
+        };
+
+        self.node(SyntaxKind::Closure, start);
+        Ok(Expr::Closure {
+            id: NodeId::DUMMY,
+            span: self.span_from(location),
+            is_move,
+            parameters,
+            return_type,
+            effects,
+            body: Box::new(body),
+            captures: Vec::new(),
+        })
+    }
+
+    /// 解析后缀表达式链：调用 f(x)、字段访问 a.b、方法调用 a.b()、索引 a[i]、a? 和 a.await
+    ///
+    /// start 是 expr 的第一个记号的下标
+    fn parse_postfix_expression(
+        &mut self,
+        mut expr: Expr,
+        start: usize,
+    ) -> Result<Expr, ParseError> {
+        while let Some(operator) = self.peek_operator(Fixity::Postfix) {
+            let location = expr.location();
+
+            self.advance(); // 消费后缀运算符
+            expr = match operator.kind {
+                OperatorKind::Call => {
+                    let arguments = self.parse_call_arguments()?;
+                    Expr::Call {
+                        id: NodeId::DUMMY,
+                        span: self.span_from(location),
+                        function: Box::new(expr),
+                        arguments,
+                    }
+                }
+                OperatorKind::Index => {
+                    let index = self.parse_nested_expression()?;
+                    self.consume(&Token::RBracket)?;
+                    Expr::Index {
+                        id: NodeId::DUMMY,
+                        span: self.span_from(location),
+                        object: Box::new(expr),
+                        index: Box::new(index),
+                    }
+                }
+                OperatorKind::Try => Expr::Try {
+                    id: NodeId::DUMMY,
+                    span: self.span_from(location),
+                    expression: Box::new(expr),
+                },
+                OperatorKind::Member => {
+                    match self.peek_data() {
+                        Some(Token::Await) => {
+                            self.advance(); // 消费await
+                            Expr::Await {
+                                id: NodeId::DUMMY,
+                                span: self.span_from(location),
+                                expression: Box::new(expr),
+                            }
+                        }
+                        Some(Token::Identifier(name)) => {
+                            let name = name.clone();
+                            self.advance(); // 消费字段名或方法名
+
+                            if self.match_token(&[Token::LParen]) {
+                                let arguments = self.parse_call_arguments()?;
+                                Expr::MethodCall {
+                                    id: NodeId::DUMMY,
+                                    span: self.span_from(location),
+                                    receiver: Box::new(expr),
+                                    method: name.into(),
+                                    arguments,
+                                }
+                            } else {
+                                Expr::Field {
+                                    id: NodeId::DUMMY,
+                                    span: self.span_from(location),
+                                    object: Box::new(expr),
+                                    field: name.into(),
+                                }
+                            }
+                        }
+                        // 元组字段访问 t.0
+                        Some(Token::IntegerLiteral(index)) => {
+                            let field = index.clone();
+                            self.advance(); // 消费字段序号
+                            Expr::Field {
+                                id: NodeId::DUMMY,
+                                span: self.span_from(location),
+                                object: Box::new(expr),
+                                field: field.into(),
+                            }
+                        }
+                        // 嵌套元组字段访问 t.0.1，词法分析器把 0.1 读成了一个浮点数
+                        Some(Token::FloatLiteral(literal))
+                            if let Some((outer, inner)) = literal.split_once('.')
+                                && Self::is_tuple_index(outer)
+                                && Self::is_tuple_index(inner) =>
+                        {
+                            let (outer, inner) = (outer.to_string(), inner.to_string());
+                            self.advance(); // 消费两个字段序号
+                            let object = Expr::Field {
+                                id: NodeId::DUMMY,
+                                span: self.span_from(location),
+                                object: Box::new(expr),
+                                field: outer.into(),
+                            };
+                            Expr::Field {
+                                id: NodeId::DUMMY,
+                                span: self.span_from(location),
+                                object: Box::new(object),
+                                field: inner.into(),
+                            }
+                        }
+                        _ => {
+                            return Err(self.expected("field or method name"));
+                        }
+                    }
+                }
+                _ => unreachable!("后缀运算符只有调用、索引、成员访问和 ?"),
+            };
+            // t.0.1 得到的两层字段访问只有一个节点
+            self.node(SyntaxKind::of_expr(&expr), start);
+        }
+
+        Ok(expr)
+    }
+
+    /// 解析结构体字面量的字段部分，左花括号已被消费
+    fn parse_struct_literal(
+        &mut self,
+        path: Vec<Symbol>,
+        location: crate::data::Location,
+    ) -> Result<Expr, ParseError> {
+        let mut fields = Vec::new();
+        let mut base = None;
+
+        while !self.check(&Token::RBrace) && !self.is_at_end() {
+            // 结构体更新语法 ..base 必须是最后一项
+            if self.match_token(&[Token::Range]) {
+                base = Some(Box::new(self.parse_expression()?));
+                break;
+            }
+
+            let start = self.current;
+            let field = if let Token::Identifier(name) = &self.peek().data {
+                Symbol::intern(name)
+            } else {
+                return Err(self.expected("field name"));
+            };
+
+            let field_token = self.advance().clone(); // 消费字段名
+
+            let value = if self.match_token(&[Token::Colon]) {
+                self.parse_expression()?
+            } else {
+                // 简写形式 Point { x } 等价于 Point { x: x }
+                Expr::Identifier {
+                    id: NodeId::DUMMY,
+                    span: self.span_from(field_token.location),
+                    name: field,
+                }
+            };
+            fields.push((field, value));
+            self.node(SyntaxKind::StructLiteralField, start);
+
+            if !self.match_token(&[Token::Comma]) {
+                break;
+            }
+        }
+
+        self.consume(&Token::RBrace)?;
+
+        Ok(Expr::StructLiteral {
+            id: NodeId::DUMMY,
+            span: self.span_from(location),
+            path,
+            fields,
+            base,
+        })
+    }
+
+    /// 解析括号内的表达式，括号内不受结构体字面量限制
+    fn parse_nested_expression(&mut self) -> Result<Expr, ParseError> {
+        let previous = std::mem::replace(&mut self.no_struct_literal, false);
+        let result = self.parse_expression();
+        self.no_struct_literal = previous;
+        result
+    }
+
+    /// 解析调用参数列表，左括号已被消费
+    fn parse_call_arguments(&mut self) -> Result<Vec<Expr>, ParseError> {
+        let mut arguments = Vec::new();
+        if !self.check(&Token::RParen) {
+            loop {
+                arguments.push(self.parse_nested_expression()?);
+
+                if !self.match_token(&[Token::Comma]) || self.check(&Token::RParen) {
+                    break;
+                }
+            }
         }
+
+        self.consume(&Token::RParen)?;
+        Ok(arguments)
     }
 
     /// 解析主要表达式（字面量、标识符、括号表达式等）
     fn parse_primary_expression(&mut self) -> Result<Expr, ParseError> {
+        let start = self.current;
+        let expr = self.parse_primary_expression_parts()?;
+        self.node(SyntaxKind::of_expr(&expr), start);
+        Ok(expr)
+    }
+
+    /// 解析主要表达式的各个部分，由 parse_primary_expression 记录语法树节点
+    fn parse_primary_expression_parts(&mut self) -> Result<Expr, ParseError> {
         if self.match_token(&[Token::True, Token::False]) {
             let token = self.previous().clone();
             let value = match token.data {
@@ -1078,19 +3863,16 @@ impl<'a> Parser<'a> {
             };
 
             Ok(Expr::Literal {
-                location_line: token.location.line,
-                location_column: token.location.column,
-                location_file: token.location.file.to_string(),
+                id: NodeId::DUMMY,
+                span: self.span_from(token.location),
                 value,
             })
         } else if self.match_token(&[
             Token::IntegerLiteral(String::new()),
             Token::FloatLiteral(String::new()),
         ]) {
-            // 注意：这里我们只是匹配类型，实际值在下面获取
-            self.current -= 1; // 回退以便正确获取值
-
-            let token = self.advance().clone();
+            // match_token 只比较记号的种类，实际值从刚消费的记号中获取
+            let token = self.previous().clone();
             let value = match &token.data {
                 Token::IntegerLiteral(value) => Literal::Integer(value.clone()),
                 Token::FloatLiteral(value) => Literal::Float(value.clone()),
@@ -1098,9 +3880,8 @@ impl<'a> Parser<'a> {
             };
 
             Ok(Expr::Literal {
-                location_line: token.location.line,
-                location_column: token.location.column,
-                location_file: token.location.file.to_string(),
+                id: NodeId::DUMMY,
+                span: self.span_from(token.location),
                 value,
             })
         } else if let Token::StringLiteral(value) = &self.peek().data {
@@ -1108,124 +3889,223 @@ impl<'a> Parser<'a> {
             let token = self.advance().clone();
 
             Ok(Expr::Literal {
-                location_line: token.location.line,
-                location_column: token.location.column,
-                location_file: token.location.file.to_string(),
+                id: NodeId::DUMMY,
+                span: self.span_from(token.location),
                 value: Literal::String(value),
             })
         } else if let Token::CharLiteral(value) = self.peek().data {
-            let value = value;
             let token = self.advance().clone();
 
             Ok(Expr::Literal {
-                location_line: token.location.line,
-                location_column: token.location.column,
-                location_file: token.location.file.to_string(),
+                id: NodeId::DUMMY,
+                span: self.span_from(token.location),
                 value: Literal::Char(value),
             })
         } else if let Token::Identifier(name) = &self.peek().data {
             let name = name.clone();
             let token = self.advance().clone();
 
-            // 检查是否是效果操作调用 (effect.operation)
-            if self.match_token(&[Token::Dot]) {
-                if let Token::Identifier(operation) = &self.peek().data {
-                    let operation = operation.clone();
-                    self.advance(); // 消费操作名
-
-                    self.consume(&Token::LParen, "Expected '(' after operation name")?;
+            // 路径表达式 a::b::c
+            if self.check(&Token::PathSep) {
+                let mut segments = vec![Symbol::intern(&name)];
+                while self.match_token(&[Token::PathSep]) {
+                    if let Token::Identifier(segment) = &self.peek().data {
+                        segments.push(Symbol::intern(segment));
+                        self.advance(); // 消费路径段
+                    } else {
+                        return Err(self.unexpected(vec![Expected::Identifier]));
+                    }
+                }
 
-                    let mut arguments = Vec::new();
-                    if !self.check(&Token::RParen) {
-                        loop {
-                            arguments.push(self.parse_expression()?);
+                if !self.no_struct_literal && self.match_token(&[Token::LBrace]) {
+                    return self.parse_struct_literal(segments, token.location);
+                }
 
-                            if !self.match_token(&[Token::Comma]) {
-                                break;
-                            }
-                        }
-                    }
+                return Ok(Expr::Path {
+                    id: NodeId::DUMMY,
+                    span: self.span_from(token.location),
+                    segments,
+                });
+            }
 
-                    self.consume(&Token::RParen, "Expected ')' after arguments")?;
+            if !self.no_struct_literal && self.match_token(&[Token::LBrace]) {
+                return self.parse_struct_literal(vec![Symbol::intern(&name)], token.location);
+            }
 
-                    Ok(Expr::EffectOperation {
-                        location_line: token.location.line,
-                        location_column: token.location.column,
-                        location_file: token.location.file.to_string(),
-                        effect: name,
-                        operation,
-                        arguments,
-                    })
+            // resume!(value) 与 resume!()
+            if name == "resume" && self.match_token(&[Token::Bang]) {
+                self.consume(&Token::LParen)?;
+                let value = if self.check(&Token::RParen) {
+                    None
                 } else {
-                    Err(ParseError::UnexpectedToken(
-                        "Expected operation name".to_string(),
-                    ))
-                }
-            } else {
-                Ok(Expr::Identifier {
-                    location_line: token.location.line,
-                    location_column: token.location.column,
-                    location_file: token.location.file.to_string(),
-                    name,
-                })
+                    Some(Box::new(self.parse_nested_expression()?))
+                };
+                self.consume(&Token::RParen)?;
+                return Ok(Expr::Resume {
+                    id: NodeId::DUMMY,
+                    span: self.span_from(token.location),
+                    value,
+                });
+            }
+
+            // 后置条件中的 result 和 old(x)
+            if self.in_postcondition && name == "result" {
+                return Ok(Expr::Result {
+                    id: NodeId::DUMMY,
+                    span: self.span_from(token.location),
+                });
+            }
+
+            if self.in_postcondition && name == "old" && self.match_token(&[Token::LParen]) {
+                let expression = self.parse_nested_expression()?;
+                self.consume(&Token::RParen)?;
+                return Ok(Expr::Old {
+                    id: NodeId::DUMMY,
+                    span: self.span_from(token.location),
+                    expression: Box::new(expression),
+                });
             }
+
+            // effect.operation(...) 在这里解析为方法调用，
+            // 由名称解析阶段（resolve模块）改写为效果操作调用
+            Ok(Expr::Identifier {
+                id: NodeId::DUMMY,
+                span: self.span_from(token.location),
+                name: name.into(),
+            })
+        } else if self.check(&Token::With) {
+            self.parse_with_expression()
         } else if self.match_token(&[Token::LParen]) {
             let location = self.previous().location;
-            let location_line = location.line;
-            let location_column = location.column;
-            let location_file = location.file.to_string();
-
-            let expression = self.parse_expression()?;
-            self.consume(&Token::RParen, "Expected ')' after expression")?;
-
-            Ok(Expr::Grouping {
-                location_line,
-                location_column,
-                location_file,
-                expression: Box::new(expression),
+
+            // () 是单元值，(a) 是分组，(a,) 和 (a, b) 是元组
+            if self.match_token(&[Token::RParen]) {
+                return Ok(Expr::Tuple {
+                    id: NodeId::DUMMY,
+                    span: self.span_from(location),
+                    elements: Vec::new(),
+                });
+            }
+
+            let expression = self.parse_nested_expression()?;
+            if !self.match_token(&[Token::Comma]) {
+                self.consume(&Token::RParen)?;
+                return Ok(Expr::Grouping {
+                    id: NodeId::DUMMY,
+                    span: self.span_from(location),
+                    expression: Box::new(expression),
+                });
+            }
+
+            let mut elements = vec![expression];
+            while !self.check(&Token::RParen) && !self.is_at_end() {
+                elements.push(self.parse_nested_expression()?);
+                if !self.match_token(&[Token::Comma]) {
+                    break;
+                }
+            }
+            self.consume(&Token::RParen)?;
+
+            Ok(Expr::Tuple {
+                id: NodeId::DUMMY,
+                span: self.span_from(location),
+                elements,
+            })
+        } else if self.match_token(&[Token::LBracket]) {
+            self.parse_array_expression()
+        } else if self.match_token(&[Token::Async]) {
+            let location = self.previous().location;
+
+            self.consume(&Token::LBrace)?;
+            let statements = self.parse_block_body()?;
+            Ok(Expr::Async {
+                id: NodeId::DUMMY,
+                span: self.span_from(location),
+                statements,
+                effects: Vec::new(),
+            })
+        } else if self.match_token(&[Token::Try]) {
+            let location = self.previous().location;
+
+            self.consume(&Token::LBrace)?;
+            let statements = self.parse_block_body()?;
+            Ok(Expr::TryBlock {
+                id: NodeId::DUMMY,
+                span: self.span_from(location),
+                statements,
+                discharged_effects: Vec::new(),
+            })
+        } else if self.match_token(&[Token::LBrace]) {
+            let location = self.previous().location;
+
+            let statements = self.parse_block_body()?;
+            Ok(Expr::Block {
+                id: NodeId::DUMMY,
+                span: self.span_from(location),
+                statements,
             })
         } else {
-            Err(ParseError::UnexpectedToken(format!(
-                "{:?}",
-                self.peek().data
-            )))
+            Err(self.expected("expression"))
         }
     }
 
-    /// 解析二元操作符
-    fn parse_binary_operator(&mut self) -> Result<BinaryOperator, ParseError> {
-        match &self.peek().data {
-            Token::Plus => Ok(BinaryOperator::Add),
-            Token::Minus => Ok(BinaryOperator::Subtract),
-            Token::Star => Ok(BinaryOperator::Multiply),
-            Token::Slash => Ok(BinaryOperator::Divide),
-            Token::Percent => Ok(BinaryOperator::Modulo),
-            Token::EqualEqual => Ok(BinaryOperator::Equal),
-            Token::BangEqual => Ok(BinaryOperator::NotEqual),
-            Token::Less => Ok(BinaryOperator::Less),
-            Token::LessEqual => Ok(BinaryOperator::LessEqual),
-            Token::Greater => Ok(BinaryOperator::Greater),
-            Token::GreaterEqual => Ok(BinaryOperator::GreaterEqual),
-            Token::And => Ok(BinaryOperator::And),
-            Token::Or => Ok(BinaryOperator::Or),
-            _ => Err(ParseError::UnexpectedToken(format!(
-                "{:?}",
-                self.peek().data
-            ))),
-        }
-    }
-
-    /// 获取当前token的优先级
-    fn get_precedence(&self) -> Option<u8> {
-        match &self.peek().data {
-            Token::Or => Some(1),
-            Token::And => Some(2),
-            Token::EqualEqual | Token::BangEqual => Some(3),
-            Token::Less | Token::LessEqual | Token::Greater | Token::GreaterEqual => Some(4),
-            Token::Plus | Token::Minus => Some(5),
-            Token::Star | Token::Slash | Token::Percent => Some(6),
-            _ => None,
+    /// 检查表达式是否是可赋值的位置（place expression）
+    fn is_place_expression(expr: &Expr) -> bool {
+        match expr {
+            Expr::Identifier { .. } | Expr::Field { .. } | Expr::Index { .. } => true,
+            Expr::Unary {
+                operator: UnaryOperator::Dereference,
+                ..
+            } => true,
+            Expr::Grouping { expression, .. } => Self::is_place_expression(expression),
+            _ => false,
+        }
+    }
+
+    /// 元组字段序号只能是十进制数字
+    fn is_tuple_index(text: &str) -> bool {
+        !text.is_empty() && text.bytes().all(|byte| byte.is_ascii_digit())
+    }
+
+    /// 检查当前token是否是以块结尾的表达式（with）的开始
+    fn is_block_like_expression_start(&self) -> bool {
+        matches!(
+            self.peek_data(),
+            Some(Token::With | Token::Async | Token::Try)
+        )
+    }
+
+    /// 检查当前token能否作为表达式的开始
+    fn can_start_expression(&self) -> bool {
+        if self.is_at_end() {
+            return false;
         }
+
+        matches!(
+            self.peek().data,
+            Token::IntegerLiteral(_)
+                | Token::FloatLiteral(_)
+                | Token::StringLiteral(_)
+                | Token::CharLiteral(_)
+                | Token::Identifier(_)
+                | Token::True
+                | Token::False
+                | Token::LParen
+                | Token::Minus
+                | Token::Bang
+                | Token::Star
+                | Token::Ampersand
+                | Token::MutRef
+                | Token::And
+                | Token::Range
+                | Token::RangeInclusive
+                | Token::With
+                | Token::Pipe
+                | Token::Or
+                | Token::LBracket
+                | Token::Async
+                | Token::Try
+        ) || (self.check(&Token::LBrace) && !self.no_struct_literal)
     }
 
     /// 检查当前token是否匹配给定的token之一
@@ -1257,38 +4137,95 @@ impl<'a> Parser<'a> {
     }
 
     /// 消费一个预期的token，如果当前token不匹配则返回错误
-    fn consume(
-        &mut self,
-        token: &Token,
-        message: &str,
-    ) -> Result<Locatable<'a, Token>, ParseError> {
+    fn consume(&mut self, token: &Token) -> Result<Locatable<'a, Token>, ParseError> {
         if self.check(token) {
             Ok(self.advance().clone())
         } else {
-            Err(ParseError::MissingToken(message.to_string()))
+            Err(self.unexpected(vec![Expected::Token(token.clone())]))
+        }
+    }
+
+    /// 当前记号不是期望的内容
+    fn unexpected(&self, expected: Vec<Expected>) -> ParseError {
+        self.error(ParseErrorKind::UnexpectedToken, expected)
+    }
+
+    /// 当前位置期望出现一类语法成分，例如 "expression"
+    fn expected(&self, description: &'static str) -> ParseError {
+        self.unexpected(vec![Expected::Syntax(description)])
+    }
+
+    /// 位于当前记号处的语法错误
+    fn error(&self, kind: ParseErrorKind, expected: Vec<Expected>) -> ParseError {
+        self.error_at(self.peek(), kind, expected)
+    }
+
+    /// 位于 token 处的语法错误，Eof 处的错误是输入末尾的空区间
+    fn error_at(
+        &self,
+        token: &Locatable<'a, Token>,
+        kind: ParseErrorKind,
+        expected: Vec<Expected>,
+    ) -> ParseError {
+        let (span, found) = if token.data == Token::Eof {
+            let location = token.location;
+            (
+                Span::empty(location.file, location.line, location.column),
+                None,
+            )
+        } else {
+            (
+                Span::of_token(token.location, &token.data),
+                Some(token.data.clone()),
+            )
+        };
+
+        ParseError {
+            kind,
+            span,
+            found,
+            expected,
         }
     }
 
     /// 获取前一个token
     fn previous(&self) -> &Locatable<'a, Token> {
-        &self.tokens[self.current - 1]
+        self.previous
+            .as_ref()
+            .expect("previous() called before consuming any token")
+    }
+
+    /// 获取当前token的数据，到达末尾时返回None
+    fn peek_data(&self) -> Option<&Token> {
+        if self.is_at_end() {
+            None
+        } else {
+            Some(&self.peek().data)
+        }
     }
 
     /// 获取当前token
     fn peek(&self) -> &Locatable<'a, Token> {
-        &self.tokens[self.current]
+        &self.lookahead[0]
+    }
+
+    /// 获取当前token之后的一个token的数据
+    fn peek_next(&self) -> Option<&Token> {
+        self.lookahead.get(1).map(|token| &token.data)
     }
 
     /// 消费当前token并前进到下一个token
     fn advance(&mut self) -> &Locatable<'a, Token> {
         if !self.is_at_end() {
+            self.previous = self.lookahead.pop_front();
             self.current += 1;
+            self.fill_lookahead();
         }
         self.previous()
     }
 
     /// 检查是否已到达token流的末尾
     fn is_at_end(&self) -> bool {
-        self.current >= self.tokens.len() || self.peek().data == Token::Eof
+        self.peek().data == Token::Eof
     }
 }
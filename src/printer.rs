@@ -0,0 +1,1402 @@
+//! 语法树打印模块
+//!
+//! 把语法树重新打印为 Rus 源代码。表达式只在优先级、结合性或语法位置需要时才加括号，
+//! 语法树中的 `Expr::Grouping` 总是打印为括号，因此打印语法分析器的输出再重新解析，
+//! 得到的语法树与原来的相同（位置和节点编号除外）。
+//! 名称解析阶段改写出的节点（枚举构造、效果操作调用、续延参数）打印为对应的源代码写法。
+//!
+//! 格式化工具（`format` 模块）在此基础上指定行宽，并把注释和空行挂到语句、代码块和声明成员上
+
+use crate::operator::{
+    self, PRECEDENCE_ASSIGNMENT, PRECEDENCE_CAST, PRECEDENCE_POSTFIX, PRECEDENCE_PREFIX,
+    PRECEDENCE_RANGE,
+};
+use crate::parser::{
+    BinaryOperator, ContractClause, EffectName, EffectRow, Expr, FunctionSignature,
+    GenericParameter, HandlerDeclaration, HandlerReference, Literal, ModuleBody, Parameter, Stmt,
+    StructFields, TraitItem, UnaryOperator, UseTree, VariantArguments, Visibility, WherePredicate,
+};
+use std::collections::{HashMap, HashSet};
+
+/// 源代码中的位置（行号，列号），语句以它开始的位置标识
+pub(crate) type Position = (usize, usize);
+
+/// 注释在打印结果中的挂载点
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum Anchor {
+    /// 独占一行，位于从该位置开始的语句之前
+    Before(Position),
+    /// 接在从该位置开始的语句的最后一行末尾
+    After(Position),
+    /// 接在第 n 个代码块（按左花括号出现的顺序编号）的左花括号之后
+    BlockStart(usize),
+    /// 独占一行，位于第 n 个代码块的右花括号之前
+    BlockEnd(usize),
+    /// 接在从该位置开始的声明主体的左花括号之后
+    MemberStart(Position),
+    /// 独占一行，位于从该位置开始的声明的第 n 个成员之前，n 等于成员个数时位于右花括号之前
+    Member(Position, usize),
+    /// 接在声明的第 n 个成员的最后一行末尾
+    MemberEnd(Position, usize),
+    /// 文件末尾
+    End,
+}
+
+/// 保留下来的一条注释
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Comment {
+    pub(crate) text: String,
+    /// 注释在源代码中的字节偏移
+    pub(crate) offset: usize,
+    /// 源代码中注释之前有空行
+    pub(crate) blank_line_before: bool,
+}
+
+/// 格式化时保留的注释和空行
+#[derive(Debug, Default)]
+pub(crate) struct Trivia {
+    pub(crate) comments: HashMap<Anchor, Vec<Comment>>,
+    /// 源代码中之前有空行的语句
+    pub(crate) blank_lines: HashSet<Position>,
+}
+
+/// 打印语句列表，每条顶层语句占一行，代码块缩进四个空格
+pub fn print_program(statements: &[Stmt]) -> String {
+    format_program(statements, Trivia::default(), usize::MAX)
+}
+
+/// 打印单条语句，结尾没有换行
+pub fn print_stmt(stmt: &Stmt) -> String {
+    let mut printer = Printer::new(usize::MAX, Trivia::default());
+    printer.stmt(stmt);
+    printer.out
+}
+
+/// 打印单个表达式
+pub fn print_expr(expr: &Expr) -> String {
+    let mut printer = Printer::new(usize::MAX, Trivia::default());
+    printer.expr(expr, Context::NESTED);
+    printer.out
+}
+
+/// 按行宽打印整个文件，注释和空行放在各自的挂载点上
+pub(crate) fn format_program(statements: &[Stmt], trivia: Trivia, width: usize) -> String {
+    let mut printer = Printer::new(width, trivia);
+    printer.statements(statements, Anchor::End);
+
+    // 挂载点没有打印出来的注释放在文件末尾，注释不会丢失
+    let mut rest: Vec<Comment> = printer
+        .trivia
+        .comments
+        .drain()
+        .flat_map(|(_, comments)| comments)
+        .collect();
+    rest.sort_by_key(|comment| comment.offset);
+    for comment in rest {
+        printer.newline();
+        printer.write(&comment.text);
+    }
+
+    // 每条顶层语句之前都换了行
+    let mut out = printer.out.split_off(printer.out.len().min(1));
+    if !out.is_empty() {
+        out.push('\n');
+    }
+    out
+}
+
+/// 表达式所在的语法位置
+#[derive(Clone, Copy)]
+struct Context {
+    /// 表达式至少要有的优先级，低于它时加括号
+    precedence: u8,
+    /// 表达式之后没有可能被它吞并的记号。闭包体和前缀范围的终点一直向右延伸，
+    /// 没有终点的范围会吞并后面能开始表达式的记号，它们不在末尾时要加括号
+    trailing: bool,
+    /// 位于禁止结构体字面量的位置（声明头部的契约子句）
+    no_struct_literal: bool,
+}
+
+impl Context {
+    /// 括号、方括号、花括号之内或者以 `;` 结尾的位置
+    const NESTED: Context = Context {
+        precedence: 0,
+        trailing: true,
+        no_struct_literal: false,
+    };
+
+    fn with_precedence(self, precedence: u8) -> Context {
+        Context { precedence, ..self }
+    }
+
+    /// 后面还有其他记号的位置
+    fn followed(self, precedence: u8) -> Context {
+        Context {
+            precedence,
+            trailing: false,
+            ..self
+        }
+    }
+}
+
+struct Printer {
+    out: String,
+    indent: usize,
+    /// 行宽，超出时逗号分隔的列表每项占一行
+    width: usize,
+    trivia: Trivia,
+    /// 已经打印的代码块个数
+    blocks: usize,
+    /// 刚换行打印完一个没有括号的列表，接下来以空格开头的内容（如函数体的 `{`）另起一行
+    break_after_list: bool,
+}
+
+impl Printer {
+    fn new(width: usize, trivia: Trivia) -> Printer {
+        Printer {
+            out: String::new(),
+            indent: 0,
+            width,
+            trivia,
+            blocks: 0,
+            break_after_list: false,
+        }
+    }
+
+    fn write(&mut self, text: &str) {
+        if self.break_after_list
+            && let Some(text) = text.strip_prefix(' ')
+        {
+            self.newline();
+            return self.write(text);
+        }
+        self.break_after_list = false;
+        self.out.push_str(text);
+    }
+
+    fn newline(&mut self) {
+        self.break_after_list = false;
+        self.out.push('\n');
+        for _ in 0..self.indent {
+            self.out.push_str("    ");
+        }
+    }
+
+    /// 打印以 separator 分隔的列表
+    fn list<T>(&mut self, items: &[T], separator: &str, mut print: impl FnMut(&mut Self, &T)) {
+        for (index, item) in items.iter().enumerate() {
+            if index > 0 {
+                self.write(separator);
+            }
+            print(self, item);
+        }
+    }
+
+    /// 当前行已经打印的字符数
+    fn column(&self) -> usize {
+        let line_start = self.out.rfind('\n').map_or(0, |index| index + 1);
+        self.out[line_start..].chars().count()
+    }
+
+    /// print 打印出的第一行能否放在当前行的行宽之内
+    fn fits(&self, print: impl FnOnce(&mut Self)) -> bool {
+        if self.width == usize::MAX {
+            return true;
+        }
+
+        // 试打印时不限制行宽，也不打印注释
+        let mut draft = Printer::new(usize::MAX, Trivia::default());
+        draft.indent = self.indent;
+        draft.blocks = self.blocks;
+        print(&mut draft);
+        let first_line = draft.out.lines().next().unwrap_or_default();
+        self.column() + first_line.chars().count() <= self.width
+    }
+
+    /// 以逗号分隔、位于 open 和 close 之间的 count 项。一行放不下时每项占一行，
+    /// trailing_comma 为 true 时最后一项之后也加逗号
+    fn delimited(
+        &mut self,
+        (open, close): (&str, &str),
+        count: usize,
+        trailing_comma: bool,
+        print: impl Fn(&mut Self, usize),
+    ) {
+        let flat = |printer: &mut Self| {
+            printer.write(open);
+            for index in 0..count {
+                if index > 0 {
+                    printer.write(", ");
+                }
+                print(printer, index);
+            }
+            printer.write(close);
+        };
+        if count == 0 || self.fits(flat) {
+            return flat(self);
+        }
+
+        self.write(open.trim_end());
+        self.indent += 1;
+        for index in 0..count {
+            self.newline();
+            print(self, index);
+            if trailing_comma || index + 1 < count {
+                self.write(",");
+            }
+        }
+        self.indent -= 1;
+        self.newline();
+        self.write(close.trim_start());
+    }
+
+    /// lead 之后没有括号、以逗号分隔的列表，如效果行和效果组。
+    /// 一行放不下时每项缩进一级占一行，最后一项之后不加逗号
+    fn separated<S: AsRef<str>>(&mut self, lead: &str, items: &[S]) {
+        let flat = format!("{} {}", lead, join(items, ", "));
+        if self.fits(|printer| printer.write(&flat)) {
+            return self.write(&flat);
+        }
+
+        self.write(lead);
+        self.indent += 1;
+        for (index, item) in items.iter().enumerate() {
+            if index > 0 {
+                self.write(",");
+            }
+            self.newline();
+            self.write(item.as_ref());
+        }
+        self.indent -= 1;
+        self.break_after_list = true;
+    }
+
+    /// 独占一行的注释
+    fn comments(&mut self, anchor: Anchor) {
+        for comment in self.trivia.comments.remove(&anchor).unwrap_or_default() {
+            if comment.blank_line_before {
+                self.out.push('\n');
+            }
+            self.newline();
+            self.write(&comment.text);
+        }
+    }
+
+    /// 接在当前行末尾的注释
+    fn trailing_comments(&mut self, anchor: Anchor) {
+        for comment in self.trivia.comments.remove(&anchor).unwrap_or_default() {
+            self.write(" ");
+            self.write(&comment.text);
+        }
+    }
+
+    /// 逐条打印语句，每条语句之前换行，end 是最后一条语句之后的注释
+    fn statements(&mut self, statements: &[Stmt], end: Anchor) {
+        for statement in statements {
+            let position = position(statement);
+            self.comments(Anchor::Before(position));
+            if self.trivia.blank_lines.contains(&position) {
+                self.out.push('\n');
+            }
+            self.newline();
+            self.stmt(statement);
+            self.trailing_comments(Anchor::After(position));
+        }
+        self.comments(end);
+    }
+
+    /// 打印代码块，每条语句占一行
+    fn block(&mut self, statements: &[Stmt]) {
+        // 代码块按左花括号出现的顺序编号，与无损语法树中的块节点一一对应
+        let index = self.blocks;
+        self.blocks += 1;
+        self.braced(
+            statements,
+            Anchor::BlockStart(index),
+            Anchor::BlockEnd(index),
+        );
+    }
+
+    /// 花括号中的语句，start 是左花括号之后同一行的注释，end 是右花括号之前的注释
+    fn braced(&mut self, statements: &[Stmt], start: Anchor, end: Anchor) {
+        if statements.is_empty()
+            && !self.trivia.comments.contains_key(&start)
+            && !self.trivia.comments.contains_key(&end)
+        {
+            self.write("{}");
+            return;
+        }
+
+        self.write("{");
+        self.trailing_comments(start);
+        self.indent += 1;
+        self.statements(statements, end);
+        self.indent -= 1;
+        self.newline();
+        self.write("}");
+    }
+
+    /// 声明的成员，每个成员占一行。owner 是声明开始的位置，内联处理器没有位置
+    fn members(
+        &mut self,
+        owner: Option<Position>,
+        count: usize,
+        mut print: impl FnMut(&mut Self, usize),
+    ) {
+        let anchor = |index| owner.map(|owner| Anchor::Member(owner, index));
+        let has_comments = |printer: &Self, index| {
+            anchor(index).is_some_and(|anchor| printer.trivia.comments.contains_key(&anchor))
+        };
+        let start = owner.map(Anchor::MemberStart);
+        let has_start_comments =
+            start.is_some_and(|start| self.trivia.comments.contains_key(&start));
+        if count == 0 && !has_comments(self, 0) && !has_start_comments {
+            self.write("{}");
+            return;
+        }
+
+        self.write("{");
+        if let Some(start) = start {
+            self.trailing_comments(start);
+        }
+        self.indent += 1;
+        for index in 0..=count {
+            if let Some(anchor) = anchor(index) {
+                self.comments(anchor);
+            }
+            if index == count {
+                break;
+            }
+            self.newline();
+            print(self, index);
+            if let Some(owner) = owner {
+                self.trailing_comments(Anchor::MemberEnd(owner, index));
+            }
+        }
+        self.indent -= 1;
+        self.newline();
+        self.write("}");
+    }
+
+    fn visibility(&mut self, visibility: Visibility) {
+        match visibility {
+            Visibility::Private => {}
+            Visibility::Crate => self.write("pub(crate) "),
+            Visibility::Public => self.write("pub "),
+        }
+    }
+
+    fn stmt(&mut self, stmt: &Stmt) {
+        let owner = Some(position(stmt));
+        match stmt {
+            Stmt::Expression {
+                expression,
+                has_semicolon,
+                ..
+            } => {
+                // 以代码块开始的语句会被解析为块语句或以块结尾的表达式语句，后面不能再跟运算符
+                let starts_with_block = matches!(
+                    leftmost(expression),
+                    Expr::Block { .. }
+                        | Expr::With { .. }
+                        | Expr::Async { .. }
+                        | Expr::TryBlock { .. }
+                );
+                let block_like = matches!(
+                    expression,
+                    Expr::With { .. } | Expr::Async { .. } | Expr::TryBlock { .. }
+                );
+                if starts_with_block && !block_like {
+                    self.parenthesized(expression);
+                } else {
+                    self.expr(expression, Context::NESTED);
+                }
+                if *has_semicolon {
+                    self.write(";");
+                }
+            }
+            Stmt::Let {
+                identifier,
+                initializer,
+                ..
+            } => self.binding("let", identifier, initializer.as_ref()),
+            Stmt::Var {
+                identifier,
+                initializer,
+                ..
+            } => self.binding("var", identifier, initializer.as_ref()),
+            Stmt::Function {
+                visibility,
+                is_async,
+                name,
+                generics,
+                parameters,
+                return_type,
+                effects,
+                where_clause,
+                contracts,
+                body,
+                ..
+            } => {
+                self.visibility(*visibility);
+                self.signature_head(*is_async, name, generics, parameters);
+                self.signature_tail(return_type.as_deref(), effects, where_clause, contracts);
+                self.write(" ");
+                self.block(body);
+            }
+            Stmt::Block { statements, .. } => self.block(statements),
+            Stmt::Effect {
+                visibility,
+                declaration,
+                ..
+            } => {
+                self.visibility(*visibility);
+                self.write("effect ");
+                self.write(&declaration.name);
+                self.generics(&declaration.generics);
+                self.write(" ");
+                let operations = &declaration.operations;
+                self.members(owner, operations.len(), |printer, index| {
+                    let operation = &operations[index];
+                    printer.write("fn ");
+                    printer.write(&operation.name);
+                    let parameters = &operation.parameters;
+                    printer.delimited(("(", ")"), parameters.len(), true, |printer, index| {
+                        let (name, parameter_type) = &parameters[index];
+                        printer.write(name);
+                        printer.write(": ");
+                        printer.write(parameter_type);
+                    });
+                    if let Some(return_type) = &operation.return_type {
+                        printer.write(" -> ");
+                        printer.write(return_type);
+                    }
+                    printer.write(";");
+                });
+            }
+            Stmt::Handler {
+                visibility,
+                declaration,
+                ..
+            } => {
+                self.visibility(*visibility);
+                self.handler(declaration, owner);
+            }
+            Stmt::EffectGroup {
+                visibility,
+                declaration,
+                ..
+            } => {
+                self.visibility(*visibility);
+                self.write("effect_group ");
+                self.write(&declaration.name);
+                self.effect_list(" =", &declaration.effects, &declaration.excluded, None);
+                self.write(";");
+            }
+            Stmt::HandlerGroup {
+                visibility,
+                declaration,
+                ..
+            } => {
+                self.visibility(*visibility);
+                self.write("handler_group ");
+                self.write(&declaration.name);
+                self.separated(" =", &declaration.handlers);
+                self.write(";");
+            }
+            Stmt::Use {
+                visibility,
+                declaration,
+                ..
+            } => {
+                self.visibility(*visibility);
+                self.write("use ");
+                self.use_tree(&declaration.tree);
+                self.write(";");
+            }
+            Stmt::Struct {
+                visibility,
+                declaration,
+                ..
+            } => {
+                self.visibility(*visibility);
+                self.write("struct ");
+                self.write(&declaration.name);
+                self.generics(&declaration.generics);
+                if let StructFields::Named(fields) = &declaration.fields {
+                    // 不变式写在字段之前：struct A invariant ... { ... }
+                    self.contracts(&declaration.contracts);
+                    self.write(" ");
+                    self.members(owner, fields.len(), |printer, index| {
+                        printer.visibility(fields[index].visibility);
+                        printer.write(&fields[index].name);
+                        printer.write(": ");
+                        printer.write(&fields[index].field_type);
+                        printer.write(",");
+                    });
+                } else {
+                    self.fields(&declaration.fields);
+                    self.contracts(&declaration.contracts);
+                    self.write(";");
+                }
+            }
+            Stmt::Enum {
+                visibility,
+                declaration,
+                ..
+            } => {
+                self.visibility(*visibility);
+                self.write("enum ");
+                self.write(&declaration.name);
+                self.generics(&declaration.generics);
+                self.write(" ");
+                let variants = &declaration.variants;
+                self.members(owner, variants.len(), |printer, index| {
+                    let variant = &variants[index];
+                    printer.write(&variant.name);
+                    if let StructFields::Named(_) = &variant.fields {
+                        printer.write(" ");
+                    }
+                    printer.fields(&variant.fields);
+                    printer.write(",");
+                });
+            }
+            Stmt::TypeAlias {
+                visibility,
+                declaration,
+                ..
+            } => {
+                self.visibility(*visibility);
+                self.write("type ");
+                self.write(&declaration.name);
+                self.generics(&declaration.generics);
+                self.write(" = ");
+                self.write(&declaration.aliased_type);
+                self.write(";");
+            }
+            Stmt::Trait {
+                visibility,
+                declaration,
+                ..
+            } => {
+                self.visibility(*visibility);
+                self.write("trait ");
+                self.write(&declaration.name);
+                self.generics(&declaration.generics);
+                if !declaration.supertraits.is_empty() {
+                    self.write(": ");
+                    self.write(&join(&declaration.supertraits, " + "));
+                }
+                self.where_clause(&declaration.where_clause);
+                self.contracts(&declaration.contracts);
+                self.write(" ");
+                let items = &declaration.items;
+                self.members(owner, items.len(), |printer, index| {
+                    printer.trait_item(&items[index]);
+                });
+            }
+            Stmt::Impl { declaration, .. } => {
+                self.write("impl");
+                self.generics(&declaration.generics);
+                self.write(" ");
+                if let Some(trait_name) = &declaration.trait_name {
+                    self.write(trait_name);
+                    self.write(" for ");
+                }
+                self.write(&declaration.self_type);
+                self.where_clause(&declaration.where_clause);
+                self.write(" ");
+                self.braced(
+                    &declaration.items,
+                    Anchor::MemberStart(position(stmt)),
+                    Anchor::Member(position(stmt), declaration.items.len()),
+                );
+            }
+            Stmt::Contract {
+                visibility,
+                declaration,
+                ..
+            } => {
+                self.visibility(*visibility);
+                self.write("contract ");
+                self.write(&declaration.name);
+                if !declaration.parameters.is_empty() {
+                    self.write("(");
+                    self.list(&declaration.parameters, ", ", Self::parameter);
+                    self.write(")");
+                }
+                self.write(" ");
+                let clauses = &declaration.clauses;
+                self.members(owner, clauses.len(), |printer, index| {
+                    printer.contract_clause(&clauses[index], Context::NESTED);
+                    printer.write(";");
+                });
+            }
+            Stmt::Module {
+                visibility,
+                declaration,
+                ..
+            } => {
+                self.visibility(*visibility);
+                self.write("mod ");
+                self.write(&declaration.name);
+                match &declaration.body {
+                    ModuleBody::Inline(items) => {
+                        self.write(" ");
+                        self.braced(
+                            items,
+                            Anchor::MemberStart(position(stmt)),
+                            Anchor::Member(position(stmt), items.len()),
+                        );
+                    }
+                    // 文件模块的内容在另一个文件中
+                    ModuleBody::File { .. } => self.write(";"),
+                }
+            }
+            Stmt::Error { .. } => self.write("/* error */"),
+        }
+    }
+
+    /// let 和 var 声明
+    fn binding(&mut self, keyword: &str, identifier: &str, initializer: Option<&Expr>) {
+        self.write(keyword);
+        self.write(" ");
+        self.write(identifier);
+        if let Some(initializer) = initializer {
+            self.write(" = ");
+            self.expr(initializer, Context::NESTED);
+        }
+        self.write(";");
+    }
+
+    /// 函数签名，从 async fn 到契约子句，不包括函数体
+    fn signature(&mut self, signature: &FunctionSignature) {
+        self.signature_head(
+            signature.is_async,
+            &signature.name,
+            &signature.generics,
+            &signature.parameters,
+        );
+        self.signature_tail(
+            signature.return_type.as_deref(),
+            &signature.effects,
+            &signature.where_clause,
+            &signature.contracts,
+        );
+    }
+
+    /// 签名中从 async fn 到参数列表的部分
+    fn signature_head(
+        &mut self,
+        is_async: bool,
+        name: &str,
+        generics: &[GenericParameter],
+        parameters: &[Parameter],
+    ) {
+        if is_async {
+            self.write("async ");
+        }
+        self.write("fn ");
+        self.write(name);
+        self.generics(generics);
+        self.delimited(("(", ")"), parameters.len(), true, |printer, index| {
+            printer.parameter(&parameters[index]);
+        });
+    }
+
+    /// 签名中参数列表之后的返回类型、效果行、where子句和契约子句
+    fn signature_tail(
+        &mut self,
+        return_type: Option<&str>,
+        effects: &EffectRow,
+        where_clause: &[WherePredicate],
+        contracts: &[ContractClause],
+    ) {
+        if let Some(return_type) = return_type {
+            self.write(" -> ");
+            self.write(return_type);
+        }
+        self.effects(effects);
+        self.where_clause(where_clause);
+        self.contracts(contracts);
+    }
+
+    fn trait_item(&mut self, item: &TraitItem) {
+        match item {
+            TraitItem::Method {
+                signature,
+                default_body,
+            } => {
+                self.signature(signature);
+                match default_body {
+                    Some(body) => {
+                        self.write(" ");
+                        self.block(body);
+                    }
+                    None => self.write(";"),
+                }
+            }
+            TraitItem::AssociatedType {
+                name,
+                bounds,
+                default,
+            } => {
+                self.write("type ");
+                self.write(name);
+                if !bounds.is_empty() {
+                    self.write(": ");
+                    self.write(&bounds.join(" + "));
+                }
+                if let Some(default) = default {
+                    self.write(" = ");
+                    self.write(default);
+                }
+                self.write(";");
+            }
+        }
+    }
+
+    fn generics(&mut self, generics: &[GenericParameter]) {
+        if generics.is_empty() {
+            return;
+        }
+
+        self.write("<");
+        self.list(generics, ", ", |printer, generic| {
+            printer.write(&generic.name);
+            if !generic.bounds.is_empty() {
+                printer.write(": ");
+                printer.write(&generic.bounds.join(" + "));
+            }
+        });
+        self.write(">");
+    }
+
+    fn where_clause(&mut self, predicates: &[WherePredicate]) {
+        if predicates.is_empty() {
+            return;
+        }
+
+        self.write(" where ");
+        self.list(predicates, ", ", |printer, predicate| {
+            printer.write(&predicate.bounded_type);
+            printer.write(": ");
+            printer.write(&predicate.bounds.join(" + "));
+        });
+    }
+
+    /// 参数：接收者写作 self、&self 和 &mut self
+    fn parameter(&mut self, parameter: &Parameter) {
+        if parameter.name == "self" {
+            match parameter.type_annotation.as_deref() {
+                Some("Self") => return self.write("self"),
+                Some("&Self") => return self.write("&self"),
+                Some("&mut Self") => return self.write("&mut self"),
+                _ => {}
+            }
+        }
+
+        self.write(&parameter.name);
+        if let Some(type_annotation) = &parameter.type_annotation {
+            self.write(": ");
+            self.write(type_annotation);
+        }
+    }
+
+    /// 函数和闭包的效果行，没有效果时什么也不打印
+    fn effects(&mut self, row: &EffectRow) {
+        if row.effects.is_empty() && row.row_variable.is_none() {
+            return;
+        }
+
+        self.effect_list(
+            " effects",
+            &row.effects,
+            &row.excluded,
+            row.row_variable.as_deref(),
+        );
+    }
+
+    /// lead 之后的效果列表。差集作用于整行，都写在最后一个效果之后，行变量写在最后
+    fn effect_list(
+        &mut self,
+        lead: &str,
+        effects: &[EffectName],
+        excluded: &[EffectName],
+        row_variable: Option<&str>,
+    ) {
+        let mut elements: Vec<String> = effects.iter().map(ToString::to_string).collect();
+        if let Some(row_variable) = row_variable {
+            elements.push(format!("..{}", row_variable));
+        }
+        // `IO, Log - Network`、`Io - Network, ..e`
+        let last = effects.len().max(1) - 1;
+        if let Some(element) = elements.get_mut(last) {
+            for effect in excluded {
+                element.push_str(" - ");
+                element.push_str(&effect.to_string());
+            }
+        }
+        self.separated(lead, &elements);
+    }
+
+    fn contracts(&mut self, clauses: &[ContractClause]) {
+        for clause in clauses {
+            self.write(" ");
+            // 子句之后是下一个子句的关键字或声明体的 {
+            let context = Context {
+                no_struct_literal: true,
+                ..Context::NESTED
+            };
+            self.contract_clause(clause, context.followed(0));
+        }
+    }
+
+    fn contract_clause(&mut self, clause: &ContractClause, context: Context) {
+        let (keyword, condition) = match clause {
+            ContractClause::Requires(condition) => ("requires ", condition),
+            ContractClause::Ensures(condition) => ("ensures ", condition),
+            ContractClause::Invariant(condition) => ("invariant ", condition),
+            ContractClause::Named(name) => {
+                self.write("contract ");
+                return self.write(name);
+            }
+        };
+        self.write(keyword);
+        self.expr(condition, context);
+    }
+
+    /// 元组结构体和枚举变体的字段，写在一行之内
+    fn fields(&mut self, fields: &StructFields) {
+        match fields {
+            StructFields::Named(fields) if fields.is_empty() => self.write("{}"),
+            StructFields::Named(fields) => {
+                self.write("{ ");
+                self.list(fields, ", ", |printer, field| {
+                    printer.visibility(field.visibility);
+                    printer.write(&field.name);
+                    printer.write(": ");
+                    printer.write(&field.field_type);
+                });
+                self.write(" }");
+            }
+            StructFields::Tuple(fields) => {
+                self.write("(");
+                self.list(fields, ", ", |printer, field| {
+                    printer.visibility(field.visibility);
+                    printer.write(&field.field_type);
+                });
+                self.write(")");
+            }
+            StructFields::Unit => {}
+        }
+    }
+
+    fn use_tree(&mut self, tree: &UseTree) {
+        match tree {
+            UseTree::Simple { path, alias } => {
+                self.write(&join(path, "::"));
+                if let Some(alias) = alias {
+                    self.write(" as ");
+                    self.write(alias);
+                }
+            }
+            UseTree::Glob { path } => {
+                self.write(&join(path, "::"));
+                self.write("::*");
+            }
+            UseTree::Group { path, items } => {
+                if !path.is_empty() {
+                    self.write(&join(path, "::"));
+                    self.write("::");
+                }
+                self.write("{");
+                self.list(items, ", ", Self::use_tree);
+                self.write("}");
+            }
+        }
+    }
+
+    /// handle 关键字开始的处理器，owner 是处理器声明开始的位置
+    fn handler(&mut self, handler: &HandlerDeclaration, owner: Option<Position>) {
+        self.write("handle ");
+        self.write(&handler.effect.to_string());
+        if let Some(name) = &handler.name {
+            self.write(" as ");
+            self.write(name);
+        }
+        self.write(" ");
+        // 成员依次是操作子句、return 子句和 finally 子句
+        let clauses = &handler.clauses;
+        let count = clauses.len()
+            + usize::from(handler.return_clause.is_some())
+            + usize::from(handler.finally_clause.is_some());
+        self.members(owner, count, |printer, index| {
+            if let Some(clause) = clauses.get(index) {
+                printer.write(&clause.operation);
+                printer.write("(");
+                printer.list(&clause.parameters, ", ", Self::parameter);
+                // 名称解析阶段移出的续延仍然写作最后一个参数
+                if let Some(continuation) = &clause.continuation {
+                    if !clause.parameters.is_empty() {
+                        printer.write(", ");
+                    }
+                    printer.write(continuation);
+                }
+                printer.write(") ");
+                printer.block(&clause.body);
+            } else if let Some(return_clause) = handler
+                .return_clause
+                .as_ref()
+                .filter(|_| index == clauses.len())
+            {
+                printer.write("return(");
+                printer.parameter(&return_clause.parameter);
+                printer.write(") ");
+                printer.block(&return_clause.body);
+            } else if let Some(finally_clause) = &handler.finally_clause {
+                printer.write("finally ");
+                printer.block(finally_clause);
+            }
+        });
+    }
+
+    /// 在括号中打印表达式，括号内没有任何限制
+    fn parenthesized(&mut self, expr: &Expr) {
+        self.write("(");
+        self.expr(expr, Context::NESTED);
+        self.write(")");
+    }
+
+    /// 以逗号分隔、位于括号或方括号内的表达式列表
+    fn exprs(&mut self, delimiters: (&str, &str), exprs: &[Expr]) {
+        self.delimited(delimiters, exprs.len(), true, |printer, index| {
+            printer.expr(&exprs[index], Context::NESTED)
+        });
+    }
+
+    fn expr(&mut self, expr: &Expr, context: Context) {
+        let needs_parentheses = precedence(expr) < context.precedence
+            || (!context.trailing && is_open_ended(expr))
+            || (context.no_struct_literal && is_struct_literal(expr));
+        if needs_parentheses {
+            return self.parenthesized(expr);
+        }
+
+        match expr {
+            Expr::Literal { value, .. } => self.literal(value),
+            Expr::Identifier { name, .. } => self.write(name),
+            Expr::Path { segments, .. } => self.list(segments, "::", |printer, segment| {
+                printer.write(segment);
+            }),
+            Expr::Binary {
+                left,
+                operator,
+                right,
+                ..
+            } => {
+                let entry = operator::binary(operator);
+                // 两侧按运算符表中的结合性加括号；`x as T < y` 中的 < 会被当作类型的泛型参数
+                let left_precedence = if *operator == BinaryOperator::Less && ends_with_cast(left) {
+                    PRECEDENCE_POSTFIX
+                } else {
+                    entry.left_binding_power()
+                };
+                self.expr(left, context.followed(left_precedence));
+                self.write(" ");
+                self.write(&operator.to_string());
+                self.write(" ");
+                self.expr(right, context.with_precedence(entry.right_binding_power()));
+            }
+            Expr::Assign {
+                target,
+                operator,
+                value,
+                ..
+            } => {
+                self.expr(target, context.followed(PRECEDENCE_ASSIGNMENT + 1));
+                self.write(" ");
+                self.write(&operator.to_string());
+                self.write(" ");
+                // 赋值是右结合的
+                self.expr(value, context.with_precedence(PRECEDENCE_ASSIGNMENT));
+            }
+            Expr::Unary {
+                operator, operand, ..
+            } => {
+                self.write(&operator.to_string());
+                if *operator == UnaryOperator::MutableReference {
+                    self.write(" ");
+                }
+                self.expr(operand, context.with_precedence(PRECEDENCE_PREFIX));
+            }
+            Expr::Cast {
+                expression,
+                target_type,
+                ..
+            } => {
+                self.expr(expression, context.followed(PRECEDENCE_CAST));
+                self.write(" as ");
+                self.write(target_type);
+            }
+            Expr::Range {
+                start,
+                end,
+                inclusive,
+                ..
+            } => {
+                if let Some(start) = start {
+                    self.expr(start, context.followed(PRECEDENCE_RANGE + 1));
+                }
+                self.write(if *inclusive { "..=" } else { ".." });
+                if let Some(end) = end {
+                    // 禁止结构体字面量的位置上，`..` 后面的 { 不会被当作区间的终点
+                    if context.no_struct_literal && matches!(leftmost(end), Expr::Block { .. }) {
+                        self.parenthesized(end);
+                    } else {
+                        self.expr(end, context.with_precedence(PRECEDENCE_RANGE + 1));
+                    }
+                }
+            }
+            Expr::Call {
+                function,
+                arguments,
+                ..
+            } => {
+                // `(a.f)(x)` 去掉括号会变成方法调用 `a.f(x)`
+                if let Expr::Field { field, .. } = &**function
+                    && !field.starts_with(|c: char| c.is_ascii_digit())
+                {
+                    self.parenthesized(function);
+                } else {
+                    self.expr(function, context.followed(PRECEDENCE_POSTFIX));
+                }
+                self.exprs(("(", ")"), arguments);
+            }
+            Expr::Grouping { expression, .. } => self.parenthesized(expression),
+            // 只有一个元素的元组总是写在一行之内
+            Expr::Tuple { elements, .. } if elements.len() == 1 => {
+                self.write("(");
+                self.expr(&elements[0], Context::NESTED);
+                self.write(",)");
+            }
+            Expr::Tuple { elements, .. } => self.exprs(("(", ")"), elements),
+            Expr::Array { elements, .. } => self.exprs(("[", "]"), elements),
+            Expr::ArrayRepeat { value, count, .. } => {
+                self.write("[");
+                self.expr(value, Context::NESTED);
+                self.write("; ");
+                self.expr(count, Context::NESTED);
+                self.write("]");
+            }
+            Expr::Async { statements, .. } => {
+                self.write("async ");
+                self.block(statements);
+            }
+            Expr::TryBlock { statements, .. } => {
+                self.write("try ");
+                self.block(statements);
+            }
+            Expr::Block { statements, .. } => self.block(statements),
+            Expr::Field { object, field, .. } => {
+                self.expr(object, context.followed(PRECEDENCE_POSTFIX));
+                // 整数字面量后紧跟 .0 会被识别为浮点数 1.0
+                if let Expr::Literal {
+                    value: Literal::Integer(_),
+                    ..
+                } = object.as_ref()
+                    && field.starts_with(|c: char| c.is_ascii_digit())
+                {
+                    self.write(" ");
+                }
+                self.write(".");
+                self.write(field);
+            }
+            Expr::MethodCall {
+                receiver,
+                method,
+                arguments,
+                ..
+            } => {
+                self.expr(receiver, context.followed(PRECEDENCE_POSTFIX));
+                self.write(".");
+                self.write(method);
+                self.exprs(("(", ")"), arguments);
+            }
+            Expr::Index { object, index, .. } => {
+                self.expr(object, context.followed(PRECEDENCE_POSTFIX));
+                self.write("[");
+                self.expr(index, Context::NESTED);
+                self.write("]");
+            }
+            Expr::Try { expression, .. } => {
+                self.expr(expression, context.followed(PRECEDENCE_POSTFIX));
+                self.write("?");
+            }
+            Expr::Await { expression, .. } => {
+                self.expr(expression, context.followed(PRECEDENCE_POSTFIX));
+                self.write(".await");
+            }
+            Expr::StructLiteral {
+                path, fields, base, ..
+            } => {
+                self.list(path, "::", |printer, segment| printer.write(segment));
+                self.write(" ");
+                self.struct_fields(fields, base.as_deref());
+            }
+            Expr::EnumConstructor {
+                enum_name,
+                variant,
+                arguments,
+                ..
+            } => {
+                self.write(enum_name);
+                self.write("::");
+                self.write(variant);
+                match arguments {
+                    VariantArguments::Unit => {}
+                    VariantArguments::Tuple(arguments) => {
+                        self.exprs(("(", ")"), arguments);
+                    }
+                    VariantArguments::Named(fields) => {
+                        self.write(" ");
+                        self.struct_fields(fields, None);
+                    }
+                }
+            }
+            Expr::With { handlers, body, .. } => {
+                self.write("with ");
+                self.list(handlers, ", ", |printer, handler| match handler {
+                    HandlerReference::Named(name) => printer.write(name),
+                    HandlerReference::Inline(handler) => printer.handler(handler, None),
+                });
+                self.write(" ");
+                self.block(body);
+            }
+            Expr::Resume { value, .. } => {
+                self.write("resume!(");
+                if let Some(value) = value {
+                    self.expr(value, Context::NESTED);
+                }
+                self.write(")");
+            }
+            Expr::Old { expression, .. } => {
+                self.write("old(");
+                self.expr(expression, Context::NESTED);
+                self.write(")");
+            }
+            Expr::Result { .. } => self.write("result"),
+            Expr::Closure {
+                is_move,
+                parameters,
+                return_type,
+                effects,
+                body,
+                ..
+            } => {
+                if *is_move {
+                    self.write("move ");
+                }
+                self.write("|");
+                self.list(parameters, ", ", Self::parameter);
+                self.write("|");
+                if let Some(return_type) = return_type {
+                    self.write(" -> ");
+                    self.write(return_type);
+                }
+                self.effects(effects);
+                self.write(" ");
+                // 没有标注的闭包体一直延伸到最右侧；有标注时闭包体是代码块
+                self.expr(body, context.with_precedence(0));
+            }
+            Expr::EffectOperation {
+                effect,
+                operation,
+                arguments,
+                ..
+            } => match arguments.as_slice() {
+                // 名称解析阶段把 x.await 改写为内置 Async 效果的 await 操作，
+                // 把 e? 改写为内置 Exception 效果的 propagate 操作
+                [future] if *effect == "Async" && *operation == "await" => {
+                    self.expr(future, context.followed(PRECEDENCE_POSTFIX));
+                    self.write(".await");
+                }
+                [result] if *effect == "Exception" && *operation == "propagate" => {
+                    self.expr(result, context.followed(PRECEDENCE_POSTFIX));
+                    self.write("?");
+                }
+                _ => {
+                    self.write(effect);
+                    self.write(".");
+                    self.write(operation);
+                    self.exprs(("(", ")"), arguments);
+                }
+            },
+        }
+    }
+
+    /// 结构体字面量和结构体变体的字段部分，值与字段同名时使用简写形式
+    fn struct_fields(&mut self, fields: &[(crate::intern::Symbol, Expr)], base: Option<&Expr>) {
+        if fields.is_empty() && base.is_none() {
+            self.write("{}");
+            return;
+        }
+
+        // ..base 之后不能有逗号
+        let count = fields.len() + usize::from(base.is_some());
+        self.delimited(
+            ("{ ", " }"),
+            count,
+            base.is_none(),
+            |printer, index| match (fields.get(index), base) {
+                (Some((field, value)), _) => {
+                    printer.write(field);
+                    if !matches!(value, Expr::Identifier { name, .. } if name == field) {
+                        printer.write(": ");
+                        printer.expr(value, Context::NESTED);
+                    }
+                }
+                (None, Some(base)) => {
+                    printer.write("..");
+                    printer.expr(base, Context::NESTED);
+                }
+                (None, None) => {}
+            },
+        );
+    }
+
+    fn literal(&mut self, literal: &Literal) {
+        match literal {
+            // 词法分析器以下划线连接数字和类型后缀 (1u32 记为 1_u32)
+            Literal::Integer(value) | Literal::Float(value) => match value.split_once('_') {
+                Some((digits, suffix)) => {
+                    self.write(digits);
+                    self.write(suffix);
+                }
+                None => self.write(value),
+            },
+            Literal::String(value) => {
+                self.write("\"");
+                for c in value.chars() {
+                    match c {
+                        '"' => self.write("\\\""),
+                        c => self.escaped_char(c),
+                    }
+                }
+                self.write("\"");
+            }
+            Literal::Char(value) => {
+                self.write("'");
+                match value {
+                    '\'' => self.write("\\'"),
+                    c => self.escaped_char(*c),
+                }
+                self.write("'");
+            }
+            Literal::Boolean(value) => self.write(if *value { "true" } else { "false" }),
+        }
+    }
+
+    /// 字符串和字符字面量共用的转义序列
+    fn escaped_char(&mut self, c: char) {
+        match c {
+            '\n' => self.write("\\n"),
+            '\r' => self.write("\\r"),
+            '\t' => self.write("\\t"),
+            '\0' => self.write("\\0"),
+            '\\' => self.write("\\\\"),
+            c => self.out.push(c),
+        }
+    }
+}
+
+/// 语句开始的位置
+fn position(stmt: &Stmt) -> Position {
+    let location = stmt.location();
+    (location.line, location.column)
+}
+
+/// 表达式作为一个整体的优先级
+fn precedence(expr: &Expr) -> u8 {
+    match expr {
+        Expr::Assign { .. } => PRECEDENCE_ASSIGNMENT,
+        Expr::Range { start: Some(_), .. } => PRECEDENCE_RANGE,
+        Expr::Binary { operator, .. } => operator.precedence(),
+        Expr::Cast { .. } => PRECEDENCE_CAST,
+        Expr::Unary { .. } | Expr::Range { start: None, .. } | Expr::Closure { .. } => {
+            PRECEDENCE_PREFIX
+        }
+        _ => PRECEDENCE_POSTFIX,
+    }
+}
+
+/// 表达式会吞并其后的记号：没有标注的闭包、前缀范围和没有终点的范围
+fn is_open_ended(expr: &Expr) -> bool {
+    match expr {
+        Expr::Closure {
+            return_type,
+            effects,
+            ..
+        } => return_type.is_none() && effects.effects.is_empty() && effects.row_variable.is_none(),
+        Expr::Range { start, end, .. } => start.is_none() || end.is_none(),
+        _ => false,
+    }
+}
+
+fn is_struct_literal(expr: &Expr) -> bool {
+    matches!(
+        expr,
+        Expr::StructLiteral { .. }
+            | Expr::EnumConstructor {
+                arguments: VariantArguments::Named(_),
+                ..
+            }
+    )
+}
+
+/// 表达式打印出来是否以 `as 类型` 结尾
+fn ends_with_cast(expr: &Expr) -> bool {
+    match expr {
+        Expr::Cast { .. } => true,
+        Expr::Binary { right, .. } => ends_with_cast(right),
+        _ => false,
+    }
+}
+
+/// 打印出来的表达式最左侧的子表达式
+fn leftmost(expr: &Expr) -> &Expr {
+    match expr {
+        Expr::Binary { left: inner, .. }
+        | Expr::Assign { target: inner, .. }
+        | Expr::Cast {
+            expression: inner, ..
+        }
+        | Expr::Range {
+            start: Some(inner), ..
+        }
+        | Expr::Call {
+            function: inner, ..
+        }
+        | Expr::Field { object: inner, .. }
+        | Expr::MethodCall {
+            receiver: inner, ..
+        }
+        | Expr::Index { object: inner, .. }
+        | Expr::Try {
+            expression: inner, ..
+        }
+        | Expr::Await {
+            expression: inner, ..
+        } => leftmost(inner),
+        _ => expr,
+    }
+}
+
+/// 以 separator 连接各项，例如路径 `a::b` 和约束 `A + B`
+fn join<S: AsRef<str>>(items: &[S], separator: &str) -> String {
+    items
+        .iter()
+        .map(AsRef::as_ref)
+        .collect::<Vec<&str>>()
+        .join(separator)
+}
@@ -0,0 +1,1224 @@
+//! 名称解析模块
+//!
+//! 在语法分析之后对AST进行依赖名称的改写。
+//! 语法分析器只看到记号，无法区分 `FileSystem.read_file(path)` 这样的效果操作调用
+//! 和 `file.read(buffer)` 这样的普通方法调用，两者都被解析为 `Expr::MethodCall`，
+//! 由本模块根据程序中声明的效果名称进行区分。
+//! 枚举构造同理：`Shape::Circle(r)` 在语法上与普通函数调用相同，
+//! 只有知道 `Shape` 是已声明的枚举之后才能改写为 `Expr::EnumConstructor`。
+//! `with` 表达式按名称引用的处理器和处理器组也在这里展开为被处理的效果。
+//! 处理器子句需要对照效果声明才能检查参数，同样在这里完成。
+//! 效果组和处理器组可以互相嵌套，在这里展开为成员效果并检查循环引用和未知名称。
+//! 闭包捕获哪些外部变量、以何种方式捕获，也需要知道局部变量的作用域，在这里确定。
+
+use crate::data::{Location, Span, Token};
+use crate::intern::Symbol;
+use crate::lex::Lexer;
+use crate::parser::{
+    Capture, CaptureMode, ContractClause, EffectDeclaration, EffectName, Expr, HandlerClause,
+    HandlerDeclaration, HandlerReference, Parser, Stmt, TraitItem, UnaryOperator, VariantArguments,
+};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::io::BufReader;
+
+/// 内置的异步效果：async fn 和 async 块执行该效果，`.await` 是它的 await 操作
+pub const ASYNC_EFFECT: &str = "Async";
+/// 内置的异常效果：`try` 块为其块体安装该效果的处理器，`e?` 是它的 propagate 操作
+pub const EXCEPTION_EFFECT: &str = "Exception";
+
+/// 名称解析错误
+#[derive(Debug, Clone, PartialEq)]
+pub enum ResolveError {
+    /// 处理器子句处理的操作不在效果声明中
+    UnknownOperation {
+        span: Span,
+        effect: String,
+        operation: String,
+    },
+    /// 处理器子句的参数个数与操作声明不一致（续延参数不计入）
+    ParameterCountMismatch {
+        span: Span,
+        operation: String,
+        expected: usize,
+        found: usize,
+    },
+    /// 处理器子句的参数类型标注与操作声明不一致
+    ParameterTypeMismatch {
+        span: Span,
+        operation: String,
+        parameter: String,
+        expected: String,
+        found: String,
+    },
+    /// 实例化泛型效果时类型实参的个数与效果声明不一致
+    TypeArgumentCountMismatch {
+        span: Span,
+        effect: String,
+        expected: usize,
+        found: usize,
+    },
+    /// 效果行或效果组引用了未声明的效果或效果组
+    UnknownEffect {
+        span: Span,
+        name: String,
+    },
+    /// with表达式或处理器组引用了未声明的处理器或处理器组
+    UnknownHandler {
+        span: Span,
+        name: String,
+    },
+    /// 效果组或处理器组循环引用自身，cycle 的首尾是同一个组
+    GroupCycle {
+        span: Span,
+        cycle: Vec<String>,
+    },
+}
+
+impl ResolveError {
+    /// 错误在源代码中的区间
+    pub fn span(&self) -> Span {
+        match self {
+            ResolveError::UnknownOperation { span, .. }
+            | ResolveError::ParameterCountMismatch { span, .. }
+            | ResolveError::ParameterTypeMismatch { span, .. }
+            | ResolveError::TypeArgumentCountMismatch { span, .. }
+            | ResolveError::UnknownEffect { span, .. }
+            | ResolveError::UnknownHandler { span, .. }
+            | ResolveError::GroupCycle { span, .. } => *span,
+        }
+    }
+}
+
+impl fmt::Display for ResolveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let span = self.span();
+        write!(f, "{}:{}:{}: ", span.file, span.line, span.column)?;
+        match self {
+            ResolveError::UnknownOperation {
+                effect, operation, ..
+            } => write!(f, "Effect '{}' has no operation '{}'", effect, operation),
+            ResolveError::ParameterCountMismatch {
+                operation,
+                expected,
+                found,
+                ..
+            } => write!(
+                f,
+                "Handler clause '{}' expects {} parameter(s), found {}",
+                operation, expected, found
+            ),
+            ResolveError::ParameterTypeMismatch {
+                operation,
+                parameter,
+                expected,
+                found,
+                ..
+            } => write!(
+                f,
+                "Parameter '{}' of handler clause '{}' has type '{}', but the operation declares '{}'",
+                parameter, operation, found, expected
+            ),
+            ResolveError::TypeArgumentCountMismatch {
+                effect,
+                expected,
+                found,
+                ..
+            } => write!(
+                f,
+                "Effect '{}' expects {} type argument(s), found {}",
+                effect, expected, found
+            ),
+            ResolveError::UnknownEffect { name, .. } => {
+                write!(f, "Unknown effect or effect group '{}'", name)
+            }
+            ResolveError::UnknownHandler { name, .. } => {
+                write!(f, "Unknown handler or handler group '{}'", name)
+            }
+            ResolveError::GroupCycle { cycle, .. } => {
+                write!(
+                    f,
+                    "Group '{}' refers to itself: {}",
+                    cycle[0],
+                    cycle.join(" -> ")
+                )
+            }
+        }
+    }
+}
+
+/// 把依赖名称的表达式改写为对应的AST节点
+///
+/// 效果操作调用改写为 `Expr::EffectOperation`，识别以下写法：
+/// - `FileSystem.read_file(path)`：接收者是效果名
+/// - `FileSystem::read_file(path)`、`std::io::Console::print(s)`：路径的倒数第二段是效果名
+/// - `use std::io::Console as Out;` 之后的 `Out.print(s)`：通过use导入的效果
+///
+/// 枚举构造改写为 `Expr::EnumConstructor`，识别以下写法：
+/// - `Shape::Empty`：单元变体
+/// - `Shape::Circle(r)`：元组变体
+/// - `Shape::Rect { w, h }`：结构体变体
+/// - `use Shape::Circle;` 之后的 `Circle(r)`：通过use导入的变体
+///
+/// `Expr::With` 的 `discharged_effects` 补全按名称引用的处理器（`handle E as Name`）
+/// 和处理器组（递归展开）所处理的效果
+///
+/// 效果组和函数签名的效果行展开嵌套的效果组并减去差集，结果写入 `flattened_effects`；
+/// async fn 的展开结果包含内置的 `Async` 效果。处理器组展开后处理的效果写入 `discharged_effects`
+///
+/// `x.await` 改写为内置 `Async` 效果的 await 操作调用，`e?` 改写为内置 `Exception` 效果的
+/// propagate 操作调用，与其他效果操作一样由处理器处理。async 块在 `effects` 中记录 `Async`，
+/// try 块在 `discharged_effects` 中记录它处理的 `Exception`
+///
+/// 闭包的 `captures` 记录闭包体使用的外部局部变量：move 闭包全部按移动捕获，
+/// 其他闭包对赋值目标和 `&mut` 的操作数按可变借用捕获，其余按共享借用捕获
+///
+/// 处理器子句对照效果声明检查参数个数和类型标注，多出的最后一个参数作为续延
+/// 移入 `HandlerClause::continuation`。发现的问题作为错误列表返回
+pub fn resolve_names(statements: &mut [Stmt]) -> Vec<ResolveError> {
+    let mut scope = NameScope::default();
+    scope.collect(statements);
+
+    let mut resolver = Resolver {
+        scope: &scope,
+        errors: Vec::new(),
+        flattened_effect_groups: HashMap::new(),
+        flattened_handler_groups: HashMap::new(),
+        expanding: Vec::new(),
+        cyclic_groups: HashSet::new(),
+        locals: Vec::new(),
+        closures: Vec::new(),
+    };
+    for statement in statements {
+        resolver.resolve_stmt(statement);
+    }
+    resolver.errors
+}
+
+/// 对AST进行改写并收集错误的名称解析器
+struct Resolver<'s> {
+    scope: &'s NameScope,
+    errors: Vec<ResolveError>,
+    /// 已展开的效果组到其包含的效果的映射
+    flattened_effect_groups: HashMap<String, Vec<EffectName>>,
+    /// 已展开的处理器组到其处理的效果的映射
+    flattened_handler_groups: HashMap<String, Vec<EffectName>>,
+    /// 正在展开的组，用于检测循环引用
+    expanding: Vec<String>,
+    /// 已经报告过循环引用的组，每个循环只报告一次
+    cyclic_groups: HashSet<String>,
+    /// 当前位置可见的局部变量，内层作用域的在后
+    locals: Vec<Symbol>,
+    /// 正在解析的闭包，内层的在后
+    closures: Vec<ClosureFrame>,
+}
+
+/// 正在解析的闭包
+struct ClosureFrame {
+    /// 闭包开始时 locals 的长度，此前的局部变量都在闭包外部
+    locals_start: usize,
+    is_move: bool,
+    captures: Vec<Capture>,
+}
+
+/// 效果组或处理器组的成员和声明位置，效果组的成员是效果，处理器组的成员是处理器名称
+struct GroupInfo<M> {
+    members: Vec<M>,
+    /// 从组中减去的效果，处理器组没有差集
+    excluded: Vec<M>,
+    span: Span,
+}
+
+/// 程序中可见的效果、枚举和处理器名称
+#[derive(Default)]
+struct NameScope {
+    /// 声明的效果名称到其声明的映射
+    effects: HashMap<String, EffectDeclaration>,
+    /// 声明的枚举名称到其变体名称的映射
+    enums: HashMap<String, HashSet<Symbol>>,
+    /// 具名处理器到其处理的效果的映射
+    handlers: HashMap<String, EffectName>,
+    /// 效果组名称到其成员的映射
+    effect_groups: HashMap<String, GroupInfo<EffectName>>,
+    /// 处理器组名称到其成员的映射
+    handler_groups: HashMap<String, GroupInfo<Symbol>>,
+    /// use导入的本地名称到完整路径的映射
+    imports: HashMap<String, Vec<Symbol>>,
+}
+
+impl NameScope {
+    /// 收集程序中声明的效果、枚举、处理器和use导入（包括嵌套在块和函数体中的声明）
+    fn collect(&mut self, statements: &[Stmt]) {
+        for statement in statements {
+            match statement {
+                Stmt::Effect { declaration, .. } => {
+                    self.effects
+                        .insert(declaration.name.to_string(), declaration.clone());
+                }
+                Stmt::Enum { declaration, .. } => {
+                    let variants = declaration
+                        .variants
+                        .iter()
+                        .map(|variant| variant.name)
+                        .collect();
+                    self.enums.insert(declaration.name.to_string(), variants);
+                }
+                Stmt::Use { declaration, .. } => {
+                    self.imports.extend(
+                        declaration
+                            .tree
+                            .bindings()
+                            .into_iter()
+                            .map(|(name, path)| (name.to_string(), path)),
+                    );
+                }
+                Stmt::Function { body, .. } => self.collect(body),
+                Stmt::Block { statements, .. } => self.collect(statements),
+                Stmt::Handler { declaration, .. } => {
+                    if let Some(name) = &declaration.name {
+                        self.handlers
+                            .insert(name.to_string(), declaration.effect.clone());
+                    }
+                    self.collect_handler(declaration);
+                }
+                Stmt::EffectGroup {
+                    span,
+                    declaration,
+                    ..
+                } => {
+                    let group = GroupInfo {
+                        members: declaration.effects.clone(),
+                        excluded: declaration.excluded.clone(),
+                        span: *span,
+                    };
+                    self.effect_groups
+                        .insert(declaration.name.to_string(), group);
+                }
+                Stmt::HandlerGroup {
+                    span,
+                    declaration,
+                    ..
+                } => {
+                    let group = GroupInfo {
+                        members: declaration.handlers.clone(),
+                        excluded: Vec::new(),
+                        span: *span,
+                    };
+                    self.handler_groups
+                        .insert(declaration.name.to_string(), group);
+                }
+                Stmt::Expression {
+                    expression: Expr::With { handlers, body, .. },
+                    ..
+                } => {
+                    for handler in handlers {
+                        if let HandlerReference::Inline(declaration) = handler {
+                            self.collect_handler(declaration);
+                        }
+                    }
+                    self.collect(body);
+                }
+                Stmt::Trait { declaration, .. } => {
+                    for item in &declaration.items {
+                        if let TraitItem::Method {
+                            default_body: Some(body),
+                            ..
+                        } = item
+                        {
+                            self.collect(body);
+                        }
+                    }
+                }
+                Stmt::Impl { declaration, .. } => self.collect(&declaration.items),
+                Stmt::Module { declaration, .. } => self.collect(declaration.body.items()),
+                Stmt::Expression { .. }
+                | Stmt::Let { .. }
+                | Stmt::Var { .. }
+                | Stmt::Error { .. }
+                | Stmt::Struct { .. }
+                | Stmt::TypeAlias { .. }
+                | Stmt::Contract { .. } => {}
+            }
+        }
+    }
+
+    /// 收集处理器各子句中的声明
+    fn collect_handler(&mut self, declaration: &HandlerDeclaration) {
+        for clause in &declaration.clauses {
+            self.collect(&clause.body);
+        }
+        if let Some(return_clause) = &declaration.return_clause {
+            self.collect(&return_clause.body);
+        }
+        if let Some(body) = &declaration.finally_clause {
+            self.collect(body);
+        }
+    }
+
+    /// 效果名称是否已知：内置效果、声明的效果、use导入的名称，或者带路径的外部效果
+    fn is_known_effect(&self, effect: &EffectName) -> bool {
+        let base = effect.name.as_str();
+        base == ASYNC_EFFECT
+            || base == EXCEPTION_EFFECT
+            || base.contains("::")
+            || self.effects.contains_key(base)
+            || self.imports.contains_key(base)
+    }
+
+    /// 处理器名称是否已知：声明的具名处理器、处理器组、use导入的名称，或者带路径的外部处理器
+    fn is_known_handler(&self, name: &str) -> bool {
+        name.contains("::")
+            || self.handlers.contains_key(name)
+            || self.handler_groups.contains_key(name)
+            || self.imports.contains_key(name)
+    }
+
+    /// 如果路径指向一个效果，返回该效果的完整名称
+    fn effect_for_path<S: AsRef<str>>(&self, path: &[S]) -> Option<String> {
+        let last = path.last()?.as_ref();
+
+        if path.len() == 1 && !self.effects.contains_key(last) {
+            // 单个名称可能是use导入的别名
+            let imported = self.imports.get(last)?;
+            return self
+                .effects
+                .contains_key(imported.last()?.as_str())
+                .then(|| join_path(imported));
+        }
+
+        self.effects.contains_key(last).then(|| join_path(path))
+    }
+
+    /// 如果路径指向一个枚举变体，返回枚举的完整名称和变体名称
+    fn variant_for_path<S: AsRef<str>>(&self, path: &[S]) -> Option<(String, String)> {
+        if path.len() == 1 {
+            // 单个名称只能是use导入的变体
+            let imported = self.imports.get(path[0].as_ref())?;
+            if imported.len() < 2 {
+                return None;
+            }
+            return self.variant_for_path(imported);
+        }
+
+        let (variant, prefix) = path.split_last()?;
+        let enum_name = self.enum_for_path(prefix)?;
+        let variant = variant.as_ref();
+        let variants = self.enums.get(enum_name.rsplit("::").next()?)?;
+        variants
+            .contains(&Symbol::intern(variant))
+            .then(|| (enum_name, variant.to_string()))
+    }
+
+    /// 如果路径指向一个枚举，返回该枚举的完整名称
+    fn enum_for_path<S: AsRef<str>>(&self, path: &[S]) -> Option<String> {
+        let last = path.last()?.as_ref();
+
+        if path.len() == 1 && !self.enums.contains_key(last) {
+            let imported = self.imports.get(last)?;
+            return self
+                .enums
+                .contains_key(imported.last()?.as_str())
+                .then(|| join_path(imported));
+        }
+
+        self.enums.contains_key(last).then(|| join_path(path))
+    }
+}
+
+/// 用 `::` 连接路径的各段
+/// 把 `x.await` 或 `e?` 改写为内置效果的操作调用，操作数是操作唯一的参数
+fn builtin_operation(expr: &mut Expr, effect: &str, operation: &str) {
+    let (Expr::Await {
+        id,
+        span,
+        expression,
+    }
+    | Expr::Try {
+        id,
+        span,
+        expression,
+    }) = expr
+    else {
+        return;
+    };
+    // 用单元值占位，取出操作数作为操作的参数
+    let argument = std::mem::replace(
+        &mut **expression,
+        Expr::Tuple {
+            id: *id,
+            span: *span,
+            elements: Vec::new(),
+        },
+    );
+    *expr = Expr::EffectOperation {
+        id: *id,
+        span: *span,
+        effect: Symbol::intern(effect),
+        operation: Symbol::intern(operation),
+        arguments: vec![argument],
+    };
+}
+
+fn join_path<S: AsRef<str>>(path: &[S]) -> String {
+    path.iter()
+        .map(AsRef::as_ref)
+        .collect::<Vec<&str>>()
+        .join("::")
+}
+
+impl Resolver<'_> {
+    fn resolve_stmt(&mut self, statement: &mut Stmt) {
+        match statement {
+            Stmt::Expression { expression, .. } => self.resolve_expr(expression),
+            Stmt::Let {
+                identifier,
+                initializer,
+                ..
+            }
+            | Stmt::Var {
+                identifier,
+                initializer,
+                ..
+            } => {
+                if let Some(initializer) = initializer {
+                    self.resolve_expr(initializer);
+                }
+                self.locals.push(*identifier);
+            }
+            Stmt::Function {
+                span,
+                is_async,
+                parameters,
+                effects,
+                contracts,
+                body,
+                ..
+            } => {
+                effects.flattened_effects =
+                    self.flatten_row(&effects.effects, &effects.excluded, *span);
+                if *is_async {
+                    push_unique(
+                        &mut effects.flattened_effects,
+                        EffectName::new(ASYNC_EFFECT),
+                    );
+                }
+
+                // 函数不捕获外部的局部变量
+                let outer_locals = std::mem::take(&mut self.locals);
+                let outer_closures = std::mem::take(&mut self.closures);
+                self.locals
+                    .extend(parameters.iter().map(|parameter| parameter.name));
+                self.resolve_contracts(contracts);
+                for statement in body {
+                    self.resolve_stmt(statement);
+                }
+                self.locals = outer_locals;
+                self.closures = outer_closures;
+            }
+            Stmt::Block { statements, .. } => self.resolve_block(statements),
+            Stmt::Handler {
+                span,
+                declaration,
+                ..
+            } => {
+                self.resolve_handler(declaration, *span);
+            }
+            Stmt::Trait {
+                span,
+                declaration,
+                ..
+            } => {
+                self.resolve_contracts(&mut declaration.contracts);
+                for item in &mut declaration.items {
+                    if let TraitItem::Method {
+                        signature,
+                        default_body,
+                    } = item
+                    {
+                        let effects = &mut signature.effects;
+                        effects.flattened_effects =
+                            self.flatten_row(&effects.effects, &effects.excluded, *span);
+                        if signature.is_async {
+                            push_unique(
+                                &mut effects.flattened_effects,
+                                EffectName::new(ASYNC_EFFECT),
+                            );
+                        }
+                        let scope_start = self.locals.len();
+                        self.locals
+                            .extend(signature.parameters.iter().map(|parameter| parameter.name));
+                        self.resolve_contracts(&mut signature.contracts);
+                        for statement in default_body.iter_mut().flatten() {
+                            self.resolve_stmt(statement);
+                        }
+                        self.locals.truncate(scope_start);
+                    }
+                }
+            }
+            Stmt::Struct { declaration, .. } => self.resolve_contracts(&mut declaration.contracts),
+            Stmt::Contract { declaration, .. } => self.resolve_contracts(&mut declaration.clauses),
+            Stmt::Impl { declaration, .. } => {
+                for statement in &mut declaration.items {
+                    self.resolve_stmt(statement);
+                }
+            }
+            Stmt::Module { declaration, .. } => {
+                // 模块中的声明看不到外部的局部变量
+                let outer_locals = std::mem::take(&mut self.locals);
+                let outer_closures = std::mem::take(&mut self.closures);
+                for statement in declaration.body.items_mut() {
+                    self.resolve_stmt(statement);
+                }
+                self.locals = outer_locals;
+                self.closures = outer_closures;
+            }
+            Stmt::EffectGroup { declaration, .. } => {
+                declaration.flattened_effects = self.flatten_effect_group(&declaration.name);
+            }
+            Stmt::HandlerGroup { declaration, .. } => {
+                declaration.discharged_effects = self.flatten_handler_group(&declaration.name);
+            }
+            Stmt::Effect { .. }
+            | Stmt::Use { .. }
+            | Stmt::Enum { .. }
+            | Stmt::TypeAlias { .. }
+            | Stmt::Error { .. } => {}
+        }
+    }
+
+    /// 展开效果行：嵌套的效果组替换为其成员，再减去 excluded 中的效果
+    ///
+    /// 未知的名称在 span 处报告，但仍保留在结果中
+    fn flatten_row(
+        &mut self,
+        members: &[EffectName],
+        excluded: &[EffectName],
+        span: Span,
+    ) -> Vec<EffectName> {
+        let mut effects = Vec::new();
+        for member in members {
+            self.expand_effect(member, span, &mut effects);
+        }
+
+        let mut removed = Vec::new();
+        for name in excluded {
+            self.expand_effect(name, span, &mut removed);
+        }
+        effects.retain(|effect| !removed.iter().any(|name| excludes(name, effect)));
+        effects
+    }
+
+    /// 把效果或效果组包含的效果追加到 effects 中，效果组没有类型实参
+    fn expand_effect(
+        &mut self,
+        effect: &EffectName,
+        span: Span,
+        effects: &mut Vec<EffectName>,
+    ) {
+        if effect.type_arguments.is_empty() && self.scope.effect_groups.contains_key(&*effect.name)
+        {
+            for effect in self.flatten_effect_group(&effect.name) {
+                push_unique(effects, effect);
+            }
+            return;
+        }
+
+        if !self.scope.is_known_effect(effect) {
+            self.errors.push(ResolveError::UnknownEffect {
+                span,
+                name: effect.to_string(),
+            });
+        }
+        push_unique(effects, effect.clone());
+    }
+
+    /// 展开效果组，返回其包含的全部效果，结果按组名缓存
+    fn flatten_effect_group(&mut self, name: &str) -> Vec<EffectName> {
+        let scope = self.scope;
+        let Some(group) = scope.effect_groups.get(name) else {
+            return Vec::new();
+        };
+        if let Some(effects) = self.flattened_effect_groups.get(name) {
+            return effects.clone();
+        }
+        if !self.enter_group(name, group) {
+            return Vec::new();
+        }
+
+        let effects = self.flatten_row(&group.members, &group.excluded, group.span);
+        self.expanding.pop();
+        self.flattened_effect_groups
+            .insert(name.to_string(), effects.clone());
+        effects
+    }
+
+    /// 展开处理器组，返回其成员处理的全部效果，结果按组名缓存
+    fn flatten_handler_group(&mut self, name: &str) -> Vec<EffectName> {
+        let scope = self.scope;
+        let Some(group) = scope.handler_groups.get(name) else {
+            return Vec::new();
+        };
+        if let Some(effects) = self.flattened_handler_groups.get(name) {
+            return effects.clone();
+        }
+        if !self.enter_group(name, group) {
+            return Vec::new();
+        }
+
+        let mut effects = Vec::new();
+        for member in &group.members {
+            self.discharged_effects(member, group.span, &mut effects);
+        }
+        self.expanding.pop();
+        self.flattened_handler_groups
+            .insert(name.to_string(), effects.clone());
+        effects
+    }
+
+    /// 把按名称引用的处理器或处理器组处理的效果追加到 effects 中
+    fn discharged_effects(
+        &mut self,
+        name: &str,
+        span: Span,
+        effects: &mut Vec<EffectName>,
+    ) {
+        if let Some(effect) = self.scope.handlers.get(name) {
+            push_unique(effects, effect.clone());
+        } else if self.scope.handler_groups.contains_key(name) {
+            for effect in self.flatten_handler_group(name) {
+                push_unique(effects, effect);
+            }
+        } else if !self.scope.is_known_handler(name) {
+            self.errors.push(ResolveError::UnknownHandler {
+                span,
+                name: name.to_string(),
+            });
+        }
+    }
+
+    /// 开始展开一个组。如果该组已经在展开中，说明存在循环引用：
+    /// 在组的声明处报告错误（同一个循环只报告一次）并返回 false
+    fn enter_group<M>(&mut self, name: &str, group: &GroupInfo<M>) -> bool {
+        let Some(start) = self.expanding.iter().position(|group| group == name) else {
+            self.expanding.push(name.to_string());
+            return true;
+        };
+
+        let mut cycle = self.expanding[start..].to_vec();
+        cycle.push(name.to_string());
+        if !cycle.iter().any(|group| self.cyclic_groups.contains(group)) {
+            self.cyclic_groups.extend(cycle.iter().cloned());
+            self.errors.push(ResolveError::GroupCycle {
+                span: group.span,
+                cycle,
+            });
+        }
+        false
+    }
+
+    /// 对照效果声明检查处理器子句，并解析各子句的代码
+    ///
+    /// 泛型效果的处理器 (handle State<i32>) 先用类型实参替换操作签名中的类型参数再检查
+    fn resolve_handler(&mut self, declaration: &mut HandlerDeclaration, span: Span) {
+        let base = declaration.effect.name.as_str();
+        let type_arguments = &declaration.effect.type_arguments;
+        let segments: Vec<&str> = base.split("::").collect();
+        let effect = self
+            .scope
+            .effect_for_path(&segments)
+            .and_then(|effect| self.scope.effects.get(effect.rsplit("::").next()?));
+
+        // 未声明的效果无法检查，交给后续阶段报告
+        let substitutions = match effect {
+            Some(effect) if effect.generics.len() != type_arguments.len() => {
+                self.errors.push(ResolveError::TypeArgumentCountMismatch {
+                    span,
+                    effect: base.to_string(),
+                    expected: effect.generics.len(),
+                    found: type_arguments.len(),
+                });
+                None
+            }
+            Some(effect) => Some(
+                effect
+                    .generics
+                    .iter()
+                    .map(|parameter| parameter.name.as_str())
+                    .zip(type_arguments.iter().map(String::as_str))
+                    .collect::<HashMap<_, _>>(),
+            ),
+            None => None,
+        };
+
+        for clause in &mut declaration.clauses {
+            if let (Some(effect), Some(substitutions)) = (effect, &substitutions) {
+                self.check_handler_clause(effect, substitutions, clause);
+            }
+            let scope_start = self.locals.len();
+            self.locals
+                .extend(clause.parameters.iter().map(|parameter| parameter.name));
+            self.locals.extend(clause.continuation.iter().cloned());
+            self.resolve_block(&mut clause.body);
+            self.locals.truncate(scope_start);
+        }
+
+        if let Some(return_clause) = &mut declaration.return_clause {
+            self.locals.push(return_clause.parameter.name);
+            self.resolve_block(&mut return_clause.body);
+            self.locals.pop();
+        }
+        if let Some(body) = &mut declaration.finally_clause {
+            self.resolve_block(body);
+        }
+    }
+
+    /// 在新的局部作用域中解析代码块，块中声明的变量在块结束后不再可见
+    fn resolve_block(&mut self, statements: &mut [Stmt]) {
+        let scope_start = self.locals.len();
+        for statement in statements {
+            self.resolve_stmt(statement);
+        }
+        self.locals.truncate(scope_start);
+    }
+
+    /// 记录闭包对外部局部变量的使用
+    ///
+    /// 变量声明在哪些正在解析的闭包之外，就被这些闭包捕获；
+    /// 同一变量多次使用时取最强的捕获方式
+    fn capture(&mut self, name: &str, mode: CaptureMode) {
+        let Some(index) = self.locals.iter().rposition(|local| local == name) else {
+            return;
+        };
+
+        for closure in self.closures.iter_mut().rev() {
+            if closure.locals_start <= index {
+                break;
+            }
+
+            let mode = if closure.is_move {
+                CaptureMode::Move
+            } else {
+                mode
+            };
+            match closure
+                .captures
+                .iter_mut()
+                .find(|capture| capture.name == name)
+            {
+                Some(capture) if capture.mode == CaptureMode::Borrow => capture.mode = mode,
+                Some(_) => {}
+                None => closure.captures.push(Capture {
+                    name: Symbol::intern(name),
+                    mode,
+                }),
+            }
+        }
+    }
+
+    /// 检查处理器子句的参数，并识别续延参数
+    fn check_handler_clause(
+        &mut self,
+        effect: &EffectDeclaration,
+        substitutions: &HashMap<&str, &str>,
+        clause: &mut HandlerClause,
+    ) {
+        let Some(operation) = effect
+            .operations
+            .iter()
+            .find(|op| op.name == clause.operation)
+        else {
+            self.errors.push(ResolveError::UnknownOperation {
+                span: clause.span,
+                effect: effect.name.to_string(),
+                operation: clause.operation.to_string(),
+            });
+            return;
+        };
+
+        let expected = operation.parameters.len();
+        if clause.continuation.is_none() && clause.parameters.len() == expected + 1 {
+            let continuation = clause.parameters.pop().map(|parameter| parameter.name);
+            clause.continuation = continuation;
+        }
+
+        if clause.parameters.len() != expected {
+            self.errors.push(ResolveError::ParameterCountMismatch {
+                span: clause.span,
+                operation: clause.operation.to_string(),
+                expected,
+                found: clause.parameters.len(),
+            });
+            return;
+        }
+
+        for (parameter, (_, declared_type)) in clause.parameters.iter().zip(&operation.parameters) {
+            let declared_type = substitute_type_parameters(declared_type, substitutions);
+            if let Some(annotation) = &parameter.type_annotation
+                && *annotation != declared_type
+            {
+                self.errors.push(ResolveError::ParameterTypeMismatch {
+                    span: clause.span,
+                    operation: clause.operation.to_string(),
+                    parameter: parameter.name.to_string(),
+                    expected: declared_type,
+                    found: annotation.clone(),
+                });
+            }
+        }
+    }
+
+    fn resolve_contracts(&mut self, clauses: &mut [ContractClause]) {
+        for clause in clauses {
+            match clause {
+                ContractClause::Requires(condition)
+                | ContractClause::Ensures(condition)
+                | ContractClause::Invariant(condition) => self.resolve_expr(condition),
+                ContractClause::Named(_) => {}
+            }
+        }
+    }
+
+    fn resolve_expr(&mut self, expr: &mut Expr) {
+        match expr {
+            Expr::Literal { .. } | Expr::Result { .. } => {}
+            Expr::Identifier {
+                id,
+                span,
+                name,
+            } => {
+                if self.locals.contains(name) {
+                    // 局部变量遮蔽同名的枚举变体
+                    self.capture(name, CaptureMode::Borrow);
+                } else if let Some((enum_name, variant)) =
+                    self.scope.variant_for_path(std::slice::from_ref(name))
+                {
+                    *expr = Expr::EnumConstructor {
+                        id: *id,
+                        span: *span,
+                        enum_name: enum_name.into(),
+                        variant: variant.into(),
+                        arguments: VariantArguments::Unit,
+                    };
+                }
+            }
+            Expr::Path {
+                id,
+                span,
+                segments,
+            } => {
+                if let Some((enum_name, variant)) = self.scope.variant_for_path(segments) {
+                    *expr = Expr::EnumConstructor {
+                        id: *id,
+                        span: *span,
+                        enum_name: enum_name.into(),
+                        variant: variant.into(),
+                        arguments: VariantArguments::Unit,
+                    };
+                }
+            }
+            Expr::Binary { left, right, .. } => {
+                self.resolve_expr(left);
+                self.resolve_expr(right);
+            }
+            Expr::Assign { target, value, .. } => {
+                if let Some(name) = place_root(target) {
+                    self.capture(name, CaptureMode::BorrowMut);
+                }
+                self.resolve_expr(target);
+                self.resolve_expr(value);
+            }
+            Expr::Unary {
+                operator, operand, ..
+            } => {
+                if *operator == UnaryOperator::MutableReference
+                    && let Some(name) = place_root(operand)
+                {
+                    self.capture(name, CaptureMode::BorrowMut);
+                }
+                self.resolve_expr(operand)
+            }
+            Expr::Closure {
+                span,
+                is_move,
+                parameters,
+                effects,
+                body,
+                captures,
+                ..
+            } => {
+                effects.flattened_effects =
+                    self.flatten_row(&effects.effects, &effects.excluded, *span);
+
+                let locals_start = self.locals.len();
+                self.closures.push(ClosureFrame {
+                    locals_start,
+                    is_move: *is_move,
+                    captures: Vec::new(),
+                });
+                self.locals
+                    .extend(parameters.iter().map(|parameter| parameter.name));
+                self.resolve_expr(body);
+                self.locals.truncate(locals_start);
+                if let Some(closure) = self.closures.pop() {
+                    *captures = closure.captures;
+                }
+            }
+            Expr::Cast { expression, .. }
+            | Expr::Grouping { expression, .. }
+            | Expr::Old { expression, .. } => self.resolve_expr(expression),
+            Expr::Await { expression, .. } => {
+                self.resolve_expr(expression);
+                builtin_operation(expr, ASYNC_EFFECT, "await");
+            }
+            Expr::Try { expression, .. } => {
+                self.resolve_expr(expression);
+                builtin_operation(expr, EXCEPTION_EFFECT, "propagate");
+            }
+            Expr::Async {
+                statements,
+                effects,
+                ..
+            } => {
+                self.resolve_block(statements);
+                *effects = vec![EffectName::new(ASYNC_EFFECT)];
+            }
+            Expr::TryBlock {
+                statements,
+                discharged_effects,
+                ..
+            } => {
+                self.resolve_block(statements);
+                *discharged_effects = vec![EffectName::new(EXCEPTION_EFFECT)];
+            }
+            Expr::Resume { value, .. } => {
+                if let Some(value) = value {
+                    self.resolve_expr(value);
+                }
+            }
+            Expr::With {
+                id: _,
+                span,
+                handlers,
+                body,
+                discharged_effects,
+            } => {
+                for handler in handlers.iter_mut() {
+                    match handler {
+                        HandlerReference::Named(name) => {
+                            self.discharged_effects(name, *span, discharged_effects)
+                        }
+                        HandlerReference::Inline(declaration) => {
+                            self.resolve_handler(declaration, *span);
+                        }
+                    }
+                }
+                self.resolve_block(body);
+            }
+            Expr::Tuple { elements, .. } | Expr::Array { elements, .. } => {
+                for element in elements {
+                    self.resolve_expr(element);
+                }
+            }
+            Expr::ArrayRepeat { value, count, .. } => {
+                self.resolve_expr(value);
+                self.resolve_expr(count);
+            }
+            Expr::Block { statements, .. } => self.resolve_block(statements),
+            Expr::Range { start, end, .. } => {
+                if let Some(start) = start {
+                    self.resolve_expr(start);
+                }
+                if let Some(end) = end {
+                    self.resolve_expr(end);
+                }
+            }
+            Expr::Call {
+                id,
+                span,
+                function,
+                arguments,
+            } => {
+                for argument in arguments.iter_mut() {
+                    self.resolve_expr(argument);
+                }
+
+                // 被调用者需要在改写为单元变体之前检查
+                let callee = match &**function {
+                    Expr::Path { segments, .. } => Some(segments.as_slice()),
+                    Expr::Identifier { name, .. } => Some(std::slice::from_ref(name)),
+                    _ => None,
+                };
+
+                if let Some(path) = callee
+                    && let Some((enum_name, variant)) = self.scope.variant_for_path(path)
+                {
+                    *expr = Expr::EnumConstructor {
+                        id: *id,
+                        span: *span,
+                        enum_name: enum_name.into(),
+                        variant: variant.into(),
+                        arguments: VariantArguments::Tuple(std::mem::take(arguments)),
+                    };
+                } else if let Some(path) = callee
+                    && let Some((operation, prefix)) = path.split_last()
+                    && let Some(effect) = self.scope.effect_for_path(prefix)
+                {
+                    *expr = Expr::EffectOperation {
+                        id: *id,
+                        span: *span,
+                        effect: effect.into(),
+                        operation: *operation,
+                        arguments: std::mem::take(arguments),
+                    };
+                } else {
+                    self.resolve_expr(function);
+                }
+            }
+            Expr::StructLiteral {
+                id,
+                span,
+                path,
+                fields,
+                base,
+            } => {
+                for (_, value) in fields.iter_mut() {
+                    self.resolve_expr(value);
+                }
+                if let Some(base) = base {
+                    self.resolve_expr(base);
+                }
+
+                if let Some((enum_name, variant)) = self.scope.variant_for_path(path) {
+                    *expr = Expr::EnumConstructor {
+                        id: *id,
+                        span: *span,
+                        enum_name: enum_name.into(),
+                        variant: variant.into(),
+                        arguments: VariantArguments::Named(std::mem::take(fields)),
+                    };
+                }
+            }
+            Expr::EnumConstructor { arguments, .. } => match arguments {
+                VariantArguments::Unit => {}
+                VariantArguments::Tuple(arguments) => {
+                    for argument in arguments {
+                        self.resolve_expr(argument);
+                    }
+                }
+                VariantArguments::Named(fields) => {
+                    for (_, value) in fields {
+                        self.resolve_expr(value);
+                    }
+                }
+            },
+            Expr::Field { object, .. } => self.resolve_expr(object),
+            Expr::Index { object, index, .. } => {
+                self.resolve_expr(object);
+                self.resolve_expr(index);
+            }
+            Expr::EffectOperation { arguments, .. } => {
+                for argument in arguments {
+                    self.resolve_expr(argument);
+                }
+            }
+            Expr::MethodCall {
+                id,
+                span,
+                receiver,
+                method,
+                arguments,
+            } => {
+                self.resolve_expr(receiver);
+                for argument in arguments.iter_mut() {
+                    self.resolve_expr(argument);
+                }
+
+                if let Expr::Identifier { name, .. } = &**receiver
+                    && let Some(effect) = self.scope.effect_for_path(std::slice::from_ref(name))
+                {
+                    *expr = Expr::EffectOperation {
+                        id: *id,
+                        span: *span,
+                        effect: effect.into(),
+                        operation: *method,
+                        arguments: std::mem::take(arguments),
+                    };
+                }
+            }
+        }
+    }
+}
+
+/// 赋值目标或借用操作数所在的局部变量：x、x.field、x[i]、*x 都返回 x
+fn place_root(expr: &Expr) -> Option<&str> {
+    match expr {
+        Expr::Identifier { name, .. } => Some(name),
+        Expr::Field { object, .. } | Expr::Index { object, .. } => place_root(object),
+        Expr::Unary {
+            operator: UnaryOperator::Dereference,
+            operand,
+            ..
+        } => place_root(operand),
+        Expr::Grouping { expression, .. } => place_root(expression),
+        _ => None,
+    }
+}
+
+/// 如果 effects 中还没有该效果则追加
+fn push_unique(effects: &mut Vec<EffectName>, effect: EffectName) {
+    if !effects.contains(&effect) {
+        effects.push(effect);
+    }
+}
+
+/// 差集中的名称是否排除该效果：不带类型实参的名称排除该效果的所有实例 (State 排除 State<i32>)
+fn excludes(name: &EffectName, effect: &EffectName) -> bool {
+    name == effect || (name.type_arguments.is_empty() && name.name == effect.name)
+}
+
+/// 把类型中的类型参数替换为类型实参，返回规范化的类型
+///
+/// 类型以规范化的源代码文本表示，替换在词法记号上进行：只替换单独作为类型名的参数，
+/// 路径中的段 (`T::Item`) 不替换。替换后的记号重新按类型解析，得到与语法分析器相同的写法
+fn substitute_type_parameters(type_text: &str, substitutions: &HashMap<&str, &str>) -> String {
+    if substitutions.is_empty() {
+        return type_text.to_string();
+    }
+
+    let tokens = lex_type(type_text);
+    let is_path_separator =
+        |index: Option<usize>| index.and_then(|index| tokens.get(index)) == Some(&Token::PathSep);
+    let mut substituted = Vec::with_capacity(tokens.len());
+    for (index, token) in tokens.iter().enumerate() {
+        if let Token::Identifier(name) = token
+            && let Some(argument) = substitutions.get(name.as_str())
+            && !is_path_separator(index.checked_sub(1))
+            && !is_path_separator(Some(index + 1))
+        {
+            substituted.extend(lex_type(argument));
+        } else {
+            substituted.push(token.clone());
+        }
+    }
+
+    let location = Location {
+        line: 1,
+        column: 1,
+        file: "",
+    };
+    let mut parser = Parser::new(
+        substituted
+            .into_iter()
+            .map(|token| crate::data::Locatable::from((location, token))),
+    );
+    parser
+        .parse_type()
+        .unwrap_or_else(|_| type_text.to_string())
+}
+
+/// 类型文本的词法记号，类型文本由语法分析器生成，没有词法错误
+fn lex_type(type_text: &str) -> Vec<Token> {
+    Lexer::new("", BufReader::new(type_text.as_bytes()))
+        .filter_map(|token| token.data.ok())
+        .collect()
+}
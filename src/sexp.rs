@@ -0,0 +1,791 @@
+//! S 表达式输出模块
+//!
+//! 把记号流和语法树输出为紧凑的 S 表达式，用于黄金测试和外部工具。
+//! 输出不含节点编号和位置，只有结构：`let x = 1 + 2;` 输出为 `(let x (+ 1 2))`。
+//! - 名称、路径、类型和运算符直接写出，含有空白、括号、引号或分号时按字符串加引号
+//! - 字符串和字符字面量总是加引号，与同名的标识符区分
+//! - 空的可选部分（泛型参数、返回类型、效果行等）省略
+//! - 名称解析阶段填写的信息（展开后的效果、变量捕获）非空时也会输出
+
+use crate::data::{LexicalError, Locatable, Token};
+use crate::intern::Symbol;
+use crate::parser::{
+    CaptureMode, ContractClause, EffectRow, Expr, FunctionSignature, GenericParameter,
+    HandlerDeclaration, HandlerReference, Literal, ModuleBody, Parameter, Stmt, StructFields,
+    TraitItem, UseTree, VariantArguments, Visibility, WherePredicate,
+};
+use std::fmt;
+
+/// 输出语句列表，每条顶层语句占一行
+pub fn dump_program(statements: &[Stmt]) -> String {
+    statements
+        .iter()
+        .map(|stmt| format!("{}\n", stmt_sexp(stmt)))
+        .collect()
+}
+
+/// 输出一条语句
+pub fn dump_stmt(stmt: &Stmt) -> String {
+    stmt_sexp(stmt).to_string()
+}
+
+/// 输出一个表达式
+pub fn dump_expr(expr: &Expr) -> String {
+    expr_sexp(expr).to_string()
+}
+
+/// 输出词法分析器产生的记号流，每个记号占一行：`(1:1 fn)`、`(1:4 (ident main))`
+pub fn dump_tokens<'a, I>(tokens: I) -> String
+where
+    I: IntoIterator<Item = Locatable<'a, Result<Token, LexicalError>>>,
+{
+    tokens
+        .into_iter()
+        .map(|token| {
+            let location = format!("{}:{}", token.location.line, token.location.column);
+            let data = match &token.data {
+                Ok(token) => token_sexp(token),
+                Err(error) => list("error", vec![Sexp::string(&error.to_string())]),
+            };
+            format!("{}\n", Sexp::List(vec![Sexp::Atom(location), data]))
+        })
+        .collect()
+}
+
+/// S 表达式：原子或列表
+enum Sexp {
+    Atom(String),
+    List(Vec<Sexp>),
+}
+
+impl Sexp {
+    /// 名称、路径或类型，需要时加引号
+    fn atom(text: &str) -> Sexp {
+        let plain = !text.is_empty()
+            && !text
+                .chars()
+                .any(|c| c.is_whitespace() || matches!(c, '(' | ')' | '"' | ';'));
+        if plain {
+            Sexp::Atom(text.to_string())
+        } else {
+            Sexp::string(text)
+        }
+    }
+
+    /// 总是加引号的字符串
+    fn string(text: &str) -> Sexp {
+        Sexp::Atom(format!("{:?}", text))
+    }
+}
+
+impl fmt::Display for Sexp {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Sexp::Atom(text) => f.write_str(text),
+            Sexp::List(elements) => {
+                f.write_str("(")?;
+                for (index, element) in elements.iter().enumerate() {
+                    if index > 0 {
+                        f.write_str(" ")?;
+                    }
+                    write!(f, "{}", element)?;
+                }
+                f.write_str(")")
+            }
+        }
+    }
+}
+
+/// 以 head 开头的列表
+fn list(head: &str, elements: Vec<Sexp>) -> Sexp {
+    let mut list = vec![Sexp::atom(head)];
+    list.extend(elements);
+    Sexp::List(list)
+}
+
+/// 以 head 开头的列表，elements 为空时省略
+fn optional_list(head: &str, elements: Vec<Sexp>) -> Option<Sexp> {
+    (!elements.is_empty()).then(|| list(head, elements))
+}
+
+fn atoms<S: fmt::Display>(names: &[S]) -> Vec<Sexp> {
+    names
+        .iter()
+        .map(|name| Sexp::atom(&name.to_string()))
+        .collect()
+}
+
+fn token_sexp(token: &Token) -> Sexp {
+    match token {
+        Token::IntegerLiteral(text) => list("int", vec![Sexp::atom(text)]),
+        Token::FloatLiteral(text) => list("float", vec![Sexp::atom(text)]),
+        Token::StringLiteral(text) => list("string", vec![Sexp::string(text)]),
+        Token::CharLiteral(c) => list("char", vec![Sexp::Atom(format!("{:?}", c))]),
+        Token::Identifier(name) => list("ident", vec![Sexp::atom(name)]),
+        Token::Error(error) => list("error", vec![Sexp::string(&error.to_string())]),
+        Token::Eof => Sexp::atom("eof"),
+        _ => Sexp::atom(&token.lexeme()),
+    }
+}
+
+fn literal_sexp(literal: &Literal) -> Sexp {
+    match literal {
+        Literal::Integer(text) | Literal::Float(text) => Sexp::atom(text),
+        Literal::String(text) => Sexp::string(text),
+        Literal::Char(c) => Sexp::Atom(format!("{:?}", c)),
+        Literal::Boolean(value) => Sexp::Atom(value.to_string()),
+    }
+}
+
+/// 表达式中的路径 a::b::c
+fn path_sexp(segments: &[Symbol]) -> Sexp {
+    let segments: Vec<&str> = segments.iter().map(Symbol::as_str).collect();
+    Sexp::atom(&segments.join("::"))
+}
+
+fn exprs(exprs: &[Expr]) -> Vec<Sexp> {
+    exprs.iter().map(expr_sexp).collect()
+}
+
+fn stmts(statements: &[Stmt]) -> Vec<Sexp> {
+    statements.iter().map(stmt_sexp).collect()
+}
+
+fn expr_sexp(expr: &Expr) -> Sexp {
+    match expr {
+        Expr::Literal { value, .. } => literal_sexp(value),
+        Expr::Identifier { name, .. } => Sexp::atom(name),
+        Expr::Path { segments, .. } => path_sexp(segments),
+        Expr::Binary {
+            left,
+            operator,
+            right,
+            ..
+        } => list(
+            &operator.to_string(),
+            vec![expr_sexp(left), expr_sexp(right)],
+        ),
+        Expr::Assign {
+            target,
+            operator,
+            value,
+            ..
+        } => list(
+            &operator.to_string(),
+            vec![expr_sexp(target), expr_sexp(value)],
+        ),
+        Expr::Unary {
+            operator, operand, ..
+        } => list(&operator.to_string(), vec![expr_sexp(operand)]),
+        Expr::Cast {
+            expression,
+            target_type,
+            ..
+        } => list("as", vec![expr_sexp(expression), Sexp::atom(target_type)]),
+        Expr::Range {
+            start,
+            end,
+            inclusive,
+            ..
+        } => {
+            let bound = |bound: &Option<Box<Expr>>| match bound {
+                Some(expr) => expr_sexp(expr),
+                None => Sexp::atom("_"),
+            };
+            let operator = if *inclusive { "..=" } else { ".." };
+            list(operator, vec![bound(start), bound(end)])
+        }
+        Expr::Call {
+            function,
+            arguments,
+            ..
+        } => {
+            let mut elements = vec![expr_sexp(function)];
+            elements.extend(exprs(arguments));
+            list("call", elements)
+        }
+        Expr::Grouping { expression, .. } => list("group", vec![expr_sexp(expression)]),
+        Expr::Tuple { elements, .. } => list("tuple", exprs(elements)),
+        Expr::Array { elements, .. } => list("array", exprs(elements)),
+        Expr::ArrayRepeat { value, count, .. } => {
+            list("array-repeat", vec![expr_sexp(value), expr_sexp(count)])
+        }
+        Expr::Async {
+            statements,
+            effects,
+            ..
+        } => {
+            let mut elements: Vec<Sexp> = optional_list("effects", atoms(effects))
+                .into_iter()
+                .collect();
+            elements.extend(stmts(statements));
+            list("async", elements)
+        }
+        Expr::TryBlock {
+            statements,
+            discharged_effects,
+            ..
+        } => {
+            let mut elements: Vec<Sexp> = optional_list("discharges", atoms(discharged_effects))
+                .into_iter()
+                .collect();
+            elements.extend(stmts(statements));
+            list("try", elements)
+        }
+        Expr::Block { statements, .. } => list("block", stmts(statements)),
+        Expr::Field { object, field, .. } => {
+            list("field", vec![expr_sexp(object), Sexp::atom(field)])
+        }
+        Expr::MethodCall {
+            receiver,
+            method,
+            arguments,
+            ..
+        } => {
+            let mut elements = vec![expr_sexp(receiver), Sexp::atom(method)];
+            elements.extend(exprs(arguments));
+            list("method-call", elements)
+        }
+        Expr::Index { object, index, .. } => {
+            list("index", vec![expr_sexp(object), expr_sexp(index)])
+        }
+        Expr::Try { expression, .. } => list("?", vec![expr_sexp(expression)]),
+        Expr::Await { expression, .. } => list("await", vec![expr_sexp(expression)]),
+        Expr::StructLiteral {
+            path, fields, base, ..
+        } => {
+            let mut elements = vec![path_sexp(path)];
+            elements.extend(named_exprs(fields));
+            if let Some(base) = base {
+                elements.push(list("..", vec![expr_sexp(base)]));
+            }
+            list("struct-literal", elements)
+        }
+        Expr::EnumConstructor {
+            enum_name,
+            variant,
+            arguments,
+            ..
+        } => {
+            let mut elements = vec![Sexp::atom(&format!("{}::{}", enum_name, variant))];
+            match arguments {
+                VariantArguments::Unit => {}
+                VariantArguments::Tuple(arguments) => elements.extend(exprs(arguments)),
+                VariantArguments::Named(fields) => elements.extend(named_exprs(fields)),
+            }
+            list("enum-constructor", elements)
+        }
+        Expr::With {
+            handlers,
+            body,
+            discharged_effects,
+            ..
+        } => {
+            let handlers = handlers
+                .iter()
+                .map(|handler| match handler {
+                    HandlerReference::Named(name) => Sexp::atom(name),
+                    HandlerReference::Inline(declaration) => {
+                        handler_sexp(Visibility::Private, declaration)
+                    }
+                })
+                .collect();
+            let mut elements = vec![list("handlers", handlers)];
+            elements.extend(optional_list("discharges", atoms(discharged_effects)));
+            elements.push(list("body", stmts(body)));
+            list("with", elements)
+        }
+        Expr::Resume { value, .. } => list("resume", value.iter().map(|v| expr_sexp(v)).collect()),
+        Expr::Old { expression, .. } => list("old", vec![expr_sexp(expression)]),
+        Expr::Result { .. } => Sexp::atom("result"),
+        Expr::Closure {
+            is_move,
+            parameters,
+            return_type,
+            effects,
+            body,
+            captures,
+            ..
+        } => {
+            let mut elements = Vec::new();
+            if *is_move {
+                elements.push(Sexp::atom("move"));
+            }
+            elements.push(list("params", parameters_sexp(parameters)));
+            elements.extend(return_type.as_deref().map(returns_sexp));
+            elements.extend(effects_sexp(effects));
+            elements.push(expr_sexp(body));
+            let captures = captures
+                .iter()
+                .map(|capture| {
+                    let mode = match capture.mode {
+                        CaptureMode::Borrow => "&",
+                        CaptureMode::BorrowMut => "&mut",
+                        CaptureMode::Move => "move",
+                    };
+                    list(mode, vec![Sexp::atom(&capture.name)])
+                })
+                .collect();
+            elements.extend(optional_list("captures", captures));
+            list("closure", elements)
+        }
+        Expr::EffectOperation {
+            effect,
+            operation,
+            arguments,
+            ..
+        } => {
+            let mut elements = vec![Sexp::atom(&format!("{}.{}", effect, operation))];
+            elements.extend(exprs(arguments));
+            list("perform", elements)
+        }
+    }
+}
+
+/// 结构体字面量和结构体变体的字段 (x 1)
+fn named_exprs<S: AsRef<str>>(fields: &[(S, Expr)]) -> Vec<Sexp> {
+    fields
+        .iter()
+        .map(|(name, value)| Sexp::List(vec![Sexp::atom(name.as_ref()), expr_sexp(value)]))
+        .collect()
+}
+
+fn visibility_sexp(visibility: Visibility) -> Option<Sexp> {
+    match visibility {
+        Visibility::Private => None,
+        Visibility::Crate => Some(Sexp::atom("pub-crate")),
+        Visibility::Public => Some(Sexp::atom("pub")),
+    }
+}
+
+/// 声明的头部：可见性修饰在名称之前
+fn declaration_head(visibility: Visibility, name: &str) -> Vec<Sexp> {
+    let mut elements: Vec<Sexp> = visibility_sexp(visibility).into_iter().collect();
+    elements.push(Sexp::atom(name));
+    elements
+}
+
+fn generics_sexp(generics: &[GenericParameter]) -> Option<Sexp> {
+    let parameters = generics
+        .iter()
+        .map(|parameter| {
+            if parameter.bounds.is_empty() {
+                Sexp::atom(&parameter.name)
+            } else {
+                let mut elements = vec![Sexp::atom(&parameter.name)];
+                elements.extend(atoms(&parameter.bounds));
+                Sexp::List(elements)
+            }
+        })
+        .collect();
+    optional_list("generics", parameters)
+}
+
+/// 参数：没有类型标注时只有名称，否则是 (名称 类型)
+fn parameters_sexp(parameters: &[Parameter]) -> Vec<Sexp> {
+    parameters
+        .iter()
+        .map(|parameter| match &parameter.type_annotation {
+            Some(annotation) => {
+                Sexp::List(vec![Sexp::atom(&parameter.name), Sexp::atom(annotation)])
+            }
+            None => Sexp::atom(&parameter.name),
+        })
+        .collect()
+}
+
+fn returns_sexp(return_type: &str) -> Sexp {
+    list("returns", vec![Sexp::atom(return_type)])
+}
+
+/// 效果行 (effects IO Log (- Network) ..e)，展开后的效果非空时附在最后
+fn effects_sexp(row: &EffectRow) -> Option<Sexp> {
+    let mut elements = atoms(&row.effects);
+    if !row.excluded.is_empty() {
+        elements.push(list("-", atoms(&row.excluded)));
+    }
+    if let Some(row_variable) = &row.row_variable {
+        elements.push(Sexp::atom(&format!("..{}", row_variable)));
+    }
+    elements.extend(optional_list("flattened", atoms(&row.flattened_effects)));
+    optional_list("effects", elements)
+}
+
+fn where_sexp(predicates: &[WherePredicate]) -> Option<Sexp> {
+    let predicates = predicates
+        .iter()
+        .map(|predicate| {
+            let mut elements = vec![Sexp::atom(&predicate.bounded_type)];
+            elements.extend(atoms(&predicate.bounds));
+            Sexp::List(elements)
+        })
+        .collect();
+    optional_list("where", predicates)
+}
+
+fn contract_clause_sexp(clause: &ContractClause) -> Sexp {
+    match clause {
+        ContractClause::Requires(expr) => list("requires", vec![expr_sexp(expr)]),
+        ContractClause::Ensures(expr) => list("ensures", vec![expr_sexp(expr)]),
+        ContractClause::Invariant(expr) => list("invariant", vec![expr_sexp(expr)]),
+        ContractClause::Named(name) => list("contract", vec![Sexp::atom(name)]),
+    }
+}
+
+fn contracts_sexp(clauses: &[ContractClause]) -> Vec<Sexp> {
+    clauses.iter().map(contract_clause_sexp).collect()
+}
+
+/// 函数声明和 trait 方法共用的部分，body 为 None 时是没有默认实现的 trait 方法
+fn function_sexp(
+    visibility: Visibility,
+    signature: &FunctionSignature,
+    body: Option<&[Stmt]>,
+) -> Sexp {
+    let mut elements = Vec::new();
+    if signature.is_async {
+        elements.push(Sexp::atom("async"));
+    }
+    elements.extend(declaration_head(visibility, &signature.name));
+    elements.extend(generics_sexp(&signature.generics));
+    elements.push(list("params", parameters_sexp(&signature.parameters)));
+    elements.extend(signature.return_type.as_deref().map(returns_sexp));
+    elements.extend(effects_sexp(&signature.effects));
+    elements.extend(where_sexp(&signature.where_clause));
+    elements.extend(contracts_sexp(&signature.contracts));
+    if let Some(body) = body {
+        elements.push(list("body", stmts(body)));
+    }
+    list("fn", elements)
+}
+
+fn fields_sexp(fields: &StructFields) -> Option<Sexp> {
+    let visibility = |visibility: Visibility, field: Vec<Sexp>| {
+        let mut elements: Vec<Sexp> = visibility_sexp(visibility).into_iter().collect();
+        elements.extend(field);
+        elements
+    };
+    match fields {
+        StructFields::Named(fields) => Some(list(
+            "fields",
+            fields
+                .iter()
+                .map(|field| {
+                    Sexp::List(visibility(
+                        field.visibility,
+                        vec![Sexp::atom(&field.name), Sexp::atom(&field.field_type)],
+                    ))
+                })
+                .collect(),
+        )),
+        StructFields::Tuple(fields) => Some(list(
+            "tuple-fields",
+            fields
+                .iter()
+                .map(|field| {
+                    let mut elements =
+                        visibility(field.visibility, vec![Sexp::atom(&field.field_type)]);
+                    if elements.len() == 1 {
+                        elements.pop().unwrap()
+                    } else {
+                        Sexp::List(elements)
+                    }
+                })
+                .collect(),
+        )),
+        StructFields::Unit => None,
+    }
+}
+
+fn handler_sexp(visibility: Visibility, declaration: &HandlerDeclaration) -> Sexp {
+    let mut elements = declaration_head(visibility, &declaration.effect.to_string());
+    if let Some(name) = &declaration.name {
+        elements.push(list("as", vec![Sexp::atom(name)]));
+    }
+    for clause in &declaration.clauses {
+        let mut clause_elements = vec![
+            Sexp::atom(&clause.operation),
+            list("params", parameters_sexp(&clause.parameters)),
+        ];
+        if let Some(continuation) = &clause.continuation {
+            clause_elements.push(list("resume", vec![Sexp::atom(continuation)]));
+        }
+        clause_elements.push(list("body", stmts(&clause.body)));
+        elements.push(list("clause", clause_elements));
+    }
+    if let Some(clause) = &declaration.return_clause {
+        elements.push(list(
+            "return",
+            vec![
+                list(
+                    "params",
+                    parameters_sexp(std::slice::from_ref(&clause.parameter)),
+                ),
+                list("body", stmts(&clause.body)),
+            ],
+        ));
+    }
+    if let Some(body) = &declaration.finally_clause {
+        elements.push(list("finally", stmts(body)));
+    }
+    list("handle", elements)
+}
+
+fn use_tree_sexp(tree: &UseTree) -> Sexp {
+    match tree {
+        UseTree::Simple { path, alias } => {
+            let path = path_sexp(path);
+            match alias {
+                Some(alias) => list("as", vec![path, Sexp::atom(alias)]),
+                None => path,
+            }
+        }
+        UseTree::Glob { path } => {
+            let mut path = path.clone();
+            path.push(Symbol::intern("*"));
+            path_sexp(&path)
+        }
+        UseTree::Group { path, items } => {
+            let prefix = if path.is_empty() {
+                Sexp::atom("_")
+            } else {
+                path_sexp(path)
+            };
+            let mut elements = vec![prefix];
+            elements.extend(items.iter().map(use_tree_sexp));
+            list("group", elements)
+        }
+    }
+}
+
+fn stmt_sexp(stmt: &Stmt) -> Sexp {
+    match stmt {
+        Stmt::Expression {
+            expression,
+            has_semicolon,
+            ..
+        } => {
+            // 没有分号的表达式语句可能是块的值
+            let head = if *has_semicolon { "expr" } else { "value" };
+            list(head, vec![expr_sexp(expression)])
+        }
+        Stmt::Let {
+            identifier,
+            initializer,
+            ..
+        } => {
+            let mut elements = vec![Sexp::atom(identifier)];
+            elements.extend(initializer.as_ref().map(expr_sexp));
+            list("let", elements)
+        }
+        Stmt::Var {
+            identifier,
+            initializer,
+            ..
+        } => {
+            let mut elements = vec![Sexp::atom(identifier)];
+            elements.extend(initializer.as_ref().map(expr_sexp));
+            list("var", elements)
+        }
+        Stmt::Function {
+            visibility,
+            is_async,
+            name,
+            generics,
+            parameters,
+            return_type,
+            effects,
+            where_clause,
+            contracts,
+            body,
+            ..
+        } => {
+            let signature = FunctionSignature {
+                is_async: *is_async,
+                name: *name,
+                generics: generics.clone(),
+                parameters: parameters.clone(),
+                return_type: return_type.clone(),
+                effects: effects.clone(),
+                where_clause: where_clause.clone(),
+                contracts: contracts.clone(),
+            };
+            function_sexp(*visibility, &signature, Some(body))
+        }
+        Stmt::Block { statements, .. } => list("block", stmts(statements)),
+        Stmt::Effect {
+            visibility,
+            declaration,
+            ..
+        } => {
+            let mut elements = declaration_head(*visibility, &declaration.name);
+            elements.extend(generics_sexp(&declaration.generics));
+            for operation in &declaration.operations {
+                let parameters = operation
+                    .parameters
+                    .iter()
+                    .map(|(name, parameter_type)| {
+                        Sexp::List(vec![Sexp::atom(name), Sexp::atom(parameter_type)])
+                    })
+                    .collect();
+                let mut operation_elements =
+                    vec![Sexp::atom(&operation.name), list("params", parameters)];
+                operation_elements.extend(operation.return_type.as_deref().map(returns_sexp));
+                elements.push(list("fn", operation_elements));
+            }
+            list("effect", elements)
+        }
+        Stmt::Handler {
+            visibility,
+            declaration,
+            ..
+        } => handler_sexp(*visibility, declaration),
+        Stmt::EffectGroup {
+            visibility,
+            declaration,
+            ..
+        } => {
+            let mut elements = declaration_head(*visibility, &declaration.name);
+            elements.push(list("effects", atoms(&declaration.effects)));
+            elements.extend(optional_list("-", atoms(&declaration.excluded)));
+            elements.extend(optional_list(
+                "flattened",
+                atoms(&declaration.flattened_effects),
+            ));
+            list("effect-group", elements)
+        }
+        Stmt::HandlerGroup {
+            visibility,
+            declaration,
+            ..
+        } => {
+            let mut elements = declaration_head(*visibility, &declaration.name);
+            elements.push(list("handlers", atoms(&declaration.handlers)));
+            elements.extend(optional_list(
+                "discharges",
+                atoms(&declaration.discharged_effects),
+            ));
+            list("handler-group", elements)
+        }
+        Stmt::Use {
+            visibility,
+            declaration,
+            ..
+        } => {
+            let mut elements: Vec<Sexp> = visibility_sexp(*visibility).into_iter().collect();
+            elements.push(use_tree_sexp(&declaration.tree));
+            list("use", elements)
+        }
+        Stmt::Struct {
+            visibility,
+            declaration,
+            ..
+        } => {
+            let mut elements = declaration_head(*visibility, &declaration.name);
+            elements.extend(generics_sexp(&declaration.generics));
+            elements.extend(fields_sexp(&declaration.fields));
+            elements.extend(contracts_sexp(&declaration.contracts));
+            list("struct", elements)
+        }
+        Stmt::Enum {
+            visibility,
+            declaration,
+            ..
+        } => {
+            let mut elements = declaration_head(*visibility, &declaration.name);
+            elements.extend(generics_sexp(&declaration.generics));
+            for variant in &declaration.variants {
+                elements.push(match fields_sexp(&variant.fields) {
+                    Some(fields) => Sexp::List(vec![Sexp::atom(&variant.name), fields]),
+                    None => Sexp::atom(&variant.name),
+                });
+            }
+            list("enum", elements)
+        }
+        Stmt::TypeAlias {
+            visibility,
+            declaration,
+            ..
+        } => {
+            let mut elements = declaration_head(*visibility, &declaration.name);
+            elements.extend(generics_sexp(&declaration.generics));
+            elements.push(Sexp::atom(&declaration.aliased_type));
+            list("type", elements)
+        }
+        Stmt::Trait {
+            visibility,
+            declaration,
+            ..
+        } => {
+            let mut elements = declaration_head(*visibility, &declaration.name);
+            elements.extend(generics_sexp(&declaration.generics));
+            elements.extend(optional_list(
+                "supertraits",
+                atoms(&declaration.supertraits),
+            ));
+            elements.extend(where_sexp(&declaration.where_clause));
+            elements.extend(contracts_sexp(&declaration.contracts));
+            for item in &declaration.items {
+                elements.push(match item {
+                    TraitItem::Method {
+                        signature,
+                        default_body,
+                    } => function_sexp(Visibility::Private, signature, default_body.as_deref()),
+                    TraitItem::AssociatedType {
+                        name,
+                        bounds,
+                        default,
+                    } => {
+                        let mut type_elements = vec![Sexp::atom(name)];
+                        type_elements.extend(optional_list("bounds", atoms(bounds)));
+                        type_elements.extend(
+                            default
+                                .as_deref()
+                                .map(|default| list("default", vec![Sexp::atom(default)])),
+                        );
+                        list("type", type_elements)
+                    }
+                });
+            }
+            list("trait", elements)
+        }
+        Stmt::Impl { declaration, .. } => {
+            let mut elements = Vec::new();
+            elements.extend(generics_sexp(&declaration.generics));
+            elements.extend(
+                declaration
+                    .trait_name
+                    .as_deref()
+                    .map(|name| list("trait", vec![Sexp::atom(name)])),
+            );
+            elements.push(Sexp::atom(&declaration.self_type));
+            elements.extend(where_sexp(&declaration.where_clause));
+            elements.extend(stmts(&declaration.items));
+            list("impl", elements)
+        }
+        Stmt::Contract {
+            visibility,
+            declaration,
+            ..
+        } => {
+            let mut elements = declaration_head(*visibility, &declaration.name);
+            elements.push(list("params", parameters_sexp(&declaration.parameters)));
+            elements.extend(contracts_sexp(&declaration.clauses));
+            list("contract", elements)
+        }
+        Stmt::Module {
+            visibility,
+            declaration,
+            ..
+        } => {
+            let mut elements = declaration_head(*visibility, &declaration.name);
+            if let ModuleBody::File { path, .. } = &declaration.body {
+                let path = path.as_deref().map(Sexp::string);
+                elements.push(list("file", path.into_iter().collect()));
+            }
+            elements.extend(stmts(declaration.body.items()));
+            list("mod", elements)
+        }
+        Stmt::Error { .. } => Sexp::atom("error"),
+    }
+}
@@ -0,0 +1,836 @@
+//! 无损语法树模块
+//!
+//! 语法树分为两层：
+//! - 绿树（`GreenNode` / `GreenToken`）是不可变的、可共享的，只记录种类、原文和长度，
+//!   所有空白、注释以及词法错误都作为叶子保留在树中，因此 `root.text()` 总是等于源代码
+//! - 红树（`SyntaxNode` / `SyntaxToken`）在绿树之上按需创建，记录父节点和在源代码中的偏移
+//!
+//! 构建语法树时语法分析器记录每条语句、块、表达式以及声明组成部分覆盖的记号区间，本模块把
+//! 这些区间和记号之间的 trivia 交织成绿树。流式解析不需要语法树，因此不记录节点。
+//!
+//! 类型化的 `Stmt` 只有一个来源：语法分析器在记录这些区间的同一次解析中构建语句，语法树
+//! 直接保存它们，不再由语法树另外降级。语法树中的语句节点按位置对应到这些语句。
+
+use crate::data::{LexicalError, Locatable, Location, Span, Token};
+use crate::lex::{Lexer, RawToken, Trivia, TriviaKind};
+use crate::parser::{Expr, ParseError, Parser, Stmt};
+use crate::visit::{Visitor, walk_stmt};
+use std::cmp::Reverse;
+use std::fmt;
+use std::io::BufReader;
+use std::ops::Range;
+use std::rc::Rc;
+
+/// 语法树中节点和叶子的种类
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SyntaxKind {
+    // 叶子
+    /// 语法分析器看到的记号
+    Token,
+    Whitespace,
+    LineComment,
+    BlockComment,
+    /// 词法错误处读入的字符
+    ErrorToken,
+
+    // 节点
+    /// 整个源文件
+    SourceFile,
+    Function,
+    Let,
+    Var,
+    Effect,
+    Handler,
+    EffectGroup,
+    HandlerGroup,
+    Use,
+    Struct,
+    Enum,
+    TypeAlias,
+    Trait,
+    Impl,
+    Contract,
+    Module,
+    ExpressionStatement,
+    BlockStatement,
+    /// 解析失败后跳过的记号
+    Error,
+    /// `{ ... }`，包括花括号
+    Block,
+
+    // 声明的组成部分
+    /// `<T: Clone, U>`
+    GenericParameterList,
+    GenericParameter,
+    /// 函数、闭包、契约和处理器子句的参数，以及效果操作的参数
+    Parameter,
+    Type,
+    /// 效果行和效果组的成员列表
+    EffectRow,
+    EffectName,
+    WherePredicate,
+    ContractClause,
+    EffectOperation,
+    HandlerClause,
+    ReturnClause,
+    FinallyClause,
+    /// with 表达式中的 `handle E { ... }`
+    InlineHandler,
+    TraitMethod,
+    AssociatedType,
+    /// 结构体或变体的 `{ a: A, b: B }`
+    NamedFields,
+    NamedField,
+    /// 结构体或变体的 `(A, B)`
+    TupleFields,
+    TupleField,
+    Variant,
+    UseTree,
+
+    // 表达式
+    Literal,
+    Identifier,
+    Path,
+    Binary,
+    Assign,
+    Unary,
+    Cast,
+    Range,
+    Call,
+    Grouping,
+    Tuple,
+    Array,
+    ArrayRepeat,
+    Async,
+    TryBlock,
+    BlockExpression,
+    Field,
+    MethodCall,
+    Index,
+    Try,
+    Await,
+    StructLiteral,
+    /// 结构体字面量中的 `x: 1` 或简写的 `x`
+    StructLiteralField,
+    With,
+    Resume,
+    Old,
+    Result,
+    Closure,
+}
+
+impl SyntaxKind {
+    /// 是否是空白或注释
+    pub fn is_trivia(self) -> bool {
+        matches!(
+            self,
+            SyntaxKind::Whitespace | SyntaxKind::LineComment | SyntaxKind::BlockComment
+        )
+    }
+
+    /// 是否是语句节点，包括解析失败的语句
+    pub fn is_item(self) -> bool {
+        matches!(
+            self,
+            SyntaxKind::Function
+                | SyntaxKind::Let
+                | SyntaxKind::Var
+                | SyntaxKind::Effect
+                | SyntaxKind::Handler
+                | SyntaxKind::EffectGroup
+                | SyntaxKind::HandlerGroup
+                | SyntaxKind::Use
+                | SyntaxKind::Struct
+                | SyntaxKind::Enum
+                | SyntaxKind::TypeAlias
+                | SyntaxKind::Trait
+                | SyntaxKind::Impl
+                | SyntaxKind::Contract
+                | SyntaxKind::Module
+                | SyntaxKind::ExpressionStatement
+                | SyntaxKind::BlockStatement
+                | SyntaxKind::Error
+        )
+    }
+
+    /// 是否是表达式节点
+    pub fn is_expression(self) -> bool {
+        matches!(
+            self,
+            SyntaxKind::Literal
+                | SyntaxKind::Identifier
+                | SyntaxKind::Path
+                | SyntaxKind::Binary
+                | SyntaxKind::Assign
+                | SyntaxKind::Unary
+                | SyntaxKind::Cast
+                | SyntaxKind::Range
+                | SyntaxKind::Call
+                | SyntaxKind::Grouping
+                | SyntaxKind::Tuple
+                | SyntaxKind::Array
+                | SyntaxKind::ArrayRepeat
+                | SyntaxKind::Async
+                | SyntaxKind::TryBlock
+                | SyntaxKind::BlockExpression
+                | SyntaxKind::Field
+                | SyntaxKind::MethodCall
+                | SyntaxKind::Index
+                | SyntaxKind::Try
+                | SyntaxKind::Await
+                | SyntaxKind::StructLiteral
+                | SyntaxKind::With
+                | SyntaxKind::Resume
+                | SyntaxKind::Old
+                | SyntaxKind::Result
+                | SyntaxKind::Closure
+        )
+    }
+
+    /// 语句对应的节点种类
+    pub fn of_stmt(statement: &Stmt) -> SyntaxKind {
+        match statement {
+            Stmt::Expression { .. } => SyntaxKind::ExpressionStatement,
+            Stmt::Let { .. } => SyntaxKind::Let,
+            Stmt::Var { .. } => SyntaxKind::Var,
+            Stmt::Function { .. } => SyntaxKind::Function,
+            Stmt::Block { .. } => SyntaxKind::BlockStatement,
+            Stmt::Effect { .. } => SyntaxKind::Effect,
+            Stmt::Handler { .. } => SyntaxKind::Handler,
+            Stmt::EffectGroup { .. } => SyntaxKind::EffectGroup,
+            Stmt::HandlerGroup { .. } => SyntaxKind::HandlerGroup,
+            Stmt::Use { .. } => SyntaxKind::Use,
+            Stmt::Struct { .. } => SyntaxKind::Struct,
+            Stmt::Enum { .. } => SyntaxKind::Enum,
+            Stmt::TypeAlias { .. } => SyntaxKind::TypeAlias,
+            Stmt::Trait { .. } => SyntaxKind::Trait,
+            Stmt::Impl { .. } => SyntaxKind::Impl,
+            Stmt::Contract { .. } => SyntaxKind::Contract,
+            Stmt::Module { .. } => SyntaxKind::Module,
+            Stmt::Error { .. } => SyntaxKind::Error,
+        }
+    }
+
+    /// 语法分析器产生的表达式对应的节点种类
+    pub(crate) fn of_expr(expr: &Expr) -> SyntaxKind {
+        match expr {
+            Expr::Literal { .. } => SyntaxKind::Literal,
+            Expr::Identifier { .. } => SyntaxKind::Identifier,
+            Expr::Path { .. } => SyntaxKind::Path,
+            Expr::Binary { .. } => SyntaxKind::Binary,
+            Expr::Assign { .. } => SyntaxKind::Assign,
+            Expr::Unary { .. } => SyntaxKind::Unary,
+            Expr::Cast { .. } => SyntaxKind::Cast,
+            Expr::Range { .. } => SyntaxKind::Range,
+            Expr::Call { .. } => SyntaxKind::Call,
+            Expr::Grouping { .. } => SyntaxKind::Grouping,
+            Expr::Tuple { .. } => SyntaxKind::Tuple,
+            Expr::Array { .. } => SyntaxKind::Array,
+            Expr::ArrayRepeat { .. } => SyntaxKind::ArrayRepeat,
+            Expr::Async { .. } => SyntaxKind::Async,
+            Expr::TryBlock { .. } => SyntaxKind::TryBlock,
+            Expr::Block { .. } => SyntaxKind::BlockExpression,
+            Expr::Field { .. } => SyntaxKind::Field,
+            Expr::MethodCall { .. } => SyntaxKind::MethodCall,
+            Expr::Index { .. } => SyntaxKind::Index,
+            Expr::Try { .. } => SyntaxKind::Try,
+            Expr::Await { .. } => SyntaxKind::Await,
+            Expr::StructLiteral { .. } => SyntaxKind::StructLiteral,
+            Expr::With { .. } => SyntaxKind::With,
+            Expr::Resume { .. } => SyntaxKind::Resume,
+            Expr::Old { .. } => SyntaxKind::Old,
+            Expr::Result { .. } => SyntaxKind::Result,
+            Expr::Closure { .. } => SyntaxKind::Closure,
+            Expr::EnumConstructor { .. } | Expr::EffectOperation { .. } => {
+                unreachable!("枚举构造和效果操作调用只由名称解析阶段创建")
+            }
+        }
+    }
+}
+
+impl From<TriviaKind> for SyntaxKind {
+    fn from(kind: TriviaKind) -> Self {
+        match kind {
+            TriviaKind::Whitespace => SyntaxKind::Whitespace,
+            TriviaKind::LineComment => SyntaxKind::LineComment,
+            TriviaKind::BlockComment => SyntaxKind::BlockComment,
+        }
+    }
+}
+
+/// 绿树的叶子：种类、记号和原文
+#[derive(Debug, Clone, PartialEq)]
+pub struct GreenToken {
+    pub kind: SyntaxKind,
+    /// 种类为 `SyntaxKind::Token` 时是语法分析器看到的记号
+    pub token: Option<Token>,
+    pub text: String,
+}
+
+/// 绿树的内部节点：种类、子节点和原文的总长度（字节）
+#[derive(Debug, Clone, PartialEq)]
+pub struct GreenNode {
+    pub kind: SyntaxKind,
+    pub children: Vec<GreenElement>,
+    pub text_len: usize,
+}
+
+/// 绿树的子节点
+#[derive(Debug, Clone, PartialEq)]
+pub enum GreenElement {
+    Node(Rc<GreenNode>),
+    Token(Rc<GreenToken>),
+}
+
+impl GreenElement {
+    fn text_len(&self) -> usize {
+        match self {
+            GreenElement::Node(node) => node.text_len,
+            GreenElement::Token(token) => token.text.len(),
+        }
+    }
+}
+
+impl GreenNode {
+    fn new(kind: SyntaxKind, children: Vec<GreenElement>) -> GreenNode {
+        let text_len = children.iter().map(GreenElement::text_len).sum();
+        GreenNode {
+            kind,
+            children,
+            text_len,
+        }
+    }
+
+    fn write_text(&self, text: &mut String) {
+        for child in &self.children {
+            match child {
+                GreenElement::Node(node) => node.write_text(text),
+                GreenElement::Token(token) => text.push_str(&token.text),
+            }
+        }
+    }
+}
+
+/// 红树节点：绿树节点加上父节点和在源代码中的字节偏移
+#[derive(Clone)]
+pub struct SyntaxNode(Rc<NodeData>);
+
+struct NodeData {
+    green: Rc<GreenNode>,
+    parent: Option<SyntaxNode>,
+    offset: usize,
+}
+
+/// 红树叶子
+#[derive(Clone)]
+pub struct SyntaxToken {
+    green: Rc<GreenToken>,
+    parent: SyntaxNode,
+    offset: usize,
+}
+
+/// 红树的子节点
+#[derive(Clone, Debug, PartialEq)]
+pub enum SyntaxElement {
+    Node(SyntaxNode),
+    Token(SyntaxToken),
+}
+
+impl SyntaxNode {
+    fn new_root(green: Rc<GreenNode>) -> SyntaxNode {
+        SyntaxNode(Rc::new(NodeData {
+            green,
+            parent: None,
+            offset: 0,
+        }))
+    }
+
+    pub fn kind(&self) -> SyntaxKind {
+        self.0.green.kind
+    }
+
+    pub fn green(&self) -> &Rc<GreenNode> {
+        &self.0.green
+    }
+
+    pub fn parent(&self) -> Option<SyntaxNode> {
+        self.0.parent.clone()
+    }
+
+    /// 节点在源代码中的字节区间
+    pub fn text_range(&self) -> Range<usize> {
+        self.0.offset..self.0.offset + self.0.green.text_len
+    }
+
+    /// 节点覆盖的原文，包括其中的空白和注释
+    pub fn text(&self) -> String {
+        let mut text = String::with_capacity(self.0.green.text_len);
+        self.0.green.write_text(&mut text);
+        text
+    }
+
+    /// 直接子节点和叶子，按源代码中的顺序排列
+    pub fn children_with_tokens(&self) -> Vec<SyntaxElement> {
+        let mut offset = self.0.offset;
+        self.0
+            .green
+            .children
+            .iter()
+            .map(|child| {
+                let element = match child {
+                    GreenElement::Node(node) => {
+                        SyntaxElement::Node(SyntaxNode(Rc::new(NodeData {
+                            green: node.clone(),
+                            parent: Some(self.clone()),
+                            offset,
+                        })))
+                    }
+                    GreenElement::Token(token) => SyntaxElement::Token(SyntaxToken {
+                        green: token.clone(),
+                        parent: self.clone(),
+                        offset,
+                    }),
+                };
+                offset += child.text_len();
+                element
+            })
+            .collect()
+    }
+
+    /// 直接子节点，不包括叶子
+    pub fn children(&self) -> Vec<SyntaxNode> {
+        self.children_with_tokens()
+            .into_iter()
+            .filter_map(|element| match element {
+                SyntaxElement::Node(node) => Some(node),
+                SyntaxElement::Token(_) => None,
+            })
+            .collect()
+    }
+
+    /// 前序遍历的所有节点，包括自身
+    pub fn descendants(&self) -> Vec<SyntaxNode> {
+        let mut nodes = vec![self.clone()];
+        for child in self.children() {
+            nodes.extend(child.descendants());
+        }
+        nodes
+    }
+
+    /// 节点中的所有叶子，包括空白和注释
+    pub fn descendant_tokens(&self) -> Vec<SyntaxToken> {
+        let mut tokens = Vec::new();
+        for element in self.children_with_tokens() {
+            match element {
+                SyntaxElement::Node(node) => tokens.extend(node.descendant_tokens()),
+                SyntaxElement::Token(token) => tokens.push(token),
+            }
+        }
+        tokens
+    }
+
+    /// 覆盖字节偏移 offset 的叶子
+    pub fn token_at_offset(&self, offset: usize) -> Option<SyntaxToken> {
+        self.descendant_tokens()
+            .into_iter()
+            .find(|token| token.text_range().contains(&offset))
+    }
+}
+
+impl PartialEq for SyntaxNode {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0.green, &other.0.green) && self.0.offset == other.0.offset
+    }
+}
+
+impl fmt::Debug for SyntaxNode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}@{:?}", self.kind(), self.text_range())
+    }
+}
+
+impl SyntaxToken {
+    pub fn kind(&self) -> SyntaxKind {
+        self.green.kind
+    }
+
+    /// 语法分析器看到的记号，空白、注释和词法错误没有记号
+    pub fn token(&self) -> Option<&Token> {
+        self.green.token.as_ref()
+    }
+
+    pub fn text(&self) -> &str {
+        &self.green.text
+    }
+
+    pub fn text_range(&self) -> Range<usize> {
+        self.offset..self.offset + self.green.text.len()
+    }
+
+    pub fn parent(&self) -> SyntaxNode {
+        self.parent.clone()
+    }
+}
+
+impl PartialEq for SyntaxToken {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.green, &other.green) && self.offset == other.offset
+    }
+}
+
+impl fmt::Debug for SyntaxToken {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{:?}@{:?} {:?}",
+            self.kind(),
+            self.text_range(),
+            self.text()
+        )
+    }
+}
+
+/// 按记号区间构建绿树，区间中的下标是语法分析器记号流中的下标
+struct TreeBuilder {
+    /// 正在构建的节点：(种类, 结束下标, 子节点)，栈底是整个源文件
+    stack: Vec<(SyntaxKind, usize, Vec<GreenElement>)>,
+    /// 还没有决定放入哪个节点的 trivia 和词法错误
+    pending: Vec<GreenElement>,
+}
+
+impl TreeBuilder {
+    fn leaf(kind: SyntaxKind, token: Option<Token>, text: String) -> GreenElement {
+        GreenElement::Token(Rc::new(GreenToken { kind, token, text }))
+    }
+
+    /// 关闭在 index 之前结束的节点，然后把待定的叶子放入当前节点
+    ///
+    /// 节点之间的空白和注释因此属于外层节点，节点总是从记号开始、以记号结束
+    fn close_before(&mut self, index: usize) {
+        while self.stack.len() > 1 && self.stack.last().is_some_and(|(_, end, _)| *end <= index) {
+            let (kind, _, children) = self.stack.pop().unwrap();
+            let node = GreenElement::Node(Rc::new(GreenNode::new(kind, children)));
+            self.stack.last_mut().unwrap().2.push(node);
+        }
+        let pending = std::mem::take(&mut self.pending);
+        self.stack.last_mut().unwrap().2.extend(pending);
+    }
+
+    fn build(
+        raw_tokens: Vec<RawToken<'_>>,
+        trailing_trivia: Vec<Trivia>,
+        ranges: Vec<(SyntaxKind, usize, usize)>,
+    ) -> Rc<GreenNode> {
+        // 外层节点先开始；区间相同时后完成的节点在外层
+        let mut ranges: Vec<_> = ranges
+            .into_iter()
+            .enumerate()
+            .filter(|(_, (_, start, end))| start < end)
+            .collect();
+        ranges.sort_by_key(|(order, (_, start, end))| (*start, Reverse(*end), Reverse(*order)));
+        let mut ranges = ranges.into_iter().map(|(_, range)| range).peekable();
+
+        let mut builder = TreeBuilder {
+            stack: vec![(SyntaxKind::SourceFile, usize::MAX, Vec::new())],
+            pending: Vec::new(),
+        };
+        let mut index = 0;
+        for raw in raw_tokens {
+            for trivia in raw.leading_trivia {
+                builder
+                    .pending
+                    .push(Self::leaf(trivia.kind.into(), None, trivia.text));
+            }
+            let token = match raw.token.data {
+                Ok(token) => token,
+                Err(_) => {
+                    builder
+                        .pending
+                        .push(Self::leaf(SyntaxKind::ErrorToken, None, raw.text));
+                    continue;
+                }
+            };
+
+            builder.close_before(index);
+            while let Some((kind, _, end)) = ranges.next_if(|(_, start, _)| *start <= index) {
+                builder.stack.push((kind, end, Vec::new()));
+            }
+            builder.stack.last_mut().unwrap().2.push(Self::leaf(
+                SyntaxKind::Token,
+                Some(token),
+                raw.text,
+            ));
+            index += 1;
+        }
+
+        for trivia in trailing_trivia {
+            builder
+                .pending
+                .push(Self::leaf(trivia.kind.into(), None, trivia.text));
+        }
+        builder.close_before(usize::MAX);
+        let (kind, _, children) = builder.stack.pop().unwrap();
+        Rc::new(GreenNode::new(kind, children))
+    }
+}
+
+/// 一个源文件的无损语法树，以及构建时遇到的错误
+pub struct SyntaxTree {
+    root: SyntaxNode,
+    file: String,
+    source: String,
+    /// 每一行开头的字节偏移
+    line_starts: Vec<usize>,
+    /// 语法分析器在构建语法树的同一次解析中得到的语句
+    statements: Vec<Stmt>,
+    errors: Vec<ParseError>,
+    lexical_errors: Vec<(Span, LexicalError)>,
+}
+
+impl SyntaxTree {
+    /// 解析源代码，遇到错误时仍然构建完整的语法树
+    pub fn parse(file: &str, source: &str) -> SyntaxTree {
+        let mut lexer = Lexer::new(file, BufReader::new(source.as_bytes()));
+        let mut raw_tokens = Vec::new();
+        while let Some(raw) = lexer.next_raw() {
+            raw_tokens.push(raw);
+        }
+        let trailing_trivia = lexer.trailing_trivia();
+
+        let mut lexical_errors = Vec::new();
+        let mut tokens = Vec::new();
+        for raw in &raw_tokens {
+            let location = raw.token.location;
+            match &raw.token.data {
+                Ok(token) => tokens.push(Locatable {
+                    location,
+                    data: token.clone(),
+                }),
+                Err(error) => lexical_errors.push((
+                    Span::between(
+                        location,
+                        Location {
+                            column: location.column + raw.text.chars().count(),
+                            ..location
+                        },
+                    ),
+                    error.clone(),
+                )),
+            }
+        }
+
+        let mut parser = Parser::new(tokens);
+        parser.record_syntax_nodes();
+        let (statements, errors) = parser.parse_with_recovery();
+        let green = TreeBuilder::build(raw_tokens, trailing_trivia, parser.take_syntax_nodes());
+
+        let line_starts = std::iter::once(0)
+            .chain(source.match_indices('\n').map(|(offset, _)| offset + 1))
+            .collect();
+        SyntaxTree {
+            root: SyntaxNode::new_root(green),
+            file: file.to_string(),
+            source: source.to_string(),
+            line_starts,
+            statements,
+            errors,
+            lexical_errors,
+        }
+    }
+
+    /// 语法树的根节点，种类为 `SyntaxKind::SourceFile`
+    pub fn root(&self) -> SyntaxNode {
+        self.root.clone()
+    }
+
+    pub fn source_file(&self) -> SourceFile {
+        SourceFile(self.root())
+    }
+
+    /// 语法错误，按出现顺序排列
+    pub fn errors(&self) -> &[ParseError] {
+        &self.errors
+    }
+
+    /// 词法错误及其位置，出错的字符作为 `SyntaxKind::ErrorToken` 保留在树中
+    pub fn lexical_errors(&self) -> &[(Span, LexicalError)] {
+        &self.lexical_errors
+    }
+
+    /// 源文件名
+    pub fn file(&self) -> &str {
+        &self.file
+    }
+
+    /// 字节偏移处的位置，行号和列号从 1 开始，列号按字符计算
+    pub fn location(&self, offset: usize) -> Location<'_> {
+        let line = self.line_starts.partition_point(|start| *start <= offset);
+        let line_start = self.line_starts[line - 1];
+        Location {
+            line,
+            column: self.source[line_start..offset].chars().count() + 1,
+            file: &self.file,
+        }
+    }
+
+    /// 类型化的顶层语句，即 `Parser::parse_with_recovery` 的结果，解析失败的语句是 `Stmt::Error`
+    pub fn statements(&self) -> &[Stmt] {
+        &self.statements
+    }
+
+    /// 语句节点对应的类型化语句，包括嵌套在块和声明中的语句。
+    /// 同一个位置开始的语句种类各不相同，因此按种类和位置查找
+    pub fn statement(&self, item: &Item) -> Option<&Stmt> {
+        let range = item.syntax().text_range();
+        let offset = match item.kind() {
+            // 块语句的位置是右花括号
+            SyntaxKind::BlockStatement => range.end - 1,
+            _ => range.start,
+        };
+        let location = self.location(offset);
+        let mut finder = StatementFinder {
+            kind: item.kind(),
+            position: (location.line, location.column),
+            found: None,
+        };
+        finder.visit_block(&self.statements);
+        finder.found
+    }
+}
+
+/// 按种类和位置查找语句
+struct StatementFinder<'ast> {
+    kind: SyntaxKind,
+    position: (usize, usize),
+    found: Option<&'ast Stmt>,
+}
+
+impl<'ast> Visitor<'ast> for StatementFinder<'ast> {
+    fn visit_stmt(&mut self, stmt: &'ast Stmt) {
+        if self.found.is_some() {
+            return;
+        }
+        let location = stmt.location();
+        if SyntaxKind::of_stmt(stmt) == self.kind
+            && (location.line, location.column) == self.position
+        {
+            self.found = Some(stmt);
+            return;
+        }
+        walk_stmt(self, stmt);
+    }
+}
+
+/// 类型化的语法树节点，是对特定种类的 `SyntaxNode` 的包装
+pub trait AstNode: Sized {
+    /// 种类匹配时包装节点
+    fn cast(node: SyntaxNode) -> Option<Self>;
+
+    fn syntax(&self) -> &SyntaxNode;
+}
+
+/// 整个源文件
+#[derive(Debug, Clone, PartialEq)]
+pub struct SourceFile(SyntaxNode);
+
+/// 一条语句或声明，包括解析失败的语句
+#[derive(Debug, Clone, PartialEq)]
+pub struct Item(SyntaxNode);
+
+/// `{ ... }` 块
+#[derive(Debug, Clone, PartialEq)]
+pub struct Block(SyntaxNode);
+
+impl AstNode for SourceFile {
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        (node.kind() == SyntaxKind::SourceFile).then_some(SourceFile(node))
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+impl AstNode for Item {
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        node.kind().is_item().then_some(Item(node))
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+impl AstNode for Block {
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        (node.kind() == SyntaxKind::Block).then_some(Block(node))
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        &self.0
+    }
+}
+
+/// 节点的直接子节点中的语句
+fn child_items(node: &SyntaxNode) -> Vec<Item> {
+    node.children().into_iter().filter_map(Item::cast).collect()
+}
+
+impl SourceFile {
+    /// 顶层语句
+    pub fn items(&self) -> Vec<Item> {
+        child_items(&self.0)
+    }
+}
+
+impl Item {
+    pub fn kind(&self) -> SyntaxKind {
+        self.0.kind()
+    }
+
+    /// 声明的名称，即可见性修饰和关键字之后的第一个标识符
+    pub fn name(&self) -> Option<String> {
+        if matches!(
+            self.kind(),
+            SyntaxKind::ExpressionStatement | SyntaxKind::BlockStatement | SyntaxKind::Error
+        ) {
+            return None;
+        }
+        let tokens: Vec<Token> = self
+            .0
+            .children_with_tokens()
+            .into_iter()
+            .filter_map(|element| match element {
+                SyntaxElement::Token(token) => token.token().cloned(),
+                SyntaxElement::Node(_) => None,
+            })
+            .collect();
+        // 跳过 pub 或 pub(crate)
+        let skip = match tokens.as_slice() {
+            [Token::Pub, Token::LParen, ..] => 4,
+            [Token::Pub, ..] => 1,
+            _ => 0,
+        };
+        tokens.into_iter().skip(skip).find_map(|token| match token {
+            Token::Identifier(name) => Some(name),
+            _ => None,
+        })
+    }
+
+    /// 直接嵌套的语句，例如内联模块中的声明
+    pub fn items(&self) -> Vec<Item> {
+        child_items(&self.0)
+    }
+
+    /// 声明的第一个块，例如函数体
+    pub fn body(&self) -> Option<Block> {
+        self.0.children().into_iter().find_map(Block::cast)
+    }
+}
+
+impl Block {
+    /// 块中的语句
+    pub fn items(&self) -> Vec<Item> {
+        child_items(&self.0)
+    }
+}
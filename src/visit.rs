@@ -0,0 +1,985 @@
+//! 语法树遍历模块
+//!
+//! `Visitor` 只读地遍历语法树，`VisitorMut` 原地修改语法树。每种节点都有一个 `visit_*`
+//! 方法，默认实现调用同名的 `walk_*` 函数访问子节点，叶子节点的默认实现什么也不做。
+//! 实现者只需重写关心的节点，在重写的方法中调用 `walk_*` 即可继续访问子节点。
+//! 按值重建语法树见 `fold` 模块。
+
+use crate::parser::{
+    Capture, ContractClause, ContractDeclaration, EffectDeclaration, EffectGroupDeclaration,
+    EffectOperation, EffectRow, EnumDeclaration, EnumVariant, Expr, FunctionSignature,
+    GenericParameter, HandlerClause, HandlerDeclaration, HandlerGroupDeclaration, HandlerReference,
+    ImplDeclaration, Literal, ModuleDeclaration, Parameter, ReturnClause, Stmt, StructDeclaration,
+    StructFields, TraitDeclaration, TraitItem, TypeAliasDeclaration, UseDeclaration,
+    VariantArguments, WherePredicate,
+};
+
+/// 只读遍历语法树，'ast 是被遍历的语法树的生命周期，访问者可以保存节点的引用
+pub trait Visitor<'ast> {
+    fn visit_stmt(&mut self, stmt: &'ast Stmt) {
+        walk_stmt(self, stmt);
+    }
+
+    fn visit_expr(&mut self, expr: &'ast Expr) {
+        walk_expr(self, expr);
+    }
+
+    /// 语句序列：函数体、块、处理器子句体等
+    fn visit_block(&mut self, statements: &'ast [Stmt]) {
+        walk_block(self, statements);
+    }
+
+    fn visit_literal(&mut self, _literal: &'ast Literal) {}
+
+    fn visit_parameter(&mut self, _parameter: &'ast Parameter) {}
+
+    fn visit_generic_parameter(&mut self, _generic: &'ast GenericParameter) {}
+
+    fn visit_where_predicate(&mut self, _predicate: &'ast WherePredicate) {}
+
+    fn visit_effect_row(&mut self, _effects: &'ast EffectRow) {}
+
+    fn visit_capture(&mut self, _capture: &'ast Capture) {}
+
+    fn visit_contract_clause(&mut self, clause: &'ast ContractClause) {
+        walk_contract_clause(self, clause);
+    }
+
+    fn visit_function_signature(&mut self, signature: &'ast FunctionSignature) {
+        walk_function_signature(self, signature);
+    }
+
+    fn visit_effect_declaration(&mut self, declaration: &'ast EffectDeclaration) {
+        walk_effect_declaration(self, declaration);
+    }
+
+    fn visit_effect_operation(&mut self, _operation: &'ast EffectOperation) {}
+
+    fn visit_handler_declaration(&mut self, declaration: &'ast HandlerDeclaration) {
+        walk_handler_declaration(self, declaration);
+    }
+
+    fn visit_handler_clause(&mut self, clause: &'ast HandlerClause) {
+        walk_handler_clause(self, clause);
+    }
+
+    fn visit_return_clause(&mut self, clause: &'ast ReturnClause) {
+        walk_return_clause(self, clause);
+    }
+
+    fn visit_handler_reference(&mut self, handler: &'ast HandlerReference) {
+        walk_handler_reference(self, handler);
+    }
+
+    fn visit_effect_group_declaration(&mut self, _declaration: &'ast EffectGroupDeclaration) {}
+
+    fn visit_handler_group_declaration(&mut self, _declaration: &'ast HandlerGroupDeclaration) {}
+
+    fn visit_use_declaration(&mut self, _declaration: &'ast UseDeclaration) {}
+
+    fn visit_struct_declaration(&mut self, declaration: &'ast StructDeclaration) {
+        walk_struct_declaration(self, declaration);
+    }
+
+    fn visit_struct_fields(&mut self, _fields: &'ast StructFields) {}
+
+    fn visit_enum_declaration(&mut self, declaration: &'ast EnumDeclaration) {
+        walk_enum_declaration(self, declaration);
+    }
+
+    fn visit_enum_variant(&mut self, variant: &'ast EnumVariant) {
+        walk_enum_variant(self, variant);
+    }
+
+    fn visit_type_alias_declaration(&mut self, declaration: &'ast TypeAliasDeclaration) {
+        walk_type_alias_declaration(self, declaration);
+    }
+
+    fn visit_trait_declaration(&mut self, declaration: &'ast TraitDeclaration) {
+        walk_trait_declaration(self, declaration);
+    }
+
+    fn visit_trait_item(&mut self, item: &'ast TraitItem) {
+        walk_trait_item(self, item);
+    }
+
+    fn visit_impl_declaration(&mut self, declaration: &'ast ImplDeclaration) {
+        walk_impl_declaration(self, declaration);
+    }
+
+    fn visit_contract_declaration(&mut self, declaration: &'ast ContractDeclaration) {
+        walk_contract_declaration(self, declaration);
+    }
+
+    fn visit_module_declaration(&mut self, declaration: &'ast ModuleDeclaration) {
+        walk_module_declaration(self, declaration);
+    }
+
+    fn visit_variant_arguments(&mut self, arguments: &'ast VariantArguments) {
+        walk_variant_arguments(self, arguments);
+    }
+}
+
+pub fn walk_stmt<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, stmt: &'ast Stmt) {
+    match stmt {
+        Stmt::Expression { expression, .. } => visitor.visit_expr(expression),
+        Stmt::Let { initializer, .. } | Stmt::Var { initializer, .. } => {
+            if let Some(initializer) = initializer {
+                visitor.visit_expr(initializer);
+            }
+        }
+        Stmt::Function {
+            generics,
+            parameters,
+            effects,
+            where_clause,
+            contracts,
+            body,
+            ..
+        } => {
+            for generic in generics {
+                visitor.visit_generic_parameter(generic);
+            }
+            for parameter in parameters {
+                visitor.visit_parameter(parameter);
+            }
+            visitor.visit_effect_row(effects);
+            for predicate in where_clause {
+                visitor.visit_where_predicate(predicate);
+            }
+            for clause in contracts {
+                visitor.visit_contract_clause(clause);
+            }
+            visitor.visit_block(body);
+        }
+        Stmt::Block { statements, .. } => visitor.visit_block(statements),
+        Stmt::Effect { declaration, .. } => visitor.visit_effect_declaration(declaration),
+        Stmt::Handler { declaration, .. } => visitor.visit_handler_declaration(declaration),
+        Stmt::EffectGroup { declaration, .. } => {
+            visitor.visit_effect_group_declaration(declaration)
+        }
+        Stmt::HandlerGroup { declaration, .. } => {
+            visitor.visit_handler_group_declaration(declaration)
+        }
+        Stmt::Use { declaration, .. } => visitor.visit_use_declaration(declaration),
+        Stmt::Struct { declaration, .. } => visitor.visit_struct_declaration(declaration),
+        Stmt::Enum { declaration, .. } => visitor.visit_enum_declaration(declaration),
+        Stmt::TypeAlias { declaration, .. } => visitor.visit_type_alias_declaration(declaration),
+        Stmt::Trait { declaration, .. } => visitor.visit_trait_declaration(declaration),
+        Stmt::Impl { declaration, .. } => visitor.visit_impl_declaration(declaration),
+        Stmt::Contract { declaration, .. } => visitor.visit_contract_declaration(declaration),
+        Stmt::Module { declaration, .. } => visitor.visit_module_declaration(declaration),
+        Stmt::Error { .. } => {}
+    }
+}
+
+pub fn walk_expr<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, expr: &'ast Expr) {
+    match expr {
+        Expr::Literal { value, .. } => visitor.visit_literal(value),
+        Expr::Identifier { .. } | Expr::Path { .. } | Expr::Result { .. } => {}
+        Expr::Binary { left, right, .. } => {
+            visitor.visit_expr(left);
+            visitor.visit_expr(right);
+        }
+        Expr::Assign { target, value, .. } => {
+            visitor.visit_expr(target);
+            visitor.visit_expr(value);
+        }
+        Expr::Unary { operand, .. } => visitor.visit_expr(operand),
+        Expr::Cast { expression, .. }
+        | Expr::Grouping { expression, .. }
+        | Expr::Try { expression, .. }
+        | Expr::Await { expression, .. }
+        | Expr::Old { expression, .. } => visitor.visit_expr(expression),
+        Expr::Range { start, end, .. } => {
+            if let Some(start) = start {
+                visitor.visit_expr(start);
+            }
+            if let Some(end) = end {
+                visitor.visit_expr(end);
+            }
+        }
+        Expr::Call {
+            function,
+            arguments,
+            ..
+        } => {
+            visitor.visit_expr(function);
+            for argument in arguments {
+                visitor.visit_expr(argument);
+            }
+        }
+        Expr::Tuple { elements, .. } | Expr::Array { elements, .. } => {
+            for element in elements {
+                visitor.visit_expr(element);
+            }
+        }
+        Expr::ArrayRepeat { value, count, .. } => {
+            visitor.visit_expr(value);
+            visitor.visit_expr(count);
+        }
+        Expr::Async { statements, .. }
+        | Expr::TryBlock { statements, .. }
+        | Expr::Block { statements, .. } => visitor.visit_block(statements),
+        Expr::Field { object, .. } => visitor.visit_expr(object),
+        Expr::MethodCall {
+            receiver,
+            arguments,
+            ..
+        } => {
+            visitor.visit_expr(receiver);
+            for argument in arguments {
+                visitor.visit_expr(argument);
+            }
+        }
+        Expr::Index { object, index, .. } => {
+            visitor.visit_expr(object);
+            visitor.visit_expr(index);
+        }
+        Expr::StructLiteral { fields, base, .. } => {
+            for (_, value) in fields {
+                visitor.visit_expr(value);
+            }
+            if let Some(base) = base {
+                visitor.visit_expr(base);
+            }
+        }
+        Expr::EnumConstructor { arguments, .. } => visitor.visit_variant_arguments(arguments),
+        Expr::With { handlers, body, .. } => {
+            for handler in handlers {
+                visitor.visit_handler_reference(handler);
+            }
+            visitor.visit_block(body);
+        }
+        Expr::Resume { value, .. } => {
+            if let Some(value) = value {
+                visitor.visit_expr(value);
+            }
+        }
+        Expr::Closure {
+            parameters,
+            effects,
+            body,
+            captures,
+            ..
+        } => {
+            for parameter in parameters {
+                visitor.visit_parameter(parameter);
+            }
+            visitor.visit_effect_row(effects);
+            visitor.visit_expr(body);
+            for capture in captures {
+                visitor.visit_capture(capture);
+            }
+        }
+        Expr::EffectOperation { arguments, .. } => {
+            for argument in arguments {
+                visitor.visit_expr(argument);
+            }
+        }
+    }
+}
+
+pub fn walk_block<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, statements: &'ast [Stmt]) {
+    for statement in statements {
+        visitor.visit_stmt(statement);
+    }
+}
+
+pub fn walk_contract_clause<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    clause: &'ast ContractClause,
+) {
+    match clause {
+        ContractClause::Requires(condition)
+        | ContractClause::Ensures(condition)
+        | ContractClause::Invariant(condition) => visitor.visit_expr(condition),
+        ContractClause::Named(_) => {}
+    }
+}
+
+pub fn walk_function_signature<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    signature: &'ast FunctionSignature,
+) {
+    for generic in &signature.generics {
+        visitor.visit_generic_parameter(generic);
+    }
+    for parameter in &signature.parameters {
+        visitor.visit_parameter(parameter);
+    }
+    visitor.visit_effect_row(&signature.effects);
+    for predicate in &signature.where_clause {
+        visitor.visit_where_predicate(predicate);
+    }
+    for clause in &signature.contracts {
+        visitor.visit_contract_clause(clause);
+    }
+}
+
+pub fn walk_effect_declaration<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    declaration: &'ast EffectDeclaration,
+) {
+    for generic in &declaration.generics {
+        visitor.visit_generic_parameter(generic);
+    }
+    for operation in &declaration.operations {
+        visitor.visit_effect_operation(operation);
+    }
+}
+
+pub fn walk_handler_declaration<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    declaration: &'ast HandlerDeclaration,
+) {
+    for clause in &declaration.clauses {
+        visitor.visit_handler_clause(clause);
+    }
+    if let Some(clause) = &declaration.return_clause {
+        visitor.visit_return_clause(clause);
+    }
+    if let Some(body) = &declaration.finally_clause {
+        visitor.visit_block(body);
+    }
+}
+
+pub fn walk_handler_clause<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    clause: &'ast HandlerClause,
+) {
+    for parameter in &clause.parameters {
+        visitor.visit_parameter(parameter);
+    }
+    visitor.visit_block(&clause.body);
+}
+
+pub fn walk_return_clause<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    clause: &'ast ReturnClause,
+) {
+    visitor.visit_parameter(&clause.parameter);
+    visitor.visit_block(&clause.body);
+}
+
+pub fn walk_handler_reference<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    handler: &'ast HandlerReference,
+) {
+    match handler {
+        HandlerReference::Named(_) => {}
+        HandlerReference::Inline(declaration) => visitor.visit_handler_declaration(declaration),
+    }
+}
+
+pub fn walk_struct_declaration<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    declaration: &'ast StructDeclaration,
+) {
+    for generic in &declaration.generics {
+        visitor.visit_generic_parameter(generic);
+    }
+    visitor.visit_struct_fields(&declaration.fields);
+    for clause in &declaration.contracts {
+        visitor.visit_contract_clause(clause);
+    }
+}
+
+pub fn walk_enum_declaration<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    declaration: &'ast EnumDeclaration,
+) {
+    for generic in &declaration.generics {
+        visitor.visit_generic_parameter(generic);
+    }
+    for variant in &declaration.variants {
+        visitor.visit_enum_variant(variant);
+    }
+}
+
+pub fn walk_enum_variant<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    variant: &'ast EnumVariant,
+) {
+    visitor.visit_struct_fields(&variant.fields);
+}
+
+pub fn walk_type_alias_declaration<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    declaration: &'ast TypeAliasDeclaration,
+) {
+    for generic in &declaration.generics {
+        visitor.visit_generic_parameter(generic);
+    }
+}
+
+pub fn walk_trait_declaration<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    declaration: &'ast TraitDeclaration,
+) {
+    for generic in &declaration.generics {
+        visitor.visit_generic_parameter(generic);
+    }
+    for predicate in &declaration.where_clause {
+        visitor.visit_where_predicate(predicate);
+    }
+    for clause in &declaration.contracts {
+        visitor.visit_contract_clause(clause);
+    }
+    for item in &declaration.items {
+        visitor.visit_trait_item(item);
+    }
+}
+
+pub fn walk_trait_item<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, item: &'ast TraitItem) {
+    match item {
+        TraitItem::Method {
+            signature,
+            default_body,
+        } => {
+            visitor.visit_function_signature(signature);
+            if let Some(body) = default_body {
+                visitor.visit_block(body);
+            }
+        }
+        TraitItem::AssociatedType { .. } => {}
+    }
+}
+
+pub fn walk_impl_declaration<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    declaration: &'ast ImplDeclaration,
+) {
+    for generic in &declaration.generics {
+        visitor.visit_generic_parameter(generic);
+    }
+    for predicate in &declaration.where_clause {
+        visitor.visit_where_predicate(predicate);
+    }
+    for item in &declaration.items {
+        visitor.visit_stmt(item);
+    }
+}
+
+pub fn walk_contract_declaration<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    declaration: &'ast ContractDeclaration,
+) {
+    for parameter in &declaration.parameters {
+        visitor.visit_parameter(parameter);
+    }
+    for clause in &declaration.clauses {
+        visitor.visit_contract_clause(clause);
+    }
+}
+
+pub fn walk_module_declaration<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    declaration: &'ast ModuleDeclaration,
+) {
+    for item in declaration.body.items() {
+        visitor.visit_stmt(item);
+    }
+}
+
+pub fn walk_variant_arguments<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    arguments: &'ast VariantArguments,
+) {
+    match arguments {
+        VariantArguments::Unit => {}
+        VariantArguments::Tuple(arguments) => {
+            for argument in arguments {
+                visitor.visit_expr(argument);
+            }
+        }
+        VariantArguments::Named(fields) => {
+            for (_, value) in fields {
+                visitor.visit_expr(value);
+            }
+        }
+    }
+}
+
+/// 遍历并原地修改语法树
+pub trait VisitorMut {
+    fn visit_stmt_mut(&mut self, stmt: &mut Stmt) {
+        walk_stmt_mut(self, stmt);
+    }
+
+    fn visit_expr_mut(&mut self, expr: &mut Expr) {
+        walk_expr_mut(self, expr);
+    }
+
+    /// 语句序列：函数体、块、处理器子句体等
+    fn visit_block_mut(&mut self, statements: &mut [Stmt]) {
+        walk_block_mut(self, statements);
+    }
+
+    fn visit_literal_mut(&mut self, _literal: &mut Literal) {}
+
+    fn visit_parameter_mut(&mut self, _parameter: &mut Parameter) {}
+
+    fn visit_generic_parameter_mut(&mut self, _generic: &mut GenericParameter) {}
+
+    fn visit_where_predicate_mut(&mut self, _predicate: &mut WherePredicate) {}
+
+    fn visit_effect_row_mut(&mut self, _effects: &mut EffectRow) {}
+
+    fn visit_capture_mut(&mut self, _capture: &mut Capture) {}
+
+    fn visit_contract_clause_mut(&mut self, clause: &mut ContractClause) {
+        walk_contract_clause_mut(self, clause);
+    }
+
+    fn visit_function_signature_mut(&mut self, signature: &mut FunctionSignature) {
+        walk_function_signature_mut(self, signature);
+    }
+
+    fn visit_effect_declaration_mut(&mut self, declaration: &mut EffectDeclaration) {
+        walk_effect_declaration_mut(self, declaration);
+    }
+
+    fn visit_effect_operation_mut(&mut self, _operation: &mut EffectOperation) {}
+
+    fn visit_handler_declaration_mut(&mut self, declaration: &mut HandlerDeclaration) {
+        walk_handler_declaration_mut(self, declaration);
+    }
+
+    fn visit_handler_clause_mut(&mut self, clause: &mut HandlerClause) {
+        walk_handler_clause_mut(self, clause);
+    }
+
+    fn visit_return_clause_mut(&mut self, clause: &mut ReturnClause) {
+        walk_return_clause_mut(self, clause);
+    }
+
+    fn visit_handler_reference_mut(&mut self, handler: &mut HandlerReference) {
+        walk_handler_reference_mut(self, handler);
+    }
+
+    fn visit_effect_group_declaration_mut(&mut self, _declaration: &mut EffectGroupDeclaration) {}
+
+    fn visit_handler_group_declaration_mut(&mut self, _declaration: &mut HandlerGroupDeclaration) {}
+
+    fn visit_use_declaration_mut(&mut self, _declaration: &mut UseDeclaration) {}
+
+    fn visit_struct_declaration_mut(&mut self, declaration: &mut StructDeclaration) {
+        walk_struct_declaration_mut(self, declaration);
+    }
+
+    fn visit_struct_fields_mut(&mut self, _fields: &mut StructFields) {}
+
+    fn visit_enum_declaration_mut(&mut self, declaration: &mut EnumDeclaration) {
+        walk_enum_declaration_mut(self, declaration);
+    }
+
+    fn visit_enum_variant_mut(&mut self, variant: &mut EnumVariant) {
+        walk_enum_variant_mut(self, variant);
+    }
+
+    fn visit_type_alias_declaration_mut(&mut self, declaration: &mut TypeAliasDeclaration) {
+        walk_type_alias_declaration_mut(self, declaration);
+    }
+
+    fn visit_trait_declaration_mut(&mut self, declaration: &mut TraitDeclaration) {
+        walk_trait_declaration_mut(self, declaration);
+    }
+
+    fn visit_trait_item_mut(&mut self, item: &mut TraitItem) {
+        walk_trait_item_mut(self, item);
+    }
+
+    fn visit_impl_declaration_mut(&mut self, declaration: &mut ImplDeclaration) {
+        walk_impl_declaration_mut(self, declaration);
+    }
+
+    fn visit_contract_declaration_mut(&mut self, declaration: &mut ContractDeclaration) {
+        walk_contract_declaration_mut(self, declaration);
+    }
+
+    fn visit_module_declaration_mut(&mut self, declaration: &mut ModuleDeclaration) {
+        walk_module_declaration_mut(self, declaration);
+    }
+
+    fn visit_variant_arguments_mut(&mut self, arguments: &mut VariantArguments) {
+        walk_variant_arguments_mut(self, arguments);
+    }
+}
+
+pub fn walk_stmt_mut<V: VisitorMut + ?Sized>(visitor: &mut V, stmt: &mut Stmt) {
+    match stmt {
+        Stmt::Expression { expression, .. } => visitor.visit_expr_mut(expression),
+        Stmt::Let { initializer, .. } | Stmt::Var { initializer, .. } => {
+            if let Some(initializer) = initializer {
+                visitor.visit_expr_mut(initializer);
+            }
+        }
+        Stmt::Function {
+            generics,
+            parameters,
+            effects,
+            where_clause,
+            contracts,
+            body,
+            ..
+        } => {
+            for generic in generics {
+                visitor.visit_generic_parameter_mut(generic);
+            }
+            for parameter in parameters {
+                visitor.visit_parameter_mut(parameter);
+            }
+            visitor.visit_effect_row_mut(effects);
+            for predicate in where_clause {
+                visitor.visit_where_predicate_mut(predicate);
+            }
+            for clause in contracts {
+                visitor.visit_contract_clause_mut(clause);
+            }
+            visitor.visit_block_mut(body);
+        }
+        Stmt::Block { statements, .. } => visitor.visit_block_mut(statements),
+        Stmt::Effect { declaration, .. } => visitor.visit_effect_declaration_mut(declaration),
+        Stmt::Handler { declaration, .. } => visitor.visit_handler_declaration_mut(declaration),
+        Stmt::EffectGroup { declaration, .. } => {
+            visitor.visit_effect_group_declaration_mut(declaration)
+        }
+        Stmt::HandlerGroup { declaration, .. } => {
+            visitor.visit_handler_group_declaration_mut(declaration)
+        }
+        Stmt::Use { declaration, .. } => visitor.visit_use_declaration_mut(declaration),
+        Stmt::Struct { declaration, .. } => visitor.visit_struct_declaration_mut(declaration),
+        Stmt::Enum { declaration, .. } => visitor.visit_enum_declaration_mut(declaration),
+        Stmt::TypeAlias { declaration, .. } => {
+            visitor.visit_type_alias_declaration_mut(declaration)
+        }
+        Stmt::Trait { declaration, .. } => visitor.visit_trait_declaration_mut(declaration),
+        Stmt::Impl { declaration, .. } => visitor.visit_impl_declaration_mut(declaration),
+        Stmt::Contract { declaration, .. } => visitor.visit_contract_declaration_mut(declaration),
+        Stmt::Module { declaration, .. } => visitor.visit_module_declaration_mut(declaration),
+        Stmt::Error { .. } => {}
+    }
+}
+
+pub fn walk_expr_mut<V: VisitorMut + ?Sized>(visitor: &mut V, expr: &mut Expr) {
+    match expr {
+        Expr::Literal { value, .. } => visitor.visit_literal_mut(value),
+        Expr::Identifier { .. } | Expr::Path { .. } | Expr::Result { .. } => {}
+        Expr::Binary { left, right, .. } => {
+            visitor.visit_expr_mut(left);
+            visitor.visit_expr_mut(right);
+        }
+        Expr::Assign { target, value, .. } => {
+            visitor.visit_expr_mut(target);
+            visitor.visit_expr_mut(value);
+        }
+        Expr::Unary { operand, .. } => visitor.visit_expr_mut(operand),
+        Expr::Cast { expression, .. }
+        | Expr::Grouping { expression, .. }
+        | Expr::Try { expression, .. }
+        | Expr::Await { expression, .. }
+        | Expr::Old { expression, .. } => visitor.visit_expr_mut(expression),
+        Expr::Range { start, end, .. } => {
+            if let Some(start) = start {
+                visitor.visit_expr_mut(start);
+            }
+            if let Some(end) = end {
+                visitor.visit_expr_mut(end);
+            }
+        }
+        Expr::Call {
+            function,
+            arguments,
+            ..
+        } => {
+            visitor.visit_expr_mut(function);
+            for argument in arguments {
+                visitor.visit_expr_mut(argument);
+            }
+        }
+        Expr::Tuple { elements, .. } | Expr::Array { elements, .. } => {
+            for element in elements {
+                visitor.visit_expr_mut(element);
+            }
+        }
+        Expr::ArrayRepeat { value, count, .. } => {
+            visitor.visit_expr_mut(value);
+            visitor.visit_expr_mut(count);
+        }
+        Expr::Async { statements, .. }
+        | Expr::TryBlock { statements, .. }
+        | Expr::Block { statements, .. } => visitor.visit_block_mut(statements),
+        Expr::Field { object, .. } => visitor.visit_expr_mut(object),
+        Expr::MethodCall {
+            receiver,
+            arguments,
+            ..
+        } => {
+            visitor.visit_expr_mut(receiver);
+            for argument in arguments {
+                visitor.visit_expr_mut(argument);
+            }
+        }
+        Expr::Index { object, index, .. } => {
+            visitor.visit_expr_mut(object);
+            visitor.visit_expr_mut(index);
+        }
+        Expr::StructLiteral { fields, base, .. } => {
+            for (_, value) in fields {
+                visitor.visit_expr_mut(value);
+            }
+            if let Some(base) = base {
+                visitor.visit_expr_mut(base);
+            }
+        }
+        Expr::EnumConstructor { arguments, .. } => visitor.visit_variant_arguments_mut(arguments),
+        Expr::With { handlers, body, .. } => {
+            for handler in handlers {
+                visitor.visit_handler_reference_mut(handler);
+            }
+            visitor.visit_block_mut(body);
+        }
+        Expr::Resume { value, .. } => {
+            if let Some(value) = value {
+                visitor.visit_expr_mut(value);
+            }
+        }
+        Expr::Closure {
+            parameters,
+            effects,
+            body,
+            captures,
+            ..
+        } => {
+            for parameter in parameters {
+                visitor.visit_parameter_mut(parameter);
+            }
+            visitor.visit_effect_row_mut(effects);
+            visitor.visit_expr_mut(body);
+            for capture in captures {
+                visitor.visit_capture_mut(capture);
+            }
+        }
+        Expr::EffectOperation { arguments, .. } => {
+            for argument in arguments {
+                visitor.visit_expr_mut(argument);
+            }
+        }
+    }
+}
+
+pub fn walk_block_mut<V: VisitorMut + ?Sized>(visitor: &mut V, statements: &mut [Stmt]) {
+    for statement in statements {
+        visitor.visit_stmt_mut(statement);
+    }
+}
+
+pub fn walk_contract_clause_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    clause: &mut ContractClause,
+) {
+    match clause {
+        ContractClause::Requires(condition)
+        | ContractClause::Ensures(condition)
+        | ContractClause::Invariant(condition) => visitor.visit_expr_mut(condition),
+        ContractClause::Named(_) => {}
+    }
+}
+
+pub fn walk_function_signature_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    signature: &mut FunctionSignature,
+) {
+    for generic in &mut signature.generics {
+        visitor.visit_generic_parameter_mut(generic);
+    }
+    for parameter in &mut signature.parameters {
+        visitor.visit_parameter_mut(parameter);
+    }
+    visitor.visit_effect_row_mut(&mut signature.effects);
+    for predicate in &mut signature.where_clause {
+        visitor.visit_where_predicate_mut(predicate);
+    }
+    for clause in &mut signature.contracts {
+        visitor.visit_contract_clause_mut(clause);
+    }
+}
+
+pub fn walk_effect_declaration_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    declaration: &mut EffectDeclaration,
+) {
+    for generic in &mut declaration.generics {
+        visitor.visit_generic_parameter_mut(generic);
+    }
+    for operation in &mut declaration.operations {
+        visitor.visit_effect_operation_mut(operation);
+    }
+}
+
+pub fn walk_handler_declaration_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    declaration: &mut HandlerDeclaration,
+) {
+    for clause in &mut declaration.clauses {
+        visitor.visit_handler_clause_mut(clause);
+    }
+    if let Some(clause) = &mut declaration.return_clause {
+        visitor.visit_return_clause_mut(clause);
+    }
+    if let Some(body) = &mut declaration.finally_clause {
+        visitor.visit_block_mut(body);
+    }
+}
+
+pub fn walk_handler_clause_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    clause: &mut HandlerClause,
+) {
+    for parameter in &mut clause.parameters {
+        visitor.visit_parameter_mut(parameter);
+    }
+    visitor.visit_block_mut(&mut clause.body);
+}
+
+pub fn walk_return_clause_mut<V: VisitorMut + ?Sized>(visitor: &mut V, clause: &mut ReturnClause) {
+    visitor.visit_parameter_mut(&mut clause.parameter);
+    visitor.visit_block_mut(&mut clause.body);
+}
+
+pub fn walk_handler_reference_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    handler: &mut HandlerReference,
+) {
+    match handler {
+        HandlerReference::Named(_) => {}
+        HandlerReference::Inline(declaration) => visitor.visit_handler_declaration_mut(declaration),
+    }
+}
+
+pub fn walk_struct_declaration_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    declaration: &mut StructDeclaration,
+) {
+    for generic in &mut declaration.generics {
+        visitor.visit_generic_parameter_mut(generic);
+    }
+    visitor.visit_struct_fields_mut(&mut declaration.fields);
+    for clause in &mut declaration.contracts {
+        visitor.visit_contract_clause_mut(clause);
+    }
+}
+
+pub fn walk_enum_declaration_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    declaration: &mut EnumDeclaration,
+) {
+    for generic in &mut declaration.generics {
+        visitor.visit_generic_parameter_mut(generic);
+    }
+    for variant in &mut declaration.variants {
+        visitor.visit_enum_variant_mut(variant);
+    }
+}
+
+pub fn walk_enum_variant_mut<V: VisitorMut + ?Sized>(visitor: &mut V, variant: &mut EnumVariant) {
+    visitor.visit_struct_fields_mut(&mut variant.fields);
+}
+
+pub fn walk_type_alias_declaration_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    declaration: &mut TypeAliasDeclaration,
+) {
+    for generic in &mut declaration.generics {
+        visitor.visit_generic_parameter_mut(generic);
+    }
+}
+
+pub fn walk_trait_declaration_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    declaration: &mut TraitDeclaration,
+) {
+    for generic in &mut declaration.generics {
+        visitor.visit_generic_parameter_mut(generic);
+    }
+    for predicate in &mut declaration.where_clause {
+        visitor.visit_where_predicate_mut(predicate);
+    }
+    for clause in &mut declaration.contracts {
+        visitor.visit_contract_clause_mut(clause);
+    }
+    for item in &mut declaration.items {
+        visitor.visit_trait_item_mut(item);
+    }
+}
+
+pub fn walk_trait_item_mut<V: VisitorMut + ?Sized>(visitor: &mut V, item: &mut TraitItem) {
+    match item {
+        TraitItem::Method {
+            signature,
+            default_body,
+        } => {
+            visitor.visit_function_signature_mut(signature);
+            if let Some(body) = default_body {
+                visitor.visit_block_mut(body);
+            }
+        }
+        TraitItem::AssociatedType { .. } => {}
+    }
+}
+
+pub fn walk_impl_declaration_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    declaration: &mut ImplDeclaration,
+) {
+    for generic in &mut declaration.generics {
+        visitor.visit_generic_parameter_mut(generic);
+    }
+    for predicate in &mut declaration.where_clause {
+        visitor.visit_where_predicate_mut(predicate);
+    }
+    for item in &mut declaration.items {
+        visitor.visit_stmt_mut(item);
+    }
+}
+
+pub fn walk_contract_declaration_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    declaration: &mut ContractDeclaration,
+) {
+    for parameter in &mut declaration.parameters {
+        visitor.visit_parameter_mut(parameter);
+    }
+    for clause in &mut declaration.clauses {
+        visitor.visit_contract_clause_mut(clause);
+    }
+}
+
+pub fn walk_module_declaration_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    declaration: &mut ModuleDeclaration,
+) {
+    for item in declaration.body.items_mut() {
+        visitor.visit_stmt_mut(item);
+    }
+}
+
+pub fn walk_variant_arguments_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    arguments: &mut VariantArguments,
+) {
+    match arguments {
+        VariantArguments::Unit => {}
+        VariantArguments::Tuple(arguments) => {
+            for argument in arguments {
+                visitor.visit_expr_mut(argument);
+            }
+        }
+        VariantArguments::Named(fields) => {
+            for (_, value) in fields {
+                visitor.visit_expr_mut(value);
+            }
+        }
+    }
+}
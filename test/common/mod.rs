@@ -0,0 +1,36 @@
+//! 解析器测试共用的辅助函数
+//! 各测试文件通过 `#[path = "../common/mod.rs"] mod common;` 引入
+
+// 每个测试只用到其中一部分函数
+#![allow(dead_code)]
+
+use rus::lex::Lexer;
+use rus::parser::{Expr, ParseError, Parser, Stmt};
+use std::io::BufReader;
+
+/// 解析源码，遇到第一个错误即返回
+pub fn parse_source(code: &str) -> Result<Vec<Stmt>, ParseError> {
+    let reader = BufReader::new(code.as_bytes());
+    let mut parser = Parser::new(Lexer::new("test.rs", reader));
+    parser.parse()
+}
+
+/// 带错误恢复地解析源码，返回所有语句和所有错误
+pub fn parse_with_recovery(code: &str) -> (Vec<Stmt>, Vec<ParseError>) {
+    let reader = BufReader::new(code.as_bytes());
+    let mut parser = Parser::new(Lexer::new("test.rs", reader));
+    parser.parse_with_recovery()
+}
+
+/// 解析必须成功的源码，失败时带上源码报错
+pub fn parse_valid(code: &str) -> Vec<Stmt> {
+    parse_source(code).unwrap_or_else(|error| panic!("Failed to parse: {}\n{}", error, code))
+}
+
+/// 解析源码，返回第一条表达式语句的表达式
+pub fn parse_expression(code: &str) -> Expr {
+    match parse_valid(code).into_iter().next() {
+        Some(Stmt::Expression { expression, .. }) => expression,
+        other => panic!("Expected expression statement, got {:?}", other),
+    }
+}
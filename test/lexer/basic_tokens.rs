@@ -7,7 +7,7 @@ use std::io::BufReader;
 
 #[test]
 fn test_keywords() {
-    let code = "fn let var with contract impl mut if else for in loop while match break continue return as use pub enum struct trait true false async await try";
+    let code = "fn let var with contract impl mut if else for in loop while match break continue return as use pub enum struct trait true false async await try type where mod";
     let reader = BufReader::new(code.as_bytes());
     let lexer = Lexer::new("test.rs", reader);
 
@@ -54,6 +54,9 @@ fn test_keywords() {
     assert_eq!(token_values[25], Token::Async);
     assert_eq!(token_values[26], Token::Await);
     assert_eq!(token_values[27], Token::Try);
+    assert_eq!(token_values[28], Token::Type);
+    assert_eq!(token_values[29], Token::Where);
+    assert_eq!(token_values[30], Token::Mod);
 }
 
 #[test]
@@ -111,3 +114,60 @@ fn test_operators() {
     // 确保没有更多token
     assert_eq!(token_values.len(), 30);
 }
+
+#[test]
+fn test_bitwise_assignment_operators() {
+    let code = "&= |= ^= <<= >>= & |";
+    let reader = BufReader::new(code.as_bytes());
+    let lexer = Lexer::new("test.rs", reader);
+
+    let token_values: Vec<Token> = lexer.map(|t| t.data.unwrap()).collect();
+
+    assert_eq!(
+        token_values,
+        vec![
+            Token::AmpersandEqual,
+            Token::PipeEqual,
+            Token::CaretEqual,
+            Token::ShlEqual,
+            Token::ShrEqual,
+            Token::Ampersand,
+            Token::Pipe,
+        ]
+    );
+}
+
+#[test]
+fn test_range_after_integer_and_ampersand_before_identifier() {
+    let code = "0..n &mutable &m &mut x";
+    let reader = BufReader::new(code.as_bytes());
+    let lexer = Lexer::new("test.rs", reader);
+
+    let token_values: Vec<Token> = lexer.map(|t| t.data.unwrap()).collect();
+
+    assert_eq!(
+        token_values,
+        vec![
+            Token::IntegerLiteral("0".to_string()),
+            Token::Range,
+            Token::Identifier("n".to_string()),
+            Token::Ampersand,
+            Token::Identifier("mutable".to_string()),
+            Token::Ampersand,
+            Token::Identifier("m".to_string()),
+            Token::MutRef,
+            Token::Identifier("x".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_line_numbers_start_at_one() {
+    let code = "let\nx";
+    let reader = BufReader::new(code.as_bytes());
+    let lexer = Lexer::new("test.rs", reader);
+
+    let lines: Vec<usize> = lexer.map(|t| t.location.line).collect();
+
+    assert_eq!(lines, vec![1, 2]);
+}
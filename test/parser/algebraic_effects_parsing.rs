@@ -1,9 +1,9 @@
 // 代数效应和处理器语法解析测试
 // 测试语法分析器对effect和handle关键字的解析能力
 
-use rus::data::{Token, Locatable};
 use rus::lex::Lexer;
 use rus::parser::{Parser, Stmt, Expr};
+use rus::resolve::resolve_names;
 use std::io::BufReader;
 
 #[test]
@@ -17,14 +17,7 @@ fn test_effect_declaration() {
     
     let reader = BufReader::new(code.as_bytes());
     let lexer = Lexer::new("test.rs", reader);
-    let tokens: Vec<Locatable<Token>> = lexer
-        .map(|locatable_result| Locatable {
-            location: locatable_result.location,
-            data: locatable_result.data.unwrap(),
-        })
-        .collect();
-    
-    let mut parser = Parser::new(tokens);
+    let mut parser = Parser::new(lexer);
     let statements = parser.parse().expect("Failed to parse");
     
     assert_eq!(statements.len(), 1);
@@ -66,14 +59,7 @@ fn test_handler_declaration() {
     
     let reader = BufReader::new(code.as_bytes());
     let lexer = Lexer::new("test.rs", reader);
-    let tokens: Vec<Locatable<Token>> = lexer
-        .map(|locatable_result| Locatable {
-            location: locatable_result.location,
-            data: locatable_result.data.unwrap(),
-        })
-        .collect();
-    
-    let mut parser = Parser::new(tokens);
+    let mut parser = Parser::new(lexer);
     let statements = parser.parse().expect("Failed to parse");
     
     assert_eq!(statements.len(), 1);
@@ -84,7 +70,8 @@ fn test_handler_declaration() {
             
             let read_clause = &declaration.clauses[0];
             assert_eq!(read_clause.operation, "read_file");
-            assert_eq!(read_clause.parameters, vec!["path"]);
+            assert_eq!(read_clause.parameters.len(), 1);
+            assert_eq!(read_clause.parameters[0].name, "path");
             // 简化断言，只检查语句数量
             assert_eq!(read_clause.body.len(), 1);
         }
@@ -105,14 +92,7 @@ fn test_complex_handler_declaration() {
     
     let reader = BufReader::new(code.as_bytes());
     let lexer = Lexer::new("test.rs", reader);
-    let tokens: Vec<Locatable<Token>> = lexer
-        .map(|locatable_result| Locatable {
-            location: locatable_result.location,
-            data: locatable_result.data.unwrap(),
-        })
-        .collect();
-    
-    let mut parser = Parser::new(tokens);
+    let mut parser = Parser::new(lexer);
     let statements = parser.parse().expect("Failed to parse");
     
     assert_eq!(statements.len(), 1);
@@ -123,7 +103,8 @@ fn test_complex_handler_declaration() {
             
             let read_clause = &declaration.clauses[0];
             assert_eq!(read_clause.operation, "read_file");
-            assert_eq!(read_clause.parameters, vec!["path"]);
+            assert_eq!(read_clause.parameters.len(), 1);
+            assert_eq!(read_clause.parameters[0].name, "path");
             assert_eq!(read_clause.body.len(), 2);
         }
         _ => panic!("Expected handler statement"),
@@ -132,22 +113,21 @@ fn test_complex_handler_declaration() {
 
 #[test]
 fn test_effect_operation_call() {
-    let code = r#"FileSystem.read_file("test.txt");"#;
+    let code = r#"
+        effect FileSystem {
+            fn read_file(path: string) -> string;
+        }
+        FileSystem.read_file("test.txt");
+    "#;
     
     let reader = BufReader::new(code.as_bytes());
     let lexer = Lexer::new("test.rs", reader);
-    let tokens: Vec<Locatable<Token>> = lexer
-        .map(|locatable_result| Locatable {
-            location: locatable_result.location,
-            data: locatable_result.data.unwrap(),
-        })
-        .collect();
-    
-    let mut parser = Parser::new(tokens);
-    let statements = parser.parse().expect("Failed to parse");
+    let mut parser = Parser::new(lexer);
+    let mut statements = parser.parse().expect("Failed to parse");
+    resolve_names(&mut statements);
     
-    assert_eq!(statements.len(), 1);
-    match &statements[0] {
+    assert_eq!(statements.len(), 2);
+    match &statements[1] {
         Stmt::Expression { expression, .. } => {
             match expression {
                 Expr::EffectOperation { effect, operation, arguments, .. } => {
@@ -160,4 +140,21 @@ fn test_effect_operation_call() {
         }
         _ => panic!("Expected expression statement"),
     }
-}
\ No newline at end of file
+}
+#[test]
+fn test_method_call_on_undeclared_effect_is_not_an_effect_operation() {
+    let code = r#"file.read_file("test.txt");"#;
+
+    let reader = BufReader::new(code.as_bytes());
+    let lexer = Lexer::new("test.rs", reader);
+    let mut parser = Parser::new(lexer);
+    let mut statements = parser.parse().expect("Failed to parse");
+    resolve_names(&mut statements);
+
+    match &statements[0] {
+        Stmt::Expression { expression, .. } => {
+            assert!(matches!(expression, Expr::MethodCall { method, .. } if method == "read_file"));
+        }
+        _ => panic!("Expected expression statement"),
+    }
+}
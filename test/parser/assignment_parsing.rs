@@ -0,0 +1,133 @@
+//! 赋值表达式解析测试
+//! 测试语法分析器对赋值和复合赋值表达式的解析能力
+
+#[path = "../common/mod.rs"]
+mod common;
+
+use common::parse_source;
+use rus::parser::{AssignmentOperator, BinaryOperator, Expr, ParseError, ParseErrorKind, Stmt};
+
+#[test]
+fn test_simple_assignment() {
+    let statements = parse_source("var x = 0; x = 1;").expect("Failed to parse");
+
+    assert_eq!(statements.len(), 2);
+    match &statements[1] {
+        Stmt::Expression { expression, .. } => match expression {
+            Expr::Assign {
+                target,
+                operator,
+                value,
+                ..
+            } => {
+                assert_eq!(operator, &AssignmentOperator::Assign);
+                match &**target {
+                    Expr::Identifier { name, .. } => assert_eq!(name, "x"),
+                    _ => panic!("Expected identifier target"),
+                }
+                assert!(matches!(&**value, Expr::Literal { .. }));
+            }
+            _ => panic!("Expected assignment expression"),
+        },
+        _ => panic!("Expected expression statement"),
+    }
+}
+
+#[test]
+fn test_compound_assignment_operators() {
+    let cases = [
+        ("x += 1;", AssignmentOperator::AddAssign),
+        ("x -= 1;", AssignmentOperator::SubtractAssign),
+        ("x *= 1;", AssignmentOperator::MultiplyAssign),
+        ("x /= 1;", AssignmentOperator::DivideAssign),
+        ("x %= 1;", AssignmentOperator::ModuloAssign),
+        ("x &= 1;", AssignmentOperator::BitAndAssign),
+        ("x |= 1;", AssignmentOperator::BitOrAssign),
+        ("x ^= 1;", AssignmentOperator::BitXorAssign),
+        ("x <<= 1;", AssignmentOperator::ShlAssign),
+        ("x >>= 1;", AssignmentOperator::ShrAssign),
+    ];
+
+    for (code, expected) in cases {
+        let statements = parse_source(code).expect("Failed to parse");
+        match &statements[0] {
+            Stmt::Expression {
+                expression: Expr::Assign { operator, .. },
+                ..
+            } => assert_eq!(operator, &expected, "while parsing {}", code),
+            _ => panic!("Expected assignment expression for {}", code),
+        }
+    }
+}
+
+#[test]
+fn test_assignment_is_right_associative() {
+    let statements = parse_source("a = b = c;").expect("Failed to parse");
+
+    match &statements[0] {
+        Stmt::Expression {
+            expression: Expr::Assign { target, value, .. },
+            ..
+        } => {
+            assert!(matches!(&**target, Expr::Identifier { name, .. } if name == "a"));
+            match &**value {
+                Expr::Assign { target, value, .. } => {
+                    assert!(matches!(&**target, Expr::Identifier { name, .. } if name == "b"));
+                    assert!(matches!(&**value, Expr::Identifier { name, .. } if name == "c"));
+                }
+                _ => panic!("Expected nested assignment"),
+            }
+        }
+        _ => panic!("Expected assignment expression"),
+    }
+}
+
+#[test]
+fn test_assignment_has_lowest_precedence() {
+    let statements = parse_source("x += a + b * c;").expect("Failed to parse");
+
+    match &statements[0] {
+        Stmt::Expression {
+            expression: Expr::Assign {
+                operator, value, ..
+            },
+            ..
+        } => {
+            assert_eq!(operator, &AssignmentOperator::AddAssign);
+            match &**value {
+                Expr::Binary { operator, .. } => assert_eq!(operator, &BinaryOperator::Add),
+                _ => panic!("Expected binary expression on the right-hand side"),
+            }
+        }
+        _ => panic!("Expected assignment expression"),
+    }
+}
+
+#[test]
+fn test_assignment_to_parenthesized_place() {
+    let statements = parse_source("(x) = 1;").expect("Failed to parse");
+
+    assert!(matches!(
+        &statements[0],
+        Stmt::Expression {
+            expression: Expr::Assign { .. },
+            ..
+        }
+    ));
+}
+
+#[test]
+fn test_invalid_assignment_target() {
+    for code in ["1 = x;", "a + b = c;", "-x = 1;", "x == y = z;"] {
+        match parse_source(code) {
+            Err(ParseError {
+                kind: ParseErrorKind::InvalidAssignmentTarget,
+                ..
+            }) => {}
+            other => panic!(
+                "Expected invalid assignment target for {}, got {:?}",
+                code, other
+            ),
+        }
+    }
+}
@@ -0,0 +1,306 @@
+//! async/await/try 解析测试
+//! 测试 async fn、async 块、try 块，以及它们在名称解析阶段与内置 Async、Exception 效果的对应
+
+#[path = "../common/mod.rs"]
+mod common;
+
+use common::parse_source;
+use rus::parser::{Expr, Stmt, TraitItem};
+use rus::printer::print_program;
+use rus::resolve::{ASYNC_EFFECT, EXCEPTION_EFFECT, ResolveError, resolve_names};
+
+fn resolve_source(code: &str) -> (Vec<Stmt>, Vec<ResolveError>) {
+    let mut statements = parse_source(code).expect("Failed to parse");
+    let errors = resolve_names(&mut statements);
+    (statements, errors)
+}
+
+#[test]
+fn test_async_fn() {
+    let statements = parse_source("async fn fetch(url: String) -> String effects Log { }").unwrap();
+
+    match &statements[0] {
+        Stmt::Function {
+            is_async,
+            name,
+            effects,
+            span,
+            ..
+        } => {
+            assert!(*is_async);
+            assert_eq!(name, "fetch");
+            assert_eq!(effects.effects, ["Log"]);
+            // 位置从async关键字开始
+            assert_eq!(span.column, 1);
+        }
+        other => panic!("Expected function, got {:?}", other),
+    }
+
+    match &parse_source("fn plain() { }").unwrap()[0] {
+        Stmt::Function { is_async, .. } => assert!(!*is_async),
+        other => panic!("Expected function, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_async_fn_adds_async_effect() {
+    let (statements, errors) = resolve_source(
+        "effect Log { fn log(message: String); }
+         async fn fetch() effects Log { }
+         fn run() effects Async, Exception { }",
+    );
+
+    assert!(errors.is_empty(), "{:?}", errors);
+    match &statements[1] {
+        Stmt::Function { effects, .. } => {
+            assert_eq!(effects.flattened_effects, ["Log", ASYNC_EFFECT]);
+        }
+        other => panic!("Expected function, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_async_methods_in_traits_and_impls() {
+    let statements = parse_source(
+        "trait Client { async fn get(&self) -> String; }
+         impl Client for Http { async fn get(&self) -> String { body } }",
+    )
+    .unwrap();
+
+    match &statements[0] {
+        Stmt::Trait { declaration, .. } => match &declaration.items[0] {
+            TraitItem::Method { signature, .. } => assert!(signature.is_async),
+            other => panic!("Expected method, got {:?}", other),
+        },
+        other => panic!("Expected trait, got {:?}", other),
+    }
+    match &statements[1] {
+        Stmt::Impl { declaration, .. } => {
+            assert!(matches!(
+                declaration.items[0],
+                Stmt::Function { is_async: true, .. }
+            ));
+        }
+        other => panic!("Expected impl, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_async_and_try_blocks() {
+    let statements = parse_source(
+        "let task = async { fetch(url).await };
+         let parsed = try { parse(text)? };
+         async { run().await; }
+         try { check()?; }",
+    )
+    .unwrap();
+
+    match &statements[0] {
+        Stmt::Let {
+            initializer: Some(Expr::Async { statements, .. }),
+            ..
+        } => assert!(matches!(
+            Stmt::block_tail(statements),
+            Some(Expr::Await { .. })
+        )),
+        other => panic!("Expected async block, got {:?}", other),
+    }
+    match &statements[1] {
+        Stmt::Let {
+            initializer: Some(Expr::TryBlock { statements, .. }),
+            ..
+        } => assert!(matches!(
+            Stmt::block_tail(statements),
+            Some(Expr::Try { .. })
+        )),
+        other => panic!("Expected try block, got {:?}", other),
+    }
+    // 语句位置的 async 块和 try 块不需要分号
+    assert!(matches!(
+        statements[2],
+        Stmt::Expression {
+            expression: Expr::Async { .. },
+            ..
+        }
+    ));
+    assert!(matches!(
+        statements[3],
+        Stmt::Expression {
+            expression: Expr::TryBlock { .. },
+            ..
+        }
+    ));
+}
+
+#[test]
+fn test_await_becomes_async_operation() {
+    let (statements, errors) = resolve_source("async fn main() { let body = fetch(url).await; }");
+
+    assert!(errors.is_empty(), "{:?}", errors);
+    let Stmt::Function { body, .. } = &statements[0] else {
+        panic!("Expected function");
+    };
+    match &body[0] {
+        Stmt::Let {
+            initializer:
+                Some(Expr::EffectOperation {
+                    effect,
+                    operation,
+                    arguments,
+                    ..
+                }),
+            ..
+        } => {
+            assert_eq!(effect, ASYNC_EFFECT);
+            assert_eq!(operation, "await");
+            assert!(matches!(arguments[..], [Expr::Call { .. }]));
+        }
+        other => panic!("Expected await operation, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_user_handler_for_builtin_async() {
+    // 内置效果可以被用户处理器处理，await 与其他效果操作没有区别
+    let (statements, errors) = resolve_source(
+        "with handle Async { await(future) { resume!(run(future)) } } {
+            task.await
+         }",
+    );
+
+    assert!(errors.is_empty(), "{:?}", errors);
+    match &statements[0] {
+        Stmt::Expression {
+            expression:
+                Expr::With {
+                    body,
+                    discharged_effects,
+                    ..
+                },
+            ..
+        } => {
+            assert_eq!(discharged_effects, &[ASYNC_EFFECT]);
+            assert!(matches!(
+                Stmt::block_tail(body),
+                Some(Expr::EffectOperation { effect, .. }) if effect == ASYNC_EFFECT
+            ));
+        }
+        other => panic!("Expected with expression, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_try_block_handles_exception() {
+    let (statements, errors) = resolve_source("fn g() { }\nlet r = try { g()? };");
+
+    assert!(errors.is_empty(), "{:?}", errors);
+    match &statements[1] {
+        Stmt::Let {
+            initializer:
+                Some(Expr::TryBlock {
+                    statements,
+                    discharged_effects,
+                    ..
+                }),
+            ..
+        } => {
+            assert_eq!(discharged_effects, &[EXCEPTION_EFFECT]);
+            match Stmt::block_tail(statements) {
+                Some(Expr::EffectOperation {
+                    effect,
+                    operation,
+                    arguments,
+                    ..
+                }) => {
+                    assert_eq!(effect, EXCEPTION_EFFECT);
+                    assert_eq!(operation, "propagate");
+                    assert!(matches!(arguments[..], [Expr::Call { .. }]));
+                }
+                other => panic!("Expected propagate operation, got {:?}", other),
+            }
+        }
+        other => panic!("Expected try block, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_question_mark_outside_try_is_exception_operation() {
+    // try 块之外的 e? 同样执行 Exception 效果，由外层的处理器处理
+    let (statements, errors) = resolve_source(
+        "with handle Exception { propagate(result) { } } {
+            parse(text)?
+         }",
+    );
+
+    assert!(errors.is_empty(), "{:?}", errors);
+    match &statements[0] {
+        Stmt::Expression {
+            expression:
+                Expr::With {
+                    body,
+                    discharged_effects,
+                    ..
+                },
+            ..
+        } => {
+            assert_eq!(discharged_effects, &[EXCEPTION_EFFECT]);
+            assert!(matches!(
+                Stmt::block_tail(body),
+                Some(Expr::EffectOperation { effect, operation, .. })
+                    if effect == EXCEPTION_EFFECT && operation == "propagate"
+            ));
+        }
+        other => panic!("Expected with expression, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_async_block_records_async() {
+    let (statements, errors) = resolve_source("let task = async { fetch(url).await };");
+
+    assert!(errors.is_empty(), "{:?}", errors);
+    match &statements[0] {
+        Stmt::Let {
+            initializer:
+                Some(Expr::Async {
+                    statements,
+                    effects,
+                    ..
+                }),
+            ..
+        } => {
+            assert_eq!(effects, &[ASYNC_EFFECT]);
+            assert!(matches!(
+                Stmt::block_tail(statements),
+                Some(Expr::EffectOperation { effect, .. }) if effect == ASYNC_EFFECT
+            ));
+        }
+        other => panic!("Expected async block, got {:?}", other),
+    }
+
+    // 解析前 effects 为空
+    match &parse_source("let task = async { 1 };").unwrap()[0] {
+        Stmt::Let {
+            initializer: Some(Expr::Async { effects, .. }),
+            ..
+        } => assert!(effects.is_empty()),
+        other => panic!("Expected async block, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_resolved_sugar_prints_as_source() {
+    let source =
+        "let r = try {\n    parse(text)?.len()\n};\nlet t = async {\n    fetch(url).await?\n};\n";
+    let (statements, errors) = resolve_source(source);
+
+    // 改写为效果操作后仍然打印为 ? 和 .await
+    assert!(errors.is_empty(), "{:?}", errors);
+    assert_eq!(print_program(&statements), source);
+}
+
+#[test]
+fn test_async_requires_block() {
+    assert!(parse_source("let x = async 1;").is_err());
+    assert!(parse_source("let x = try 1;").is_err());
+}
@@ -1,7 +1,6 @@
 //! 基本语法解析测试
 //! 测试语法分析器对基本语法结构的解析能力
 
-use rus::data::{Token, LexicalError, Locatable};
 use rus::lex::Lexer;
 use rus::parser::{Parser, Expr, Stmt, Literal, BinaryOperator, UnaryOperator};
 use std::io::BufReader;
@@ -11,14 +10,7 @@ fn test_basic_expressions() {
     let code = "42;";
     let reader = BufReader::new(code.as_bytes());
     let lexer = Lexer::new("test.rs", reader);
-    let tokens: Vec<Locatable<Token>> = lexer
-        .map(|locatable_result| Locatable {
-            location: locatable_result.location,
-            data: locatable_result.data.unwrap(),
-        })
-        .collect();
-    
-    let mut parser = Parser::new(tokens);
+    let mut parser = Parser::new(lexer);
     let statements = parser.parse().expect("Failed to parse");
     
     assert_eq!(statements.len(), 1);
@@ -43,14 +35,7 @@ fn test_binary_expressions() {
     let code = "1 + 2;";
     let reader = BufReader::new(code.as_bytes());
     let lexer = Lexer::new("test.rs", reader);
-    let tokens: Vec<Locatable<Token>> = lexer
-        .map(|locatable_result| Locatable {
-            location: locatable_result.location,
-            data: locatable_result.data.unwrap(),
-        })
-        .collect();
-    
-    let mut parser = Parser::new(tokens);
+    let mut parser = Parser::new(lexer);
     let statements = parser.parse().expect("Failed to parse");
     
     assert_eq!(statements.len(), 1);
@@ -92,14 +77,7 @@ fn test_operator_precedence() {
     let code = "1 + 2 * 3;";
     let reader = BufReader::new(code.as_bytes());
     let lexer = Lexer::new("test.rs", reader);
-    let tokens: Vec<Locatable<Token>> = lexer
-        .map(|locatable_result| Locatable {
-            location: locatable_result.location,
-            data: locatable_result.data.unwrap(),
-        })
-        .collect();
-    
-    let mut parser = Parser::new(tokens);
+    let mut parser = Parser::new(lexer);
     let statements = parser.parse().expect("Failed to parse");
     
     assert_eq!(statements.len(), 1);
@@ -161,14 +139,7 @@ fn test_unary_expressions() {
     let code = "-42;";
     let reader = BufReader::new(code.as_bytes());
     let lexer = Lexer::new("test.rs", reader);
-    let tokens: Vec<Locatable<Token>> = lexer
-        .map(|locatable_result| Locatable {
-            location: locatable_result.location,
-            data: locatable_result.data.unwrap(),
-        })
-        .collect();
-    
-    let mut parser = Parser::new(tokens);
+    let mut parser = Parser::new(lexer);
     let statements = parser.parse().expect("Failed to parse");
     
     assert_eq!(statements.len(), 1);
@@ -200,14 +171,7 @@ fn test_grouping_expressions() {
     let code = "(1 + 2) * 3;";
     let reader = BufReader::new(code.as_bytes());
     let lexer = Lexer::new("test.rs", reader);
-    let tokens: Vec<Locatable<Token>> = lexer
-        .map(|locatable_result| Locatable {
-            location: locatable_result.location,
-            data: locatable_result.data.unwrap(),
-        })
-        .collect();
-    
-    let mut parser = Parser::new(tokens);
+    let mut parser = Parser::new(lexer);
     let statements = parser.parse().expect("Failed to parse");
     
     assert_eq!(statements.len(), 1);
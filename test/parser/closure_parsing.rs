@@ -0,0 +1,265 @@
+//! 闭包表达式解析测试
+//! 测试闭包的参数、返回类型和效果标注、闭包体，以及名称解析阶段确定的捕获方式
+
+#[path = "../common/mod.rs"]
+mod common;
+
+use common::{parse_expression, parse_source};
+use rus::parser::{BinaryOperator, Capture, CaptureMode, Expr, Stmt};
+use rus::resolve::{ResolveError, resolve_names};
+
+fn resolve_source(code: &str) -> (Vec<Stmt>, Vec<ResolveError>) {
+    let mut statements = parse_source(code).expect("Failed to parse");
+    let errors = resolve_names(&mut statements);
+    (statements, errors)
+}
+
+/// 取出函数体中第 index 条语句里 let 绑定的闭包的捕获列表
+fn closure_captures(statements: &[Stmt], index: usize) -> Vec<Capture> {
+    let Some(Stmt::Function { body, .. }) = statements.last() else {
+        panic!("Expected function");
+    };
+    match &body[index] {
+        Stmt::Let {
+            initializer: Some(Expr::Closure { captures, .. }),
+            ..
+        } => captures.clone(),
+        other => panic!("Expected closure binding, got {:?}", other),
+    }
+}
+
+fn capture(name: &str, mode: CaptureMode) -> Capture {
+    Capture {
+        name: name.into(),
+        mode,
+    }
+}
+
+#[test]
+fn test_expression_closure() {
+    match parse_expression("|x, y| x + y;") {
+        Expr::Closure {
+            is_move,
+            parameters,
+            return_type,
+            body,
+            ..
+        } => {
+            assert!(!is_move);
+            let names: Vec<&str> = parameters.iter().map(|p| p.name.as_str()).collect();
+            assert_eq!(names, ["x", "y"]);
+            assert!(parameters.iter().all(|p| p.type_annotation.is_none()));
+            assert!(return_type.is_none());
+            assert!(matches!(
+                *body,
+                Expr::Binary {
+                    operator: BinaryOperator::Add,
+                    ..
+                }
+            ));
+        }
+        other => panic!("Expected closure, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_closure_without_parameters() {
+    match parse_expression("|| 42;") {
+        Expr::Closure { parameters, .. } => assert!(parameters.is_empty()),
+        other => panic!("Expected closure, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_annotated_closure() {
+    match parse_expression("move |path: String| -> String effects FileSystem, ..e { path };") {
+        Expr::Closure {
+            is_move,
+            parameters,
+            return_type,
+            effects,
+            body,
+            ..
+        } => {
+            assert!(is_move);
+            assert_eq!(parameters[0].type_annotation.as_deref(), Some("String"));
+            assert_eq!(return_type.as_deref(), Some("String"));
+            assert_eq!(effects.effects, ["FileSystem"]);
+            assert_eq!(effects.row_variable.as_deref(), Some("e"));
+            match *body {
+                Expr::Block { statements, .. } => {
+                    assert!(matches!(
+                        Stmt::block_tail(&statements),
+                        Some(Expr::Identifier { name, .. }) if name == "path"
+                    ));
+                }
+                other => panic!("Expected block body, got {:?}", other),
+            }
+        }
+        other => panic!("Expected closure, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_annotated_closure_requires_block() {
+    assert!(parse_source("|x: i32| -> i32 x + 1;").is_err());
+    assert!(parse_source("|x| effects Log x;").is_err());
+}
+
+#[test]
+fn test_closure_as_call_argument() {
+    match parse_expression("items.map(|x| x * 2, limit);") {
+        Expr::MethodCall { arguments, .. } => {
+            assert_eq!(arguments.len(), 2);
+            assert!(matches!(arguments[0], Expr::Closure { .. }));
+            assert!(matches!(arguments[1], Expr::Identifier { .. }));
+        }
+        other => panic!("Expected method call, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_pipe_after_operand_is_bitwise_or() {
+    assert!(matches!(
+        parse_expression("a | b;"),
+        Expr::Binary {
+            operator: BinaryOperator::BitOr,
+            ..
+        }
+    ));
+    assert!(matches!(
+        parse_expression("a || b;"),
+        Expr::Binary {
+            operator: BinaryOperator::Or,
+            ..
+        }
+    ));
+}
+
+#[test]
+fn test_borrow_and_mutable_captures() {
+    let (statements, errors) = resolve_source(
+        "fn run(limit: i32) {
+            let total = 0;
+            let seen = 0;
+            let f = |x| { total += x; seen.count = seen.count + 1; x < limit };
+            let g = |x| helper(x, &mut total);
+        }",
+    );
+
+    assert!(errors.is_empty(), "{:?}", errors);
+    assert_eq!(
+        closure_captures(&statements, 2),
+        [
+            capture("total", CaptureMode::BorrowMut),
+            capture("seen", CaptureMode::BorrowMut),
+            capture("limit", CaptureMode::Borrow),
+        ]
+    );
+    // helper 不是局部变量，不会被捕获
+    assert_eq!(
+        closure_captures(&statements, 3),
+        [capture("total", CaptureMode::BorrowMut)]
+    );
+}
+
+#[test]
+fn test_move_closure_captures() {
+    let (statements, _) = resolve_source(
+        "fn run() {
+            let name = 1;
+            let count = 0;
+            let f = move |x| { count = count + x; name };
+        }",
+    );
+
+    assert_eq!(
+        closure_captures(&statements, 2),
+        [
+            capture("count", CaptureMode::Move),
+            capture("name", CaptureMode::Move),
+        ]
+    );
+}
+
+#[test]
+fn test_parameters_and_inner_locals_are_not_captured() {
+    let (statements, _) = resolve_source(
+        "fn run() {
+            let x = 1;
+            let y = 2;
+            let f = |x| { let y = x; y + x };
+        }",
+    );
+
+    assert!(closure_captures(&statements, 2).is_empty());
+}
+
+#[test]
+fn test_nested_closure_captures() {
+    let (statements, _) = resolve_source(
+        "fn run() {
+            let total = 0;
+            let f = |a| |b| { total = a + b; };
+        }",
+    );
+
+    assert_eq!(
+        closure_captures(&statements, 1),
+        [capture("total", CaptureMode::BorrowMut)]
+    );
+    let Some(Stmt::Function { body, .. }) = statements.last() else {
+        panic!("Expected function");
+    };
+    match &body[1] {
+        Stmt::Let {
+            initializer: Some(Expr::Closure { body: inner, .. }),
+            ..
+        } => match &**inner {
+            Expr::Closure { captures, .. } => assert_eq!(
+                captures,
+                &[
+                    capture("total", CaptureMode::BorrowMut),
+                    capture("a", CaptureMode::Borrow),
+                ]
+            ),
+            other => panic!("Expected inner closure, got {:?}", other),
+        },
+        other => panic!("Expected closure binding, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_closure_effects_resolved() {
+    let (statements, errors) = resolve_source(
+        "effect Log { fn log(message: String); }
+         fn run() {
+            let f = |m: String| effects Log, Missing { Log.log(m); };
+         }",
+    );
+
+    assert_eq!(errors.len(), 1);
+    assert!(matches!(&errors[0], ResolveError::UnknownEffect { name, .. } if name == "Missing"));
+    let Some(Stmt::Function { body, .. }) = statements.last() else {
+        panic!("Expected function");
+    };
+    match &body[0] {
+        Stmt::Let {
+            initializer: Some(Expr::Closure { effects, body, .. }),
+            ..
+        } => {
+            let Expr::Block { statements, .. } = &**body else {
+                panic!("Expected block body, got {:?}", body);
+            };
+            assert_eq!(effects.flattened_effects, ["Log", "Missing"]);
+            assert!(matches!(
+                &statements[0],
+                Stmt::Expression {
+                    expression: Expr::EffectOperation { .. },
+                    ..
+                }
+            ));
+        }
+        other => panic!("Expected closure binding, got {:?}", other),
+    }
+}
@@ -0,0 +1,163 @@
+//! 元组、数组和块表达式解析测试
+//! 测试元组和单元值、数组和重复数组、对它们的索引，以及以尾表达式为值的块
+
+#[path = "../common/mod.rs"]
+mod common;
+
+use common::{parse_expression, parse_source};
+use rus::parser::{Expr, Literal, Stmt};
+
+fn integer(expr: &Expr) -> &str {
+    match expr {
+        Expr::Literal {
+            value: Literal::Integer(value),
+            ..
+        } => value,
+        other => panic!("Expected integer literal, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_unit_and_tuples() {
+    assert!(matches!(
+        parse_expression("();"),
+        Expr::Tuple { elements, .. } if elements.is_empty()
+    ));
+    assert!(matches!(parse_expression("(1);"), Expr::Grouping { .. }));
+
+    match parse_expression("(1,);") {
+        Expr::Tuple { elements, .. } => assert_eq!(elements.len(), 1),
+        other => panic!("Expected tuple, got {:?}", other),
+    }
+
+    match parse_expression("(1, (2, 3), ());") {
+        Expr::Tuple { elements, .. } => {
+            assert_eq!(elements.len(), 3);
+            assert_eq!(integer(&elements[0]), "1");
+            assert!(matches!(&elements[1], Expr::Tuple { elements, .. } if elements.len() == 2));
+            assert!(matches!(&elements[2], Expr::Tuple { elements, .. } if elements.is_empty()));
+        }
+        other => panic!("Expected tuple, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_arrays() {
+    match parse_expression("[1, 2, 3,];") {
+        Expr::Array { elements, .. } => {
+            let values: Vec<&str> = elements.iter().map(integer).collect();
+            assert_eq!(values, ["1", "2", "3"]);
+        }
+        other => panic!("Expected array, got {:?}", other),
+    }
+
+    assert!(matches!(
+        parse_expression("[];"),
+        Expr::Array { elements, .. } if elements.is_empty()
+    ));
+
+    match parse_expression("[0; N * 2];") {
+        Expr::ArrayRepeat { value, count, .. } => {
+            assert_eq!(integer(&value), "0");
+            assert!(matches!(*count, Expr::Binary { .. }));
+        }
+        other => panic!("Expected repeat array, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_indexing_tuples_and_arrays() {
+    match parse_expression("[1, 2, 3][0];") {
+        Expr::Index { object, index, .. } => {
+            assert!(matches!(*object, Expr::Array { .. }));
+            assert_eq!(integer(&index), "0");
+        }
+        other => panic!("Expected index, got {:?}", other),
+    }
+
+    match parse_expression("(1, 2).1;") {
+        Expr::Field { object, field, .. } => {
+            assert!(matches!(*object, Expr::Tuple { .. }));
+            assert_eq!(field, "1");
+        }
+        other => panic!("Expected field access, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_block_expression_value() {
+    let statements = parse_source("let x = { let y = 1; y + 1 };").expect("Failed to parse");
+
+    match &statements[0] {
+        Stmt::Let {
+            initializer: Some(Expr::Block { statements, .. }),
+            ..
+        } => {
+            assert_eq!(statements.len(), 2);
+            assert!(matches!(
+                Stmt::block_tail(statements),
+                Some(Expr::Binary { .. })
+            ));
+        }
+        other => panic!("Expected block initializer, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_block_without_tail() {
+    let statements = parse_source("let x = { foo(); };").expect("Failed to parse");
+
+    match &statements[0] {
+        Stmt::Let {
+            initializer: Some(Expr::Block { statements, .. }),
+            ..
+        } => assert!(Stmt::block_tail(statements).is_none()),
+        other => panic!("Expected block initializer, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_tail_expression_in_function_and_block_statement() {
+    let statements = parse_source(
+        "fn pair(a: i32) -> (i32, i32) {
+            let b = a * 2;
+            (a, b)
+        }
+        { run(); done }",
+    )
+    .expect("Failed to parse");
+
+    match &statements[0] {
+        Stmt::Function { body, .. } => {
+            assert!(matches!(
+                Stmt::block_tail(body),
+                Some(Expr::Tuple { elements, .. }) if elements.len() == 2
+            ));
+        }
+        other => panic!("Expected function, got {:?}", other),
+    }
+    match &statements[1] {
+        Stmt::Block { statements, .. } => {
+            assert!(matches!(
+                Stmt::block_tail(statements),
+                Some(Expr::Identifier { name, .. }) if name == "done"
+            ));
+        }
+        other => panic!("Expected block, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_semicolon_required_before_block_end() {
+    assert!(parse_source("fn f() { a b }").is_err());
+    assert!(parse_source("fn f() { a; b }").is_ok());
+    assert!(parse_source("a").is_err());
+}
+
+#[test]
+fn test_struct_literal_inside_nested_block() {
+    // 块内部重新允许结构体字面量
+    let statements =
+        parse_source("fn f() requires valid({ Point { x: 1 } }) { }").expect("Failed to parse");
+    assert!(matches!(statements[0], Stmt::Function { .. }));
+}
@@ -0,0 +1,193 @@
+//! 契约解析测试
+//! 测试语法分析器对 requires、ensures、invariant 子句和具名契约的解析能力
+
+#[path = "../common/mod.rs"]
+mod common;
+
+use common::parse_source;
+use rus::parser::{BinaryOperator, ContractClause, Expr, Stmt, TraitItem};
+
+fn function_contracts(code: &str) -> Vec<ContractClause> {
+    let statements = parse_source(code).expect("Failed to parse");
+    match statements.into_iter().next() {
+        Some(Stmt::Function { contracts, .. }) => contracts,
+        other => panic!("Expected function declaration, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_requires_and_ensures() {
+    let contracts = function_contracts(
+        "fn divide(a: i32, b: i32) -> i32
+            requires b != 0
+            ensures result * b == a
+        { }",
+    );
+
+    assert_eq!(contracts.len(), 2);
+    assert!(matches!(
+        &contracts[0],
+        ContractClause::Requires(Expr::Binary {
+            operator: BinaryOperator::NotEqual,
+            ..
+        })
+    ));
+
+    match &contracts[1] {
+        ContractClause::Ensures(Expr::Binary { left, .. }) => assert!(matches!(
+            &**left,
+            Expr::Binary { left, .. } if matches!(**left, Expr::Result { .. })
+        )),
+        other => panic!("Expected postcondition, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_old_in_postcondition() {
+    let contracts = function_contracts(
+        "fn deposit(&mut self, amount: i64) ensures self.balance == old(self.balance) + amount { }",
+    );
+
+    match &contracts[0] {
+        ContractClause::Ensures(Expr::Binary { right, .. }) => match &**right {
+            Expr::Binary { left, .. } => assert!(matches!(
+                &**left,
+                Expr::Old { expression, .. } if matches!(**expression, Expr::Field { .. })
+            )),
+            other => panic!("Expected addition, got {:?}", other),
+        },
+        other => panic!("Expected postcondition, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_old_and_result_are_plain_names_outside_postconditions() {
+    let contracts = function_contracts("fn f(result) requires old(result) { }");
+
+    match &contracts[0] {
+        ContractClause::Requires(Expr::Call {
+            function,
+            arguments,
+            ..
+        }) => {
+            assert!(matches!(&**function, Expr::Identifier { name, .. } if name == "old"));
+            assert!(matches!(&arguments[0], Expr::Identifier { name, .. } if name == "result"));
+        }
+        other => panic!("Expected call of 'old', got {:?}", other),
+    }
+}
+
+#[test]
+fn test_condition_before_body_is_not_a_struct_literal() {
+    let contracts = function_contracts("fn f(ready: bool) requires ready { ready; }");
+
+    assert!(matches!(
+        &contracts[0],
+        ContractClause::Requires(Expr::Identifier { name, .. }) if name == "ready"
+    ));
+}
+
+#[test]
+fn test_struct_invariants() {
+    let statements = parse_source(
+        "struct Account invariant self.balance >= 0 { balance: i64 }
+         struct Percent(u8) invariant self.0 <= 100;",
+    )
+    .expect("Failed to parse");
+
+    match &statements[0] {
+        Stmt::Struct { declaration, .. } => {
+            assert_eq!(declaration.contracts.len(), 1);
+            assert!(matches!(
+                declaration.contracts[0],
+                ContractClause::Invariant(_)
+            ));
+        }
+        other => panic!("Expected struct declaration, got {:?}", other),
+    }
+
+    match &statements[1] {
+        Stmt::Struct { declaration, .. } => {
+            assert!(matches!(
+                declaration.contracts[0],
+                ContractClause::Invariant(_)
+            ));
+        }
+        other => panic!("Expected struct declaration, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_named_contract_declaration() {
+    let statements = parse_source(
+        "contract SafeDivision(a: i32, b: i32) {
+            requires b != 0;
+            ensures result * b == a;
+            contract NonNegative;
+        }",
+    )
+    .expect("Failed to parse");
+
+    match &statements[0] {
+        Stmt::Contract { declaration, .. } => {
+            assert_eq!(declaration.name, "SafeDivision");
+            assert_eq!(declaration.parameters.len(), 2);
+            assert_eq!(declaration.clauses.len(), 3);
+            assert!(matches!(
+                declaration.clauses[0],
+                ContractClause::Requires(_)
+            ));
+            assert!(matches!(declaration.clauses[1], ContractClause::Ensures(_)));
+            assert_eq!(
+                declaration.clauses[2],
+                ContractClause::Named("NonNegative".into())
+            );
+        }
+        other => panic!("Expected contract declaration, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_named_contracts_attached_to_functions_and_traits() {
+    let contracts = function_contracts("fn divide(a, b) contract SafeDivision { }");
+    assert_eq!(
+        contracts,
+        vec![ContractClause::Named("SafeDivision".into())]
+    );
+
+    let statements = parse_source(
+        "trait Stack contract StackLaws invariant self.len() >= 0 {
+            fn pop(&mut self) -> i32 requires self.len() > 0;
+        }",
+    )
+    .expect("Failed to parse");
+
+    match &statements[0] {
+        Stmt::Trait { declaration, .. } => {
+            assert_eq!(
+                declaration.contracts[0],
+                ContractClause::Named("StackLaws".into())
+            );
+            assert!(matches!(
+                declaration.contracts[1],
+                ContractClause::Invariant(_)
+            ));
+            match &declaration.items[0] {
+                TraitItem::Method { signature, .. } => {
+                    assert!(matches!(
+                        signature.contracts[0],
+                        ContractClause::Requires(_)
+                    ));
+                }
+                other => panic!("Expected trait method, got {:?}", other),
+            }
+        }
+        other => panic!("Expected trait declaration, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_misplaced_contract_clauses_are_rejected() {
+    assert!(parse_source("fn f(x) invariant x > 0 { }").is_err());
+    assert!(parse_source("struct S requires true { }").is_err());
+}
@@ -0,0 +1,253 @@
+//! 语法树和记号流输出测试
+//! 测试 S 表达式输出的格式，启用 serde 特性时测试 JSON 序列化
+
+#[path = "../common/mod.rs"]
+mod common;
+
+use common::parse_valid;
+use rus::lex::Lexer;
+use rus::parser::Stmt;
+use rus::sexp::{dump_expr, dump_program, dump_stmt, dump_tokens};
+use std::io::BufReader;
+
+/// 解析一条表达式语句并输出其中的表达式
+fn dump(code: &str) -> String {
+    match &parse_valid(code)[..] {
+        [Stmt::Expression { expression, .. }] => dump_expr(expression),
+        statements => panic!("expected one expression statement, got {:?}", statements),
+    }
+}
+
+#[test]
+fn test_dump_expressions() {
+    let cases = [
+        ("1 + 2 * 3;", "(+ 1 (* 2 3))"),
+        ("(1 + 2) * 3;", "(* (group (+ 1 2)) 3)"),
+        ("a = b += c;", "(= a (+= b c))"),
+        ("-x as i64;", "(as (- x) i64)"),
+        ("&mut *p;", "(&mut (* p))"),
+        ("a..=b;", "(..= a b)"),
+        ("..b;", "(.. _ b)"),
+        ("f(x)(y);", "(call (call f x) y)"),
+        (
+            "a.b.c(1)[i]?.await;",
+            "(await (? (index (method-call (field a b) c 1) i)))",
+        ),
+        ("t.0;", "(field t 0)"),
+        ("std::io::Console;", "std::io::Console"),
+        ("(a,);", "(tuple a)"),
+        ("[0; n];", "(array-repeat 0 n)"),
+        (r#""a b";"#, r#""a b""#),
+        ("'c';", "'c'"),
+        ("true;", "true"),
+        (
+            "Point { x: 1, y, ..origin };",
+            "(struct-literal Point (x 1) (y y) (.. origin))",
+        ),
+        (
+            "move |x: i32| -> i32 { x };",
+            "(closure move (params (x i32)) (returns i32) (block (value x)))",
+        ),
+        ("async { f().await };", "(async (value (await (call f))))"),
+    ];
+    for (code, expected) in cases {
+        assert_eq!(dump(code), expected, "{}", code);
+    }
+}
+
+#[test]
+fn test_dump_statements() {
+    let code = r#"
+let x = 1;
+var y;
+{ x }
+pub fn f<T: Clone>(a: T, b) -> T effects IO - Network, ..e where T: Debug requires a > 0 { a }
+"#;
+    assert_eq!(
+        dump_program(&parse_valid(code)),
+        "(let x 1)\n(var y)\n(block (value x))\n\
+         (fn pub f (generics (T Clone)) (params (a T) b) (returns T) \
+         (effects IO (- Network) ..e) (where (T Debug)) (requires (> a 0)) (body (value a)))\n"
+    );
+}
+
+#[test]
+fn test_dump_declarations() {
+    let cases = [
+        (
+            "effect State<S> { fn get() -> S; fn put(s: S); }",
+            "(effect State (generics S) (fn get (params) (returns S)) (fn put (params (s S))))",
+        ),
+        (
+            "handle Log as Console { log(m: String, k) { resume!(()) } return(x) { x } finally { close() } }",
+            "(handle Log (as Console) (clause log (params (m String) k) (body (value (resume (tuple))))) \
+             (return (params x) (body (value x))) (finally (value (call close))))",
+        ),
+        (
+            "effect_group Local = Io, Log - Network;",
+            "(effect-group Local (effects Io Log) (- Network))",
+        ),
+        (
+            "pub handler_group Defaults = Console, Files;",
+            "(handler-group pub Defaults (handlers Console Files))",
+        ),
+        (
+            "use a::{self, b as c, d::*};",
+            "(use (group a self (as b c) d::*))",
+        ),
+        (
+            "pub(crate) struct P<T> invariant self.y > 0 { pub x: T, y: &mut T }",
+            "(struct pub-crate P (generics T) (fields (pub x T) (y \"&mut T\")) (invariant (> (field self y) 0)))",
+        ),
+        (
+            "struct Meters(pub f64);",
+            "(struct Meters (tuple-fields (pub f64)))",
+        ),
+        ("struct Marker;", "(struct Marker)"),
+        (
+            "enum Shape { Empty, Circle(f64), Rect { w: f64 } }",
+            "(enum Shape Empty (Circle (tuple-fields f64)) (Rect (fields (w f64))))",
+        ),
+        (
+            "type Pair<T> = (T, T);",
+            "(type Pair (generics T) \"(T, T)\")",
+        ),
+        (
+            "trait Shape: Debug { type Output: Clone = i32; fn area(&self) -> f64; fn one() -> i32 { 1 } }",
+            "(trait Shape (supertraits Debug) (type Output (bounds Clone) (default i32)) \
+             (fn area (params (self &Self)) (returns f64)) (fn one (params) (returns i32) (body (value 1))))",
+        ),
+        (
+            "impl<T> Shape for Vec<T> { fn area(&self) -> f64 { 0.0 } }",
+            "(impl (generics T) (trait Shape) Vec<T> (fn area (params (self &Self)) (returns f64) (body (value 0.0))))",
+        ),
+        (
+            "contract SafeDivision(a, b) { requires b != 0; ensures result * b == a; }",
+            "(contract SafeDivision (params a b) (requires (!= b 0)) (ensures (== (* result b) a)))",
+        ),
+        (
+            "mod geometry { fn f() {} }",
+            "(mod geometry (fn f (params) (body)))",
+        ),
+        ("mod network;", "(mod network (file))"),
+    ];
+    for (code, expected) in cases {
+        let statements = parse_valid(code);
+        assert_eq!(statements.len(), 1, "{}", code);
+        assert_eq!(dump_stmt(&statements[0]), expected, "{}", code);
+    }
+}
+
+#[test]
+fn test_dump_tokens() {
+    let lexer = Lexer::new(
+        "test.rs",
+        BufReader::new("let s = \"a b\";\nx &mut 'c' @".as_bytes()),
+    );
+    assert_eq!(
+        dump_tokens(lexer),
+        "(1:1 let)\n(1:5 (ident s))\n(1:7 =)\n(1:9 (string \"a b\"))\n(1:14 \";\")\n\
+         (2:1 (ident x))\n(2:3 &mut)\n(2:8 (char 'c'))\n(2:12 @)\n"
+    );
+
+    let lexer = Lexer::new("test.rs", BufReader::new("\"open".as_bytes()));
+    assert!(dump_tokens(lexer).starts_with("(1:1 (error "));
+}
+
+#[cfg(feature = "serde")]
+mod json {
+    use crate::common::parse_valid;
+    use rus::data::{Locatable, Location, Token};
+    use rus::lex::Lexer;
+    use serde_json::json;
+    use std::io::BufReader;
+
+    #[test]
+    fn test_serialize_statements() {
+        let statements = parse_valid("let x = a + 1;");
+        let value = serde_json::to_value(&statements).unwrap();
+        assert_eq!(
+            value,
+            json!([{
+                "Let": {
+                    "id": 0,
+                    "span": { "file": "test.rs", "line": 1, "column": 1, "end_line": 1, "end_column": 15 },
+                    "identifier": "x",
+                    "initializer": {
+                        "Binary": {
+                            "id": 1,
+                            "span": { "file": "test.rs", "line": 1, "column": 9, "end_line": 1, "end_column": 14 },
+                            "left": {
+                                "Identifier": {
+                                    "id": 2,
+                                    "span": { "file": "test.rs", "line": 1, "column": 9, "end_line": 1, "end_column": 10 },
+                                    "name": "a"
+                                }
+                            },
+                            "operator": "Add",
+                            "right": {
+                                "Literal": {
+                                    "id": 3,
+                                    "span": { "file": "test.rs", "line": 1, "column": 13, "end_line": 1, "end_column": 14 },
+                                    "value": { "Integer": "1" }
+                                }
+                            }
+                        }
+                    }
+                }
+            }])
+        );
+    }
+
+    #[test]
+    fn test_serialize_declarations() {
+        let code = "pub effect Log { fn log(message: String); }\n\
+                    struct P { x: i32 }\n\
+                    handle Log as Console { log(m, k) { resume!(()) } }";
+        let value = serde_json::to_value(parse_valid(code)).unwrap();
+        assert_eq!(value[0]["Effect"]["visibility"], "Public");
+        assert_eq!(
+            value[0]["Effect"]["declaration"]["operations"][0],
+            json!({ "name": "log", "parameters": [["message", "String"]], "return_type": null })
+        );
+        assert_eq!(
+            value[1]["Struct"]["declaration"]["fields"],
+            json!({ "Named": [{ "visibility": "Private", "name": "x", "field_type": "i32" }] })
+        );
+        assert_eq!(value[2]["Handler"]["declaration"]["name"], "Console");
+        assert_eq!(
+            value[2]["Handler"]["declaration"]["clauses"][0]["operation"],
+            "log"
+        );
+    }
+
+    #[test]
+    fn test_serialize_tokens() {
+        let location = Location {
+            line: 1,
+            column: 5,
+            file: "test.rs",
+        };
+        assert_eq!(
+            serde_json::to_value(location).unwrap(),
+            json!({ "line": 1, "column": 5, "file": "test.rs" })
+        );
+        assert_eq!(
+            serde_json::to_value(Token::Identifier("x".to_string())).unwrap(),
+            json!({ "Identifier": "x" })
+        );
+
+        let lexer = Lexer::new("test.rs", BufReader::new("fn \"open".as_bytes()));
+        let tokens: Vec<Locatable<_>> = lexer.collect();
+        assert_eq!(
+            serde_json::to_value(&tokens).unwrap(),
+            json!([
+                { "location": { "line": 1, "column": 1, "file": "test.rs" }, "data": { "Ok": "Fn" } },
+                {
+                    "location": { "line": 1, "column": 4, "file": "test.rs" },
+                    "data": { "Err": "UnterminatedString" }
+                }
+            ])
+        );
+    }
+}
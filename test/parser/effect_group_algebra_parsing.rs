@@ -0,0 +1,210 @@
+//! 效果组代数测试
+//! 测试效果组的嵌套、差集、行变量，以及名称解析阶段的展开、循环和未知名称检查
+
+#[path = "../common/mod.rs"]
+mod common;
+
+use common::parse_source;
+use rus::data::Span;
+use rus::parser::{EffectName, Stmt};
+use rus::resolve::{ResolveError, resolve_names};
+
+fn resolve_source(code: &str) -> (Vec<Stmt>, Vec<ResolveError>) {
+    let mut statements = parse_source(code).expect("Failed to parse");
+    let errors = resolve_names(&mut statements);
+    (statements, errors)
+}
+
+const EFFECTS: &str = "effect FileSystem { fn read(path: String) -> String; }\n\
+                       effect Network { fn fetch(url: String) -> String; }\n\
+                       effect Log { fn log(message: String); }\n\
+                       effect State<S> { fn get() -> S; }\n";
+
+fn flattened_group(statements: &[Stmt], name: &str) -> Vec<EffectName> {
+    statements
+        .iter()
+        .find_map(|statement| match statement {
+            Stmt::EffectGroup { declaration, .. } if declaration.name == name => {
+                Some(declaration.flattened_effects.clone())
+            }
+            _ => None,
+        })
+        .expect("Effect group not found")
+}
+
+#[test]
+fn test_parse_group_difference() {
+    let statements = parse_source("effect_group Safe = IO, Log - Network - Random;").unwrap();
+
+    match &statements[0] {
+        Stmt::EffectGroup { declaration, .. } => {
+            assert_eq!(declaration.effects, ["IO", "Log"]);
+            assert_eq!(declaration.excluded, ["Network", "Random"]);
+            assert!(declaration.flattened_effects.is_empty());
+        }
+        other => panic!("Expected effect group, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_parse_row_variable_in_function() {
+    let statements = parse_source("fn map(f: F) effects Log - Network, ..e { }").unwrap();
+
+    match &statements[0] {
+        Stmt::Function { effects, .. } => {
+            assert_eq!(effects.effects, ["Log"]);
+            assert_eq!(effects.excluded, ["Network"]);
+            assert_eq!(effects.row_variable.as_deref(), Some("e"));
+        }
+        other => panic!("Expected function, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_row_variable_rejected_in_group() {
+    assert!(parse_source("effect_group Open = Log, ..e;").is_err());
+    assert!(parse_source("fn f() effects ..a, ..b { }").is_err());
+}
+
+#[test]
+fn test_flatten_nested_groups() {
+    let code = format!(
+        "{}effect_group Io = FileSystem, Network;\n\
+         effect_group All = Io, Log, FileSystem;",
+        EFFECTS
+    );
+    let (statements, errors) = resolve_source(&code);
+
+    assert!(errors.is_empty(), "{:?}", errors);
+    assert_eq!(
+        flattened_group(&statements, "Io"),
+        ["FileSystem", "Network"]
+    );
+    assert_eq!(
+        flattened_group(&statements, "All"),
+        ["FileSystem", "Network", "Log"]
+    );
+}
+
+#[test]
+fn test_flatten_difference() {
+    let code = format!(
+        "{}effect_group Io = FileSystem, Network;\n\
+         effect_group Local = Io, Log, State<i32> - Network - State;\n\
+         effect_group Quiet = Local - Io;",
+        EFFECTS
+    );
+    let (statements, errors) = resolve_source(&code);
+
+    assert!(errors.is_empty(), "{:?}", errors);
+    assert_eq!(flattened_group(&statements, "Local"), ["FileSystem", "Log"]);
+    assert_eq!(flattened_group(&statements, "Quiet"), ["Log"]);
+}
+
+#[test]
+fn test_function_row_flattened() {
+    let code = format!(
+        "{}effect_group Io = FileSystem, Network;\n\
+         fn fetch_all() effects Io, Log - FileSystem, ..e {{ }}",
+        EFFECTS
+    );
+    let (statements, errors) = resolve_source(&code);
+
+    assert!(errors.is_empty(), "{:?}", errors);
+    match statements.last().unwrap() {
+        Stmt::Function { effects, .. } => {
+            assert_eq!(effects.flattened_effects, ["Network", "Log"]);
+            assert_eq!(effects.row_variable.as_deref(), Some("e"));
+        }
+        other => panic!("Expected function, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_group_cycle_reported_once() {
+    let code = "effect_group A = B;\n\
+                effect_group B = C;\n\
+                effect_group C = A;";
+    let (_, errors) = resolve_source(code);
+
+    assert_eq!(
+        errors,
+        vec![ResolveError::GroupCycle {
+            span: Span {
+                file: "test.rs".into(),
+                line: 1,
+                column: 1,
+                end_line: 1,
+                end_column: 20,
+            },
+            cycle: vec!["A".into(), "B".into(), "C".into(), "A".into()],
+        }]
+    );
+    assert_eq!(
+        errors[0].to_string(),
+        "test.rs:1:1: Group 'A' refers to itself: A -> B -> C -> A"
+    );
+}
+
+#[test]
+fn test_unknown_effect_reported_at_group() {
+    let code = format!("{}\neffect_group Io = FileSystem, Netwrok;", EFFECTS);
+    let (statements, errors) = resolve_source(&code);
+
+    assert_eq!(errors.len(), 1);
+    match &errors[0] {
+        ResolveError::UnknownEffect { name, span } => {
+            assert_eq!(name, "Netwrok");
+            assert_eq!(span.line, 6);
+        }
+        other => panic!("Expected unknown effect, got {:?}", other),
+    }
+    // 未知的名称仍然保留在展开结果中
+    assert_eq!(
+        flattened_group(&statements, "Io"),
+        ["FileSystem", "Netwrok"]
+    );
+}
+
+#[test]
+fn test_paths_and_imports_are_known() {
+    let code = "use std::io::Console;\n\
+                effect_group Io = Console, fs::FileSystem;";
+    let (_, errors) = resolve_source(code);
+
+    assert!(errors.is_empty(), "{:?}", errors);
+}
+
+#[test]
+fn test_handler_groups_nest() {
+    let code = format!(
+        "{}handle Log as ConsoleLog {{ log(message) {{ }} }}\n\
+         handle Network as Offline {{ fetch(url) {{ }} }}\n\
+         handler_group Basic = ConsoleLog;\n\
+         handler_group Testing = Basic, Offline;\n\
+         handler_group Loop = Loop;\n\
+         handler_group Broken = Missing;",
+        EFFECTS
+    );
+    let (statements, errors) = resolve_source(&code);
+
+    let discharged: Vec<Vec<EffectName>> = statements
+        .iter()
+        .filter_map(|statement| match statement {
+            Stmt::HandlerGroup { declaration, .. } => Some(declaration.discharged_effects.clone()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(discharged[0], ["Log"]);
+    assert_eq!(discharged[1], ["Log", "Network"]);
+
+    assert_eq!(errors.len(), 2, "{:?}", errors);
+    assert!(matches!(
+        &errors[0],
+        ResolveError::GroupCycle { cycle, span } if cycle == &["Loop", "Loop"] && span.line == 9
+    ));
+    assert!(matches!(
+        &errors[1],
+        ResolveError::UnknownHandler { name, span } if name == "Missing" && span.line == 10
+    ));
+}
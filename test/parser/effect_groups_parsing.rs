@@ -1,4 +1,3 @@
-use rus::data::{Locatable, Token};
 use rus::lex::Lexer;
 use rus::parser::{Parser, Stmt};
 use std::io::BufReader;
@@ -11,14 +10,7 @@ fn test_effect_group_declaration() {
 
     let reader = BufReader::new(code.as_bytes());
     let lexer = Lexer::new("test.rs", reader);
-    let tokens: Vec<Locatable<Token>> = lexer
-        .map(|locatable| Locatable {
-            location: locatable.location,
-            data: locatable.data.unwrap(),
-        })
-        .collect();
-
-    let mut parser = Parser::new(tokens);
+    let mut parser = Parser::new(lexer);
     let statements = parser.parse().expect("Failed to parse");
 
     assert_eq!(statements.len(), 1);
@@ -42,14 +34,7 @@ fn test_handler_group_declaration() {
 
     let reader = BufReader::new(code.as_bytes());
     let lexer = Lexer::new("test.rs", reader);
-    let tokens: Vec<Locatable<Token>> = lexer
-        .map(|locatable| Locatable {
-            location: locatable.location,
-            data: locatable.data.unwrap(),
-        })
-        .collect();
-
-    let mut parser = Parser::new(tokens);
+    let mut parser = Parser::new(lexer);
     let statements = parser.parse().expect("Failed to parse");
 
     assert_eq!(statements.len(), 1);
@@ -75,14 +60,7 @@ fn test_function_with_effects() {
 
     let reader = BufReader::new(code.as_bytes());
     let lexer = Lexer::new("test.rs", reader);
-    let tokens: Vec<Locatable<Token>> = lexer
-        .map(|locatable| Locatable {
-            location: locatable.location,
-            data: locatable.data.unwrap(),
-        })
-        .collect();
-
-    let mut parser = Parser::new(tokens);
+    let mut parser = Parser::new(lexer);
     let statements = parser.parse().expect("Failed to parse");
 
     assert_eq!(statements.len(), 1);
@@ -106,14 +84,7 @@ fn test_multiple_declarations() {
 
     let reader = BufReader::new(code.as_bytes());
     let lexer = Lexer::new("test.rs", reader);
-    let tokens: Vec<Locatable<Token>> = lexer
-        .map(|locatable| Locatable {
-            location: locatable.location,
-            data: locatable.data.unwrap(),
-        })
-        .collect();
-
-    let mut parser = Parser::new(tokens);
+    let mut parser = Parser::new(lexer);
     let statements = parser.parse().expect("Failed to parse");
 
     assert_eq!(statements.len(), 3);
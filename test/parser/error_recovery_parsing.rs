@@ -0,0 +1,150 @@
+//! 错误恢复测试
+//! 测试解析器在错误后同步到 `;`、`}` 和声明关键字，收集所有错误并保留部分语法树
+
+#[path = "../common/mod.rs"]
+mod common;
+
+use common::parse_with_recovery;
+use rus::lex::Lexer;
+use rus::parser::{ModuleBody, Parser, Stmt};
+use std::io::BufReader;
+
+/// 语句的简短描述，便于比较部分语法树的形状
+fn kind(statement: &Stmt) -> &'static str {
+    match statement {
+        Stmt::Function { .. } => "fn",
+        Stmt::Let { .. } => "let",
+        Stmt::Effect { .. } => "effect",
+        Stmt::Struct { .. } => "struct",
+        Stmt::Expression { .. } => "expression",
+        Stmt::Module { .. } => "mod",
+        Stmt::Error { .. } => "error",
+        _ => "other",
+    }
+}
+
+fn kinds(statements: &[Stmt]) -> Vec<&'static str> {
+    statements.iter().map(kind).collect()
+}
+
+#[test]
+fn test_valid_program_has_no_errors() {
+    let (statements, errors) = parse_with_recovery("let x = 1; fn f() { x; }");
+
+    assert!(errors.is_empty());
+    assert_eq!(kinds(&statements), vec!["let", "fn"]);
+}
+
+#[test]
+fn test_reports_every_top_level_error() {
+    let (statements, errors) = parse_with_recovery(
+        "let x = ;
+         let y = 2;
+         fn (a) { }
+         effect Log { fn log(message: String); }
+         struct = 1;",
+    );
+
+    assert_eq!(errors.len(), 3);
+    assert_eq!(
+        kinds(&statements),
+        vec!["error", "let", "error", "effect", "error"]
+    );
+}
+
+#[test]
+fn test_error_node_location() {
+    let (statements, _) = parse_with_recovery("let a = 1;\nlet b = ;\n");
+
+    match &statements[1] {
+        Stmt::Error { span, .. } => {
+            assert_eq!(span.line, 2);
+            assert_eq!(span.column, 1);
+        }
+        other => panic!("Expected error node, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_synchronizes_at_declaration_keyword() {
+    // 缺少分号时，下一个声明关键字就是恢复点
+    let (statements, errors) = parse_with_recovery("let x = 1 + fn f() { }");
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(kinds(&statements), vec!["error", "fn"]);
+}
+
+#[test]
+fn test_recovers_inside_function_body() {
+    let (statements, errors) = parse_with_recovery(
+        "fn f() {
+             let a = 1;
+             let b = * ;
+             a + ;
+             let c = 3;
+         }
+         fn g() { }",
+    );
+
+    assert_eq!(errors.len(), 2);
+    assert_eq!(kinds(&statements), vec!["fn", "fn"]);
+    match &statements[0] {
+        Stmt::Function { body, .. } => {
+            assert_eq!(kinds(body), vec!["let", "error", "error", "let"]);
+        }
+        other => panic!("Expected function, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_skips_braced_group_after_error() {
+    // 参数列表出错后，整个函数体被跳过，不会在函数体内部产生额外的错误
+    let (statements, errors) = parse_with_recovery("fn f(: i32) { let x = ; } let y = 1;");
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(kinds(&statements), vec!["error", "let"]);
+}
+
+#[test]
+fn test_stray_closing_brace() {
+    let (statements, errors) = parse_with_recovery("let x = 1; } let y = 2;");
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(kinds(&statements), vec!["let", "error", "let"]);
+}
+
+#[test]
+fn test_unclosed_block_at_end_of_input() {
+    let (statements, errors) = parse_with_recovery("let a = 1; fn f() { let x = 1;");
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(kinds(&statements), vec!["let", "error"]);
+}
+
+#[test]
+fn test_recovers_inside_module() {
+    let (statements, errors) =
+        parse_with_recovery("mod m { let x = 1; fn f() { } struct ; struct S; }");
+
+    assert_eq!(errors.len(), 2);
+    match &statements[0] {
+        Stmt::Module { declaration, .. } => match &declaration.body {
+            ModuleBody::Inline(items) => {
+                assert_eq!(kinds(items), vec!["error", "fn", "error", "struct"])
+            }
+            other => panic!("Expected inline module, got {:?}", other),
+        },
+        other => panic!("Expected module, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_parse_returns_first_error() {
+    let source = "let x = ; let y = ;";
+    let lexer = Lexer::new("test.rs", BufReader::new(source.as_bytes()));
+    let first = Parser::new(lexer).parse().unwrap_err();
+    let (_, errors) = parse_with_recovery(source);
+
+    assert_eq!(errors.len(), 2);
+    assert_eq!(first, errors[0]);
+}
@@ -0,0 +1,351 @@
+//! 代码格式化测试
+//! 测试注释和空行的保留、按行宽换行、格式化的幂等性，以及格式化不改变语法树
+
+#[path = "../common/mod.rs"]
+mod common;
+
+use common::parse_valid;
+use rus::format::{FormatError, FormatOptions, format_source};
+use rus::printer::print_program;
+
+fn format(code: &str) -> String {
+    format_width(code, 100)
+}
+
+fn format_width(code: &str, line_width: usize) -> String {
+    let options = FormatOptions { line_width };
+    format_source("test.rs", code, &options)
+        .unwrap_or_else(|error| panic!("Failed to format: {}\n{}", error, code))
+}
+
+/// 格式化结果再格式化不变，并且与原来的源代码解析出相同的语法树
+fn assert_canonical(code: &str, formatted: &str) {
+    assert_eq!(format(formatted), formatted, "not idempotent");
+    assert_eq!(
+        print_program(&parse_valid(formatted)),
+        print_program(&parse_valid(code))
+    );
+}
+
+const FIXTURE: &str = r#"// 文件开头的注释
+
+use std::io; // 行尾注释
+
+
+/// 日志效果
+effect Log {
+    // 第一个操作
+    fn log(message: String); // 输出消息
+    fn level() -> u8;
+    // 效果末尾
+}
+
+handle Log as Console {
+    log(message, k) { print(message); resume!(()) } // 子句之后
+    // 返回子句之前
+    return(x) { x }
+    finally {
+        // 只有注释
+    }
+}
+
+struct Point {
+    x: i32, // 横坐标
+    // 纵坐标
+    y: HashMap<i32, i32>,
+}
+
+enum Shape { Circle(i32), /* 圆 */ Rect { w: i32, h: i32 } }
+
+trait Area {
+    // 面积
+    fn area(&self) -> f64;
+    fn scaled(&self, k: f64) -> f64 { self.area() * k } // 默认方法
+}
+
+contract Positive {
+    // 参数为正
+    requires x > 0;
+    ensures result > 0; // 结果为正
+}
+
+effect_group Local = Io, Log - Network; // 效果组
+handler_group Defaults = Console;
+
+fn main() -> i32 {
+    let x = 1; // 一
+    /* 块注释 */ let y = 2;
+
+
+
+    // 空行之后
+    {
+        // 空代码块
+    }
+    x + y
+    // 函数末尾
+}
+
+impl Point {
+    // 第一个方法
+    fn get(&self) -> i32 { self.x }
+
+    // 实现末尾
+}
+// 文件末尾
+"#;
+
+#[test]
+fn test_format_keeps_statement_comments() {
+    let code = "// 开头\nlet x = 1; // 行尾\n\n\n\n/* 之前 */ let y = 2;\n// 末尾\n";
+    assert_eq!(
+        format(code),
+        "// 开头\nlet x = 1; // 行尾\n\n/* 之前 */\nlet y = 2;\n// 末尾\n"
+    );
+}
+
+#[test]
+fn test_format_keeps_block_comments() {
+    let code = "fn f() {\n    let x = 1;\n    // 末尾\n}\nfn g() { /* 空 */ }\nfn h() {}\n";
+    assert_eq!(
+        format(code),
+        "fn f() {\n    let x = 1;\n    // 末尾\n}\nfn g() {\n    /* 空 */\n}\nfn h() {}\n"
+    );
+}
+
+#[test]
+fn test_format_keeps_member_comments() {
+    let formatted = format(FIXTURE);
+    let expected = r#"// 文件开头的注释
+
+use std::io; // 行尾注释
+
+/// 日志效果
+effect Log {
+    // 第一个操作
+    fn log(message: String); // 输出消息
+    fn level() -> u8;
+    // 效果末尾
+}
+
+handle Log as Console {
+    log(message, k) {
+        print(message);
+        resume!(())
+    } // 子句之后
+    // 返回子句之前
+    return(x) {
+        x
+    }
+    finally {
+        // 只有注释
+    }
+}
+
+struct Point {
+    x: i32, // 横坐标
+    // 纵坐标
+    y: HashMap<i32, i32>,
+}
+
+enum Shape {
+    Circle(i32), /* 圆 */
+    Rect { w: i32, h: i32 },
+}
+
+trait Area {
+    // 面积
+    fn area(&self) -> f64;
+    fn scaled(&self, k: f64) -> f64 {
+        self.area() * k
+    } // 默认方法
+}
+
+contract Positive {
+    // 参数为正
+    requires x > 0;
+    ensures result > 0; // 结果为正
+}
+"#;
+    assert!(
+        formatted.starts_with(expected),
+        "unexpected output:\n{}",
+        formatted
+    );
+    assert!(formatted.ends_with("    // 实现末尾\n}\n// 文件末尾\n"));
+    assert_canonical(FIXTURE, &formatted);
+}
+
+#[test]
+fn test_format_comment_after_multiline_member_comment() {
+    // 与多行块注释的最后一行在同一行的注释仍然跟在同一个成员后面
+    let code = "effect E { fn a() /* x\ny */; /* b */ fn c(); }";
+    let formatted = format(code);
+    assert_eq!(
+        formatted,
+        "effect E {\n    fn a(); /* x\ny */ /* b */\n    fn c();\n}\n"
+    );
+    assert_canonical(code, &formatted);
+}
+
+#[test]
+fn test_format_keeps_every_comment() {
+    let formatted = format(FIXTURE);
+    for line in FIXTURE.lines() {
+        for marker in ["//", "/*"] {
+            if let Some(index) = line.find(marker) {
+                let comment = &line[index..];
+                let comment = comment.split(" */").next().unwrap();
+                assert!(
+                    formatted.contains(comment),
+                    "lost {}:\n{}",
+                    comment,
+                    formatted
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn test_format_line_width() {
+    let code = "let z = foo(alpha, beta, gamma);\nfn f(a: i32, b: i32) {}\nlet p = Point { x: 1, ..origin };\n";
+    assert_eq!(format(code), code);
+
+    let formatted = format_width(code, 18);
+    assert_eq!(
+        formatted,
+        "let z = foo(\n    alpha,\n    beta,\n    gamma,\n);\nfn f(\n    a: i32,\n    b: i32,\n) {}\nlet p = Point {\n    x: 1,\n    ..origin\n};\n"
+    );
+    assert_eq!(format_width(&formatted, 18), formatted);
+    assert_eq!(
+        print_program(&parse_valid(&formatted)),
+        print_program(&parse_valid(code))
+    );
+}
+
+#[test]
+fn test_format_line_width_effect_lists() {
+    let code = "fn f() effects FileSystem, Network, Console, Logger, Random, Clock, Database {}
+effect_group Everything = FileSystem, Network, Console, Logger, Random - Clock;
+handler_group Defaults = ConsoleLogger, FileSystemHandler, NetworkHandler, RandomHandler;
+trait Io {
+    fn read() -> String effects FileSystem, Network, Console, Logger;
+}
+";
+    assert_eq!(format(code), code);
+
+    let formatted = format_width(code, 40);
+    assert_eq!(
+        formatted,
+        "fn f() effects
+    FileSystem,
+    Network,
+    Console,
+    Logger,
+    Random,
+    Clock,
+    Database
+{}
+effect_group Everything =
+    FileSystem,
+    Network,
+    Console,
+    Logger,
+    Random - Clock;
+handler_group Defaults =
+    ConsoleLogger,
+    FileSystemHandler,
+    NetworkHandler,
+    RandomHandler;
+trait Io {
+    fn read() -> String effects
+        FileSystem,
+        Network,
+        Console,
+        Logger;
+}
+"
+    );
+    assert_eq!(format_width(&formatted, 40), formatted);
+    assert_eq!(
+        print_program(&parse_valid(&formatted)),
+        print_program(&parse_valid(code))
+    );
+}
+
+#[test]
+fn test_format_comment_after_open_brace() {
+    // 与左花括号在同一行的行尾注释留在原处
+    let code = "fn main() { // 主函数\n    run();\n}\neffect Log { // 日志\n    fn log(m: String);\n}\nmod m { // 模块\n    fn f() {}\n}\n";
+    assert_eq!(format(code), code);
+
+    // 左花括号之后还有其他代码的注释不是行尾注释
+    assert_eq!(
+        format("fn g() { /* 空 */ }\nstruct P { /* 字段 */ x: i32 }\n"),
+        "fn g() {\n    /* 空 */\n}\nstruct P {\n    /* 字段 */\n    x: i32,\n}\n"
+    );
+}
+
+#[test]
+fn test_format_moves_expression_comments_before_statement() {
+    // 表达式中的注释没有挂载点，独占一行移到所在语句之前，不会丢失
+    let code = "fn main() {\n    let x = 1 + /* 内联 */ 2;\n    let y = foo(\n        a, // 第一个\n        b,\n    );\n}\n";
+    assert_eq!(
+        format(code),
+        "fn main() {\n    /* 内联 */\n    let x = 1 + 2;\n    // 第一个\n    let y = foo(a, b);\n}\n"
+    );
+}
+
+#[test]
+fn test_format_declarations_consistently() {
+    let code = "effect E{fn a(x:i32)->i32;fn b();}\neffect_group G=A,B-C;\nhandler_group H=X,Y;\nhandle E{a(x,k){resume!(x)}}\n";
+    let formatted = format(code);
+    assert_eq!(
+        formatted,
+        "effect E {\n    fn a(x: i32) -> i32;\n    fn b();\n}\neffect_group G = A, B - C;\nhandler_group H = X, Y;\nhandle E {\n    a(x, k) {\n        resume!(x)\n    }\n}\n"
+    );
+    assert_canonical(code, &formatted);
+}
+
+#[test]
+fn test_format_tuple_fields() {
+    // 以数字结尾的标识符后的元组字段不加空格，嵌套元组字段也不加
+    let code = "p1.0;\nv2.0.x;\nt.0.1;\n";
+    assert_eq!(format(code), code);
+    assert_canonical(code, code);
+}
+
+#[test]
+fn test_format_idempotent_at_every_width() {
+    for width in [20, 40, 60, 80, 100] {
+        let formatted = format_width(FIXTURE, width);
+        assert_eq!(
+            format_width(&formatted, width),
+            formatted,
+            "width {}",
+            width
+        );
+        assert_canonical(FIXTURE, &format(&formatted));
+    }
+}
+
+#[test]
+fn test_format_errors() {
+    let options = FormatOptions::default();
+    match format_source("test.rs", "let x = ;", &options) {
+        Err(FormatError::Parse(error)) => {
+            assert_eq!(
+                error.to_string(),
+                "test.rs:1:9: expected expression, found `;`"
+            )
+        }
+        other => panic!("expected parse error, got {:?}", other),
+    }
+    match format_source("test.rs", "let s = \"open;", &options) {
+        Err(error @ FormatError::Lexical(..)) => {
+            assert!(error.to_string().starts_with("test.rs:1:"))
+        }
+        other => panic!("expected lexical error, got {:?}", other),
+    }
+}
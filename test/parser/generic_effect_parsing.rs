@@ -0,0 +1,182 @@
+//! 泛型效果解析测试
+//! 测试带类型参数的效果声明、实例化泛型效果的处理器和效果列表
+
+#[path = "../common/mod.rs"]
+mod common;
+
+use common::parse_source;
+use rus::parser::{Expr, Stmt};
+use rus::resolve::{ResolveError, resolve_names};
+
+const STATE: &str = "effect State<S> { fn get() -> S; fn put(s: S); }";
+
+fn resolve_source(code: &str) -> (Vec<Stmt>, Vec<ResolveError>) {
+    let mut statements = parse_source(code).expect("Failed to parse");
+    let errors = resolve_names(&mut statements);
+    (statements, errors)
+}
+
+#[test]
+fn test_generic_effect_declaration() {
+    let statements = parse_source(STATE).expect("Failed to parse");
+
+    match &statements[0] {
+        Stmt::Effect { declaration, .. } => {
+            assert_eq!(declaration.name, "State");
+            assert_eq!(declaration.generics.len(), 1);
+            assert_eq!(declaration.generics[0].name, "S");
+            assert_eq!(declaration.operations[0].return_type.as_deref(), Some("S"));
+            assert_eq!(declaration.operations[1].parameters[0].1, "S");
+        }
+        other => panic!("Expected effect declaration, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_instantiated_effects_in_function_rows() {
+    let statements =
+        parse_source("fn run() -> i32 effects State<i32>, Exception<Vec<String>>, Log { }")
+            .expect("Failed to parse");
+
+    match &statements[0] {
+        Stmt::Function { effects, .. } => {
+            assert_eq!(
+                effects.effects,
+                ["State<i32>", "Exception<Vec<String>>", "Log"]
+            );
+        }
+        other => panic!("Expected function declaration, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_handler_of_instantiated_effect() {
+    let (statements, errors) = resolve_source(&format!(
+        "{} handle State<i32> as Counter {{ get(k) {{ }} put(s: i32) {{ }} }}",
+        STATE
+    ));
+
+    assert!(errors.is_empty(), "{:?}", errors);
+    match &statements[1] {
+        Stmt::Handler { declaration, .. } => {
+            assert_eq!(declaration.effect, "State<i32>");
+            assert_eq!(declaration.clauses[0].continuation.as_deref(), Some("k"));
+        }
+        other => panic!("Expected handler declaration, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_handler_parameter_types_are_instantiated() {
+    let (_, errors) = resolve_source(&format!(
+        "{} handle State<Vec<u8>> {{ put(s: Vec<i32>) {{ }} }}",
+        STATE
+    ));
+
+    match &errors[..] {
+        [
+            ResolveError::ParameterTypeMismatch {
+                expected, found, ..
+            },
+        ] => {
+            assert_eq!(expected, "Vec<u8>");
+            assert_eq!(found, "Vec<i32>");
+        }
+        other => panic!("Expected a type mismatch, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_function_type_arguments() {
+    // 类型实参中的 -> 不影响类型实参的个数，替换后的类型与注解按规范化的写法比较
+    let (statements, errors) = resolve_source(
+        "effect Reader<F, C> { fn ask(f: F, config: C) -> Option<C>; }
+         handle Reader<fn(i32) -> bool, i32> {
+             ask(f: fn(i32) -> bool, config: i32, k) { }
+         }",
+    );
+
+    assert!(errors.is_empty(), "{:?}", errors);
+    match &statements[1] {
+        Stmt::Handler { declaration, .. } => {
+            assert_eq!(declaration.effect.name, "Reader");
+            assert_eq!(
+                declaration.effect.type_arguments,
+                ["fn(i32) -> bool", "i32"]
+            );
+            assert_eq!(declaration.effect, "Reader<fn(i32) -> bool, i32>");
+        }
+        other => panic!("Expected handler declaration, got {:?}", other),
+    }
+
+    let (_, errors) = resolve_source(
+        "effect Reader<F, C> { fn ask(f: F) -> C; }
+         handle Reader<fn(i32) -> bool, i32> { ask(f: fn(i32) -> i32, k) { } }",
+    );
+    match &errors[..] {
+        [
+            ResolveError::ParameterTypeMismatch {
+                expected, found, ..
+            },
+        ] => {
+            assert_eq!(expected, "fn(i32) -> bool");
+            assert_eq!(found, "fn(i32) -> i32");
+        }
+        other => panic!("Expected a type mismatch, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_type_parameters_are_substituted_as_whole_types() {
+    // 只替换作为类型名的参数：Vec<S> 中的 S 被替换，路径 S::Item 和名称 Sx 不受影响
+    let (_, errors) = resolve_source(
+        "effect Store<S> { fn put(items: Vec<S>, item: S::Item, other: Sx); }
+         handle Store<(u8, bool)> { put(items: Vec<(u8, bool)>, item: S::Item, other: Sx) { } }",
+    );
+    assert!(errors.is_empty(), "{:?}", errors);
+}
+
+#[test]
+fn test_type_argument_count_is_checked() {
+    let (_, errors) = resolve_source(&format!("{} handle State {{ }}", STATE));
+
+    assert!(matches!(
+        &errors[..],
+        [ResolveError::TypeArgumentCountMismatch {
+            expected: 1,
+            found: 0,
+            ..
+        }]
+    ));
+}
+
+#[test]
+fn test_with_discharges_instantiated_effect() {
+    let (statements, errors) = resolve_source(&format!(
+        "{} handle State<i32> as Counter {{ }} with Counter {{ State.get(); }}",
+        STATE
+    ));
+
+    assert!(errors.is_empty(), "{:?}", errors);
+    match &statements[2] {
+        Stmt::Expression {
+            expression:
+                Expr::With {
+                    discharged_effects,
+                    body,
+                    ..
+                },
+            ..
+        } => {
+            assert_eq!(discharged_effects, &["State<i32>"]);
+            assert!(matches!(
+                &body[0],
+                Stmt::Expression {
+                    expression: Expr::EffectOperation { effect, .. },
+                    ..
+                } if effect == "State"
+            ));
+        }
+        other => panic!("Expected with expression, got {:?}", other),
+    }
+}
@@ -0,0 +1,161 @@
+//! 处理器子句解析测试
+//! 测试 return 子句、finally 子句、带类型的参数、续延参数和 resume! 表达式，
+//! 以及名称解析阶段对照效果声明进行的参数检查
+
+#[path = "../common/mod.rs"]
+mod common;
+
+use common::parse_source;
+use rus::parser::{Expr, HandlerDeclaration, Parameter, Stmt};
+use rus::resolve::{ResolveError, resolve_names};
+
+const FILE_SYSTEM: &str = "effect FileSystem {
+    fn read_file(path: string) -> string;
+    fn write_file(path: string, content: string);
+}";
+
+/// 在 FileSystem 效果声明之后解析处理器并进行名称解析
+fn resolve_handler(handler: &str) -> (HandlerDeclaration, Vec<ResolveError>) {
+    let code = format!("{} {}", FILE_SYSTEM, handler);
+    let mut statements = parse_source(&code).expect("Failed to parse");
+    let errors = resolve_names(&mut statements);
+    match statements.pop() {
+        Some(Stmt::Handler { declaration, .. }) => (declaration, errors),
+        other => panic!("Expected handler declaration, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_typed_clause_parameters() {
+    let (declaration, errors) =
+        resolve_handler("handle FileSystem { read_file(path: string) { } }");
+
+    assert!(errors.is_empty(), "{:?}", errors);
+    assert_eq!(
+        declaration.clauses[0].parameters,
+        vec![Parameter {
+            name: "path".into(),
+            type_annotation: Some("string".to_string()),
+        }]
+    );
+    assert_eq!(declaration.clauses[0].continuation, None);
+}
+
+#[test]
+fn test_continuation_binding() {
+    let (declaration, errors) =
+        resolve_handler("handle FileSystem { read_file(path, k) { k(\"contents\"); } }");
+
+    assert!(errors.is_empty(), "{:?}", errors);
+    let clause = &declaration.clauses[0];
+    assert_eq!(clause.parameters.len(), 1);
+    assert_eq!(clause.parameters[0].name, "path");
+    assert_eq!(clause.continuation.as_deref(), Some("k"));
+}
+
+#[test]
+fn test_resume_expression() {
+    let statements =
+        parse_source("handle FileSystem { read_file(path) { resume!(\"contents\"); resume!(); } }")
+            .expect("Failed to parse");
+
+    match &statements[0] {
+        Stmt::Handler { declaration, .. } => {
+            let body = &declaration.clauses[0].body;
+            assert!(matches!(
+                &body[0],
+                Stmt::Expression {
+                    expression: Expr::Resume { value: Some(_), .. },
+                    ..
+                }
+            ));
+            assert!(matches!(
+                &body[1],
+                Stmt::Expression {
+                    expression: Expr::Resume { value: None, .. },
+                    ..
+                }
+            ));
+        }
+        other => panic!("Expected handler declaration, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_return_and_finally_clauses() {
+    let (declaration, errors) = resolve_handler(
+        "handle FileSystem {
+            return(value: i32) { value; }
+            read_file(path) { }
+            finally { close(); }
+        }",
+    );
+
+    assert!(errors.is_empty(), "{:?}", errors);
+    assert_eq!(declaration.clauses.len(), 1);
+
+    let return_clause = declaration.return_clause.expect("Expected return clause");
+    assert_eq!(return_clause.parameter.name, "value");
+    assert_eq!(
+        return_clause.parameter.type_annotation.as_deref(),
+        Some("i32")
+    );
+    assert_eq!(return_clause.body.len(), 1);
+
+    assert_eq!(declaration.finally_clause.map(|body| body.len()), Some(1));
+}
+
+#[test]
+fn test_duplicate_return_clause_is_rejected() {
+    assert!(parse_source("handle E { return(x) { } return(y) { } }").is_err());
+}
+
+#[test]
+fn test_unknown_operation_is_reported() {
+    let (_, errors) = resolve_handler("handle FileSystem { delete_file(path) { } }");
+
+    assert!(matches!(
+        &errors[..],
+        [ResolveError::UnknownOperation { operation, .. }] if operation == "delete_file"
+    ));
+}
+
+#[test]
+fn test_parameter_count_mismatch_is_reported() {
+    let (_, errors) = resolve_handler("handle FileSystem { write_file(path) { } }");
+
+    assert!(matches!(
+        &errors[..],
+        [ResolveError::ParameterCountMismatch {
+            expected: 2,
+            found: 1,
+            ..
+        }]
+    ));
+}
+
+#[test]
+fn test_parameter_type_mismatch_is_reported() {
+    let (_, errors) = resolve_handler("handle FileSystem {\n    read_file(path: i32) { }\n}");
+
+    match &errors[..] {
+        [
+            error @ ResolveError::ParameterTypeMismatch {
+                expected, found, ..
+            },
+        ] => {
+            assert_eq!(expected, "string");
+            assert_eq!(found, "i32");
+            assert_eq!(error.span().line, 5);
+        }
+        other => panic!("Expected a type mismatch, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_handlers_of_undeclared_effects_are_not_checked() {
+    let mut statements =
+        parse_source("handle Unknown { anything(a, b, c) { } }").expect("Failed to parse");
+
+    assert!(resolve_names(&mut statements).is_empty());
+}
@@ -0,0 +1,278 @@
+//! 驻留字符串与节点编号测试
+//! 测试标识符和文件名的驻留、语法树节点的先序编号，以及以编号为键的附加信息表
+
+#[path = "../common/mod.rs"]
+mod common;
+
+use common::{parse_valid, parse_with_recovery};
+use rus::ast::{NodeId, NodeMap, assign_node_ids};
+use rus::data::Span;
+use rus::intern::Symbol;
+use rus::module::load_modules;
+use rus::parser::{Expr, ModuleBody, Stmt};
+use rus::resolve::resolve_names;
+use rus::syntax::SyntaxTree;
+use std::fs;
+
+#[test]
+fn test_symbol_interning() {
+    let a = Symbol::intern("counter");
+    let b = Symbol::intern(&String::from("counter"));
+    let c = Symbol::intern("count");
+
+    assert_eq!(a, b);
+    assert_ne!(a, c);
+    assert_eq!(a.as_str(), "counter");
+    assert_eq!(a, "counter");
+    assert_eq!(a.len(), 7);
+    assert_eq!(a.to_string(), "counter");
+    assert_eq!(format!("{:?}", a), "\"counter\"");
+    assert_eq!(std::mem::size_of::<Symbol>(), 4);
+}
+
+#[test]
+fn test_many_symbols_across_threads() {
+    // 驻留的字符串跨越多个块，并且可以在其他线程中读取
+    let symbols: Vec<Symbol> = (0..1000)
+        .map(|index| Symbol::intern(&format!("symbol_{}", index)))
+        .collect();
+
+    let handles: Vec<_> = (0..4)
+        .map(|thread| {
+            let symbols = symbols.clone();
+            std::thread::spawn(move || {
+                for (index, symbol) in symbols.iter().enumerate() {
+                    assert_eq!(symbol.as_str(), format!("symbol_{}", index));
+                }
+                Symbol::intern(&format!("thread_{}", thread))
+            })
+        })
+        .collect();
+    for (thread, handle) in handles.into_iter().enumerate() {
+        assert_eq!(handle.join().unwrap(), format!("thread_{}", thread));
+    }
+    assert_eq!(Symbol::intern("symbol_999"), symbols[999]);
+}
+
+#[test]
+fn test_identifiers_and_files_are_interned() {
+    let statements = parse_valid("let total = 1;\nlet other = total;");
+
+    let (
+        Stmt::Let {
+            identifier, span, ..
+        },
+        Stmt::Let {
+            initializer:
+                Some(Expr::Identifier {
+                    name,
+                    span: name_span,
+                    ..
+                }),
+            ..
+        },
+    ) = (&statements[0], &statements[1])
+    else {
+        panic!("Expected two let statements, got {:?}", statements);
+    };
+
+    // 同名的标识符和同一文件名都是同一个 Symbol
+    assert_eq!(identifier, name);
+    assert_eq!(*identifier, Symbol::intern("total"));
+    assert_eq!(span.file, name_span.file);
+    assert_eq!(span.file, "test.rs");
+}
+
+#[test]
+fn test_compact_spans() {
+    let statements = parse_valid("let total = a +\n    b;\nfn f() { total }");
+
+    // 区间从第一个记号开始，到最后一个记号之后结束
+    let span = statements[0].span();
+    assert_eq!((span.line, span.column), (1, 1));
+    assert_eq!((span.end_line, span.end_column), (2, 7));
+    let Stmt::Let {
+        initializer: Some(binary),
+        ..
+    } = &statements[0]
+    else {
+        panic!("Expected let");
+    };
+    let span = binary.span();
+    assert_eq!(
+        (span.line, span.column, span.end_line, span.end_column),
+        (1, 13, 2, 6)
+    );
+    assert_eq!(binary.location().column, 13);
+
+    let span = statements[1].span();
+    assert_eq!(
+        (span.line, span.column, span.end_line, span.end_column),
+        (3, 1, 3, 17)
+    );
+
+    // 文件名是驻留的 Symbol，整个区间按值复制
+    assert_eq!(std::mem::size_of::<Span>(), 20);
+    let copy = span;
+    assert_eq!(copy, span);
+}
+
+#[test]
+fn test_node_ids_in_preorder() {
+    let statements = parse_valid("let x = a + b;\nfn f() { x }");
+
+    // let 0, a + b 1, a 2, b 3, fn 4, 表达式语句 5, x 6
+    assert_eq!(statements[0].id(), NodeId::new(0));
+    let Stmt::Let {
+        initializer: Some(Expr::Binary {
+            id, left, right, ..
+        }),
+        ..
+    } = &statements[0]
+    else {
+        panic!("Expected let with binary initializer");
+    };
+    assert_eq!(*id, NodeId::new(1));
+    assert_eq!(left.id(), NodeId::new(2));
+    assert_eq!(right.id(), NodeId::new(3));
+
+    let Stmt::Function { id, body, .. } = &statements[1] else {
+        panic!("Expected function");
+    };
+    assert_eq!(*id, NodeId::new(4));
+    assert_eq!(body[0].id(), NodeId::new(5));
+    match &body[0] {
+        Stmt::Expression { expression, .. } => assert_eq!(expression.id(), NodeId::new(6)),
+        other => panic!("Expected expression statement, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_assign_node_ids_counts_nested_nodes() {
+    let mut statements = parse_valid(
+        "effect Log { fn log(message: String); }
+         fn run() -> i32 requires x > 0 {
+             with handle Log { log(message) { resume!(()) } } {
+                 Log.log(\"hi\");
+             }
+             [1; 3]
+         }",
+    );
+
+    let count = assign_node_ids(&mut statements);
+    // effect 1 + fn 1 + requires x > 0 3 + with 语句和表达式 2 + resume!(()) 3
+    // + 效果调用语句 4 + [1; 3] 语句和表达式 4
+    assert_eq!(count, 18);
+    assert_eq!(statements[1].id(), NodeId::new(1));
+
+    // 重新编号得到相同的结果
+    let before = statements.clone();
+    assert_eq!(assign_node_ids(&mut statements), count);
+    assert_eq!(statements, before);
+}
+
+#[test]
+fn test_error_nodes_numbered() {
+    let (statements, errors) = parse_with_recovery("let x = ;\nlet y = 2;");
+
+    assert_eq!(errors.len(), 1);
+    assert!(matches!(statements[0], Stmt::Error { .. }));
+    assert_eq!(statements[0].id(), NodeId::new(0));
+    assert_eq!(statements[1].id(), NodeId::new(1));
+}
+
+#[test]
+fn test_syntax_tree_statements_numbered() {
+    let source = "fn f() { 1 }\nlet x = f();";
+    let tree = SyntaxTree::parse("test.rs", source);
+
+    let statements = tree.statements();
+    assert_eq!(statements, parse_valid(source));
+    assert_eq!(statements[1].id(), NodeId::new(3));
+
+    // 语句节点对应的语句保留同一次编号
+    let statement = tree.statement(&tree.source_file().items()[1]).unwrap();
+    assert_eq!(statement.id(), NodeId::new(3));
+}
+
+#[test]
+fn test_name_resolution_keeps_node_ids() {
+    let mut statements = parse_valid(
+        "enum Shape { Circle(i32) }
+         let s = Shape::Circle(1);",
+    );
+    let Stmt::Let {
+        initializer: Some(call),
+        ..
+    } = &statements[1]
+    else {
+        panic!("Expected let");
+    };
+    let call_id = call.id();
+
+    assert!(resolve_names(&mut statements).is_empty());
+    match &statements[1] {
+        Stmt::Let {
+            initializer: Some(constructor @ Expr::EnumConstructor { .. }),
+            ..
+        } => assert_eq!(constructor.id(), call_id),
+        other => panic!("Expected enum constructor, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_loaded_modules_numbered_across_files() {
+    let root = std::env::temp_dir().join(format!("rus_interned_ast_tests_{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(&root).unwrap();
+    fs::write(root.join("main.rus"), "mod util;\nlet a = 1;").unwrap();
+    fs::write(root.join("util.rus"), "let b = 2;").unwrap();
+
+    let main = root.join("main.rus");
+    let mut statements = parse_valid(&fs::read_to_string(&main).unwrap());
+    assert!(load_modules(&mut statements, &main).is_empty());
+
+    // mod 0, 模块文件中的 let 1 和 2, 入口文件的 let 3 和 4
+    let Stmt::Module { declaration, .. } = &statements[0] else {
+        panic!("Expected module");
+    };
+    let ModuleBody::File { items, .. } = &declaration.body else {
+        panic!("Expected file module");
+    };
+    assert_eq!(items[0].id(), NodeId::new(1));
+    assert_eq!(statements[1].id(), NodeId::new(3));
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn test_node_map() {
+    let statements = parse_valid("let x = 1;\nlet y = x;");
+    let mut types: NodeMap<&str> = NodeMap::new();
+
+    assert!(types.is_empty());
+    assert_eq!(types.insert(statements[1].id(), "i32"), None);
+    assert_eq!(types.insert(statements[0].id(), "unit"), None);
+    assert_eq!(types.insert(statements[0].id(), "()"), Some("unit"));
+
+    assert_eq!(types.get(statements[1].id()), Some(&"i32"));
+    assert_eq!(types.get(NodeId::new(100)), None);
+    assert_eq!(types.len(), 2);
+    let entries: Vec<(NodeId, &&str)> = types.iter().collect();
+    assert_eq!(
+        entries,
+        vec![(statements[0].id(), &"()"), (statements[1].id(), &"i32")]
+    );
+
+    *types.get_mut(statements[1].id()).unwrap() = "i64";
+    assert_eq!(types.remove(statements[1].id()), Some("i64"));
+    assert!(!types.contains(statements[1].id()));
+}
+
+#[test]
+fn test_unnumbered_node_id() {
+    assert!(NodeId::DUMMY.is_dummy());
+    assert!(!NodeId::new(0).is_dummy());
+    assert_eq!(format!("{:?}", NodeId::DUMMY), "NodeId(DUMMY)");
+    assert_eq!(format!("{:?}", NodeId::new(7)), "NodeId(7)");
+}
@@ -0,0 +1,344 @@
+//! 可见性与模块解析测试
+//! 测试 pub / pub(crate) 修饰符、内联模块，以及 mod name; 对应文件的加载
+
+#[path = "../common/mod.rs"]
+mod common;
+
+use common::parse_source;
+use rus::module::{ModuleError, load_modules};
+use rus::parser::{ModuleBody, Stmt, StructFields, Visibility};
+use std::fs;
+use std::path::PathBuf;
+
+/// 在临时目录下创建一组源文件，返回该目录
+fn create_files(test_name: &str, files: &[(&str, &str)]) -> PathBuf {
+    let root = std::env::temp_dir().join(format!(
+        "rus_module_tests_{}_{}",
+        std::process::id(),
+        test_name
+    ));
+    let _ = fs::remove_dir_all(&root);
+    for (path, content) in files {
+        let path = root.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+    root
+}
+
+/// 取出模块声明的名称和内容
+fn module(statement: &Stmt) -> (&str, &ModuleBody) {
+    match statement {
+        Stmt::Module { declaration, .. } => (&declaration.name, &declaration.body),
+        other => panic!("Expected module declaration, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_declaration_visibility() {
+    let statements = parse_source(
+        "pub fn a() { }
+         pub(crate) struct B;
+         enum C { X }
+         pub effect D { fn op(); }
+         pub(crate) type E = i32;
+         pub use std::io;
+         pub trait F { }",
+    )
+    .unwrap();
+
+    let visibilities: Vec<_> = statements.iter().map(Stmt::visibility).collect();
+    assert_eq!(
+        visibilities,
+        vec![
+            Some(Visibility::Public),
+            Some(Visibility::Crate),
+            Some(Visibility::Private),
+            Some(Visibility::Public),
+            Some(Visibility::Crate),
+            Some(Visibility::Public),
+            Some(Visibility::Public),
+        ]
+    );
+}
+
+#[test]
+fn test_pub_async_fn_location() {
+    let statements = parse_source("pub async fn run() { }").unwrap();
+
+    match &statements[0] {
+        Stmt::Function {
+            is_async,
+            visibility,
+            span,
+            ..
+        } => {
+            assert!(*is_async);
+            assert_eq!(*visibility, Visibility::Public);
+            // 区间从 pub 开始，到函数体的 } 之后结束
+            assert_eq!((span.line, span.column), (1, 1));
+            assert_eq!((span.end_line, span.end_column), (1, 23));
+        }
+        other => panic!("Expected function, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_statements_without_visibility() {
+    let statements = parse_source("let x = 1; impl Point { }").unwrap();
+
+    assert_eq!(statements[0].visibility(), None);
+    assert_eq!(statements[1].visibility(), None);
+}
+
+#[test]
+fn test_pub_on_let_or_impl_rejected() {
+    assert!(parse_source("pub let x = 1;").is_err());
+    assert!(parse_source("pub var x = 1;").is_err());
+    assert!(parse_source("pub impl Point { }").is_err());
+    assert!(parse_source("pub pub fn a() { }").is_err());
+}
+
+#[test]
+fn test_struct_field_visibility() {
+    let statements = parse_source(
+        "pub struct Point { pub x: i32, pub(crate) y: i32, z: i32 } struct Meters(pub f64, i32);",
+    )
+    .unwrap();
+
+    match &statements[0] {
+        Stmt::Struct { declaration, .. } => match &declaration.fields {
+            StructFields::Named(fields) => {
+                let visibilities: Vec<_> = fields.iter().map(|f| f.visibility).collect();
+                assert_eq!(
+                    visibilities,
+                    vec![Visibility::Public, Visibility::Crate, Visibility::Private]
+                );
+            }
+            other => panic!("Expected named fields, got {:?}", other),
+        },
+        other => panic!("Expected struct, got {:?}", other),
+    }
+
+    match &statements[1] {
+        Stmt::Struct { declaration, .. } => match &declaration.fields {
+            StructFields::Tuple(fields) => {
+                assert_eq!(fields[0].visibility, Visibility::Public);
+                assert_eq!(fields[0].field_type, "f64");
+                assert_eq!(fields[1].visibility, Visibility::Private);
+            }
+            other => panic!("Expected tuple fields, got {:?}", other),
+        },
+        other => panic!("Expected struct, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_enum_variant_field_visibility_rejected() {
+    let error = parse_source("enum Shape { Circle(pub f64) }").unwrap_err();
+    assert!(error.to_string().contains("cannot have visibility"));
+
+    assert!(parse_source("enum Shape { Rect { pub w: f64 } }").is_err());
+}
+
+#[test]
+fn test_impl_item_visibility() {
+    let statements = parse_source(
+        "impl Point { pub fn new() -> Point { } fn helper() { } pub(crate) type Unit = i32; }",
+    )
+    .unwrap();
+
+    match &statements[0] {
+        Stmt::Impl { declaration, .. } => {
+            let visibilities: Vec<_> = declaration.items.iter().map(Stmt::visibility).collect();
+            assert_eq!(
+                visibilities,
+                vec![
+                    Some(Visibility::Public),
+                    Some(Visibility::Private),
+                    Some(Visibility::Crate),
+                ]
+            );
+        }
+        other => panic!("Expected impl, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_inline_module() {
+    let statements =
+        parse_source("pub mod geometry { pub struct Point; mod detail { fn f() { } } }").unwrap();
+
+    assert_eq!(statements[0].visibility(), Some(Visibility::Public));
+    let (name, body) = module(&statements[0]);
+    assert_eq!(name, "geometry");
+    match body {
+        ModuleBody::Inline(items) => {
+            assert_eq!(items.len(), 2);
+            let (nested_name, _) = module(&items[1]);
+            assert_eq!(nested_name, "detail");
+        }
+        other => panic!("Expected inline module, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_file_module_declaration() {
+    let statements = parse_source("mod network; pub(crate) mod storage;").unwrap();
+
+    assert_eq!(
+        module(&statements[0]).1,
+        &ModuleBody::File {
+            path: None,
+            items: vec![]
+        }
+    );
+    assert_eq!(statements[1].visibility(), Some(Visibility::Crate));
+}
+
+#[test]
+fn test_module_rejects_statements() {
+    assert!(parse_source("mod m { let x = 1; }").is_err());
+    assert!(parse_source("mod m").is_err());
+}
+
+#[test]
+fn test_load_flat_and_nested_modules() {
+    let root = create_files(
+        "flat_and_nested",
+        &[
+            ("main.rus", "mod network; mod storage;"),
+            ("network.rus", "pub fn connect() { } mod tcp;"),
+            ("network/tcp.rus", "pub struct Socket;"),
+            ("storage/mod.rus", "mod disk;"),
+            ("storage/disk.rus", "pub fn write() { }"),
+        ],
+    );
+    let main = root.join("main.rus");
+    let mut statements = parse_source(&fs::read_to_string(&main).unwrap()).unwrap();
+
+    let errors = load_modules(&mut statements, &main);
+    assert!(errors.is_empty(), "{:?}", errors);
+
+    match module(&statements[0]).1 {
+        ModuleBody::File { path, items } => {
+            assert_eq!(
+                path.as_deref(),
+                Some(root.join("network.rus").display().to_string().as_str())
+            );
+            assert_eq!(items.len(), 2);
+            match module(&items[1]).1 {
+                ModuleBody::File { items, .. } => {
+                    assert!(
+                        matches!(&items[0], Stmt::Struct { declaration, .. } if declaration.name == "Socket")
+                    )
+                }
+                other => panic!("Expected file module, got {:?}", other),
+            }
+        }
+        other => panic!("Expected file module, got {:?}", other),
+    }
+
+    match module(&statements[1]).1 {
+        ModuleBody::File { path, items } => {
+            assert!(path.as_ref().unwrap().ends_with("mod.rus"));
+            match module(&items[0]).1 {
+                ModuleBody::File { items, .. } => {
+                    assert!(matches!(&items[0], Stmt::Function { name, .. } if name == "write"))
+                }
+                other => panic!("Expected file module, got {:?}", other),
+            }
+        }
+        other => panic!("Expected file module, got {:?}", other),
+    }
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn test_load_module_inside_inline_module() {
+    let root = create_files(
+        "inside_inline",
+        &[
+            ("main.rus", "mod outer { mod inner; }"),
+            ("outer/inner.rus", "fn f() { }"),
+        ],
+    );
+    let main = root.join("main.rus");
+    let mut statements = parse_source(&fs::read_to_string(&main).unwrap()).unwrap();
+
+    let errors = load_modules(&mut statements, &main);
+    assert!(errors.is_empty(), "{:?}", errors);
+
+    match module(&statements[0]).1 {
+        ModuleBody::Inline(items) => match module(&items[0]).1 {
+            ModuleBody::File { items, .. } => assert_eq!(items.len(), 1),
+            other => panic!("Expected file module, got {:?}", other),
+        },
+        other => panic!("Expected inline module, got {:?}", other),
+    }
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn test_missing_module_file() {
+    let root = create_files("missing", &[("main.rus", "mod absent;")]);
+    let main = root.join("main.rus");
+    let mut statements = parse_source("mod absent;").unwrap();
+
+    let errors = load_modules(&mut statements, &main);
+    assert_eq!(errors.len(), 1);
+    match &errors[0] {
+        ModuleError::NotFound {
+            module, candidates, ..
+        } => {
+            assert_eq!(module, "absent");
+            assert_eq!(candidates.len(), 2);
+        }
+        other => panic!("Expected NotFound, got {:?}", other),
+    }
+    assert!(
+        errors[0]
+            .to_string()
+            .contains("File for module 'absent' not found")
+    );
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn test_ambiguous_module_file() {
+    let root = create_files(
+        "ambiguous",
+        &[
+            ("main.rus", "mod both;"),
+            ("both.rus", ""),
+            ("both/mod.rus", ""),
+        ],
+    );
+    let main = root.join("main.rus");
+    let mut statements = parse_source("mod both;").unwrap();
+
+    let errors = load_modules(&mut statements, &main);
+    assert!(matches!(&errors[..], [ModuleError::Ambiguous { module, .. }] if module == "both"));
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn test_module_file_parse_error() {
+    let root = create_files(
+        "parse_error",
+        &[("main.rus", "mod broken;"), ("broken.rus", "fn (")],
+    );
+    let main = root.join("main.rus");
+    let mut statements = parse_source("mod broken;").unwrap();
+
+    let errors = load_modules(&mut statements, &main);
+    assert!(
+        matches!(&errors[..], [ModuleError::Parse { error }] if error.span.file.ends_with("broken.rus"))
+    );
+
+    fs::remove_dir_all(root).unwrap();
+}
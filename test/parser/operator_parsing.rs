@@ -0,0 +1,236 @@
+//! 运算符解析测试
+//! 测试语法分析器对位运算、移位、范围、类型转换以及借用/解引用运算符的解析能力
+
+#[path = "../common/mod.rs"]
+mod common;
+
+use common::{parse_expression, parse_source};
+use rus::parser::{BinaryOperator, Expr, ParseError, ParseErrorKind, UnaryOperator};
+
+fn binary_parts(expr: &Expr) -> (&Expr, &BinaryOperator, &Expr) {
+    match expr {
+        Expr::Binary {
+            left,
+            operator,
+            right,
+            ..
+        } => (left, operator, right),
+        other => panic!("Expected binary expression, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_bitwise_operators() {
+    let cases = [
+        ("a & b;", BinaryOperator::BitAnd),
+        ("a | b;", BinaryOperator::BitOr),
+        ("a ^ b;", BinaryOperator::BitXor),
+        ("a << b;", BinaryOperator::Shl),
+        ("a >> b;", BinaryOperator::Shr),
+    ];
+
+    for (code, expected) in cases {
+        let expr = parse_expression(code);
+        let (_, operator, _) = binary_parts(&expr);
+        assert_eq!(operator, &expected, "while parsing {}", code);
+    }
+}
+
+#[test]
+fn test_bitwise_precedence_follows_rust() {
+    // a | b ^ c & d << e + f 应解析为 a | (b ^ (c & (d << (e + f))))
+    let expr = parse_expression("a | b ^ c & d << e + f;");
+
+    let (_, operator, right) = binary_parts(&expr);
+    assert_eq!(operator, &BinaryOperator::BitOr);
+    let (_, operator, right) = binary_parts(right);
+    assert_eq!(operator, &BinaryOperator::BitXor);
+    let (_, operator, right) = binary_parts(right);
+    assert_eq!(operator, &BinaryOperator::BitAnd);
+    let (_, operator, right) = binary_parts(right);
+    assert_eq!(operator, &BinaryOperator::Shl);
+    let (_, operator, _) = binary_parts(right);
+    assert_eq!(operator, &BinaryOperator::Add);
+}
+
+#[test]
+fn test_comparison_binds_looser_than_bitwise() {
+    // a & b == c 应解析为 (a & b) == c
+    let expr = parse_expression("a & b == c;");
+
+    let (left, operator, _) = binary_parts(&expr);
+    assert_eq!(operator, &BinaryOperator::Equal);
+    let (_, operator, _) = binary_parts(left);
+    assert_eq!(operator, &BinaryOperator::BitAnd);
+}
+
+#[test]
+fn test_cast_expression() {
+    // -x as u8 + 1 应解析为 ((-x) as u8) + 1
+    let expr = parse_expression("-x as u8 + 1;");
+
+    let (left, operator, _) = binary_parts(&expr);
+    assert_eq!(operator, &BinaryOperator::Add);
+    match left {
+        Expr::Cast {
+            expression,
+            target_type,
+            ..
+        } => {
+            assert_eq!(target_type, "u8");
+            assert!(matches!(
+                &**expression,
+                Expr::Unary {
+                    operator: UnaryOperator::Negate,
+                    ..
+                }
+            ));
+        }
+        other => panic!("Expected cast expression, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_cast_to_generic_type() {
+    let expr = parse_expression("x as Vec<Vec<i32>> as &mut [u8; 4];");
+
+    match expr {
+        Expr::Cast {
+            expression,
+            target_type,
+            ..
+        } => {
+            assert_eq!(target_type, "&mut [u8; 4]");
+            match *expression {
+                Expr::Cast { target_type, .. } => assert_eq!(target_type, "Vec<Vec<i32>>"),
+                other => panic!("Expected inner cast, got {:?}", other),
+            }
+        }
+        other => panic!("Expected cast expression, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_range_expressions() {
+    let cases = [
+        ("a..b;", true, true, false),
+        ("a..=b;", true, true, true),
+        ("a..;", true, false, false),
+        ("..b;", false, true, false),
+        ("..=b;", false, true, true),
+        ("..;", false, false, false),
+    ];
+
+    for (code, has_start, has_end, expected_inclusive) in cases {
+        match parse_expression(code) {
+            Expr::Range {
+                start,
+                end,
+                inclusive,
+                ..
+            } => {
+                assert_eq!(start.is_some(), has_start, "start of {}", code);
+                assert_eq!(end.is_some(), has_end, "end of {}", code);
+                assert_eq!(inclusive, expected_inclusive, "inclusivity of {}", code);
+            }
+            other => panic!("Expected range expression for {}, got {:?}", code, other),
+        }
+    }
+}
+
+#[test]
+fn test_range_binds_looser_than_logical_or() {
+    // 0..n + 1 应解析为 0..(n + 1)
+    match parse_expression("0..n + 1;") {
+        Expr::Range { end: Some(end), .. } => {
+            let (_, operator, _) = binary_parts(&end);
+            assert_eq!(operator, &BinaryOperator::Add);
+        }
+        other => panic!("Expected range expression, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_reference_and_dereference() {
+    let cases = [
+        ("&x;", UnaryOperator::Reference),
+        ("&mut x;", UnaryOperator::MutableReference),
+        ("& mut x;", UnaryOperator::MutableReference),
+        ("*x;", UnaryOperator::Dereference),
+    ];
+
+    for (code, expected) in cases {
+        match parse_expression(code) {
+            Expr::Unary { operator, .. } => {
+                assert_eq!(operator, expected, "while parsing {}", code)
+            }
+            other => panic!("Expected unary expression for {}, got {:?}", code, other),
+        }
+    }
+}
+
+#[test]
+fn test_double_reference() {
+    match parse_expression("&&x;") {
+        Expr::Unary {
+            operator: UnaryOperator::Reference,
+            operand,
+            ..
+        } => assert!(matches!(
+            *operand,
+            Expr::Unary {
+                operator: UnaryOperator::Reference,
+                ..
+            }
+        )),
+        other => panic!("Expected nested reference, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_assignment_through_dereference() {
+    assert!(matches!(parse_expression("*x = 1;"), Expr::Assign { .. }));
+}
+
+#[test]
+fn test_chained_comparison_is_rejected() {
+    for code in ["a < b < c;", "a == b == c;", "a < b == c;"] {
+        match parse_source(code) {
+            Err(
+                error @ ParseError {
+                    kind: ParseErrorKind::ChainedComparison { .. },
+                    ..
+                },
+            ) => {
+                let message = error.to_string();
+                assert!(message.contains("&&"), "unhelpful message: {}", message)
+            }
+            other => panic!(
+                "Expected chained operator error for {}, got {:?}",
+                code, other
+            ),
+        }
+    }
+}
+
+#[test]
+fn test_parenthesized_comparisons_are_allowed() {
+    let expr = parse_expression("(a < b) == c;");
+    let (_, operator, _) = binary_parts(&expr);
+    assert_eq!(operator, &BinaryOperator::Equal);
+
+    let expr = parse_expression("a < b && b < c;");
+    let (_, operator, _) = binary_parts(&expr);
+    assert_eq!(operator, &BinaryOperator::And);
+}
+
+#[test]
+fn test_chained_range_is_rejected() {
+    assert!(matches!(
+        parse_source("a..b..c;"),
+        Err(ParseError {
+            kind: ParseErrorKind::ChainedRange,
+            ..
+        })
+    ));
+}
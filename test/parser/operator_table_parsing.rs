@@ -0,0 +1,362 @@
+//! 运算符表一致性测试
+//! 测试运算符表本身的完整性，并对表中每一对运算符生成表达式，
+//! 检查解析结果的结合方式（或语法错误）与表中的绑定强度和结合性一致，打印后重新解析也不变
+
+#[path = "../common/mod.rs"]
+mod common;
+
+use common::parse_source;
+use rus::operator::{Associativity, Fixity, OPERATORS, Operator, OperatorKind};
+use rus::parser::{
+    AssignmentOperator, BinaryOperator, ParseError, ParseErrorKind, Stmt, UnaryOperator,
+};
+use rus::printer::print_expr;
+use rus::sexp::dump_expr;
+
+/// 解析一条表达式语句，返回表达式的 S 表达式
+fn parse_dump(code: &str) -> Result<String, ParseError> {
+    match parse_source(code)?.as_slice() {
+        [Stmt::Expression { expression, .. }] => Ok(dump_expr(expression)),
+        statements => panic!(
+            "{}: expected one expression statement, got {:?}",
+            code, statements
+        ),
+    }
+}
+
+/// 按表中的运算符组合出的预期语法树
+enum Tree {
+    Leaf(&'static str),
+    Node(&'static Operator, Vec<Tree>),
+}
+
+impl Tree {
+    /// 与 `rus::sexp` 的输出格式相同
+    fn dump(&self) -> String {
+        let Tree::Node(operator, children) = self else {
+            let Tree::Leaf(name) = self else {
+                unreachable!()
+            };
+            return name.to_string();
+        };
+        // `a.f()` 是方法调用，不是先取字段再调用
+        if operator.kind == OperatorKind::Call
+            && let [Tree::Node(member, receiver)] = children.as_slice()
+            && member.kind == OperatorKind::Member
+        {
+            return format!("(method-call {} f)", receiver[0].dump());
+        }
+        let children: Vec<String> = children.iter().map(Tree::dump).collect();
+        let lexeme = operator.token.lexeme();
+        match (&operator.kind, operator.fixity) {
+            (OperatorKind::Range { .. }, Fixity::Prefix) => {
+                format!("({} _ {})", lexeme, children[0])
+            }
+            (OperatorKind::DoubleReference, _) => format!("(& (& {}))", children[0]),
+            (OperatorKind::Call, _) => format!("(call {})", children[0]),
+            (OperatorKind::Index, _) => format!("(index {} i)", children[0]),
+            (OperatorKind::Member, _) => format!("(field {} f)", children[0]),
+            (OperatorKind::Try, _) => format!("(? {})", children[0]),
+            _ => format!("({} {})", lexeme, children.join(" ")),
+        }
+    }
+
+    /// 可以作为赋值目标的表达式
+    fn is_place(&self) -> bool {
+        match self {
+            Tree::Leaf(_) => true,
+            Tree::Node(operator, _) => matches!(
+                operator.kind,
+                OperatorKind::Index
+                    | OperatorKind::Member
+                    | OperatorKind::Unary(UnaryOperator::Dereference)
+            ),
+        }
+    }
+
+    /// 树中是否有不合法的赋值目标
+    fn invalid_assignment(&self) -> bool {
+        match self {
+            Tree::Leaf(_) => false,
+            Tree::Node(operator, children) => {
+                (matches!(operator.kind, OperatorKind::Assign(_)) && !children[0].is_place())
+                    || children.iter().any(Tree::invalid_assignment)
+            }
+        }
+    }
+}
+
+/// 运算符在源代码中的写法，后缀运算符带上参数或字段名
+fn postfix_source(operator: &Operator) -> String {
+    match operator.kind {
+        OperatorKind::Call => "()".to_string(),
+        OperatorKind::Index => "[i]".to_string(),
+        OperatorKind::Member => ".f".to_string(),
+        _ => operator.token.lexeme(),
+    }
+}
+
+/// 运算符右侧（前缀运算符的操作数）要求的最低绑定强度，由表中的结合性决定
+fn right_binding_power(operator: &Operator) -> u8 {
+    match operator.associativity {
+        Associativity::Right => operator.binding_power,
+        Associativity::Left | Associativity::None => operator.binding_power + 1,
+    }
+}
+
+/// 预期的解析结果：语法树，或者表中的结合性规定的语法错误
+enum Expected {
+    Tree(Tree),
+    Chained,
+    InvalidAssignmentTarget,
+}
+
+/// first 之后紧接着 second 组成的表达式源代码和按表推出的预期结果，组合没有意义时返回 None
+///
+/// - 前缀：`p a`，中缀：`a o b`，后缀：`a!`（以 `!` 代表后缀写法）
+/// - second 为前缀运算符时作用于 first 的最后一个操作数
+/// - 否则 second 的绑定强度不低于 first 右侧要求的强度时，second 作用于 first 的最后一个操作数，
+///   反之作用于 first 构成的整个表达式；不可结合的运算符在后一种情况下与同一强度的运算连用是错误
+fn combine(first: &'static Operator, second: &'static Operator) -> Option<(String, Expected)> {
+    // as 的右侧是类型，不是表达式，不能带前缀或后缀运算符；类型后的 < 和 << 是泛型参数的开始
+    if first.kind == OperatorKind::Cast
+        && (second.fixity != Fixity::Infix
+            || matches!(
+                second.kind,
+                OperatorKind::Binary(BinaryOperator::Less | BinaryOperator::Shl)
+            ))
+    {
+        return None;
+    }
+    // 后缀运算符之后不能跟前缀运算符
+    if first.fixity == Fixity::Postfix && second.fixity == Fixity::Prefix {
+        return None;
+    }
+
+    let lexeme = |operator: &Operator| operator.token.lexeme();
+    let (mut source, operands) = match first.fixity {
+        Fixity::Prefix => (String::new(), vec![]),
+        Fixity::Infix => (format!("a {} ", lexeme(first)), vec![Tree::Leaf("a")]),
+        Fixity::Postfix => (format!("a{}", postfix_source(first)), vec![Tree::Leaf("a")]),
+    };
+    if first.fixity == Fixity::Prefix {
+        source.push_str(&format!("{} ", lexeme(first)));
+    }
+
+    // first 的最后一个操作数
+    let last = if first.fixity == Fixity::Prefix {
+        "a"
+    } else {
+        "b"
+    };
+    let build = |mut operands: Vec<Tree>, last_operand: Tree| {
+        if first.fixity != Fixity::Postfix {
+            operands.push(last_operand);
+        }
+        Tree::Node(first, operands)
+    };
+
+    let tree = match second.fixity {
+        Fixity::Prefix => {
+            source.push_str(&format!("{} {}", lexeme(second), last));
+            build(operands, Tree::Node(second, vec![Tree::Leaf(last)]))
+        }
+        Fixity::Infix | Fixity::Postfix => {
+            let next = if first.fixity == Fixity::Postfix {
+                "b"
+            } else {
+                "c"
+            };
+            if first.fixity != Fixity::Postfix {
+                source.push_str(last);
+            }
+            let second_operands = |left: Tree| match second.fixity {
+                Fixity::Infix => vec![left, Tree::Leaf(next)],
+                _ => vec![left],
+            };
+            match second.fixity {
+                Fixity::Infix => source.push_str(&format!(" {} {}", lexeme(second), next)),
+                _ => source.push_str(&postfix_source(second)),
+            }
+
+            let binds_to_operand = first.fixity != Fixity::Postfix
+                && second.binding_power >= right_binding_power(first);
+            if binds_to_operand {
+                build(
+                    operands,
+                    Tree::Node(second, second_operands(Tree::Leaf(last))),
+                )
+            } else {
+                if second.associativity == Associativity::None
+                    && second.binding_power == first.binding_power
+                {
+                    return Some((format!("{};", source), Expected::Chained));
+                }
+                let left = build(operands, Tree::Leaf(last));
+                Tree::Node(second, second_operands(left))
+            }
+        }
+    };
+
+    let expected = if tree.invalid_assignment() {
+        Expected::InvalidAssignmentTarget
+    } else {
+        Expected::Tree(tree)
+    };
+    Some((format!("{};", source), expected))
+}
+
+#[test]
+fn test_operator_table_is_complete() {
+    // 同一个记号在同一位置上只有一种含义
+    for (index, operator) in OPERATORS.iter().enumerate() {
+        assert!(
+            !OPERATORS[..index]
+                .iter()
+                .any(|other| other.token == operator.token && other.fixity == operator.fixity),
+            "duplicate entry for {:?} {:?}",
+            operator.fixity,
+            operator.token
+        );
+    }
+
+    let has = |kind: OperatorKind| OPERATORS.iter().any(|operator| operator.kind == kind);
+    let binary = [
+        BinaryOperator::Add,
+        BinaryOperator::Subtract,
+        BinaryOperator::Multiply,
+        BinaryOperator::Divide,
+        BinaryOperator::Modulo,
+        BinaryOperator::Equal,
+        BinaryOperator::NotEqual,
+        BinaryOperator::Less,
+        BinaryOperator::LessEqual,
+        BinaryOperator::Greater,
+        BinaryOperator::GreaterEqual,
+        BinaryOperator::And,
+        BinaryOperator::Or,
+        BinaryOperator::BitAnd,
+        BinaryOperator::BitOr,
+        BinaryOperator::BitXor,
+        BinaryOperator::Shl,
+        BinaryOperator::Shr,
+    ];
+    for operator in binary {
+        assert!(
+            has(OperatorKind::Binary(operator.clone())),
+            "{:?}",
+            operator
+        );
+    }
+    let assignment = [
+        AssignmentOperator::Assign,
+        AssignmentOperator::AddAssign,
+        AssignmentOperator::SubtractAssign,
+        AssignmentOperator::MultiplyAssign,
+        AssignmentOperator::DivideAssign,
+        AssignmentOperator::ModuloAssign,
+        AssignmentOperator::BitAndAssign,
+        AssignmentOperator::BitOrAssign,
+        AssignmentOperator::BitXorAssign,
+        AssignmentOperator::ShlAssign,
+        AssignmentOperator::ShrAssign,
+    ];
+    for operator in assignment {
+        assert!(
+            has(OperatorKind::Assign(operator.clone())),
+            "{:?}",
+            operator
+        );
+    }
+    let unary = [
+        UnaryOperator::Negate,
+        UnaryOperator::Not,
+        UnaryOperator::Dereference,
+        UnaryOperator::Reference,
+        UnaryOperator::MutableReference,
+    ];
+    for operator in unary {
+        assert!(has(OperatorKind::Unary(operator.clone())), "{:?}", operator);
+    }
+
+    // 比较运算符不可结合，其他二元运算符左结合，赋值右结合
+    for operator in OPERATORS {
+        let expected = match &operator.kind {
+            OperatorKind::Binary(binary) if binary.is_comparison() => Associativity::None,
+            OperatorKind::Range { .. } => Associativity::None,
+            OperatorKind::Assign(_) | OperatorKind::Unary(_) | OperatorKind::DoubleReference => {
+                Associativity::Right
+            }
+            _ => Associativity::Left,
+        };
+        assert_eq!(operator.associativity, expected, "{:?}", operator.token);
+    }
+}
+
+#[test]
+fn test_every_operator_pair_parses_as_the_table_says() {
+    let mut checked = 0;
+    for first in OPERATORS {
+        for second in OPERATORS {
+            let Some((source, expected)) = combine(first, second) else {
+                continue;
+            };
+            checked += 1;
+            let result = parse_dump(&source);
+            match expected {
+                Expected::Tree(tree) => {
+                    let dumped = result
+                        .unwrap_or_else(|error| panic!("{}: unexpected error: {}", source, error));
+                    assert_eq!(dumped, tree.dump(), "{}", source);
+                }
+                Expected::Chained => {
+                    let error = result.expect_err(&source);
+                    assert!(
+                        matches!(
+                            error.kind,
+                            ParseErrorKind::ChainedComparison { .. } | ParseErrorKind::ChainedRange
+                        ),
+                        "{}: {}",
+                        source,
+                        error
+                    );
+                }
+                Expected::InvalidAssignmentTarget => {
+                    let error = result.expect_err(&source);
+                    assert_eq!(
+                        error.kind,
+                        ParseErrorKind::InvalidAssignmentTarget,
+                        "{}: {}",
+                        source,
+                        error
+                    );
+                }
+            }
+        }
+    }
+    // 表中共 44 项，除去没有意义的组合后还有 1890 对
+    assert_eq!(checked, 1890);
+}
+
+#[test]
+fn test_every_operator_pair_prints_back() {
+    for first in OPERATORS {
+        for second in OPERATORS {
+            let Some((source, Expected::Tree(_))) = combine(first, second) else {
+                continue;
+            };
+            let statements = parse_source(&source).unwrap();
+            let Stmt::Expression { expression, .. } = &statements[0] else {
+                unreachable!()
+            };
+            let printed = print_expr(expression);
+            assert_eq!(
+                parse_dump(&format!("{};", printed)).unwrap(),
+                dump_expr(expression),
+                "{} printed as {}",
+                source,
+                printed
+            );
+        }
+    }
+}
@@ -0,0 +1,196 @@
+//! 解析错误测试
+//! 测试解析错误携带的位置、实际遇到的记号和期望集合，以及由这些数据生成的错误信息
+
+#[path = "../common/mod.rs"]
+mod common;
+
+use common::parse_source;
+use rus::data::{Span, Token};
+use rus::lex::Lexer;
+use rus::parser::{ContractOwner, Expected, ParseError, ParseErrorKind, Parser};
+use std::io::BufReader;
+
+fn span(line: u32, column: u32, end_column: u32) -> Span {
+    Span {
+        file: "test.rs".into(),
+        line,
+        column,
+        end_line: line,
+        end_column,
+    }
+}
+
+#[test]
+fn test_missing_token() {
+    let error = parse_source("let x = 1\nlet y = 2;").unwrap_err();
+
+    assert_eq!(error.kind, ParseErrorKind::UnexpectedToken);
+    assert_eq!(error.span, span(2, 1, 4));
+    assert_eq!(error.found, Some(Token::Let));
+    assert_eq!(error.expected, vec![Expected::Token(Token::Semicolon)]);
+    assert_eq!(error.to_string(), "test.rs:2:1: expected `;`, found `let`");
+}
+
+#[test]
+fn test_end_of_input() {
+    let error = parse_source("fn main(").unwrap_err();
+
+    assert_eq!(error.found, None);
+    // 输入末尾的错误位于最后一个记号之后
+    assert_eq!(error.span, span(1, 9, 9));
+    assert!(error.to_string().ends_with("found end of input"));
+}
+
+#[test]
+fn test_expected_syntax() {
+    let error = parse_source("fn (x) { }").unwrap_err();
+
+    assert_eq!(error.expected, vec![Expected::Syntax("function name")]);
+    assert_eq!(error.found, Some(Token::LParen));
+    assert_eq!(
+        error.to_string(),
+        "test.rs:1:4: expected function name, found `(`"
+    );
+
+    let error = parse_source("let = 1;").unwrap_err();
+    assert_eq!(error.expected, vec![Expected::Identifier]);
+    assert_eq!(
+        error.to_string(),
+        "test.rs:1:5: expected identifier, found `=`"
+    );
+}
+
+#[test]
+fn test_expected_alternatives() {
+    let error = parse_source("mod network struct").unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "test.rs:1:13: expected `{` or `;`, found `struct`"
+    );
+
+    let error = parse_source("trait T { let }").unwrap_err();
+    assert_eq!(
+        error.expected,
+        vec![
+            Expected::Token(Token::Type),
+            Expected::Token(Token::Async),
+            Expected::Token(Token::Fn),
+        ]
+    );
+    assert_eq!(
+        error.to_string(),
+        "test.rs:1:11: expected `type`, `async`, or `fn`, found `let`"
+    );
+}
+
+#[test]
+fn test_found_literal_and_identifier() {
+    let error = parse_source("struct \"name\" { }").unwrap_err();
+    assert_eq!(error.found, Some(Token::StringLiteral("name".to_string())));
+    assert_eq!(error.span, span(1, 8, 14));
+    assert!(error.to_string().ends_with("found `\"name\"`"));
+
+    let error = parse_source("impl Point { struct }").unwrap_err();
+    assert_eq!(error.found, Some(Token::Struct));
+    assert!(
+        error
+            .to_string()
+            .contains("expected `fn`, `async`, or `type`")
+    );
+}
+
+#[test]
+fn test_invalid_assignment_target() {
+    let error = parse_source("a + b += 1;").unwrap_err();
+
+    assert_eq!(error.kind, ParseErrorKind::InvalidAssignmentTarget);
+    assert_eq!(error.found, Some(Token::PlusEqual));
+    assert_eq!(error.span, span(1, 7, 9));
+    assert_eq!(
+        error.to_string(),
+        "test.rs:1:7: left-hand side of `+=` must be an assignable place"
+    );
+}
+
+#[test]
+fn test_chained_operators() {
+    let error = parse_source("a < b <= c;").unwrap_err();
+    assert!(matches!(
+        error.kind,
+        ParseErrorKind::ChainedComparison { .. }
+    ));
+    assert_eq!(error.span, span(1, 7, 9));
+    assert!(error.to_string().contains("`(a < b) && (b <= c)`"));
+
+    let error = parse_source("a..b..c;").unwrap_err();
+    assert_eq!(error.kind, ParseErrorKind::ChainedRange);
+    assert_eq!(error.span, span(1, 5, 7));
+}
+
+#[test]
+fn test_duplicate_handler_clause() {
+    let error = parse_source("handle E { return(x) { } return(y) { } }").unwrap_err();
+
+    assert_eq!(error.kind, ParseErrorKind::DuplicateClause);
+    assert_eq!(error.found, Some(Token::Return));
+    assert_eq!(error.span, span(1, 26, 32));
+    assert_eq!(
+        error.to_string(),
+        "test.rs:1:26: handler already has a `return` clause"
+    );
+}
+
+#[test]
+fn test_contract_clause_not_allowed() {
+    let error = parse_source("fn f(x) requires x > 0 invariant x > 0 { }").unwrap_err();
+
+    assert_eq!(
+        error.kind,
+        ParseErrorKind::ClauseNotAllowed(ContractOwner::Function)
+    );
+    assert_eq!(error.span, span(1, 24, 33));
+    assert_eq!(
+        error.to_string(),
+        "test.rs:1:24: `invariant` clauses are not allowed on functions"
+    );
+
+    let error = parse_source("struct S requires true { }").unwrap_err();
+    assert_eq!(
+        error.kind,
+        ParseErrorKind::ClauseNotAllowed(ContractOwner::Type)
+    );
+}
+
+#[test]
+fn test_effect_row_errors() {
+    let error = parse_source("effect_group Open = Log, ..e;").unwrap_err();
+    assert_eq!(error.kind, ParseErrorKind::RowVariableNotAllowed);
+    assert_eq!(error.found, Some(Token::Range));
+
+    let error = parse_source("fn f() effects ..a, ..b { }").unwrap_err();
+    assert_eq!(error.kind, ParseErrorKind::DuplicateRowVariable);
+    assert_eq!(error.span, span(1, 21, 23));
+}
+
+#[test]
+fn test_visibility_not_allowed() {
+    let error = parse_source("enum Shape { Circle(pub f64) }").unwrap_err();
+
+    assert_eq!(error.kind, ParseErrorKind::VisibilityNotAllowed);
+    assert_eq!(error.found, Some(Token::Pub));
+    assert_eq!(error.span, span(1, 21, 24));
+}
+
+#[test]
+fn test_recovered_errors_are_located() {
+    let reader = BufReader::new("let a = ;\nlet b = 1\nfn f() { }".as_bytes());
+    let (_, errors) = Parser::new(Lexer::new("test.rs", reader)).parse_with_recovery();
+    let messages: Vec<String> = errors.iter().map(ParseError::to_string).collect();
+    assert_eq!(
+        messages,
+        vec![
+            "test.rs:1:9: expected expression, found `;`",
+            "test.rs:3:1: expected `;`, found `fn`",
+        ]
+    );
+}
@@ -0,0 +1,192 @@
+//! 路径与use导入解析测试
+//! 测试语法分析器对 :: 路径表达式和use声明的解析能力
+
+#[path = "../common/mod.rs"]
+mod common;
+
+use common::parse_source;
+use rus::intern::Symbol;
+use rus::parser::{Expr, Stmt, UseTree};
+use rus::resolve::resolve_names;
+
+fn parse_use_tree(code: &str) -> UseTree {
+    let statements = parse_source(code).expect("Failed to parse");
+    match statements.into_iter().next() {
+        Some(Stmt::Use { declaration, .. }) => declaration.tree,
+        other => panic!("Expected use declaration, got {:?}", other),
+    }
+}
+
+fn symbols(items: &[&str]) -> Vec<Symbol> {
+    items.iter().map(|item| Symbol::intern(item)).collect()
+}
+
+#[test]
+fn test_path_expression() {
+    let statements = parse_source("std::io::Console;").expect("Failed to parse");
+
+    match &statements[0] {
+        Stmt::Expression {
+            expression: Expr::Path { segments, .. },
+            ..
+        } => assert_eq!(segments, &symbols(&["std", "io", "Console"])),
+        other => panic!("Expected path expression, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_path_call() {
+    let statements = parse_source("FileSystem::read_file(path);").expect("Failed to parse");
+
+    match &statements[0] {
+        Stmt::Expression {
+            expression: Expr::Call { function, .. },
+            ..
+        } => assert!(matches!(
+            &**function,
+            Expr::Path { segments, .. } if segments == &symbols(&["FileSystem", "read_file"])
+        )),
+        other => panic!("Expected call of a path, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_simple_use() {
+    assert_eq!(
+        parse_use_tree("use std::io::Console;"),
+        UseTree::Simple {
+            path: symbols(&["std", "io", "Console"]),
+            alias: None,
+        }
+    );
+}
+
+#[test]
+fn test_use_with_alias() {
+    assert_eq!(
+        parse_use_tree("use std::io::Console as Out;"),
+        UseTree::Simple {
+            path: symbols(&["std", "io", "Console"]),
+            alias: Some("Out".into()),
+        }
+    );
+}
+
+#[test]
+fn test_glob_use() {
+    assert_eq!(
+        parse_use_tree("use effects::io::*;"),
+        UseTree::Glob {
+            path: symbols(&["effects", "io"]),
+        }
+    );
+}
+
+#[test]
+fn test_grouped_use() {
+    let tree = parse_use_tree("use std::{io::{self, Console as Out}, fs::*, net,};");
+
+    assert_eq!(
+        tree,
+        UseTree::Group {
+            path: symbols(&["std"]),
+            items: vec![
+                UseTree::Group {
+                    path: symbols(&["io"]),
+                    items: vec![
+                        UseTree::Simple {
+                            path: symbols(&["self"]),
+                            alias: None,
+                        },
+                        UseTree::Simple {
+                            path: symbols(&["Console"]),
+                            alias: Some("Out".into()),
+                        },
+                    ],
+                },
+                UseTree::Glob {
+                    path: symbols(&["fs"]),
+                },
+                UseTree::Simple {
+                    path: symbols(&["net"]),
+                    alias: None,
+                },
+            ],
+        }
+    );
+
+    assert_eq!(
+        tree.bindings(),
+        vec![
+            ("io".into(), symbols(&["std", "io"])),
+            ("Out".into(), symbols(&["std", "io", "Console"])),
+            ("net".into(), symbols(&["std", "net"])),
+        ]
+    );
+}
+
+#[test]
+fn test_invalid_use() {
+    assert!(parse_source("use *;").is_err());
+    assert!(parse_source("use a::b as;").is_err());
+    assert!(parse_source("use a::{b, c};").is_ok());
+    assert!(parse_source("use a::{b c};").is_err());
+}
+
+#[test]
+fn test_effect_rows_accept_paths() {
+    let statements = parse_source(
+        "effect_group Io = std::io::Console, fs::FileSystem;\n\
+         fn main() effects std::io::Console { }",
+    )
+    .expect("Failed to parse");
+
+    match &statements[0] {
+        Stmt::EffectGroup { declaration, .. } => {
+            assert_eq!(
+                declaration.effects,
+                vec!["std::io::Console".to_string(), "fs::FileSystem".to_string()]
+            );
+        }
+        other => panic!("Expected effect group, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_path_calls_resolve_to_effect_operations() {
+    let mut statements = parse_source(
+        r#"
+        effect Console {
+            fn print(message: string);
+        }
+        use std::io::Console as Out;
+        Console::print("a");
+        std::io::Console::print("b");
+        Out.print("c");
+        Other::print("d");
+        "#,
+    )
+    .expect("Failed to parse");
+    resolve_names(&mut statements);
+
+    let effects: Vec<Option<String>> = statements[2..]
+        .iter()
+        .map(|statement| match statement {
+            Stmt::Expression {
+                expression: Expr::EffectOperation { effect, .. },
+                ..
+            } => Some(effect.to_string()),
+            _ => None,
+        })
+        .collect();
+
+    assert_eq!(
+        effects,
+        vec![
+            Some("Console".to_string()),
+            Some("std::io::Console".to_string()),
+            Some("std::io::Console".to_string()),
+            None,
+        ]
+    );
+}
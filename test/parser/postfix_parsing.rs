@@ -0,0 +1,183 @@
+//! 后缀表达式解析测试
+//! 测试语法分析器对字段访问、方法调用、索引、调用链、? 和 .await 的解析能力
+
+#[path = "../common/mod.rs"]
+mod common;
+
+use common::{parse_expression, parse_source};
+use rus::parser::{Expr, ParseError, ParseErrorKind, UnaryOperator};
+
+#[test]
+fn test_field_access() {
+    match parse_expression("a.b;") {
+        Expr::Field { object, field, .. } => {
+            assert_eq!(field, "b");
+            assert!(matches!(*object, Expr::Identifier { name, .. } if name == "a"));
+        }
+        other => panic!("Expected field access, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_tuple_field_access() {
+    match parse_expression("pair.0;") {
+        Expr::Field { field, .. } => assert_eq!(field, "0"),
+        other => panic!("Expected field access, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_nested_tuple_field_access() {
+    // t.0.1 的 0.1 被词法分析为浮点数，应拆成两次字段访问 Field(Field(t, 0), 1)
+    match parse_expression("t.0.1;") {
+        Expr::Field { object, field, .. } => {
+            assert_eq!(field, "1");
+            assert!(matches!(*object, Expr::Field { field, .. } if field == "0"));
+        }
+        other => panic!("Expected field access, got {:?}", other),
+    }
+
+    match parse_expression("t.0.1.2;") {
+        Expr::Field { object, field, .. } => {
+            assert_eq!(field, "2");
+            assert!(matches!(*object, Expr::Field { field, .. } if field == "1"));
+        }
+        other => panic!("Expected field access, got {:?}", other),
+    }
+
+    // 调用嵌套元组字段中的函数
+    assert!(matches!(parse_expression("t.0.1();"), Expr::Call { .. }));
+}
+
+#[test]
+fn test_method_call_chain() {
+    // a.b.c(1, 2) 应解析为 MethodCall(Field(a, b), c, [1, 2])
+    match parse_expression("a.b.c(1, 2);") {
+        Expr::MethodCall {
+            receiver,
+            method,
+            arguments,
+            ..
+        } => {
+            assert_eq!(method, "c");
+            assert_eq!(arguments.len(), 2);
+            assert!(matches!(*receiver, Expr::Field { field, .. } if field == "b"));
+        }
+        other => panic!("Expected method call, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_index_expression() {
+    match parse_expression("xs[i + 1];") {
+        Expr::Index { object, index, .. } => {
+            assert!(matches!(*object, Expr::Identifier { name, .. } if name == "xs"));
+            assert!(matches!(*index, Expr::Binary { .. }));
+        }
+        other => panic!("Expected index expression, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_curried_call() {
+    // f(x)(y) 应解析为 Call(Call(f, [x]), [y])
+    match parse_expression("f(x)(y);") {
+        Expr::Call {
+            function,
+            arguments,
+            ..
+        } => {
+            assert_eq!(arguments.len(), 1);
+            match *function {
+                Expr::Call {
+                    function,
+                    arguments,
+                    ..
+                } => {
+                    assert_eq!(arguments.len(), 1);
+                    assert!(matches!(*function, Expr::Identifier { name, .. } if name == "f"));
+                }
+                other => panic!("Expected inner call, got {:?}", other),
+            }
+        }
+        other => panic!("Expected call expression, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_call_with_trailing_comma() {
+    match parse_expression("f(a, b,);") {
+        Expr::Call { arguments, .. } => assert_eq!(arguments.len(), 2),
+        other => panic!("Expected call expression, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_try_and_await() {
+    // fetch(url).await?.body 应解析为 Field(Try(Await(Call(fetch))), body)
+    match parse_expression("fetch(url).await?.body;") {
+        Expr::Field { object, field, .. } => {
+            assert_eq!(field, "body");
+            match *object {
+                Expr::Try { expression, .. } => {
+                    assert!(matches!(*expression, Expr::Await { .. }));
+                }
+                other => panic!("Expected try expression, got {:?}", other),
+            }
+        }
+        other => panic!("Expected field access, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_postfix_binds_tighter_than_prefix() {
+    // -a.b() 应解析为 -(a.b())，*r? 应解析为 *(r?)
+    match parse_expression("-a.b();") {
+        Expr::Unary {
+            operator: UnaryOperator::Negate,
+            operand,
+            ..
+        } => assert!(matches!(*operand, Expr::MethodCall { .. })),
+        other => panic!("Expected negation, got {:?}", other),
+    }
+
+    match parse_expression("*r?;") {
+        Expr::Unary {
+            operator: UnaryOperator::Dereference,
+            operand,
+            ..
+        } => assert!(matches!(*operand, Expr::Try { .. })),
+        other => panic!("Expected dereference, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_assignment_to_field_and_index() {
+    assert!(matches!(parse_expression("a.b = 1;"), Expr::Assign { .. }));
+    assert!(matches!(
+        parse_expression("xs[0] += 1;"),
+        Expr::Assign { .. }
+    ));
+}
+
+#[test]
+fn test_assignment_to_call_is_rejected() {
+    assert!(matches!(
+        parse_source("f() = 1;"),
+        Err(ParseError {
+            kind: ParseErrorKind::InvalidAssignmentTarget,
+            ..
+        })
+    ));
+}
+
+#[test]
+fn test_missing_member_name() {
+    assert!(matches!(
+        parse_source("a.;"),
+        Err(ParseError {
+            kind: ParseErrorKind::UnexpectedToken,
+            ..
+        })
+    ));
+}
@@ -0,0 +1,807 @@
+//! 语法树打印测试
+//! 测试打印出的源代码重新解析后得到相同的语法树、括号只在需要时添加，
+//! 并用随机生成的程序检查打印和解析的往返
+
+#[path = "../common/mod.rs"]
+mod common;
+
+use common::parse_valid;
+use rus::ast::NodeId;
+use rus::data::Span;
+use rus::intern::Symbol;
+use rus::parser::{
+    AssignmentOperator, BinaryOperator, ContractClause, EffectRow, Expr, HandlerClause,
+    HandlerDeclaration, HandlerReference, Literal, Parameter, ReturnClause, Stmt, UnaryOperator,
+    Visibility,
+};
+use rus::printer::{print_expr, print_program, print_stmt};
+use rus::resolve::resolve_names;
+use rus::visit::{self, VisitorMut};
+
+/// 把各种节点的编号和位置重置，使只有位置不同的语法树相等
+macro_rules! reset_location {
+    ($node:expr, $($variant:path),+ $(,)?) => {
+        match $node {
+            $($variant { id, span, .. })|+ => {
+                *id = NodeId::DUMMY;
+                *span = no_span();
+            }
+        }
+    };
+}
+
+/// 生成的和重置后的节点使用的空区间
+fn no_span() -> Span {
+    Span::empty("test.rs", 0, 0)
+}
+
+struct Normalize;
+
+impl VisitorMut for Normalize {
+    fn visit_stmt_mut(&mut self, stmt: &mut Stmt) {
+        reset_location!(
+            stmt,
+            Stmt::Expression,
+            Stmt::Let,
+            Stmt::Var,
+            Stmt::Function,
+            Stmt::Block,
+            Stmt::Effect,
+            Stmt::Handler,
+            Stmt::EffectGroup,
+            Stmt::HandlerGroup,
+            Stmt::Use,
+            Stmt::Struct,
+            Stmt::Enum,
+            Stmt::TypeAlias,
+            Stmt::Trait,
+            Stmt::Impl,
+            Stmt::Contract,
+            Stmt::Module,
+            Stmt::Error,
+        );
+        visit::walk_stmt_mut(self, stmt);
+    }
+
+    fn visit_expr_mut(&mut self, expr: &mut Expr) {
+        reset_location!(
+            expr,
+            Expr::Literal,
+            Expr::Identifier,
+            Expr::Path,
+            Expr::Binary,
+            Expr::Assign,
+            Expr::Unary,
+            Expr::Cast,
+            Expr::Range,
+            Expr::Call,
+            Expr::Grouping,
+            Expr::Tuple,
+            Expr::Array,
+            Expr::ArrayRepeat,
+            Expr::Async,
+            Expr::TryBlock,
+            Expr::Block,
+            Expr::Field,
+            Expr::MethodCall,
+            Expr::Index,
+            Expr::Try,
+            Expr::Await,
+            Expr::StructLiteral,
+            Expr::EnumConstructor,
+            Expr::With,
+            Expr::Resume,
+            Expr::Old,
+            Expr::Result,
+            Expr::Closure,
+            Expr::EffectOperation,
+        );
+        visit::walk_expr_mut(self, expr);
+    }
+
+    fn visit_handler_clause_mut(&mut self, clause: &mut HandlerClause) {
+        clause.span = no_span();
+        visit::walk_handler_clause_mut(self, clause);
+    }
+}
+
+/// 去掉所有括号节点
+struct StripGroupings;
+
+impl VisitorMut for StripGroupings {
+    fn visit_expr_mut(&mut self, expr: &mut Expr) {
+        while let Expr::Grouping { expression, .. } = expr {
+            *expr = std::mem::replace(
+                &mut **expression,
+                Expr::Result {
+                    id: NodeId::DUMMY,
+                    span: no_span(),
+                },
+            );
+        }
+        visit::walk_expr_mut(self, expr);
+    }
+}
+
+fn normalized(mut statements: Vec<Stmt>) -> Vec<Stmt> {
+    Normalize.visit_block_mut(&mut statements);
+    statements
+}
+
+/// 打印后重新解析，检查得到相同的语法树，并且再次打印的结果不变
+fn assert_round_trip(source: &str) {
+    let statements = parse_valid(source);
+    let printed = print_program(&statements);
+    let reparsed = parse_valid(&printed);
+
+    assert_eq!(
+        normalized(reparsed.clone()),
+        normalized(statements),
+        "printed:\n{}",
+        printed
+    );
+    assert_eq!(print_program(&reparsed), printed);
+}
+
+/// 解析 `let r = <expr>;`，去掉括号节点后重新打印表达式
+fn reprint_without_groupings(expr: &str) -> String {
+    let mut statements = parse_valid(&format!("let r = {};", expr));
+    StripGroupings.visit_block_mut(&mut statements);
+    match &statements[0] {
+        Stmt::Let {
+            initializer: Some(initializer),
+            ..
+        } => print_expr(initializer),
+        other => panic!("Expected let, got {:?}", other),
+    }
+}
+
+const DECLARATIONS: &str = r#"
+use std::io::{self, Read as R, prelude::*};
+pub use {a, b::c};
+pub(crate) mod shapes {
+    pub struct Point<T: Clone + Debug> {
+        pub x: T,
+        pub(crate) y: T,
+    }
+    struct Meters(pub f64) invariant true;
+    struct Unit;
+}
+mod external;
+enum Shape<T> { Empty, Circle(i32), Rect { w: T, h: T } }
+type Pair<A, B> = (A, [B; 4]);
+type Callback = fn(&mut Vec<Vec<i32>>) -> bool;
+trait Describe<T>: Clone + Into<String> where T: Debug invariant count >= 0 {
+    type Output: Clone = i32;
+    type Item;
+    fn describe(&self) -> String;
+    async fn fetch(&mut self, key: T) -> Option<T> effects Net { key }
+}
+impl<T> Describe<T> for Vec<T> where T: Debug, Vec<T>: Clone {
+    pub type Output = i32;
+    pub(crate) fn describe(&self) -> String { "vec" }
+}
+contract NonNegative(value) { requires value >= 0; ensures result == old(value); }
+contract Empty {}
+effect Log { fn log(message: String); fn level() -> u8; }
+effect State<S> { fn get() -> S; fn put(value: S); }
+handle Log as Console {
+    log(message: String, k) { print(message); resume!(()) }
+    return(x) { x }
+    finally { flush() }
+}
+handle State<i32> {}
+effect_group Safe = IO, Log - Network - Disk;
+handler_group Defaults = Console, io::Logger;
+pub async fn run<T: Clone>(x: T, y: &str) -> i32 effects Safe, ..e - Net
+where
+    T: Debug
+requires x > 0 && y != ""
+ensures result >= old(x)
+contract NonNegative
+{
+    let a = 1;
+    var b;
+    b = a + 2;
+    { b }
+}
+"#;
+
+#[test]
+fn test_round_trip_declarations() {
+    assert_round_trip(DECLARATIONS);
+}
+
+const EXPRESSIONS: &str = r#"
+fn main() {
+    let literals = ("text with \"quotes\"\n\t\\", '\'', '\n', 1.5, 42, 7u8, 2.5e3, true);
+    let arithmetic = -a * (b + c) - d / e % f;
+    let logic = !done && (x < y || y >= z) && (a == b) != c;
+    let bits = a & b | c ^ d << 2 >> 1;
+    let casts = x as i64 as f64 + (y as i32) * 2;
+    let compared = (x as i32) < y;
+    let ranges = (0..10, ..=end, start.., .., a..b + 1);
+    let borrowed = (&x, &mut y, &&z, *ptr, &mut *ptr);
+    a = b = c;
+    total += values[index].amount;
+    *ptr -= 1;
+    let postfix = list.iter().map(|x| x * 2).collect()?.len();
+    let tuple = (single,).0 + pair.1;
+    let nested_tuple = t.0.1 + p1.0 + v2.0.x;
+    let arrays = ([1, 2, 3], [0; N], []);
+    let point = Point { x, y: 2, ..origin };
+    let path = std::io::stdin().read_line(buffer);
+    let shape = shapes::Shape::Rect { w: 1, h: 2 };
+    let closures = (move |a, b: i32| a + b, || 1, |x| -> i32 { x }, |x| effects Log, ..e { x });
+    let applied = (|x| x)(1) + |y| y;
+    let blocks = { let inner = 1; inner } + async { ready.await } .await;
+    let handled = with Console, handle Log { log(message) { resume!() } } {
+        Log.log("hi");
+        try { risky()? }
+    };
+    async { work().await };
+    try { risky()? };
+    with Defaults {
+        run()
+    }
+    (1 + 2)
+}
+"#;
+
+#[test]
+fn test_round_trip_expressions() {
+    assert_round_trip(EXPRESSIONS);
+}
+
+#[test]
+fn test_printer_layout() {
+    let statements = parse_valid(
+        "fn add(a: i32, b: i32) -> i32 requires a > 0 { let sum = a+b; sum }
+         effect Log { fn log(message: String); }
+         struct Point { x: i32, y: i32 }",
+    );
+
+    assert_eq!(
+        print_program(&statements),
+        "fn add(a: i32, b: i32) -> i32 requires a > 0 {
+    let sum = a + b;
+    sum
+}
+effect Log {
+    fn log(message: String);
+}
+struct Point {
+    x: i32,
+    y: i32,
+}
+"
+    );
+}
+
+#[test]
+fn test_groupings_are_kept() {
+    let statements = parse_valid("let a = ((1)) + (b);");
+    assert_eq!(print_stmt(&statements[0]), "let a = ((1)) + (b);");
+}
+
+#[test]
+fn test_parentheses_only_where_required() {
+    for (source, expected) in [
+        ("(a + b) * c", "(a + b) * c"),
+        ("a + (b * c)", "a + b * c"),
+        ("(a - b) - c", "a - b - c"),
+        ("a - (b - c)", "a - (b - c)"),
+        ("a = (b = c)", "a = b = c"),
+        ("(a == b) == c", "(a == b) == c"),
+        ("a == (b < c)", "a == (b < c)"),
+        ("(a && b) || c", "a && b || c"),
+        ("a && (b || c)", "a && (b || c)"),
+        ("(-a).b", "(-a).b"),
+        ("-(a.b)", "-a.b"),
+        ("-(a * b)", "-(a * b)"),
+        ("(-a) as i64", "-a as i64"),
+        ("-(a as i64)", "-(a as i64)"),
+        ("(a as i32) as i64", "a as i32 as i64"),
+        ("(x as i32) < y", "(x as i32) < y"),
+        ("(x as i32) > y", "x as i32 > y"),
+        ("(a + b as i32) < c", "(a + b as i32) < c"),
+        ("(a..b).len()", "(a..b).len()"),
+        ("(a..b) == c", "(a..b) == c"),
+        ("(a + b)..(c * d)", "a + b..c * d"),
+        ("(a..b)..c", "(a..b)..c"),
+        ("(..a) == b", "(..a) == b"),
+        ("(a..) == b", "(a..) == b"),
+        ("f((a..), (..))", "f(a.., ..)"),
+        ("(|x| x)(1)", "(|x| x)(1)"),
+        ("(a.f)(x)", "(a.f)(x)"),
+        ("(t.0)(x)", "t.0(x)"),
+        ("(|x| x) + 1", "(|x| x) + 1"),
+        ("1 + (|x| x)", "1 + |x| x"),
+        ("-(|x| x) * 2", "-(|x| x) * 2"),
+        ("(|x| -> i32 { x }) + 1", "|x| -> i32 {\n    x\n} + 1"),
+        ("(a = b) + c", "(a = b) + c"),
+        ("({ a }).b", "{\n    a\n}.b"),
+        ("(async { a }).await", "async {\n    a\n}.await"),
+        ("(Point { x: 1 }).x", "Point { x: 1 }.x"),
+    ] {
+        assert_eq!(reprint_without_groupings(source), expected, "{}", source);
+    }
+}
+
+#[test]
+fn test_parentheses_for_statement_and_clause_positions() {
+    // 语句开头的代码块后面不能再跟运算符，契约子句中不能直接写结构体字面量
+    let mut statements = parse_valid(
+        "fn f() requires (Point { x: 1 }) == p ensures (a..) == b {
+            ({ a }).b();
+            (async { a }).await;
+            (with H { a }) + 1;
+        }",
+    );
+    StripGroupings.visit_block_mut(&mut statements);
+    let printed = print_program(&statements);
+
+    assert_eq!(
+        printed,
+        "fn f() requires (Point { x: 1 }) == p ensures (a..) == b {
+    ({
+        a
+    }.b());
+    (async {
+        a
+    }.await);
+    (with H {
+        a
+    } + 1);
+}
+"
+    );
+    let mut reparsed = parse_valid(&printed);
+    StripGroupings.visit_block_mut(&mut reparsed);
+    assert_eq!(normalized(reparsed), normalized(statements));
+}
+
+#[test]
+fn test_literal_spelling() {
+    for source in [
+        "\"a\\\"b\\\\c\\n\"",
+        "'\\''",
+        "'\"'",
+        "'\\0'",
+        "1u32",
+        "1.5f64",
+        "0xFF",
+        "t.0.1",
+        "p1.0",
+        "v2.0.x",
+        "1 .0",
+        "1.max(2)",
+    ] {
+        assert_eq!(reprint_without_groupings(source), source);
+    }
+}
+
+#[test]
+fn test_printing_resolved_nodes() {
+    let mut statements = parse_valid(
+        "enum Shape { Circle(i32), Rect { w: i32 } }
+         effect Log { fn log(message: String); }
+         handle Log { log(message, k) { k(()) } }
+         let a = Shape::Circle(1);
+         let b = Shape::Rect { w: 2 };
+         let c = ready.await;
+         Log.log(\"hi\");",
+    );
+    assert!(resolve_names(&mut statements).is_empty());
+
+    let printed: Vec<String> = statements[2..].iter().map(print_stmt).collect();
+    assert_eq!(
+        printed,
+        vec![
+            "handle Log {\n    log(message, k) {\n        k(())\n    }\n}",
+            "let a = Shape::Circle(1);",
+            "let b = Shape::Rect { w: 2 };",
+            "let c = ready.await;",
+            "Log.log(\"hi\");",
+        ]
+    );
+}
+
+/// 生成随机程序的伪随机数发生器（xorshift），固定种子使测试可以重现
+struct Generator {
+    state: u64,
+}
+
+const IDENTIFIERS: &[&str] = &["a", "b", "x", "y", "count", "total"];
+const TYPES: &[&str] = &["i32", "u8", "&str", "Vec<i32>", "(i32, bool)"];
+
+const BINARY_OPERATORS: &[BinaryOperator] = &[
+    BinaryOperator::Add,
+    BinaryOperator::Subtract,
+    BinaryOperator::Multiply,
+    BinaryOperator::Divide,
+    BinaryOperator::Modulo,
+    BinaryOperator::Equal,
+    BinaryOperator::NotEqual,
+    BinaryOperator::Less,
+    BinaryOperator::LessEqual,
+    BinaryOperator::Greater,
+    BinaryOperator::GreaterEqual,
+    BinaryOperator::And,
+    BinaryOperator::Or,
+    BinaryOperator::BitAnd,
+    BinaryOperator::BitOr,
+    BinaryOperator::BitXor,
+    BinaryOperator::Shl,
+    BinaryOperator::Shr,
+];
+
+const ASSIGNMENT_OPERATORS: &[AssignmentOperator] = &[
+    AssignmentOperator::Assign,
+    AssignmentOperator::AddAssign,
+    AssignmentOperator::ShlAssign,
+    AssignmentOperator::BitXorAssign,
+];
+
+const UNARY_OPERATORS: &[UnaryOperator] = &[
+    UnaryOperator::Negate,
+    UnaryOperator::Not,
+    UnaryOperator::Dereference,
+    UnaryOperator::Reference,
+    UnaryOperator::MutableReference,
+];
+
+/// 生成的节点没有位置，编号尚未分配
+macro_rules! node {
+    ($kind:ident :: $variant:ident { $($field:ident $(: $value:expr)?),* $(,)? }) => {
+        $kind::$variant {
+            id: NodeId::DUMMY,
+            span: no_span(),
+            $($field $(: $value)?),*
+        }
+    };
+}
+
+impl Generator {
+    fn next(&mut self) -> u64 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        self.state
+    }
+
+    fn below(&mut self, bound: usize) -> usize {
+        (self.next() % bound as u64) as usize
+    }
+
+    fn chance(&mut self, percent: usize) -> bool {
+        self.below(100) < percent
+    }
+
+    fn pick<T: Clone>(&mut self, items: &[T]) -> T {
+        items[self.below(items.len())].clone()
+    }
+
+    fn name(&mut self) -> Symbol {
+        Symbol::intern(self.pick(IDENTIFIERS))
+    }
+
+    fn boxed(&mut self, depth: usize, postcondition: bool) -> Box<Expr> {
+        Box::new(self.expr(depth, postcondition))
+    }
+
+    fn exprs(&mut self, depth: usize, postcondition: bool) -> Vec<Expr> {
+        (0..self.below(3))
+            .map(|_| self.expr(depth, postcondition))
+            .collect()
+    }
+
+    fn leaf(&mut self, postcondition: bool) -> Expr {
+        let value = match self.below(if postcondition { 10 } else { 8 }) {
+            0 => Literal::Integer(self.pick(&["0", "42", "7_u8", "0xFF"]).to_string()),
+            1 => Literal::Float(self.pick(&["1.5", "2.5e3", "0.5_f32"]).to_string()),
+            2 => Literal::String(self.pick(&["", "hi", "a\"b\n\\"]).to_string()),
+            3 => Literal::Char(self.pick(&['c', '\'', '\n', '"'])),
+            4 => Literal::Boolean(self.chance(50)),
+            5 => {
+                let segments = vec![self.name(), Symbol::intern("inner"), self.name()];
+                return node!(Expr::Path { segments });
+            }
+            8 => return node!(Expr::Result {}),
+            _ => return node!(Expr::Identifier { name: self.name() }),
+        };
+        node!(Expr::Literal { value })
+    }
+
+    /// 可以作为赋值目标的表达式
+    fn place(&mut self, depth: usize, postcondition: bool) -> Expr {
+        match self.below(4) {
+            0 => node!(Expr::Field {
+                object: self.boxed(depth, postcondition),
+                field: self.pick(&["value", "0"]).into(),
+            }),
+            1 => node!(Expr::Index {
+                object: self.boxed(depth, postcondition),
+                index: self.boxed(depth, postcondition),
+            }),
+            2 => node!(Expr::Unary {
+                operator: UnaryOperator::Dereference,
+                operand: self.boxed(depth, postcondition),
+            }),
+            _ => node!(Expr::Identifier { name: self.name() }),
+        }
+    }
+
+    fn expr(&mut self, depth: usize, postcondition: bool) -> Expr {
+        if depth == 0 || self.chance(15) {
+            return self.leaf(postcondition);
+        }
+
+        let depth = depth - 1;
+        let p = postcondition;
+        match self.below(28) {
+            0..=4 => node!(Expr::Binary {
+                left: self.boxed(depth, p),
+                operator: self.pick(BINARY_OPERATORS),
+                right: self.boxed(depth, p),
+            }),
+            5 => node!(Expr::Assign {
+                target: Box::new(self.place(depth, p)),
+                operator: self.pick(ASSIGNMENT_OPERATORS),
+                value: self.boxed(depth, p),
+            }),
+            6 | 7 => node!(Expr::Unary {
+                operator: self.pick(UNARY_OPERATORS),
+                operand: self.boxed(depth, p),
+            }),
+            8 => node!(Expr::Cast {
+                expression: self.boxed(depth, p),
+                target_type: self.pick(TYPES).to_string(),
+            }),
+            9 => {
+                let start = self.chance(70).then(|| self.boxed(depth, p));
+                let end = self.chance(70).then(|| self.boxed(depth, p));
+                let inclusive = end.is_some() && self.chance(30);
+                node!(Expr::Range {
+                    start,
+                    end,
+                    inclusive
+                })
+            }
+            10 => node!(Expr::Call {
+                function: self.boxed(depth, p),
+                arguments: self.exprs(depth, p),
+            }),
+            11 => node!(Expr::Tuple {
+                elements: self.exprs(depth, p),
+            }),
+            12 => node!(Expr::Array {
+                elements: self.exprs(depth, p),
+            }),
+            13 => node!(Expr::ArrayRepeat {
+                value: self.boxed(depth, p),
+                count: self.boxed(depth, p),
+            }),
+            14 => node!(Expr::Async {
+                statements: self.block(depth, p),
+                effects: Vec::new(),
+            }),
+            15 => node!(Expr::TryBlock {
+                statements: self.block(depth, p),
+                discharged_effects: Vec::new(),
+            }),
+            16 => node!(Expr::Block {
+                statements: self.block(depth, p),
+            }),
+            17 => node!(Expr::Field {
+                object: self.boxed(depth, p),
+                field: self.pick(&["value", "len", "0", "1"]).into(),
+            }),
+            18 => node!(Expr::MethodCall {
+                receiver: self.boxed(depth, p),
+                method: self.pick(&["map", "get"]).into(),
+                arguments: self.exprs(depth, p),
+            }),
+            19 => node!(Expr::Index {
+                object: self.boxed(depth, p),
+                index: self.boxed(depth, p),
+            }),
+            20 => node!(Expr::Try {
+                expression: self.boxed(depth, p),
+            }),
+            21 => node!(Expr::Await {
+                expression: self.boxed(depth, p),
+            }),
+            22 => {
+                let path = if self.chance(50) {
+                    vec![Symbol::intern("Point")]
+                } else {
+                    vec![Symbol::intern("shapes"), Symbol::intern("Point")]
+                };
+                let fields = (0..self.below(3))
+                    .map(|_| (self.name(), self.expr(depth, p)))
+                    .collect();
+                let base = self.chance(30).then(|| self.boxed(depth, p));
+                node!(Expr::StructLiteral { path, fields, base })
+            }
+            23 => self.with(depth, p),
+            24 => node!(Expr::Resume {
+                value: self.chance(70).then(|| self.boxed(depth, p)),
+            }),
+            25 if p => node!(Expr::Old {
+                expression: self.boxed(depth, p),
+            }),
+            _ => self.closure(depth, p),
+        }
+    }
+
+    fn closure(&mut self, depth: usize, postcondition: bool) -> Expr {
+        let parameters = (0..self.below(3))
+            .map(|_| Parameter {
+                name: self.name(),
+                type_annotation: self.chance(30).then(|| self.pick(TYPES).to_string()),
+            })
+            .collect();
+        let return_type = self.chance(30).then(|| "i32".to_string());
+        let mut effects = EffectRow::default();
+        if self.chance(20) {
+            effects.effects.push("Log".into());
+            if self.chance(50) {
+                effects.excluded.push("Net".into());
+            }
+            if self.chance(50) {
+                effects.row_variable = Some("e".into());
+            }
+        }
+        // 有标注的闭包体必须是代码块
+        let annotated = return_type.is_some() || !effects.effects.is_empty();
+        let body = if annotated {
+            node!(Expr::Block {
+                statements: self.block(depth, postcondition),
+            })
+        } else {
+            self.expr(depth, postcondition)
+        };
+        node!(Expr::Closure {
+            is_move: self.chance(20),
+            parameters,
+            return_type,
+            effects,
+            body: Box::new(body),
+            captures: Vec::new(),
+        })
+    }
+
+    fn with(&mut self, depth: usize, postcondition: bool) -> Expr {
+        let mut handlers = Vec::new();
+        let mut discharged_effects = Vec::new();
+        for _ in 0..=self.below(2) {
+            if self.chance(50) {
+                handlers.push(HandlerReference::Named(
+                    self.pick(&["Console", "io::Console"]).into(),
+                ));
+                continue;
+            }
+
+            let clauses = (0..self.below(3))
+                .map(|_| HandlerClause {
+                    span: no_span(),
+                    operation: self.pick(&["log", "await"]).into(),
+                    parameters: vec![Parameter {
+                        name: self.name(),
+                        type_annotation: None,
+                    }],
+                    continuation: None,
+                    body: self.block(depth, postcondition),
+                })
+                .collect();
+            let return_clause = self.chance(30).then(|| ReturnClause {
+                parameter: Parameter {
+                    name: self.name(),
+                    type_annotation: None,
+                },
+                body: self.block(depth, postcondition),
+            });
+            let finally_clause = self.chance(30).then(|| self.block(depth, postcondition));
+            discharged_effects.push("Log".into());
+            handlers.push(HandlerReference::Inline(HandlerDeclaration {
+                name: None,
+                effect: "Log".into(),
+                clauses,
+                return_clause,
+                finally_clause,
+            }));
+        }
+        node!(Expr::With {
+            handlers,
+            body: self.block(depth, postcondition),
+            discharged_effects,
+        })
+    }
+
+    fn stmt(&mut self, depth: usize, postcondition: bool) -> Stmt {
+        match self.below(6) {
+            0 => node!(Stmt::Let {
+                identifier: self.name(),
+                initializer: self.chance(80).then(|| self.expr(depth, postcondition)),
+            }),
+            1 => node!(Stmt::Var {
+                identifier: self.name(),
+                initializer: self.chance(80).then(|| self.expr(depth, postcondition)),
+            }),
+            2 if depth > 0 => node!(Stmt::Block {
+                statements: self.block(depth - 1, postcondition),
+            }),
+            _ => node!(Stmt::Expression {
+                expression: self.expr(depth, postcondition),
+                has_semicolon: true,
+            }),
+        }
+    }
+
+    /// 代码块，最后一条表达式语句可能没有分号
+    fn block(&mut self, depth: usize, postcondition: bool) -> Vec<Stmt> {
+        let mut statements: Vec<Stmt> = (0..self.below(3))
+            .map(|_| self.stmt(depth, postcondition))
+            .collect();
+        if let Some(Stmt::Expression { has_semicolon, .. }) = statements.last_mut() {
+            *has_semicolon = self.chance(50);
+        }
+        statements
+    }
+
+    /// 带契约子句的函数，契约子句中不能直接出现结构体字面量
+    fn function(&mut self, depth: usize) -> Stmt {
+        let mut contracts = Vec::new();
+        for _ in 0..self.below(3) {
+            contracts.push(if self.chance(50) {
+                ContractClause::Requires(self.expr(depth, false))
+            } else {
+                ContractClause::Ensures(self.expr(depth, true))
+            });
+        }
+        node!(Stmt::Function {
+            visibility: Visibility::Private,
+            is_async: self.chance(20),
+            name: Symbol::intern("generated"),
+            generics: Vec::new(),
+            parameters: Vec::new(),
+            return_type: None,
+            effects: EffectRow::default(),
+            where_clause: Vec::new(),
+            contracts,
+            body: self.block(depth, false),
+        })
+    }
+}
+
+#[test]
+fn test_generated_programs_round_trip() {
+    let mut generator = Generator {
+        state: 0x2545_f491_4f6c_dd1d,
+    };
+
+    for _ in 0..500 {
+        let program: Vec<Stmt> = (0..3)
+            .map(|_| {
+                if generator.chance(30) {
+                    generator.function(4)
+                } else {
+                    generator.stmt(4, false)
+                }
+            })
+            .collect();
+
+        // 生成的语法树没有括号节点，打印时补上的括号在比较前去掉
+        let printed = print_program(&program);
+        let mut reparsed = parse_valid(&printed);
+        StripGroupings.visit_block_mut(&mut reparsed);
+
+        assert_eq!(
+            normalized(reparsed),
+            normalized(program),
+            "printed:\n{}",
+            printed
+        );
+    }
+}
@@ -0,0 +1,149 @@
+//! 流式语法分析测试
+//! 测试语法分析器直接从词法分析器按需读取记号，以及词法错误与语法错误一起报告
+
+#[path = "../common/mod.rs"]
+mod common;
+
+use common::parse_with_recovery;
+use rus::data::{LexicalError, Locatable, Location, Span, Token};
+use rus::lex::Lexer;
+use rus::parser::{ParseErrorKind, Parser, Stmt};
+use std::cell::Cell;
+use std::io::BufReader;
+use std::rc::Rc;
+
+/// 生成 count 条 `let x = 1;` 的记号流，pulled 记录已经读取的记号个数
+fn let_statements(
+    count: usize,
+    pulled: Rc<Cell<usize>>,
+) -> impl Iterator<Item = Locatable<'static, Result<Token, LexicalError>>> {
+    (0..count).flat_map(move |line| {
+        let pulled = pulled.clone();
+        [
+            Token::Let,
+            Token::Identifier("x".to_string()),
+            Token::Equal,
+            Token::IntegerLiteral("1".to_string()),
+            Token::Semicolon,
+        ]
+        .into_iter()
+        .enumerate()
+        .map(move |(column, token)| {
+            pulled.set(pulled.get() + 1);
+            Locatable {
+                location: Location {
+                    line: line + 1,
+                    column: column + 1,
+                    file: "generated.rs",
+                },
+                data: Ok(token),
+            }
+        })
+    })
+}
+
+#[test]
+fn test_parse_directly_from_lexer() {
+    let (statements, errors) = parse_with_recovery("let x = 1; fn f() { x + 1 }");
+
+    assert!(errors.is_empty());
+    assert_eq!(statements.len(), 2);
+}
+
+#[test]
+fn test_token_vector_still_accepted() {
+    let location = Location {
+        line: 1,
+        column: 1,
+        file: "test.rs",
+    };
+    let tokens = vec![
+        Locatable {
+            location,
+            data: Token::IntegerLiteral("1".to_string()),
+        },
+        Locatable {
+            location: Location {
+                column: 2,
+                ..location
+            },
+            data: Token::Semicolon,
+        },
+    ];
+
+    assert_eq!(Parser::new(tokens).parse().unwrap().len(), 1);
+}
+
+#[test]
+fn test_bounded_lookahead() {
+    let pulled = Rc::new(Cell::new(0));
+    let mut parser = Parser::new(let_statements(10_000, pulled.clone()));
+
+    // 创建时只读取向前看需要的记号
+    assert_eq!(pulled.get(), 2);
+
+    let statements = parser.parse().unwrap();
+    assert_eq!(statements.len(), 10_000);
+    assert_eq!(pulled.get(), 50_000);
+}
+
+#[test]
+fn test_lexical_error_reported() {
+    let error = Parser::new(Lexer::new(
+        "test.rs",
+        BufReader::new("let x = 1 ` 2;".as_bytes()),
+    ))
+    .parse()
+    .unwrap_err();
+
+    assert_eq!(
+        error.kind,
+        ParseErrorKind::Lexical(LexicalError::UnknownCharacter('`'))
+    );
+    assert_eq!(error.span, Span::empty("test.rs", 1, 11));
+    assert_eq!(error.found, None);
+    assert_eq!(error.to_string(), "test.rs:1:11: Unknown character: '`'");
+}
+
+#[test]
+fn test_lexical_errors_merged_in_source_order() {
+    // 词法错误在读入向前看的记号时就已发现，仍按位置排在前面的语法错误之后
+    let (statements, errors) = parse_with_recovery("let a = ;\nlet ` b = 2;\nlet c = ;\n$ `");
+
+    let kinds: Vec<&ParseErrorKind> = errors.iter().map(|error| &error.kind).collect();
+    assert_eq!(
+        kinds,
+        vec![
+            &ParseErrorKind::UnexpectedToken,
+            &ParseErrorKind::Lexical(LexicalError::UnknownCharacter('`')),
+            &ParseErrorKind::UnexpectedToken,
+            &ParseErrorKind::UnexpectedToken,
+            &ParseErrorKind::Lexical(LexicalError::UnknownCharacter('`')),
+        ]
+    );
+    let lines: Vec<u32> = errors.iter().map(|error| error.span.line).collect();
+    assert_eq!(lines, vec![1, 2, 3, 4, 4]);
+    assert_eq!(statements.len(), 4);
+}
+
+#[test]
+fn test_lexical_error_does_not_stop_parsing() {
+    // 出错的字符被跳过，前后的记号照常解析
+    let (statements, errors) = parse_with_recovery("let x = 1; ` fn f() { }");
+
+    assert_eq!(errors.len(), 1);
+    assert!(matches!(&statements[1], Stmt::Function { name, .. } if name == "f"));
+}
+
+#[test]
+fn test_end_of_input_location() {
+    let error = Parser::new(Lexer::new(
+        "test.rs",
+        BufReader::new("fn main(\n  a,".as_bytes()),
+    ))
+    .parse()
+    .unwrap_err();
+
+    assert_eq!(error.found, None);
+    assert_eq!(error.span, Span::empty("test.rs", 2, 5));
+}
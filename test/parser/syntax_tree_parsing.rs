@@ -0,0 +1,388 @@
+//! 无损语法树测试
+//! 测试语法树保留所有空白和注释、容忍错误，以及语句节点与语法分析器构建的类型化语句的对应
+
+#[path = "../common/mod.rs"]
+mod common;
+
+use common::parse_with_recovery;
+use rus::data::{LexicalError, Token};
+use rus::lex::{Lexer, TriviaKind};
+use rus::parser::Stmt;
+use rus::syntax::{AstNode, Item, SyntaxKind, SyntaxTree};
+use std::io::BufReader;
+
+const PROGRAM: &str = "// 入口
+/* 块注释 /* 可以嵌套 */ */
+pub fn main() -> i32 {
+    let x = 1;   // 行尾注释
+    x * 2
+}
+
+struct Point { x: i32, y: i32 }
+mod shapes {
+    pub fn area() { }
+}
+";
+
+#[test]
+fn test_round_trip() {
+    let sources = [
+        PROGRAM,
+        "",
+        "   \n\t",
+        "let x = 1;",
+        "fn f() {}// 末尾没有换行",
+        "let s = \"字符串\\n\"; let c = '\\'';\r\n",
+        "fn (  ) { let = ; }\n}} struct",
+    ];
+
+    for source in sources {
+        let tree = SyntaxTree::parse("test.rs", source);
+        assert_eq!(tree.root().text(), source);
+        assert_eq!(tree.root().text_range(), 0..source.len());
+    }
+}
+
+#[test]
+fn test_comments_are_trivia() {
+    let tree = SyntaxTree::parse("test.rs", PROGRAM);
+
+    let comments: Vec<String> = tree
+        .root()
+        .descendant_tokens()
+        .into_iter()
+        .filter(|token| {
+            matches!(
+                token.kind(),
+                SyntaxKind::LineComment | SyntaxKind::BlockComment
+            )
+        })
+        .map(|token| token.text().to_string())
+        .collect();
+    assert_eq!(
+        comments,
+        vec!["// 入口", "/* 块注释 /* 可以嵌套 */ */", "// 行尾注释"]
+    );
+    assert!(tree.errors().is_empty());
+}
+
+#[test]
+fn test_tree_shape() {
+    let tree = SyntaxTree::parse("test.rs", PROGRAM);
+    let items = tree.source_file().items();
+
+    let kinds: Vec<SyntaxKind> = items.iter().map(|item| item.kind()).collect();
+    assert_eq!(
+        kinds,
+        vec![SyntaxKind::Function, SyntaxKind::Struct, SyntaxKind::Module]
+    );
+    let names: Vec<Option<String>> = items.iter().map(|item| item.name()).collect();
+    assert_eq!(
+        names,
+        vec![
+            Some("main".to_string()),
+            Some("Point".to_string()),
+            Some("shapes".to_string())
+        ]
+    );
+
+    // 语句从第一个记号开始，前面的注释属于外层节点
+    assert!(items[0].syntax().text().starts_with("pub fn main"));
+    assert!(items[0].syntax().text().ends_with('}'));
+
+    let body = items[0].body().unwrap();
+    let statements: Vec<SyntaxKind> = body.items().iter().map(|item| item.kind()).collect();
+    assert_eq!(
+        statements,
+        vec![SyntaxKind::Let, SyntaxKind::ExpressionStatement]
+    );
+    assert_eq!(body.syntax().parent().unwrap(), *items[0].syntax());
+
+    let module_items = items[2].items();
+    assert_eq!(module_items.len(), 1);
+    assert_eq!(module_items[0].name(), Some("area".to_string()));
+}
+
+#[test]
+fn test_token_at_offset() {
+    let source = "let answer = 42; // 注释";
+    let tree = SyntaxTree::parse("test.rs", source);
+
+    let token = tree.root().token_at_offset(6).unwrap();
+    assert_eq!(token.text(), "answer");
+    assert_eq!(token.text_range(), 4..10);
+    assert_eq!(
+        token.token(),
+        Some(&Token::Identifier("answer".to_string()))
+    );
+    assert_eq!(token.parent().kind(), SyntaxKind::Let);
+
+    let comment = tree.root().token_at_offset(source.len() - 1).unwrap();
+    assert_eq!(comment.kind(), SyntaxKind::LineComment);
+    assert_eq!(comment.parent().kind(), SyntaxKind::SourceFile);
+}
+
+#[test]
+fn test_error_tolerance() {
+    let source = "let x = ;\nfn f() { let y = * ; y }\n} struct S;";
+    let tree = SyntaxTree::parse("test.rs", source);
+
+    assert_eq!(tree.root().text(), source);
+    assert_eq!(tree.errors().len(), 3);
+    let kinds: Vec<SyntaxKind> = tree
+        .source_file()
+        .items()
+        .iter()
+        .map(|item| item.kind())
+        .collect();
+    assert_eq!(
+        kinds,
+        vec![
+            SyntaxKind::Error,
+            SyntaxKind::Function,
+            SyntaxKind::Error,
+            SyntaxKind::Struct
+        ]
+    );
+}
+
+#[test]
+fn test_lexical_errors_kept_in_tree() {
+    let source = "let c = 1 ` 2; let d = 1; /* 没有结束";
+    let tree = SyntaxTree::parse("test.rs", source);
+
+    assert_eq!(tree.root().text(), source);
+    let errors: Vec<&LexicalError> = tree.lexical_errors().iter().map(|(_, e)| e).collect();
+    assert_eq!(
+        errors,
+        vec![
+            &LexicalError::UnknownCharacter('`'),
+            &LexicalError::UnterminatedComment
+        ]
+    );
+    assert_eq!(tree.lexical_errors()[0].0.column, 11);
+    assert!(
+        tree.root()
+            .descendant_tokens()
+            .iter()
+            .any(|token| token.kind() == SyntaxKind::ErrorToken)
+    );
+}
+
+#[test]
+fn test_statements_match_parser() {
+    let sources = [
+        PROGRAM,
+        "let a = 1;\n  let b = a +\n    2;",
+        "fn f() { let x = 1; x + 1 }\nfn g() { { 1 } }",
+        "effect Log { fn log(message: String); }\nhandle Log { fn log(message) { } }",
+        "impl Point { pub fn new() -> Point { Point { x: 0, y: 0 } } }\nmod m { struct S; }",
+        "let x = ;\nfn f() { let y = * ; y }\n} struct S;",
+        "\t/* 注释 */ let s = \"多字节\"; let t = s;",
+    ];
+
+    for source in sources {
+        let tree = SyntaxTree::parse("test.rs", source);
+        let (statements, errors) = parse_with_recovery(source);
+        assert_eq!(tree.statements(), statements, "{}", source);
+        assert_eq!(tree.errors(), &errors[..]);
+    }
+}
+
+#[test]
+fn test_statement_for_item() {
+    let tree = SyntaxTree::parse("test.rs", "struct A;\n  fn f() { 1 }");
+    let items = tree.source_file().items();
+
+    match tree.statement(&items[1]).unwrap() {
+        Stmt::Function { name, span, .. } => {
+            assert_eq!(*name, "f");
+            assert_eq!(span.line, 2);
+            assert_eq!(span.column, 3);
+        }
+        other => panic!("Expected function, got {:?}", other),
+    }
+
+    // 嵌套在函数体中的语句
+    let tail = &items[1].body().unwrap().items()[0];
+    assert!(matches!(
+        tree.statement(tail),
+        Some(Stmt::Expression { .. })
+    ));
+
+    // 解析失败的语句对应错误节点
+    let tree = SyntaxTree::parse("test.rs", "let x = ;\n{ let y = 1; }");
+    let items = tree.source_file().items();
+    assert!(matches!(
+        tree.statement(&items[0]),
+        Some(Stmt::Error { .. })
+    ));
+    assert!(matches!(
+        tree.statement(&items[1]),
+        Some(Stmt::Block { .. })
+    ));
+}
+
+const ALL_CONSTRUCTS: &str = "use std::io::{self, Read as R, fmt::*};
+pub(crate) use a::b;
+use effects::io::*;
+effect State<T> { fn get() -> T; fn set(value: T); }
+effect_group Console = IO, State<i32> - Log;
+pub handler_group Defaults = StdOut, log::Memory;
+handle State<i32> as Memory {
+    get(k) { resume!(1) }
+    set(value: i32, k) { resume!(()) }
+    return(x) { x }
+    finally { close() }
+}
+handle Async { await(future) { resume!(run(future)) } }
+pub struct Point<T: Clone + Copy> { pub x: T, pub(crate) y: Vec<Vec<T>> }
+struct Pair(pub i32, (i32, String));
+struct Unit;
+struct Positive invariant self.value > 0 { value: i32 }
+enum Shape { Circle(f64), Rect { w: f64, h: f64 }, Empty }
+type Map<K, V> = HashMap<K, Vec<(K, V)>>;
+type Callback = fn(&i32, &mut [u8; 4], &&str) -> ();
+trait Show: Debug + Clone where Self: Sized {
+    type Output: Display = String;
+    type Item;
+    fn show(&self) -> String;
+    async fn run(&mut self, x: i32) -> i32 effects IO, ..e { x }
+}
+impl<T> Show for Point<T> where T: Show {
+    fn show(&self) -> String { format(self.x) }
+}
+impl Point<i32> { fn new(self) -> Self { Point { x: 0, ..origin() } } }
+contract NonNegative(x: i32) { requires x >= 0; ensures result >= old(x); contract Other; }
+mod inner { pub fn f() {} }
+mod external;
+pub async fn main<T: Ord>(items: [T], n: i32) -> (i32,) effects IO, State<i32> - Log
+    where T: Clone
+    requires n > 0
+    ensures result.0 == n
+    contract NonNegative
+{
+    let a = -1 + 2 * 3 - !x;
+    var b = &&c;
+    b += &mut d as i64;
+    let r = ..;
+    let s = 1..=n;
+    let t = (1, \"s\", 'c', 2.5, true, false);
+    let u = t.0.1 + t.1;
+    let v = [1, 2, 3][0] + [0; 8][1];
+    let w = obj.method(1, 2)?.field.await;
+    let y = std::mem::swap(a, b);
+    let z = move |x: i32, y| -> i32 { x + y };
+    let f = |x| x * 2;
+    let g = Point { x, y: 2 };
+    let h = async { 1 };
+    let i = try { f()? };
+    let j = { 1 };
+    let k = (a);
+    let l = ();
+    with Memory, handle IO { print(s) { resume!(()) } }, other::H {
+        State.set(1);
+    }
+    { nested(); }
+    a = b = c;
+    ..5;
+    x
+}
+";
+
+#[test]
+fn test_every_item_has_statement() {
+    let tree = SyntaxTree::parse("test.rs", ALL_CONSTRUCTS);
+    assert!(tree.errors().is_empty(), "{:?}", tree.errors());
+
+    // 每个语句节点，包括嵌套在块、闭包、处理器和实现中的语句，都对应一条同种类的语句
+    let items: Vec<Item> = tree
+        .root()
+        .descendants()
+        .into_iter()
+        .filter_map(Item::cast)
+        .collect();
+    assert!(items.len() > 60);
+    for item in items {
+        let statement = tree.statement(&item);
+        assert!(
+            statement.is_some_and(|statement| SyntaxKind::of_stmt(statement) == item.kind()),
+            "{}",
+            item.syntax().text()
+        );
+    }
+}
+
+#[test]
+fn test_expression_nodes() {
+    let tree = SyntaxTree::parse("test.rs", "let a = 1 + f(2) * x.y;");
+    let kinds: Vec<SyntaxKind> = tree
+        .root()
+        .descendants()
+        .iter()
+        .map(|node| node.kind())
+        .filter(|kind| kind.is_expression())
+        .collect();
+    assert_eq!(
+        kinds,
+        vec![
+            SyntaxKind::Binary,
+            SyntaxKind::Literal,
+            SyntaxKind::Binary,
+            SyntaxKind::Call,
+            SyntaxKind::Identifier,
+            SyntaxKind::Literal,
+            SyntaxKind::Field,
+            SyntaxKind::Identifier
+        ]
+    );
+
+    let binary = tree.root().descendants()[2].clone();
+    assert_eq!(binary.text(), "1 + f(2) * x.y");
+    assert_eq!(binary.parent().unwrap().kind(), SyntaxKind::Let);
+}
+
+#[test]
+fn test_lexer_trivia() {
+    let source = "  // a\nx /* b */ y";
+    let mut lexer = Lexer::new("test.rs", BufReader::new(source.as_bytes()));
+
+    let first = lexer.next_raw().unwrap();
+    let kinds: Vec<TriviaKind> = first.leading_trivia.iter().map(|t| t.kind).collect();
+    assert_eq!(
+        kinds,
+        vec![
+            TriviaKind::Whitespace,
+            TriviaKind::LineComment,
+            TriviaKind::Whitespace
+        ]
+    );
+    assert_eq!(first.leading_trivia[1].text, "// a");
+    assert_eq!(first.text, "x");
+    assert_eq!(first.token.location.line, 2);
+
+    let second = lexer.next_raw().unwrap();
+    assert_eq!(second.leading_trivia[1].text, "/* b */");
+    assert_eq!(second.token.location.column, 11);
+    assert!(lexer.next_raw().is_none());
+    assert!(lexer.trailing_trivia().is_empty());
+}
+
+#[test]
+fn test_comments_skipped_by_token_stream() {
+    let source = "let /* 类型 */ x = 1; // 结束\n";
+    let tokens: Vec<Token> = Lexer::new("test.rs", BufReader::new(source.as_bytes()))
+        .map(|token| token.data.unwrap())
+        .collect();
+
+    assert_eq!(
+        tokens,
+        vec![
+            Token::Let,
+            Token::Identifier("x".to_string()),
+            Token::Equal,
+            Token::IntegerLiteral("1".to_string()),
+            Token::Semicolon
+        ]
+    );
+}
@@ -0,0 +1,177 @@
+//! trait与impl块解析测试
+//! 测试语法分析器对trait声明、impl块、泛型约束和where子句的解析能力
+
+#[path = "../common/mod.rs"]
+mod common;
+
+use common::parse_source;
+use rus::parser::{GenericParameter, Parameter, Stmt, TraitItem, WherePredicate};
+
+fn strings(items: &[&str]) -> Vec<String> {
+    items.iter().map(|item| item.to_string()).collect()
+}
+
+fn parameter(name: &str, type_annotation: &str) -> Parameter {
+    Parameter {
+        name: name.into(),
+        type_annotation: Some(type_annotation.to_string()),
+    }
+}
+
+#[test]
+fn test_function_signature() {
+    let statements = parse_source(
+        "fn largest<T: PartialOrd + Copy>(items: &[T], n: usize) -> T effects Log where T: Debug { }",
+    )
+    .expect("Failed to parse");
+
+    match &statements[0] {
+        Stmt::Function {
+            name,
+            generics,
+            parameters,
+            return_type,
+            effects,
+            where_clause,
+            ..
+        } => {
+            assert_eq!(name, "largest");
+            assert_eq!(
+                generics,
+                &vec![GenericParameter {
+                    name: "T".into(),
+                    bounds: strings(&["PartialOrd", "Copy"]),
+                }]
+            );
+            assert_eq!(
+                parameters,
+                &vec![parameter("items", "&[T]"), parameter("n", "usize")]
+            );
+            assert_eq!(return_type.as_deref(), Some("T"));
+            assert_eq!(effects.effects, strings(&["Log"]));
+            assert_eq!(
+                where_clause,
+                &vec![WherePredicate {
+                    bounded_type: "T".to_string(),
+                    bounds: strings(&["Debug"]),
+                }]
+            );
+        }
+        other => panic!("Expected function declaration, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_trait_declaration() {
+    let statements = parse_source(
+        "trait Shape<T>: Display + Debug where T: Copy {
+            type Output: Clone = i32;
+            fn area(&self) -> f64 effects Log;
+            fn scale(&mut self, factor: f64);
+            fn describe(self) -> String { let s = 1; }
+        }",
+    )
+    .expect("Failed to parse");
+
+    match &statements[0] {
+        Stmt::Trait { declaration, .. } => {
+            assert_eq!(declaration.name, "Shape");
+            assert_eq!(declaration.generics.len(), 1);
+            assert_eq!(declaration.supertraits, strings(&["Display", "Debug"]));
+            assert_eq!(declaration.where_clause.len(), 1);
+            assert_eq!(declaration.items.len(), 4);
+
+            assert_eq!(
+                declaration.items[0],
+                TraitItem::AssociatedType {
+                    name: "Output".into(),
+                    bounds: strings(&["Clone"]),
+                    default: Some("i32".to_string()),
+                }
+            );
+
+            match &declaration.items[1] {
+                TraitItem::Method {
+                    signature,
+                    default_body,
+                } => {
+                    assert_eq!(signature.name, "area");
+                    assert_eq!(signature.parameters, vec![parameter("self", "&Self")]);
+                    assert_eq!(signature.effects.effects, strings(&["Log"]));
+                    assert!(default_body.is_none());
+                }
+                other => panic!("Expected trait method, got {:?}", other),
+            }
+
+            match &declaration.items[2] {
+                TraitItem::Method { signature, .. } => assert_eq!(
+                    signature.parameters,
+                    vec![parameter("self", "&mut Self"), parameter("factor", "f64")]
+                ),
+                other => panic!("Expected trait method, got {:?}", other),
+            }
+
+            match &declaration.items[3] {
+                TraitItem::Method {
+                    signature,
+                    default_body,
+                } => {
+                    assert_eq!(signature.parameters, vec![parameter("self", "Self")]);
+                    assert_eq!(default_body.as_ref().map(Vec::len), Some(1));
+                }
+                other => panic!("Expected trait method, got {:?}", other),
+            }
+        }
+        other => panic!("Expected trait declaration, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_inherent_impl() {
+    let statements = parse_source("impl Point { fn origin() -> Point { } fn x(&self) -> i32 { } }")
+        .expect("Failed to parse");
+
+    match &statements[0] {
+        Stmt::Impl { declaration, .. } => {
+            assert!(declaration.trait_name.is_none());
+            assert_eq!(declaration.self_type, "Point");
+            assert_eq!(declaration.items.len(), 2);
+            assert!(matches!(&declaration.items[1], Stmt::Function { name, .. } if name == "x"));
+        }
+        other => panic!("Expected impl block, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_trait_impl_with_generics_and_where_clause() {
+    let statements = parse_source(
+        "impl<T: Clone> Shape<T> for Vec<T> where T: Debug, Vec<T>: Send, {
+            type Output = T;
+            fn area(&self) -> f64 { }
+        }",
+    )
+    .expect("Failed to parse");
+
+    match &statements[0] {
+        Stmt::Impl { declaration, .. } => {
+            assert_eq!(declaration.generics[0].bounds, strings(&["Clone"]));
+            assert_eq!(declaration.trait_name.as_deref(), Some("Shape<T>"));
+            assert_eq!(declaration.self_type, "Vec<T>");
+            assert_eq!(declaration.where_clause.len(), 2);
+            assert_eq!(declaration.where_clause[1].bounded_type, "Vec<T>");
+            assert!(matches!(&declaration.items[0], Stmt::TypeAlias { .. }));
+            assert!(matches!(&declaration.items[1], Stmt::Function { .. }));
+        }
+        other => panic!("Expected impl block, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_impl_rejects_non_item_members() {
+    assert!(parse_source("impl Point { let x = 1; }").is_err());
+}
+
+#[test]
+fn test_reference_parameter_must_be_self() {
+    assert!(parse_source("fn f(&other) { }").is_err());
+}
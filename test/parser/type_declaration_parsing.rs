@@ -0,0 +1,229 @@
+//! 结构体、枚举与类型别名解析测试
+//! 测试语法分析器对类型声明、结构体字面量和枚举构造的解析能力
+
+#[path = "../common/mod.rs"]
+mod common;
+
+use common::parse_source;
+use rus::parser::{
+    Expr, FieldDeclaration, GenericParameter, Stmt, StructFields, TupleField, VariantArguments,
+    Visibility,
+};
+use rus::resolve::resolve_names;
+
+/// 解析并进行名称解析，返回最后一条表达式语句的表达式
+fn resolve_last_expression(code: &str) -> Expr {
+    let mut statements = parse_source(code).expect("Failed to parse");
+    resolve_names(&mut statements);
+    match statements.pop() {
+        Some(Stmt::Expression { expression, .. }) => expression,
+        other => panic!("Expected expression statement, got {:?}", other),
+    }
+}
+
+fn field(name: &str, field_type: &str) -> FieldDeclaration {
+    FieldDeclaration {
+        visibility: Visibility::Private,
+        name: name.into(),
+        field_type: field_type.to_string(),
+    }
+}
+
+fn tuple_fields(field_types: &[&str]) -> StructFields {
+    StructFields::Tuple(
+        field_types
+            .iter()
+            .map(|field_type| TupleField {
+                visibility: Visibility::Private,
+                field_type: field_type.to_string(),
+            })
+            .collect(),
+    )
+}
+
+#[test]
+fn test_named_struct_declaration() {
+    let statements = parse_source("struct Point<T> { x: T, y: T, }").expect("Failed to parse");
+
+    match &statements[0] {
+        Stmt::Struct { declaration, .. } => {
+            assert_eq!(declaration.name, "Point");
+            assert_eq!(
+                declaration.generics,
+                vec![GenericParameter {
+                    name: "T".into(),
+                    bounds: Vec::new(),
+                }]
+            );
+            assert_eq!(
+                declaration.fields,
+                StructFields::Named(vec![field("x", "T"), field("y", "T")])
+            );
+        }
+        other => panic!("Expected struct declaration, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_tuple_and_unit_struct_declarations() {
+    let statements =
+        parse_source("struct Meters(f64); struct Pair<A, B>(A, Vec<B>); struct Marker;")
+            .expect("Failed to parse");
+
+    match &statements[0] {
+        Stmt::Struct { declaration, .. } => {
+            assert_eq!(declaration.name, "Meters");
+            assert_eq!(declaration.fields, tuple_fields(&["f64"]));
+        }
+        other => panic!("Expected struct declaration, got {:?}", other),
+    }
+
+    match &statements[1] {
+        Stmt::Struct { declaration, .. } => {
+            assert_eq!(declaration.generics.len(), 2);
+            assert_eq!(declaration.fields, tuple_fields(&["A", "Vec<B>"]));
+        }
+        other => panic!("Expected struct declaration, got {:?}", other),
+    }
+
+    match &statements[2] {
+        Stmt::Struct { declaration, .. } => {
+            assert_eq!(declaration.name, "Marker");
+            assert_eq!(declaration.fields, StructFields::Unit);
+        }
+        other => panic!("Expected struct declaration, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_tuple_struct_requires_semicolon() {
+    assert!(parse_source("struct Meters(f64)").is_err());
+}
+
+#[test]
+fn test_enum_declaration() {
+    let statements =
+        parse_source("enum Shape<T> { Empty, Circle(T), Rect { width: T, height: T } }")
+            .expect("Failed to parse");
+
+    match &statements[0] {
+        Stmt::Enum { declaration, .. } => {
+            assert_eq!(declaration.name, "Shape");
+            assert_eq!(declaration.generics.len(), 1);
+            assert_eq!(declaration.variants.len(), 3);
+            assert_eq!(declaration.variants[0].name, "Empty");
+            assert_eq!(declaration.variants[0].fields, StructFields::Unit);
+            assert_eq!(declaration.variants[1].fields, tuple_fields(&["T"]));
+            assert_eq!(
+                declaration.variants[2].fields,
+                StructFields::Named(vec![field("width", "T"), field("height", "T")])
+            );
+        }
+        other => panic!("Expected enum declaration, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_type_alias() {
+    let statements = parse_source("type Pair<T> = (T, T); type Callback = fn(i32) -> bool;")
+        .expect("Failed to parse");
+
+    match &statements[0] {
+        Stmt::TypeAlias { declaration, .. } => {
+            assert_eq!(declaration.name, "Pair");
+            assert_eq!(declaration.generics.len(), 1);
+            assert_eq!(declaration.aliased_type, "(T, T)");
+        }
+        other => panic!("Expected type alias, got {:?}", other),
+    }
+
+    match &statements[1] {
+        Stmt::TypeAlias { declaration, .. } => {
+            assert_eq!(declaration.aliased_type, "fn(i32) -> bool");
+        }
+        other => panic!("Expected type alias, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_struct_literal() {
+    let statements = parse_source("let p = Point { x: 1, y, ..origin };").expect("Failed to parse");
+
+    match &statements[0] {
+        Stmt::Let {
+            initializer:
+                Some(Expr::StructLiteral {
+                    path, fields, base, ..
+                }),
+            ..
+        } => {
+            assert_eq!(path, &vec!["Point".to_string()]);
+            assert_eq!(fields.len(), 2);
+            assert_eq!(fields[0].0, "x");
+            assert!(matches!(fields[1].1, Expr::Identifier { ref name, .. } if name == "y"));
+            assert!(matches!(
+                base.as_deref(),
+                Some(Expr::Identifier { name, .. }) if name == "origin"
+            ));
+        }
+        other => panic!("Expected struct literal, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_enum_constructors() {
+    let declaration = "enum Shape { Empty, Circle(f64), Rect { w: f64, h: f64 } }";
+
+    let unit = resolve_last_expression(&format!("{} Shape::Empty;", declaration));
+    assert!(matches!(
+        unit,
+        Expr::EnumConstructor { ref enum_name, ref variant, arguments: VariantArguments::Unit, .. }
+            if enum_name == "Shape" && variant == "Empty"
+    ));
+
+    let tuple = resolve_last_expression(&format!("{} Shape::Circle(1.0);", declaration));
+    match tuple {
+        Expr::EnumConstructor {
+            variant,
+            arguments: VariantArguments::Tuple(arguments),
+            ..
+        } => {
+            assert_eq!(variant, "Circle");
+            assert_eq!(arguments.len(), 1);
+        }
+        other => panic!("Expected tuple variant constructor, got {:?}", other),
+    }
+
+    let named = resolve_last_expression(&format!("{} Shape::Rect {{ w: 1.0, h }};", declaration));
+    match named {
+        Expr::EnumConstructor {
+            variant,
+            arguments: VariantArguments::Named(fields),
+            ..
+        } => {
+            assert_eq!(variant, "Rect");
+            assert_eq!(fields[0].0, "w");
+            assert_eq!(fields[1].0, "h");
+        }
+        other => panic!("Expected struct variant constructor, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_imported_variant_constructor() {
+    let expression =
+        resolve_last_expression("enum Shape { Circle(f64) } use Shape::Circle; Circle(2.0);");
+
+    assert!(matches!(
+        expression,
+        Expr::EnumConstructor { ref enum_name, ref variant, .. }
+            if enum_name == "Shape" && variant == "Circle"
+    ));
+}
+
+#[test]
+fn test_unknown_variant_is_not_an_enum_constructor() {
+    let expression = resolve_last_expression("enum Shape { Empty } Shape::Square(1);");
+
+    assert!(matches!(expression, Expr::Call { .. }));
+}
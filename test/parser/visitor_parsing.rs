@@ -0,0 +1,253 @@
+//! 语法树遍历测试
+//! 测试 Visitor / VisitorMut / Fold 的默认遍历覆盖所有节点，以及只重写部分节点的用法
+
+#[path = "../common/mod.rs"]
+mod common;
+
+use common::parse_valid;
+use rus::ast::assign_node_ids;
+use rus::fold::{self, Fold};
+use rus::intern::Symbol;
+use rus::parser::{BinaryOperator, Expr, Literal, Stmt};
+use rus::visit::{self, Visitor, VisitorMut};
+
+/// 标识符出现在各种嵌套位置的程序
+const PROGRAM: &str = "
+effect Log { fn log(message: String); }
+struct Account invariant balance >= 0 { balance: i32 }
+trait Shape { fn area(&self) -> i32 { side * side } }
+impl Account { fn deposit(amount: i32) requires amount > 0 { balance += amount; } }
+mod inner { fn helper() { let t = (first, [second; count]); } }
+fn main() {
+    let f = move |x| x + captured;
+    with handle Log { log(message) { resume!(()) } finally { cleanup } } {
+        Log.log(greeting);
+    }
+    let p = Point { x: px, ..origin };
+    async { ready.await };
+    try { risky? };
+    list[index].field.method(argument);
+    -negated as i64;
+    start..end;
+}
+";
+
+/// 收集所有标识符表达式的名称
+#[derive(Default)]
+struct IdentifierCollector<'ast> {
+    names: Vec<&'ast str>,
+}
+
+impl<'ast> Visitor<'ast> for IdentifierCollector<'ast> {
+    fn visit_expr(&mut self, expr: &'ast Expr) {
+        if let Expr::Identifier { name, .. } = expr {
+            self.names.push(name.as_str());
+        }
+        visit::walk_expr(self, expr);
+    }
+}
+
+/// 统计访问到的语句和表达式个数
+#[derive(Default)]
+struct NodeCounter {
+    statements: usize,
+    expressions: usize,
+}
+
+impl Visitor<'_> for NodeCounter {
+    fn visit_stmt(&mut self, stmt: &Stmt) {
+        self.statements += 1;
+        visit::walk_stmt(self, stmt);
+    }
+
+    fn visit_expr(&mut self, expr: &Expr) {
+        self.expressions += 1;
+        visit::walk_expr(self, expr);
+    }
+}
+
+#[test]
+fn test_visitor_reaches_nested_expressions() {
+    let statements = parse_valid(PROGRAM);
+    let mut collector = IdentifierCollector::default();
+    collector.visit_block(&statements);
+
+    for name in [
+        "balance", "side", "amount", "first", "second", "count", "x", "captured", "cleanup",
+        "greeting", "px", "origin", "ready", "risky", "list", "index", "argument", "negated",
+        "start", "end",
+    ] {
+        assert!(collector.names.contains(&name), "missing {}", name);
+    }
+}
+
+#[test]
+fn test_visitor_counts_every_node_once() {
+    let mut statements = parse_valid(PROGRAM);
+    let mut counter = NodeCounter::default();
+    counter.visit_block(&statements);
+
+    assert_eq!(
+        counter.statements + counter.expressions,
+        assign_node_ids(&mut statements)
+    );
+}
+
+#[test]
+fn test_visitor_can_skip_subtrees() {
+    /// 不进入闭包体
+    struct OutsideClosures(Vec<String>);
+
+    impl Visitor<'_> for OutsideClosures {
+        fn visit_expr(&mut self, expr: &Expr) {
+            match expr {
+                Expr::Closure { .. } => {}
+                Expr::Identifier { name, .. } => self.0.push(name.to_string()),
+                _ => visit::walk_expr(self, expr),
+            }
+        }
+    }
+
+    let statements = parse_valid("let f = |a| a + b; let g = c;");
+    let mut visitor = OutsideClosures(Vec::new());
+    visitor.visit_block(&statements);
+
+    assert_eq!(visitor.0, vec!["c"]);
+}
+
+#[test]
+fn test_visitor_mut_renames_identifiers() {
+    struct Rename;
+
+    impl VisitorMut for Rename {
+        fn visit_expr_mut(&mut self, expr: &mut Expr) {
+            if let Expr::Identifier { name, .. } = expr
+                && *name == "old"
+            {
+                *name = Symbol::intern("new");
+            }
+            visit::walk_expr_mut(self, expr);
+        }
+    }
+
+    let mut statements = parse_valid("fn f() { let a = old + { old * 2 }; g(|x| old); }");
+    Rename.visit_block_mut(&mut statements);
+
+    let mut collector = IdentifierCollector::default();
+    collector.visit_block(&statements);
+    assert_eq!(collector.names, vec!["new", "new", "g", "new"]);
+}
+
+#[test]
+fn test_default_fold_is_identity() {
+    struct Identity;
+    impl Fold for Identity {}
+
+    let statements = parse_valid(PROGRAM);
+    let folded = Identity.fold_block(statements.clone());
+
+    assert_eq!(folded, statements);
+}
+
+#[test]
+fn test_fold_desugars_groupings_and_constants() {
+    /// 去掉括号，并计算两个整数字面量相加
+    struct Simplify;
+
+    impl Fold for Simplify {
+        fn fold_expr(&mut self, expr: Expr) -> Expr {
+            match fold::walk_expr(self, expr) {
+                Expr::Grouping { expression, .. } => *expression,
+                Expr::Binary {
+                    id,
+                    span,
+                    left,
+                    operator: BinaryOperator::Add,
+                    right,
+                } if matches!(
+                    (&*left, &*right),
+                    (
+                        Expr::Literal {
+                            value: Literal::Integer(_),
+                            ..
+                        },
+                        Expr::Literal {
+                            value: Literal::Integer(_),
+                            ..
+                        }
+                    )
+                ) =>
+                {
+                    let value = |expr: &Expr| match expr {
+                        Expr::Literal {
+                            value: Literal::Integer(value),
+                            ..
+                        } => value.parse::<i64>().unwrap(),
+                        _ => unreachable!(),
+                    };
+                    Expr::Literal {
+                        id,
+                        span,
+                        value: Literal::Integer((value(&left) + value(&right)).to_string()),
+                    }
+                }
+                other => other,
+            }
+        }
+    }
+
+    let statements = parse_valid("let a = ((1 + 2) + (3));");
+    let folded = Simplify.fold_block(statements);
+
+    match &folded[0] {
+        Stmt::Let {
+            initializer: Some(Expr::Literal { value, .. }),
+            ..
+        } => assert_eq!(value, &Literal::Integer("6".to_string())),
+        other => panic!("Expected folded literal, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_fold_rewrites_statements_in_nested_blocks() {
+    /// 把 var 声明改写为 let 声明
+    struct VarToLet;
+
+    impl Fold for VarToLet {
+        fn fold_stmt(&mut self, stmt: Stmt) -> Stmt {
+            match fold::walk_stmt(self, stmt) {
+                Stmt::Var {
+                    id,
+                    span,
+                    identifier,
+                    initializer,
+                } => Stmt::Let {
+                    id,
+                    span,
+                    identifier,
+                    initializer,
+                },
+                other => other,
+            }
+        }
+    }
+
+    let statements =
+        parse_valid("fn f() { var a = 1; { var b = 2; } }\nmod m { fn g() { var c = 3; } }");
+    let folded = VarToLet.fold_block(statements);
+
+    struct CountVars(usize, usize);
+    impl Visitor<'_> for CountVars {
+        fn visit_stmt(&mut self, stmt: &Stmt) {
+            match stmt {
+                Stmt::Var { .. } => self.0 += 1,
+                Stmt::Let { .. } => self.1 += 1,
+                _ => {}
+            }
+            visit::walk_stmt(self, stmt);
+        }
+    }
+    let mut counts = CountVars(0, 0);
+    counts.visit_block(&folded);
+    assert_eq!((counts.0, counts.1), (0, 3));
+}
@@ -0,0 +1,150 @@
+//! with表达式解析测试
+//! 测试语法分析器对处理器安装表达式的解析，以及名称解析阶段对被处理效果的记录
+
+#[path = "../common/mod.rs"]
+mod common;
+
+use common::parse_source;
+use rus::parser::{Expr, HandlerReference, Stmt};
+use rus::resolve::resolve_names;
+
+/// 解析并进行名称解析，返回最后一条语句中的表达式
+fn resolve_last_expression(code: &str) -> Expr {
+    let mut statements = parse_source(code).expect("Failed to parse");
+    resolve_names(&mut statements);
+    match statements.pop() {
+        Some(Stmt::Expression { expression, .. }) => expression,
+        other => panic!("Expected expression statement, got {:?}", other),
+    }
+}
+
+fn strings(items: &[&str]) -> Vec<String> {
+    items.iter().map(|item| item.to_string()).collect()
+}
+
+#[test]
+fn test_named_handler_declaration() {
+    let statements = parse_source("handle FileSystem as LocalFs { read_file(path) { } }")
+        .expect("Failed to parse");
+
+    match &statements[0] {
+        Stmt::Handler { declaration, .. } => {
+            assert_eq!(declaration.name.as_deref(), Some("LocalFs"));
+            assert_eq!(declaration.effect, "FileSystem");
+        }
+        other => panic!("Expected handler declaration, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_with_named_handlers() {
+    let statements =
+        parse_source("with LocalFs, WebHandlers { let x = 1; x; }").expect("Failed to parse");
+
+    match &statements[0] {
+        Stmt::Expression {
+            expression: Expr::With { handlers, body, .. },
+            ..
+        } => {
+            assert_eq!(
+                handlers,
+                &vec![
+                    HandlerReference::Named("LocalFs".into()),
+                    HandlerReference::Named("WebHandlers".into()),
+                ]
+            );
+            assert_eq!(body.len(), 2);
+        }
+        other => panic!("Expected with expression, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_with_inline_handler() {
+    let statements = parse_source(
+        "with handle Log { log(message) { } }, LocalFs {
+            Log.log(message);
+        }",
+    )
+    .expect("Failed to parse");
+
+    match &statements[0] {
+        Stmt::Expression {
+            expression:
+                Expr::With {
+                    handlers,
+                    discharged_effects,
+                    ..
+                },
+            ..
+        } => {
+            match &handlers[0] {
+                HandlerReference::Inline(declaration) => {
+                    assert!(declaration.name.is_none());
+                    assert_eq!(declaration.effect, "Log");
+                    assert_eq!(declaration.clauses[0].operation, "log");
+                }
+                other => panic!("Expected inline handler, got {:?}", other),
+            }
+            assert_eq!(discharged_effects, &strings(&["Log"]));
+        }
+        other => panic!("Expected with expression, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_with_statement_does_not_need_semicolon() {
+    let statements =
+        parse_source("with LocalFs { } let y = 2; with LocalFs { }; -y;").expect("Failed to parse");
+
+    assert_eq!(statements.len(), 4);
+    assert!(matches!(statements[1], Stmt::Let { .. }));
+    assert!(matches!(
+        statements[3],
+        Stmt::Expression {
+            expression: Expr::Unary { .. },
+            ..
+        }
+    ));
+}
+
+#[test]
+fn test_with_as_expression() {
+    let statements =
+        parse_source("let value = with LocalFs { compute(); };").expect("Failed to parse");
+
+    assert!(matches!(
+        statements[0],
+        Stmt::Let {
+            initializer: Some(Expr::With { .. }),
+            ..
+        }
+    ));
+}
+
+#[test]
+fn test_discharged_effects_are_resolved_through_handler_groups() {
+    let expression = resolve_last_expression(
+        "handle FileSystem as LocalFs { }
+         handle Network as MockNet { }
+         handle Logger as Stdout { }
+         handler_group Inner = MockNet, Stdout;
+         handler_group Outer = LocalFs, Inner, Outer;
+         with Outer, handle Clock { } { }",
+    );
+
+    match expression {
+        Expr::With {
+            discharged_effects, ..
+        } => assert_eq!(
+            discharged_effects,
+            strings(&["Clock", "FileSystem", "Network", "Logger"])
+        ),
+        other => panic!("Expected with expression, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_with_requires_a_handler() {
+    assert!(parse_source("with { }").is_err());
+}